@@ -5,7 +5,7 @@
 //! for component placement and net routing priorities.
 
 use anyhow::{Context, Result};
-use picoplace_engine::{placer_sa::PlacementHints, Point};
+use picoplace_engine::Point;
 use picoplace_netlist::Schematic;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -14,7 +14,7 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AIHints {
     /// Suggested component placements (component refdes -> position)
-    pub placement_suggestions: PlacementHints,
+    pub placement_suggestions: HashMap<String, Point>,
     /// Routing priorities (net names in order of importance)
     pub routing_priorities: Vec<String>,
     /// Additional reasoning from the AI
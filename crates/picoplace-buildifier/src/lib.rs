@@ -12,6 +12,7 @@
 
 use anyhow::{Context, Result};
 use once_cell::sync::OnceCell;
+use serde::Deserialize;
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -87,6 +88,81 @@ fn get_cached_binary_path() -> Result<PathBuf> {
     Ok(binary_path)
 }
 
+/// Result of [`Buildifier::format_dir`]
+#[derive(Debug, Default)]
+pub struct FormatSummary {
+    /// Files that were reformatted (or would be, when only checking)
+    pub formatted: Vec<PathBuf>,
+    /// Number of files that were already formatted
+    pub unchanged: usize,
+    /// Files buildifier failed on, with the error message
+    pub errored: Vec<(PathBuf, String)>,
+}
+
+impl FormatSummary {
+    /// Whether every file was already formatted and none failed
+    pub fn is_clean(&self) -> bool {
+        self.formatted.is_empty() && self.errored.is_empty()
+    }
+}
+
+/// A single lint warning reported by [`Buildifier::lint_file`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintFinding {
+    /// Buildifier warning category, e.g. `unused-variable`
+    pub category: String,
+    pub message: String,
+    /// 1-based line the warning starts on
+    pub line: u32,
+    /// Whether the warning points at something that should be fixed, as
+    /// opposed to a purely informational finding
+    pub actionable: bool,
+}
+
+/// Top level of buildifier's `--format=json` output
+#[derive(Deserialize)]
+struct JsonReport {
+    #[serde(default)]
+    files: Vec<JsonFile>,
+}
+
+#[derive(Deserialize)]
+struct JsonFile {
+    #[serde(default)]
+    warnings: Vec<JsonWarning>,
+}
+
+#[derive(Deserialize)]
+struct JsonWarning {
+    start: JsonPosition,
+    category: String,
+    message: String,
+    #[serde(default)]
+    actionable: bool,
+}
+
+#[derive(Deserialize)]
+struct JsonPosition {
+    line: u32,
+}
+
+/// Parse the lint findings out of buildifier's `--format=json` output
+fn parse_lint_json(json: &str) -> Result<Vec<LintFinding>> {
+    let report: JsonReport =
+        serde_json::from_str(json).context("Failed to parse buildifier lint output")?;
+    Ok(report
+        .files
+        .into_iter()
+        .flat_map(|file| file.warnings)
+        .map(|warning| LintFinding {
+            category: warning.category,
+            message: warning.message,
+            line: warning.start.line,
+            actionable: warning.actionable,
+        })
+        .collect())
+}
+
 /// Represents a buildifier instance that can format Starlark files
 pub struct Buildifier {
     binary_path: PathBuf,
@@ -122,6 +198,35 @@ impl Buildifier {
             .context("Failed to execute buildifier")
     }
 
+    /// Run buildifier with `input` piped to stdin, capturing stdout/stderr
+    pub fn run_with_stdin(&self, args: &[String], input: &str) -> Result<Output> {
+        let mut child = Command::new(&self.binary_path)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute buildifier")?;
+
+        // Write from a separate thread so a large output can't block on a full pipe
+        let mut stdin = child
+            .stdin
+            .take()
+            .context("Failed to open buildifier stdin")?;
+        let input = input.to_owned();
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+        let output = child
+            .wait_with_output()
+            .context("Failed to wait for buildifier")?;
+        writer
+            .join()
+            .map_err(|_| anyhow::anyhow!("Failed to write to buildifier stdin"))?
+            .context("Failed to write to buildifier stdin")?;
+
+        Ok(output)
+    }
+
     /// Check if a file needs formatting (returns true if already formatted)
     pub fn check_file(&self, file_path: &Path) -> Result<bool> {
         let output = self.run(&[
@@ -157,6 +262,94 @@ impl Buildifier {
         Ok(())
     }
 
+    /// Format Starlark source held in memory, returning the formatted text
+    pub fn format_source(&self, content: &str) -> Result<String> {
+        let output = self.run_with_stdin(
+            &[
+                "--mode=fix".to_string(),
+                "--type=default".to_string(),
+                "-".to_string(),
+            ],
+            content,
+        )?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Failed to format source: {stderr}");
+        }
+
+        String::from_utf8(output.stdout).context("Buildifier produced invalid UTF-8")
+    }
+
+    /// Format a string of Starlark source, see [`Self::format_source`]
+    pub fn format_string(&self, content: &str) -> Result<String> {
+        self.format_source(content)
+    }
+
+    /// Format every file under `root` whose extension is one of `extensions`
+    /// (without the leading dot), skipping paths excluded by `.gitignore`.
+    ///
+    /// With `check_only` set no files are written; files that would change are
+    /// reported in [`FormatSummary::formatted`].
+    pub fn format_dir(
+        &self,
+        root: &Path,
+        extensions: &[&str],
+        check_only: bool,
+    ) -> Result<FormatSummary> {
+        let mut summary = FormatSummary::default();
+
+        let walker = ignore::WalkBuilder::new(root)
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+        for entry in walker {
+            let entry = entry.context("Failed to walk directory")?;
+            let path = entry.path();
+            let matches = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| extensions.contains(&ext));
+            if !entry.file_type().is_some_and(|t| t.is_file()) || !matches {
+                continue;
+            }
+
+            let result = self.check_file(path).and_then(|formatted| {
+                if !formatted && !check_only {
+                    self.format_file(path)?;
+                }
+                Ok(formatted)
+            });
+            match result {
+                Ok(true) => summary.unchanged += 1,
+                Ok(false) => summary.formatted.push(path.to_path_buf()),
+                Err(e) => summary.errored.push((path.to_path_buf(), e.to_string())),
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Run buildifier's linter on a file and return its warnings
+    pub fn lint_file(&self, file_path: &Path) -> Result<Vec<LintFinding>> {
+        let output = self.run_with_io(&[
+            "--mode=check".to_string(),
+            "--lint=warn".to_string(),
+            "--format=json".to_string(),
+            file_path.to_string_lossy().to_string(),
+        ])?;
+
+        // Exit code 4 means there are warnings or formatting issues
+        match output.status.code() {
+            Some(0) | Some(4) => parse_lint_json(&String::from_utf8_lossy(&output.stdout)),
+            Some(code) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                anyhow::bail!("Buildifier lint failed with code {}: {}", code, stderr)
+            }
+            None => anyhow::bail!("Buildifier was terminated by signal"),
+        }
+    }
+
     /// Get the diff that would be applied to format a file
     pub fn diff_file(&self, file_path: &Path) -> Result<String> {
         let output = self.run_with_io(&[
@@ -201,6 +394,107 @@ mod tests {
         assert!(version.contains("buildifier"));
     }
 
+    #[test]
+    fn test_format_source() {
+        let buildifier = Buildifier::new().unwrap();
+        let formatted = buildifier.format_source("x=[1,2]\n").unwrap();
+        assert_eq!(formatted, "x = [1, 2]\n");
+
+        // Already formatted source is returned unchanged
+        assert_eq!(buildifier.format_source(&formatted).unwrap(), formatted);
+
+        // Indentation is normalized to four spaces
+        let formatted = buildifier
+            .format_source("def f(a,b):\n  if a:\n   return b\n  return None\n")
+            .unwrap();
+        assert_eq!(
+            formatted,
+            "def f(a, b):\n    if a:\n        return b\n    return None\n"
+        );
+    }
+
+    #[test]
+    fn test_format_string() {
+        let buildifier = Buildifier::new().unwrap();
+        assert_eq!(
+            buildifier.format_string("x=[1,2]\n").unwrap(),
+            "x = [1, 2]\n"
+        );
+    }
+
+    #[test]
+    fn test_format_dir() {
+        let buildifier = Buildifier::new().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::create_dir_all(root.join("ignored")).unwrap();
+        fs::write(root.join(".gitignore"), "ignored/\n").unwrap();
+        fs::write(root.join("a.zen"), "x = 1\n").unwrap();
+        fs::write(root.join("sub/b.star"), "x=[1,2]\n").unwrap();
+        fs::write(root.join("ignored/c.zen"), "x=[1,2]\n").unwrap();
+        fs::write(root.join("notes.txt"), "x=[1,2]\n").unwrap();
+
+        let summary = buildifier.format_dir(root, &["zen", "star"], true).unwrap();
+        assert_eq!(summary.formatted, vec![root.join("sub/b.star")]);
+        assert_eq!(summary.unchanged, 1);
+        assert!(!summary.is_clean());
+        assert_eq!(
+            fs::read_to_string(root.join("sub/b.star")).unwrap(),
+            "x=[1,2]\n"
+        );
+
+        buildifier
+            .format_dir(root, &["zen", "star"], false)
+            .unwrap();
+        let summary = buildifier.format_dir(root, &["zen", "star"], true).unwrap();
+        assert!(summary.is_clean());
+        assert_eq!(
+            fs::read_to_string(root.join("ignored/c.zen")).unwrap(),
+            "x=[1,2]\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_lint_json() {
+        let json = r#"{
+            "success": false,
+            "files": [
+                {
+                    "filename": "board.zen",
+                    "formatted": true,
+                    "valid": true,
+                    "warnings": [
+                        {
+                            "start": {"line": 3, "column": 1},
+                            "end": {"line": 3, "column": 8},
+                            "category": "unused-variable",
+                            "actionable": true,
+                            "autoFixable": false,
+                            "message": "Variable \"unused\" is unused.",
+                            "url": "https://github.com/bazelbuild/buildtools/blob/master/WARNINGS.md#unused-variable"
+                        }
+                    ]
+                },
+                {"filename": "clean.zen", "formatted": true, "valid": true, "warnings": []}
+            ]
+        }"#;
+
+        let findings = parse_lint_json(json).unwrap();
+        assert_eq!(
+            findings,
+            vec![LintFinding {
+                category: "unused-variable".to_string(),
+                message: "Variable \"unused\" is unused.".to_string(),
+                line: 3,
+                actionable: true,
+            }]
+        );
+
+        assert!(parse_lint_json("{\"success\": true}").unwrap().is_empty());
+        assert!(parse_lint_json("not json").is_err());
+    }
+
     #[test]
     fn test_caching() {
         // Create two instances and verify they use the same cached binary
@@ -1,12 +1,18 @@
 use anyhow::Result;
-use clap::Args;
+use clap::{Args, ValueEnum};
 use log::debug;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
 use picoplace_ui::prelude::*;
 use picoplace_lang::file_extensions;
-use picoplace_lang::EvalSeverity;
-use std::collections::HashSet;
+use picoplace_lang::{EvalSeverity, JsonDiagnostic, WithDiagnostics};
+use picoplace_netlist::Schematic;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
 
 #[derive(Args, Debug, Default, Clone)]
 #[command(about = "Build PCB projects from .zen files")]
@@ -19,17 +25,77 @@ pub struct BuildArgs {
     /// Print JSON netlist to stdout (undocumented)
     #[arg(long = "netlist", hide = true)]
     pub netlist: bool,
+
+    /// Rebuild whenever a source file or one of its dependencies changes
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// Print a machine-readable JSON report to stdout instead of human output.
+    /// Progress is reported on stderr as one JSON object per event.
+    #[arg(long, conflicts_with_all = ["watch", "netlist"])]
+    pub json: bool,
+
+    /// Print the graph of `load()`/`Module()` dependencies between files to
+    /// stdout instead of building. Nodes are absolute paths.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        conflicts_with_all = ["watch", "netlist", "json"]
+    )]
+    pub print_deps: Option<DepsFormat>,
+
+    /// Build each module instantiated, or `Module()` loader bound, at the top
+    /// level of the given files as a separate design, e.g. every board
+    /// variant kept in one file
+    #[arg(long, conflicts_with_all = ["watch", "netlist", "json", "print_deps"])]
+    pub all_variants: bool,
+}
+
+/// Output format for `build --print-deps`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepsFormat {
+    /// Graphviz digraph
+    Dot,
+    /// Adjacency list: an object mapping each file to the files it references
+    Json,
+}
+
+/// Report printed by `build --json`
+#[derive(Serialize)]
+struct BuildReport {
+    success: bool,
+    /// In the format of [`picoplace_lang::diagnostics_to_json`]
+    diagnostics: Vec<JsonDiagnostic>,
+    /// Files written by the build. `build` only validates designs, so this is
+    /// empty until a build step produces artifacts.
+    artifacts: Vec<PathBuf>,
 }
 
+/// How long to wait for further file events before rebuilding in watch mode
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Stack size for evaluation worker threads; deeply nested designs recurse
+/// through the Starlark evaluator and need more than the 2 MiB default.
+const EVAL_THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Outcome of evaluating one file: the schematic, the files it depended on and
+/// the lines it printed.
+type EvalResult = (WithDiagnostics<Schematic>, Vec<PathBuf>, Vec<String>);
+
 /// Evaluate a single Starlark file and print any diagnostics
 /// Returns the evaluation result and whether there were any errors
 pub fn evaluate_zen_file(path: &Path) -> (picoplace_lang::WithDiagnostics<picoplace_netlist::Schematic>, bool) {
     debug!("Compiling Zener file: {}", path.display());
 
     // Evaluate the design
-    let eval_result = picoplace_lang::run(path);
+    let (eval_result, _, print_output) = picoplace_lang::run_with_dependencies(path);
     let mut has_errors = false;
 
+    for line in &print_output {
+        eprintln!("{line}");
+    }
+
     // Print diagnostics
     for diag in eval_result.diagnostics.iter() {
         picoplace_lang::render_diagnostic(diag);
@@ -55,71 +121,184 @@ pub fn execute(args: BuildArgs) -> Result<()> {
         );
     }
 
+    if args.watch {
+        return watch(&args, &zen_paths);
+    }
+
+    if args.json {
+        return build_json(&zen_paths);
+    }
+
+    if let Some(format) = args.print_deps {
+        return print_deps(&zen_paths, format);
+    }
+
+    if args.all_variants {
+        return build_variants(&zen_paths);
+    }
+
+    if build_all(&args, &zen_paths) {
+        anyhow::bail!("Build failed with errors");
+    }
+
+    Ok(())
+}
+
+/// Build every file in `zen_paths`, returning whether any of them failed.
+///
+/// Files are evaluated concurrently, but diagnostics and summaries are printed
+/// afterwards in input order so output from different files never interleaves.
+fn build_all(args: &BuildArgs, zen_paths: &[PathBuf]) -> bool {
+    let results = evaluate_with_spinner(zen_paths);
+    report_all(args, zen_paths, &results)
+}
+
+/// Evaluate `zen_paths` concurrently while showing a progress spinner.
+fn evaluate_with_spinner(zen_paths: &[PathBuf]) -> Vec<EvalResult> {
+    let message = match zen_paths {
+        [single] => format!(
+            "{}: Building",
+            single.file_name().unwrap().to_string_lossy()
+        ),
+        _ => format!("Building {} files", zen_paths.len()),
+    };
+    let spinner = Spinner::builder(message).start();
+    let results = evaluate_all(zen_paths);
+    spinner.finish();
+    results
+}
+
+/// Print the results of evaluating `zen_paths`, returning whether any failed.
+fn report_all(args: &BuildArgs, zen_paths: &[PathBuf], results: &[EvalResult]) -> bool {
     let mut has_errors = false;
+    for (zen_path, (eval_result, _, print_output)) in zen_paths.iter().zip(results) {
+        has_errors |= report_file(args, zen_path, eval_result, print_output);
+    }
+    has_errors
+}
 
-    // Process each .zen file
-    for zen_path in zen_paths {
-        let file_name = zen_path.file_name().unwrap().to_string_lossy();
+/// Build every file in `zen_paths` and print a single [`BuildReport`] to
+/// stdout. Diagnostics are only reported in the JSON, not rendered to stderr.
+fn build_json(zen_paths: &[PathBuf]) -> Result<()> {
+    picoplace_ui::set_output_mode(picoplace_ui::OutputMode::Json);
+    let results = evaluate_all(zen_paths);
 
-        // Show spinner while building
-        let spinner = Spinner::builder(format!("{file_name}: Building")).start();
+    let mut success = true;
+    let mut diagnostics = Vec::new();
+    for (eval_result, _, print_output) in &results {
+        // print() output still goes to stderr so stdout stays valid JSON
+        for line in print_output {
+            eprintln!("{line}");
+        }
+        success &= !eval_result
+            .diagnostics
+            .iter()
+            .any(|diag| matches!(diag.severity, EvalSeverity::Error));
+        success &= eval_result.output.is_some();
+        diagnostics.extend(eval_result.diagnostics.iter().map(JsonDiagnostic::from));
+    }
 
-        // Evaluate the design
-        let eval_result = picoplace_lang::run(&zen_path);
+    let report = BuildReport {
+        success,
+        diagnostics,
+        artifacts: Vec::new(),
+    };
+    println!("{}", serde_json::to_string_pretty(&report)?);
 
-        // Check if we have diagnostics to print
-        if !eval_result.diagnostics.is_empty() {
-            // Finish spinner before printing diagnostics
-            spinner.finish();
+    if !success {
+        anyhow::bail!("Build failed with errors");
+    }
 
-            // Now print diagnostics
-            let mut file_has_errors = false;
-            for diag in eval_result.diagnostics.iter() {
-                picoplace_lang::render_diagnostic(diag);
-                eprintln!();
+    Ok(())
+}
 
-                if matches!(diag.severity, EvalSeverity::Error) {
-                    file_has_errors = true;
-                }
-            }
+/// Evaluate every file in `zen_paths` and print the union of their dependency
+/// graphs to stdout. Diagnostics are still rendered to stderr.
+fn print_deps(zen_paths: &[PathBuf], format: DepsFormat) -> Result<()> {
+    let mut graph: BTreeMap<PathBuf, BTreeSet<PathBuf>> = BTreeMap::new();
+    let mut has_errors = false;
 
-            if file_has_errors {
-                println!(
-                    "{} {}: Build failed",
-                    picoplace_ui::icons::error(),
-                    file_name.with_style(Style::Red).bold()
-                );
-                has_errors = true;
-            }
-        } else if let Some(schematic) = &eval_result.output {
-            spinner.finish();
-
-            // If netlist flag is set, print JSON to stdout
-            if args.netlist {
-                match schematic.to_json() {
-                    Ok(json) => println!("{json}"),
-                    Err(e) => {
-                        eprintln!("Error serializing netlist to JSON: {e}");
-                        has_errors = true;
-                    }
+    for zen_path in zen_paths {
+        let (eval_result, deps) = picoplace_lang::run_with_dependency_graph(zen_path);
+        for diag in eval_result.diagnostics.iter() {
+            picoplace_lang::render_diagnostic(diag);
+            eprintln!();
+            has_errors |= matches!(diag.severity, EvalSeverity::Error);
+        }
+
+        // List every entry file, even one that loads nothing
+        graph.entry(zen_path.canonicalize()?).or_default();
+        for (from, to) in deps {
+            graph.entry(from).or_default().extend(to);
+        }
+    }
+
+    match format {
+        DepsFormat::Json => println!("{}", serde_json::to_string_pretty(&graph)?),
+        DepsFormat::Dot => {
+            let quote = |path: &Path| format!("{:?}", path.display().to_string());
+            println!("digraph deps {{");
+            for (from, to) in &graph {
+                if to.is_empty() {
+                    println!("  {};", quote(from));
+                }
+                for dep in to {
+                    println!("  {} -> {};", quote(from), quote(dep));
                 }
-            } else {
-                // Print success with component count
-                let component_count = schematic
-                    .instances
-                    .values()
-                    .filter(|i| i.kind == picoplace_netlist::InstanceKind::Component)
-                    .count();
-                eprintln!(
-                    "{} {} ({} components)",
-                    picoplace_ui::icons::success(),
-                    file_name.with_style(Style::Green).bold(),
-                    component_count
-                );
             }
-        } else {
-            spinner.error(format!("{file_name}: No output generated"));
+            println!("}}");
+        }
+    }
+
+    if has_errors {
+        anyhow::bail!("Build failed with errors");
+    }
+
+    Ok(())
+}
+
+/// Build every variant declared in each file of `zen_paths` (see
+/// [`picoplace_lang::run_variants`]) and print a summary line per variant.
+fn build_variants(zen_paths: &[PathBuf]) -> Result<()> {
+    let mut has_errors = false;
+
+    for zen_path in zen_paths {
+        let file_name = zen_path.file_name().unwrap().to_string_lossy();
+        let eval_result = picoplace_lang::run_variants(zen_path);
+        for diag in eval_result.diagnostics.iter() {
+            picoplace_lang::render_diagnostic(diag);
+            eprintln!();
+            has_errors |= matches!(diag.severity, EvalSeverity::Error);
+        }
+
+        let Some(variants) = eval_result.output else {
             has_errors = true;
+            println!(
+                "{} {}: Build failed",
+                picoplace_ui::icons::error(),
+                file_name.with_style(Style::Red).bold()
+            );
+            continue;
+        };
+        if variants.is_empty() {
+            eprintln!("{file_name}: no Module() variants found");
+        }
+
+        let variants: BTreeMap<String, Schematic> = variants.into_iter().collect();
+        for (name, schematic) in &variants {
+            let component_count = schematic
+                .instances
+                .values()
+                .filter(|i| i.kind == picoplace_netlist::InstanceKind::Component)
+                .count();
+            eprintln!(
+                "{} {} ({} components)",
+                picoplace_ui::icons::success(),
+                format!("{file_name}:{name}")
+                    .with_style(Style::Green)
+                    .bold(),
+                component_count
+            );
         }
     }
 
@@ -130,6 +309,200 @@ pub fn execute(args: BuildArgs) -> Result<()> {
     Ok(())
 }
 
+/// Evaluate `zen_paths` on a bounded pool of worker threads.
+/// Results are returned in the same order as the input paths.
+fn evaluate_all(zen_paths: &[PathBuf]) -> Vec<EvalResult> {
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(zen_paths.len());
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<EvalResult>>> =
+        Mutex::new((0..zen_paths.len()).map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            std::thread::Builder::new()
+                .stack_size(EVAL_THREAD_STACK_SIZE)
+                .spawn_scoped(scope, || loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(zen_path) = zen_paths.get(idx) else {
+                        break;
+                    };
+                    debug!("Compiling Zener file: {}", zen_path.display());
+                    let result = picoplace_lang::run_with_dependencies(zen_path);
+                    results.lock().unwrap()[idx] = Some(result);
+                })
+                .expect("failed to spawn evaluation thread");
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every file is evaluated exactly once"))
+        .collect()
+}
+
+/// Print diagnostics and a summary line for a single evaluated .zen file.
+/// Returns whether the build failed.
+fn report_file(
+    args: &BuildArgs,
+    zen_path: &Path,
+    eval_result: &WithDiagnostics<Schematic>,
+    print_output: &[String],
+) -> bool {
+    let file_name = zen_path.file_name().unwrap().to_string_lossy();
+    let mut has_errors = false;
+
+    for line in print_output {
+        eprintln!("{line}");
+    }
+
+    // Check if we have diagnostics to print
+    if !eval_result.diagnostics.is_empty() {
+        // Print diagnostics
+        for diag in eval_result.diagnostics.iter() {
+            picoplace_lang::render_diagnostic(diag);
+            eprintln!();
+
+            if matches!(diag.severity, EvalSeverity::Error) {
+                has_errors = true;
+            }
+        }
+
+        if has_errors {
+            println!(
+                "{} {}: Build failed",
+                picoplace_ui::icons::error(),
+                file_name.with_style(Style::Red).bold()
+            );
+        }
+    } else if let Some(schematic) = &eval_result.output {
+        // If netlist flag is set, print JSON to stdout
+        if args.netlist {
+            match schematic.to_json() {
+                Ok(json) => println!("{json}"),
+                Err(e) => {
+                    eprintln!("Error serializing netlist to JSON: {e}");
+                    has_errors = true;
+                }
+            }
+        } else {
+            // Print success with component count
+            let component_count = schematic
+                .instances
+                .values()
+                .filter(|i| i.kind == picoplace_netlist::InstanceKind::Component)
+                .count();
+            eprintln!(
+                "{} {} ({} components)",
+                picoplace_ui::icons::success(),
+                file_name.with_style(Style::Green).bold(),
+                component_count
+            );
+        }
+    } else {
+        eprintln!(
+            "{} {}: No output generated",
+            picoplace_ui::icons::error(),
+            file_name.with_style(Style::Red).bold()
+        );
+        has_errors = true;
+    }
+
+    has_errors
+}
+
+/// Rebuild `zen_paths` every time one of their sources changes on disk.
+///
+/// The parent directories of all dependencies are watched rather than the
+/// files themselves so that editors which save by replacing the file are
+/// still noticed. The dependency set is refreshed after each build so newly
+/// added `load()`s are picked up. Only the files that depend on something
+/// that changed are re-evaluated; the others keep their previous results,
+/// which are reprinted. Runs until interrupted with Ctrl-C.
+fn watch(args: &BuildArgs, zen_paths: &[PathBuf]) -> Result<()> {
+    let (tx, rx) = mpsc::channel::<Vec<PathBuf>>();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        if let Ok(event) = event {
+            if !matches!(event.kind, EventKind::Access(_)) {
+                let _ = tx.send(event.paths);
+            }
+        }
+    })?;
+
+    let mut watched_dirs: HashSet<PathBuf> = HashSet::new();
+
+    picoplace_ui::clear_screen();
+    let mut results = evaluate_with_spinner(zen_paths);
+
+    loop {
+        report_all(args, zen_paths, &results);
+
+        // Each file depends on itself and everything it loaded
+        let deps_by_file: Vec<HashSet<PathBuf>> = zen_paths
+            .iter()
+            .zip(&results)
+            .map(|(zen_path, (_, deps, _))| {
+                deps.iter().cloned().chain([zen_path.clone()]).collect()
+            })
+            .collect();
+        let dependencies: HashSet<PathBuf> = deps_by_file.iter().flatten().cloned().collect();
+
+        // Sync the watcher with the directories of the latest dependency set.
+        let dirs: HashSet<PathBuf> = dependencies
+            .iter()
+            .filter_map(|path| path.parent().map(Path::to_path_buf))
+            .collect();
+        for dir in watched_dirs.difference(&dirs) {
+            let _ = watcher.unwatch(dir);
+        }
+        for dir in dirs.difference(&watched_dirs) {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                debug!("Failed to watch {}: {e}", dir.display());
+            }
+        }
+        watched_dirs = dirs;
+
+        let message = format!(
+            "Watching {} files for changes (Ctrl-C to exit)",
+            dependencies.len()
+        );
+        eprintln!("\n{}", message.dimmed());
+
+        // Block until one of our dependencies changes, then gather the burst
+        // of events an editor typically emits for a single save.
+        let mut changed: HashSet<PathBuf> = HashSet::new();
+        let mut record = |paths: Vec<PathBuf>| {
+            changed.extend(paths.into_iter().filter(|path| dependencies.contains(path)));
+        };
+        loop {
+            let Ok(paths) = rx.recv() else {
+                return Ok(());
+            };
+            record(paths);
+            if !changed.is_empty() {
+                break;
+            }
+        }
+        while let Ok(paths) = rx.recv_timeout(WATCH_DEBOUNCE) {
+            record(paths);
+        }
+
+        let stale: Vec<usize> = (0..zen_paths.len())
+            .filter(|&idx| !deps_by_file[idx].is_disjoint(&changed))
+            .collect();
+        let stale_paths: Vec<PathBuf> = stale.iter().map(|&idx| zen_paths[idx].clone()).collect();
+
+        picoplace_ui::clear_screen();
+        for (idx, result) in stale.into_iter().zip(evaluate_with_spinner(&stale_paths)) {
+            results[idx] = result;
+        }
+    }
+}
+
 /// Collect .zen files from the provided paths
 pub fn collect_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
     let mut unique: HashSet<PathBuf> = HashSet::new();
@@ -1,6 +1,11 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Args;
+use picoplace_kicad_exporter::{utils, LayoutPaths};
 use picoplace_lang::load::{cache_dir, find_workspace_root};
+use picoplace_ui::prelude::*;
+use std::path::Path;
+
+use crate::build::collect_files;
 
 #[derive(Args, Debug)]
 #[command(about = "Clean generated files")]
@@ -13,6 +18,19 @@ pub struct CleanArgs {
         help = "Avoid removing the remote cache (downloaded packages & GitHub repos)"
     )]
     pub keep_cache: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "all",
+        help = "Never remove layout .kicad_pcb files"
+    )]
+    pub keep_pcb: bool,
+
+    #[arg(
+        long,
+        help = "Also remove .kicad_pcb files that were edited since they were generated"
+    )]
+    pub all: bool,
 }
 
 pub fn execute(args: CleanArgs) -> Result<()> {
@@ -20,6 +38,16 @@ pub fn execute(args: CleanArgs) -> Result<()> {
     let current_dir = std::env::current_dir()?;
     let project_root = find_workspace_root(&current_dir).unwrap_or(current_dir);
 
+    // Evaluating a design can fetch dependencies into the directories removed
+    // below, so find every layout before removing anything
+    let designs: Vec<_> = collect_files(&[])?
+        .into_iter()
+        .map(|zen_path| {
+            let layout = find_layout(&zen_path);
+            (zen_path, layout)
+        })
+        .collect();
+
     // Define the temp directories to clean
     let temp_dirs = vec![project_root.join(".pcb")];
 
@@ -31,6 +59,11 @@ pub fn execute(args: CleanArgs) -> Result<()> {
         }
     }
 
+    // Remove files generated for the designs in the current directory
+    for (zen_path, layout) in &designs {
+        clean_design(&args, zen_path, layout.as_ref())?;
+    }
+
     // Remove remote cache directory
     if !args.keep_cache {
         if let Ok(cache_dir) = cache_dir() {
@@ -44,3 +77,88 @@ pub fn execute(args: CleanArgs) -> Result<()> {
     println!("Clean complete");
     Ok(())
 }
+
+/// Layout files of the design at `zen_path`, or `None` if it has no layout.
+/// Designs that fail to evaluate are reported and treated as having none.
+fn find_layout(zen_path: &Path) -> Option<LayoutPaths> {
+    let eval_result = picoplace_lang::run(zen_path);
+    let Some(schematic) = eval_result.output.as_ref() else {
+        for diag in eval_result
+            .diagnostics
+            .iter()
+            .filter(|diag| diag.is_error())
+        {
+            picoplace_lang::render_diagnostic(diag);
+            eprintln!();
+        }
+        eprintln!(
+            "{}",
+            format!(
+                "Skipping layout files of {}: it failed to evaluate",
+                zen_path.display()
+            )
+            .warning()
+        );
+        return None;
+    };
+    let layout_dir = utils::resolve_layout_dir(schematic, zen_path)?;
+    Some(utils::get_layout_paths(&layout_dir))
+}
+
+/// Remove the images and layout artifacts generated for one design.
+///
+/// Netlists, schematics and logs are always regenerated, so they are always
+/// removed. The `.kicad_pcb` holds manual layout work and is only removed when
+/// it is byte-for-byte what was last generated, unless `--all` or `--keep-pcb`
+/// say otherwise.
+fn clean_design(args: &CleanArgs, zen_path: &Path, layout: Option<&LayoutPaths>) -> Result<()> {
+    for extension in ["svg", "png"] {
+        remove_file(&zen_path.with_extension(extension))?;
+    }
+
+    let Some(paths) = layout else {
+        return Ok(());
+    };
+
+    for path in [
+        &paths.netlist,
+        &paths.json_netlist,
+        &paths.schematic,
+        &paths.log,
+    ] {
+        remove_file(path)?;
+    }
+
+    if args.keep_pcb || !paths.pcb.exists() {
+        return Ok(());
+    }
+    if !args.all {
+        match utils::pcb_matches_recorded_hash(paths) {
+            Ok(true) => {}
+            Ok(false) => {
+                eprintln!(
+                    "Keeping {}: it was edited since it was generated (use --all to remove it)",
+                    paths.pcb.display()
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Keeping {}: {e:#}", paths.pcb.display());
+                return Ok(());
+            }
+        }
+    }
+    remove_file(&paths.pcb)?;
+    remove_file(&paths.snapshot)?;
+    remove_file(&paths.pcb_hash)
+}
+
+/// Remove `path` if it exists, reporting what was removed.
+fn remove_file(path: &Path) -> Result<()> {
+    if path.exists() {
+        println!("Removing {}", path.display());
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use inquire::Select;
-use picoplace_kicad_exporter::{process_layout, LayoutError};
+use picoplace_kicad_exporter::{process_layout, utils, LayoutError};
+use picoplace_netlist::kicad_schematic::{to_kicad_schematic, write_schematic_file};
+use picoplace_netlist::Schematic;
 use picoplace_ui::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::build::collect_files;
 
@@ -20,8 +22,9 @@ pub struct ExportArgs {
     )]
     pub select: bool,
     
-    /// The output format. Currently only 'kicad' is supported.
-    #[arg(long, short = 't', default_value = "kicad")]
+    /// The output format: 'kicad' for a PCB layout or 'kicad-sch' for a
+    /// schematic
+    #[arg(long, short = 't', visible_alias = "format", default_value = "kicad")]
     pub to: String,
 
     /// One or more .zen files to process for layout generation.
@@ -31,8 +34,12 @@ pub struct ExportArgs {
 }
 
 pub fn execute(args: ExportArgs) -> Result<()> {
-    if args.to.to_lowercase() != "kicad" {
-        anyhow::bail!("Unsupported export format '{}'. Currently, only 'kicad' is supported.", args.to);
+    let format = args.to.to_lowercase();
+    if format != "kicad" && format != "kicad-sch" {
+        anyhow::bail!(
+            "Unsupported export format '{}'. Supported formats are 'kicad' and 'kicad-sch'.",
+            args.to
+        );
     }
 
     // Collect .zen files to process
@@ -92,6 +99,34 @@ pub fn execute(args: ExportArgs) -> Result<()> {
             spinner.set_message(format!("{file_name}: Exporting to KiCad"));
         }
 
+        if let (Some(schematic), "kicad-sch") = (&eval_result.output, format.as_str()) {
+            spinner.finish();
+            match export_schematic(schematic, &zen_path) {
+                Ok(output_path) => {
+                    let relative_path = zen_path
+                        .parent()
+                        .and_then(|parent| output_path.strip_prefix(parent).ok())
+                        .unwrap_or(&output_path);
+                    println!(
+                        "{} {} ({})",
+                        picoplace_ui::icons::success(),
+                        file_name.with_style(Style::Green).bold(),
+                        relative_path.display()
+                    );
+                }
+                Err(e) => {
+                    println!(
+                        "{} {}: Export failed",
+                        picoplace_ui::icons::error(),
+                        file_name.with_style(Style::Red).bold()
+                    );
+                    eprintln!("  Error: {e:#}");
+                    has_errors = true;
+                }
+            }
+            continue;
+        }
+
         // Check if the schematic has a layout
         if let Some(schematic) = &eval_result.output {
             match process_layout(schematic, &zen_path) {
@@ -142,6 +177,11 @@ pub fn execute(args: ExportArgs) -> Result<()> {
         anyhow::bail!("Export failed with errors");
     }
 
+    // Schematics aren't opened afterwards
+    if format == "kicad-sch" {
+        return Ok(());
+    }
+
     if generated_layouts.is_empty() {
         println!("\nNo layouts found to export.");
         return Ok(());
@@ -164,6 +204,28 @@ pub fn execute(args: ExportArgs) -> Result<()> {
     Ok(())
 }
 
+/// Write a `.kicad_sch` for `schematic` into its layout directory, or next to
+/// the .zen file when the design has no layout path. Returns the written path.
+fn export_schematic(schematic: &Schematic, zen_path: &Path) -> Result<PathBuf> {
+    let output_path = match utils::resolve_layout_dir(schematic, zen_path) {
+        Some(layout_dir) => {
+            std::fs::create_dir_all(&layout_dir).with_context(|| {
+                format!(
+                    "Failed to create layout directory: {}",
+                    layout_dir.display()
+                )
+            })?;
+            utils::get_layout_paths(&layout_dir).schematic
+        }
+        None => zen_path.with_extension("kicad_sch"),
+    };
+
+    let content = to_kicad_schematic(schematic, &output_path, &[])?;
+    write_schematic_file(&content, &output_path)
+        .with_context(|| format!("Failed to write schematic: {}", output_path.display()))?;
+    Ok(output_path)
+}
+
 /// Let the user choose which layout to open
 fn choose_layout(layouts: &[(PathBuf, PathBuf)]) -> Result<usize> {
     // Get current directory for making relative paths
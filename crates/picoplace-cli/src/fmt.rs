@@ -1,12 +1,14 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use log::debug;
-use picoplace_buildifier::Buildifier;
+use picoplace_buildifier::{Buildifier, LintFinding};
 use picoplace_ui::prelude::*;
 use picoplace_lang::file_extensions;
 use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 #[derive(Args, Debug, Default, Clone)]
 #[command(about = "Format .zen and .star files using buildifier")]
@@ -18,32 +20,92 @@ pub struct FmtArgs {
 
     /// Check if files are formatted correctly without modifying them.
     /// Exit with non-zero code if any file needs formatting.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "diff")]
     pub check: bool,
 
-    /// Show diffs instead of writing files
+    /// Print a unified diff for each file that would change, without writing files
     #[arg(long)]
     pub diff: bool,
+
+    /// Report buildifier lint warnings instead of formatting.
+    /// Exit with non-zero code if any actionable warning is found.
+    #[arg(long, conflicts_with_all = ["check", "diff"])]
+    pub lint: bool,
+
+    /// Number of files to process in parallel (defaults to the available parallelism)
+    #[arg(short, long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
+}
+
+/// Result of processing a single file
+enum Outcome {
+    /// The file is (now) formatted
+    Formatted,
+    /// The file needs formatting; carries the diff in `--diff` mode
+    NeedsFormatting(String),
+    /// Lint warnings found in `--lint` mode
+    Lint(Vec<LintFinding>),
 }
 
 /// Format a single file using buildifier
-fn format_file(buildifier: &Buildifier, file_path: &Path, args: &FmtArgs) -> Result<bool> {
+fn format_file(buildifier: &Buildifier, file_path: &Path, args: &FmtArgs) -> Result<Outcome> {
     debug!("Formatting file: {}", file_path.display());
 
-    if args.check {
-        buildifier.check_file(file_path)
+    if args.lint {
+        Ok(Outcome::Lint(buildifier.lint_file(file_path)?))
+    } else if args.check {
+        Ok(if buildifier.check_file(file_path)? {
+            Outcome::Formatted
+        } else {
+            Outcome::NeedsFormatting(String::new())
+        })
     } else if args.diff {
         let diff = buildifier.diff_file(file_path)?;
-        if !diff.is_empty() {
-            print!("{diff}");
-        }
-        Ok(true)
+        Ok(if diff.is_empty() {
+            Outcome::Formatted
+        } else {
+            Outcome::NeedsFormatting(diff)
+        })
     } else {
         buildifier.format_file(file_path)?;
-        Ok(true)
+        Ok(Outcome::Formatted)
     }
 }
 
+/// Process `paths` on a bounded pool of worker threads.
+/// Results are returned in the same order as the input paths.
+fn format_all(buildifier: &Buildifier, paths: &[PathBuf], args: &FmtArgs) -> Vec<Result<Outcome>> {
+    let workers = args
+        .jobs
+        .map(usize::from)
+        .or_else(|| std::thread::available_parallelism().map(|n| n.get()).ok())
+        .unwrap_or(1)
+        .min(paths.len());
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<Outcome>>>> =
+        Mutex::new((0..paths.len()).map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some(file_path) = paths.get(idx) else {
+                    break;
+                };
+                let result = format_file(buildifier, file_path, args);
+                results.lock().unwrap()[idx] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every file is processed exactly once"))
+        .collect()
+}
+
 /// Recursively collect .zen and .star files from a directory
 fn collect_files_recursive(dir: &Path, files: &mut HashSet<PathBuf>) -> Result<()> {
     for entry in fs::read_dir(dir)?.flatten() {
@@ -116,67 +178,91 @@ pub fn execute(args: FmtArgs) -> Result<()> {
         );
     }
 
-    let mut all_formatted = true;
     let mut files_needing_format = Vec::new();
+    let mut failed_files = 0;
+    let mut actionable_findings = 0;
 
-    // Process each file
-    for file_path in starlark_paths {
+    // Show spinner while processing
+    let count = starlark_paths.len();
+    let spinner = if args.lint {
+        Spinner::builder(format!("Linting {count} files")).start()
+    } else if args.check {
+        Spinner::builder(format!("Checking format of {count} files")).start()
+    } else if args.diff {
+        Spinner::builder(format!("Checking diff of {count} files")).start()
+    } else {
+        Spinner::builder(format!("Formatting {count} files")).start()
+    };
+    let outcomes = format_all(&buildifier, &starlark_paths, &args);
+    spinner.finish();
+
+    // Report in path order, independent of which worker finished first
+    for (file_path, outcome) in starlark_paths.iter().zip(outcomes) {
         let file_name = file_path.file_name().unwrap().to_string_lossy();
 
-        // Show spinner while processing
-        let spinner = if args.check {
-            Spinner::builder(format!("{file_name}: Checking format")).start()
-        } else if args.diff {
-            Spinner::builder(format!("{file_name}: Checking diff")).start()
-        } else {
-            Spinner::builder(format!("{file_name}: Formatting")).start()
-        };
-
-        match format_file(&buildifier, &file_path, &args) {
-            Ok(is_formatted) => {
-                spinner.finish();
-
-                if args.check {
-                    if is_formatted {
-                        println!(
-                            "{} {}",
-                            picoplace_ui::icons::success(),
-                            file_name.with_style(Style::Green).bold()
-                        );
-                    } else {
-                        println!(
-                            "{} {} (needs formatting)",
-                            picoplace_ui::icons::warning(),
-                            file_name.with_style(Style::Yellow).bold()
-                        );
-                        all_formatted = false;
-                        files_needing_format.push(file_path.clone());
-                    }
-                } else {
-                    // For both diff mode and regular format mode, show success
+        match outcome {
+            Ok(Outcome::Lint(findings)) if !findings.is_empty() => {
+                println!(
+                    "{} {} ({} warnings)",
+                    picoplace_ui::icons::warning(),
+                    file_name.with_style(Style::Yellow).bold(),
+                    findings.len()
+                );
+                for finding in &findings {
                     println!(
-                        "{} {}",
-                        picoplace_ui::icons::success(),
-                        file_name.with_style(Style::Green).bold()
+                        "  {}:{}: {} [{}]",
+                        file_path.display(),
+                        finding.line,
+                        finding.message,
+                        finding.category
                     );
                 }
+                actionable_findings += findings.iter().filter(|f| f.actionable).count();
+            }
+            Ok(Outcome::Formatted | Outcome::Lint(_)) => {
+                println!(
+                    "{} {}",
+                    picoplace_ui::icons::success(),
+                    file_name.with_style(Style::Green).bold()
+                );
+            }
+            Ok(Outcome::NeedsFormatting(diff)) => {
+                println!(
+                    "{} {} (needs formatting)",
+                    picoplace_ui::icons::warning(),
+                    file_name.with_style(Style::Yellow).bold()
+                );
+                print!("{diff}");
+                files_needing_format.push(file_path.clone());
             }
             Err(e) => {
-                spinner.error(format!("{file_name}: Format failed"));
+                eprintln!(
+                    "{} {}: Format failed",
+                    picoplace_ui::icons::error(),
+                    file_name.with_style(Style::Red).bold()
+                );
                 eprintln!("Error: {e}");
-                all_formatted = false;
+                failed_files += 1;
             }
         }
     }
 
+    // Diffs are a preview only, so they don't fail the command
+    if args.diff && !files_needing_format.is_empty() {
+        eprintln!(
+            "\n{} files would be reformatted",
+            files_needing_format.len()
+        );
+    }
+
     // Handle check mode results
-    if args.check && !all_formatted {
+    if args.check && !files_needing_format.is_empty() {
         eprintln!("\n{} files need formatting:", files_needing_format.len());
         for file in &files_needing_format {
             eprintln!("  {}", file.display());
         }
         eprintln!(
-            "\nRun 'pcb fmt {}' to format these files.",
+            "\nRun 'picoplace fmt {}' to format these files.",
             files_needing_format
                 .iter()
                 .map(|p| p.to_string_lossy())
@@ -187,5 +273,13 @@ pub fn execute(args: FmtArgs) -> Result<()> {
         anyhow::bail!("Some files are not formatted correctly");
     }
 
+    if actionable_findings > 0 {
+        anyhow::bail!("Found {actionable_findings} actionable lint warning(s)");
+    }
+
+    if failed_files > 0 {
+        anyhow::bail!("Failed to process {failed_files} file(s)");
+    }
+
     Ok(())
 }
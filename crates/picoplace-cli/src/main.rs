@@ -8,6 +8,7 @@ mod export; // Renamed from layout
 mod fmt;
 mod lsp;
 mod open;
+mod params;
 mod visualize; // New command
 
 #[derive(Parser)]
@@ -32,6 +33,9 @@ enum Commands {
     #[command(alias = "v")]
     Visualize(visualize::VisualizeArgs),
 
+    /// List the parameters a module accepts
+    Params(params::ParamsArgs),
+
     /// Clean build artifacts
     Clean(clean::CleanArgs),
 
@@ -60,6 +64,7 @@ fn main() -> anyhow::Result<()> {
         Commands::Build(args) => build::execute(args),
         Commands::Export(args) => export::execute(args),
         Commands::Visualize(args) => visualize::execute(args),
+        Commands::Params(args) => params::execute(args),
         Commands::Clean(args) => clean::execute(args),
         Commands::Fmt(args) => fmt::execute(args),
         Commands::Lsp(args) => lsp::execute(args),
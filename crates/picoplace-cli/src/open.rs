@@ -2,13 +2,17 @@ use anyhow::{Context, Result};
 use clap::Args;
 use inquire::Select;
 use picoplace_kicad_exporter::utils;
-use std::path::{Path, PathBuf};
+use picoplace_lang::file_extensions;
+use picoplace_netlist::ATTR_LAYOUT_PATH;
+use std::path::PathBuf;
 
 use crate::build::{collect_files, evaluate_zen_file};
 
 #[derive(Args, Debug)]
 pub struct OpenArgs {
-    /// One or more .zen files to build/open. When omitted, behaves like before.
+    /// One or more .zen designs whose layouts to open, or layout files to open
+    /// directly. When omitted, designs and layouts in the current directory
+    /// are considered.
     #[arg(value_name = "PATHS", value_hint = clap::ValueHint::AnyPath)]
     paths: Vec<PathBuf>,
 }
@@ -17,9 +21,25 @@ pub fn execute(args: OpenArgs) -> Result<()> {
     open_layout(args.paths)
 }
 
-fn open_layout(zen_paths: Vec<PathBuf>) -> Result<()> {
+fn open_layout(paths: Vec<PathBuf>) -> Result<()> {
+    // Layout files given directly are opened as they are
+    let (layout_files, design_paths): (Vec<PathBuf>, Vec<PathBuf>) = paths
+        .into_iter()
+        .partition(|path| path.is_file() && !file_extensions::is_starlark_file(path.extension()));
+    for layout_file in &layout_files {
+        open::that(layout_file)
+            .with_context(|| format!("Failed to open file: {}", layout_file.display()))?;
+    }
+    if !layout_files.is_empty() && design_paths.is_empty() {
+        return Ok(());
+    }
+
+    // Designs named on the command line must have a layout; ones found in
+    // the current directory are skipped when they don't.
+    let explicit = !design_paths.is_empty();
+
     // Collect .zen files to process
-    let zen_paths = collect_files(&zen_paths)?;
+    let zen_paths = collect_files(&design_paths)?;
 
     if zen_paths.is_empty() {
         // Try to find a layout file in the current directory
@@ -66,23 +86,26 @@ fn open_layout(zen_paths: Vec<PathBuf>) -> Result<()> {
         }
 
         // Check if the schematic has a layout
-        if let Some(schematic) = &eval_result.output {
-            if let Some(layout_path_attr) = utils::extract_layout_path(schematic) {
-                // Convert relative path to absolute based on star file location
-                let layout_dir = if layout_path_attr.is_relative() {
-                    zen_path
-                        .parent()
-                        .unwrap_or(Path::new("."))
-                        .join(&layout_path_attr)
-                } else {
-                    layout_path_attr
-                };
-
-                let layout_path = utils::get_layout_paths(&layout_dir).pcb;
-                if layout_path.exists() {
-                    available_layouts.push((zen_path.clone(), layout_path));
-                }
+        let Some(schematic) = &eval_result.output else {
+            continue;
+        };
+        let Some(layout_dir) = utils::resolve_layout_dir(schematic, &zen_path) else {
+            if explicit {
+                anyhow::bail!(
+                    "{file_name} has no layout: its root module does not set `{ATTR_LAYOUT_PATH}`"
+                );
             }
+            continue;
+        };
+
+        let layout_path = utils::get_layout_paths(&layout_dir).pcb;
+        if layout_path.exists() {
+            available_layouts.push((zen_path.clone(), layout_path));
+        } else if explicit {
+            anyhow::bail!(
+                "{file_name} has no layout file at {}. Run 'picoplace export' to generate it first.",
+                layout_path.display()
+            );
         }
     }
 
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use clap::Args;
+use picoplace_lang::{ParameterInfo, TypeInfo, WithDiagnostics};
+use picoplace_ui::prelude::*;
+use std::path::PathBuf;
+
+#[derive(Args, Debug, Clone)]
+#[command(about = "List the parameters accepted by a Zener module")]
+pub struct ParamsArgs {
+    /// The .zen file whose parameters should be listed
+    #[arg(value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    pub file: PathBuf,
+}
+
+pub fn execute(args: ParamsArgs) -> Result<()> {
+    let WithDiagnostics {
+        output,
+        diagnostics,
+    } = picoplace_lang::run_full(&args.file);
+
+    let mut has_errors = false;
+    for diag in diagnostics.iter() {
+        picoplace_lang::render_diagnostic(diag);
+        eprintln!();
+
+        if diag.is_error() {
+            has_errors = true;
+        }
+    }
+
+    let output = match output {
+        Some(output) if !has_errors => output,
+        _ => anyhow::bail!("Failed to evaluate {}", args.file.display()),
+    };
+
+    if output.signature.is_empty() {
+        println!("{} declares no parameters", args.file.display());
+        return Ok(());
+    }
+
+    for param in &output.signature {
+        print_parameter(param);
+    }
+
+    Ok(())
+}
+
+fn print_parameter(param: &ParameterInfo) {
+    let mut line = format!(
+        "{}: {}",
+        param.name.as_str().with_style(Style::Cyan).bold(),
+        type_label(&param.type_info)
+    );
+
+    if let Some(default) = &param.default_value {
+        line.push_str(&format!(" = {default}"));
+    } else if param.required {
+        line.push_str(&format!(" {}", "(required)".with_style(Style::Yellow)));
+    }
+
+    println!("{line}");
+
+    if let Some(help) = &param.help {
+        println!("    {}", help.as_str().dimmed());
+    }
+}
+
+/// Render a [`TypeInfo`] the way it would be spelled in a .zen file.
+fn type_label(type_info: &TypeInfo) -> String {
+    match type_info {
+        TypeInfo::String => "str".to_string(),
+        TypeInfo::Int => "int".to_string(),
+        TypeInfo::Float => "float".to_string(),
+        TypeInfo::Bool => "bool".to_string(),
+        TypeInfo::List { element } => format!("list[{}]", type_label(element)),
+        TypeInfo::Dict { key, value } => {
+            format!("dict[{}, {}]", type_label(key), type_label(value))
+        }
+        TypeInfo::Net => "Net".to_string(),
+        TypeInfo::Enum { name, .. }
+        | TypeInfo::Record { name, .. }
+        | TypeInfo::Interface { name, .. } => name.clone(),
+        TypeInfo::Unknown { type_name } => type_name.clone(),
+    }
+}
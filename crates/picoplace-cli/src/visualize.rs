@@ -1,24 +1,91 @@
 use anyhow::{Context, Result};
-use clap::Args;
-use picoplace_engine::{placer, svg_generator};
+use clap::{Args, ValueEnum};
+use picoplace_ai_engine::AIEngine;
+use picoplace_command_runner::CommandRunner;
+use picoplace_engine::{placer, placer_sa, svg_generator, Layout, LayoutStats};
 use picoplace_lang::WithDiagnostics;
+use picoplace_netlist::Schematic;
 use picoplace_ui::prelude::*;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::build::collect_files;
 
 #[derive(Args, Debug, Default, Clone)]
-#[command(about = "Visualize a Zener design as an SVG layout")]
+#[command(about = "Visualize a Zener design as an SVG or PNG layout")]
 pub struct VisualizeArgs {
     /// One or more .zen files to visualize.
     /// When omitted, all .zen files in the current directory are processed.
     #[arg(value_name = "PATHS", value_hint = clap::ValueHint::AnyPath)]
     pub paths: Vec<PathBuf>,
 
-    #[arg(long, help = "Skip opening the SVG file after generation")]
+    #[arg(long, help = "Skip opening the image file after generation")]
     pub no_open: bool,
+
+    /// Image format to generate
+    #[arg(long, value_enum, default_value_t = OutputFormat::Svg)]
+    pub format: OutputFormat,
+
+    /// Write the image to this path instead of next to the .zen file.
+    /// Only valid with a single input; the extension must match `--format`.
+    #[arg(short, long, value_name = "PATH", value_hint = clap::ValueHint::FilePath)]
+    pub output: Option<PathBuf>,
+
+    /// Placement algorithm used to lay out components.
+    /// `grid` is deterministic; `sa` is deterministic for a given `--seed`.
+    #[arg(long, value_enum, default_value_t = PlacerKind::Grid)]
+    pub placer: PlacerKind,
+
+    /// Random seed for the annealing placer (random when omitted)
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<u64>,
+
+    /// Moves attempted at each temperature step of the annealing placer
+    #[arg(long, value_name = "N")]
+    pub iterations: Option<usize>,
+
+    /// Snap annealed component positions to a grid with this pitch in mm,
+    /// e.g. `--grid 1.27` for a 50 mil grid
+    #[arg(long, value_name = "MM")]
+    pub grid: Option<f64>,
+
+    /// Ask an LLM for placement hints and feed them to the annealing placer.
+    /// Requires OPENAI_API_KEY; implies `--placer sa`.
+    #[arg(long)]
+    pub ai: bool,
+}
+
+/// Available placement algorithms
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PlacerKind {
+    /// Simple deterministic grid
+    #[default]
+    Grid,
+    /// Simulated annealing (deterministic given `--seed`)
+    Sa,
+}
+
+/// Image formats the visualization can be written as
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Scalable vector graphics
+    #[default]
+    Svg,
+    /// Raster image, rendered from the SVG with `rsvg-convert`
+    Png,
+}
+
+impl OutputFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Svg => "svg",
+            OutputFormat::Png => "png",
+        }
+    }
 }
 
+/// External rasterizer used for PNG output (part of librsvg)
+const RSVG_CONVERT: &str = "rsvg-convert";
+
 pub fn execute(args: VisualizeArgs) -> Result<()> {
     let zen_paths = collect_files(&args.paths)?;
 
@@ -30,6 +97,38 @@ pub fn execute(args: VisualizeArgs) -> Result<()> {
         );
     }
 
+    if let Some(output) = &args.output {
+        if zen_paths.len() > 1 {
+            anyhow::bail!("--output can only be used when visualizing a single file");
+        }
+        let extension = output.extension().and_then(|ext| ext.to_str());
+        if !extension.is_some_and(|ext| ext.eq_ignore_ascii_case(args.format.extension())) {
+            anyhow::bail!(
+                "Output path {} does not match --format {} (expected a .{} file)",
+                output.display(),
+                args.format.extension(),
+                args.format.extension()
+            );
+        }
+    }
+
+    if args.placer == PlacerKind::Grid && !args.ai {
+        let sa_flags: Vec<&str> = [
+            ("--seed", args.seed.is_some()),
+            ("--iterations", args.iterations.is_some()),
+            ("--grid", args.grid.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(flag, set)| set.then_some(flag))
+        .collect();
+        if !sa_flags.is_empty() {
+            anyhow::bail!(
+                "{} can only be used with the annealing placer (--placer sa)",
+                sa_flags.join(", ")
+            );
+        }
+    }
+
     for zen_path in zen_paths {
         let spinner = Spinner::builder(format!("Visualizing {}", zen_path.display())).start();
 
@@ -61,27 +160,130 @@ pub fn execute(args: VisualizeArgs) -> Result<()> {
         let schematic = schematic.unwrap();
 
         // 2. Pass the Schematic to the placer
-        spinner.set_message("Placing components...");
-        let layout = placer::run(&schematic);
+        let (layout, stats) = place(&args, &schematic, &spinner)?;
 
-        // 3. Generate the SVG
-        spinner.set_message("Generating SVG...");
-        let output_path = zen_path.with_extension("svg");
-        svg_generator::run(&layout, &schematic, &output_path)
-            .context("Failed to generate SVG")?;
+        // 3. Generate the image
+        let output_path = args
+            .output
+            .clone()
+            .unwrap_or_else(|| zen_path.with_extension(args.format.extension()));
+        match args.format {
+            OutputFormat::Svg => {
+                spinner.set_message("Generating SVG...");
+                svg_generator::run(&layout, &schematic, &output_path)
+                    .context("Failed to generate SVG")?;
+            }
+            OutputFormat::Png => {
+                spinner.set_message("Generating PNG...");
+                rasterize(&svg_generator::render(&layout, &schematic), &output_path)?;
+            }
+        }
 
         spinner.success(format!(
             "Successfully generated visualization: {}",
             output_path.display()
         ));
+        eprintln!("  {}", stats.to_string().dimmed());
 
-        // 4. Open the SVG
+        // 4. Open the image
         if !args.no_open {
-            open::that(&output_path).with_context(|| {
-                format!("Failed to open SVG file {}", output_path.display())
-            })?;
+            open::that(&output_path)
+                .with_context(|| format!("Failed to open image file {}", output_path.display()))?;
         }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Lay out `schematic` with the placer selected in `args` and summarize the
+/// result.
+fn place<'a>(
+    args: &VisualizeArgs,
+    schematic: &'a Schematic,
+    spinner: &Spinner,
+) -> Result<(Layout<'a>, LayoutStats)> {
+    let layout = run_placer(args, schematic, spinner)?;
+    let stats = layout.stats(schematic);
+    Ok((layout, stats))
+}
+
+/// Run the placer selected in `args`.
+///
+/// With `--ai`, hints are requested from the AI engine first; if that fails
+/// for any reason we warn and fall back to the grid placer so that a
+/// visualization is still produced.
+fn run_placer<'a>(
+    args: &VisualizeArgs,
+    schematic: &'a Schematic,
+    spinner: &Spinner,
+) -> Result<Layout<'a>> {
+    if args.ai {
+        spinner.set_message("Requesting AI placement hints...");
+        match generate_ai_hints(schematic) {
+            Ok(hints) => {
+                spinner.set_message("Placing components with AI hints...");
+                return placer_sa::run_with_config(schematic, sa_config(args), Some(hints));
+            }
+            Err(e) => {
+                spinner.suspend(|| {
+                    eprintln!(
+                        "{}",
+                        format!("AI placement failed, falling back to grid placer: {e:#}")
+                            .warning()
+                    );
+                });
+                spinner.set_message("Placing components...");
+                return Ok(placer::run(schematic));
+            }
+        }
+    }
+
+    spinner.set_message("Placing components...");
+    match args.placer {
+        PlacerKind::Grid => Ok(placer::run(schematic)),
+        PlacerKind::Sa => placer_sa::run_with_config(schematic, sa_config(args), None),
+    }
+}
+
+/// Annealing configuration with the `--seed`, `--iterations` and `--grid` overrides applied
+fn sa_config(args: &VisualizeArgs) -> placer_sa::PlacerConfig {
+    let mut config = placer_sa::PlacerConfig {
+        seed: args.seed,
+        ..Default::default()
+    };
+    if let Some(iterations) = args.iterations {
+        config.iterations_per_temp = iterations;
+    }
+    if let Some(grid) = args.grid {
+        config.grid_pitch = grid;
+    }
+    config
+}
+
+/// Render `svg` to a PNG at `output_path` by piping it through `rsvg-convert`
+fn rasterize(svg: &str, output_path: &Path) -> Result<()> {
+    let output = CommandRunner::new(RSVG_CONVERT)
+        .args(["--format", "png", "--output"])
+        .arg(output_path.to_string_lossy())
+        .stdin_input(svg)
+        .run()
+        .with_context(|| {
+            format!("Failed to run {RSVG_CONVERT}; install librsvg to generate PNG files")
+        })?;
+
+    if !output.success {
+        anyhow::bail!("{RSVG_CONVERT} failed: {}", output.plain_as_string().trim());
+    }
+
+    Ok(())
+}
+
+fn generate_ai_hints(schematic: &Schematic) -> Result<placer_sa::PlacementHints> {
+    if std::env::var("OPENAI_API_KEY").unwrap_or_default().is_empty() {
+        anyhow::bail!("OPENAI_API_KEY is not set");
+    }
+
+    let engine = AIEngine::with_defaults()?;
+    let hints = engine.generate_hints(schematic)?;
+    Ok(hints.placement_suggestions.into())
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+#[test]
+fn test_build_json_reports_failure() -> Result<()> {
+    let temp = TempDir::new()?;
+    let zen_file = temp.child("broken.zen");
+    zen_file.write_str("undefined_function()\n")?;
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["build", "--json"])
+        .arg(zen_file.path())
+        .output()?;
+
+    assert!(!output.status.success());
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["success"], false);
+    let diagnostics = report["diagnostics"].as_array().unwrap();
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics[0]["message"].is_string());
+    assert_eq!(diagnostics[0]["severity"], "error");
+    assert!(report["artifacts"].as_array().unwrap().is_empty());
+
+    // Diagnostics are only reported in the JSON
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(!stderr.contains("undefined_function"), "{stderr}");
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+#[test]
+fn test_build_prints_to_stderr() -> Result<()> {
+    let temp = TempDir::new()?;
+    let zen_file = temp.child("board.zen");
+    zen_file.write_str("print(\"rail voltage:\", 3.3)\n")?;
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["build", "--json"])
+        .arg(zen_file.path())
+        .output()?;
+
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("rail voltage: 3.3"), "{stderr}");
+
+    // stdout stays parseable as the JSON report
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["success"], true);
+
+    Ok(())
+}
+
+#[test]
+fn test_build_prints_from_submodule_of_failed_eval() -> Result<()> {
+    let temp = TempDir::new()?;
+    temp.child("sub.zen")
+        .write_str("print(\"hello from sub\")\n")?;
+    let zen_file = temp.child("board.zen");
+    zen_file
+        .write_str("Sub = Module(\"sub.zen\")\nSub(name = \"S1\")\nfail(\"broken board\")\n")?;
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .arg("build")
+        .arg(zen_file.path())
+        .output()?;
+
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("hello from sub"), "{stderr}");
+    assert!(stderr.contains("broken board"), "{stderr}");
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use anyhow::{bail, Result};
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Output of a running `picoplace build --watch`, killed when dropped
+struct Watch {
+    child: Child,
+    stdout: Receiver<String>,
+    stderr: Receiver<String>,
+}
+
+impl Watch {
+    fn spawn(temp: &TempDir) -> Result<Self> {
+        let mut child = Command::new(assert_cmd::cargo::cargo_bin("picoplace"))
+            .current_dir(temp.path())
+            .args(["build", "--watch", "board.zen"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // stdout is read in raw chunks since clearing the screen writes no newline
+        let (stdout_tx, stdout) = mpsc::channel();
+        let mut child_stdout = child.stdout.take().unwrap();
+        thread::spawn(move || {
+            let mut buf = [0; 1024];
+            while let Ok(n @ 1..) = child_stdout.read(&mut buf) {
+                let _ = stdout_tx.send(String::from_utf8_lossy(&buf[..n]).into_owned());
+            }
+        });
+
+        let (stderr_tx, stderr) = mpsc::channel();
+        let child_stderr = child.stderr.take().unwrap();
+        thread::spawn(move || {
+            for line in BufReader::new(child_stderr).lines().map_while(Result::ok) {
+                let _ = stderr_tx.send(line);
+            }
+        });
+
+        Ok(Self {
+            child,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// Wait until a line containing `needle` is written to stderr
+    fn wait_for_stderr(&self, needle: &str) -> Result<()> {
+        let deadline = Instant::now() + TIMEOUT;
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            if self.stderr.recv_timeout(timeout)?.contains(needle) {
+                return Ok(());
+            }
+        }
+        bail!("timed out waiting for {needle:?} on stderr")
+    }
+
+    /// The next chunk written to stdout
+    fn next_stdout(&self) -> Result<String> {
+        Ok(self.stdout.recv_timeout(TIMEOUT)?)
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_watch_rebuilds_on_change() -> Result<()> {
+    let temp = TempDir::new()?;
+    temp.child("board.zen")
+        .write_str("print(\"first build\")\n")?;
+
+    let watch = Watch::spawn(&temp)?;
+    // The screen is cleared before each build even though nothing else is
+    // printed to stdout
+    assert_eq!(watch.next_stdout()?, CLEAR_SCREEN);
+    watch.wait_for_stderr("first build")?;
+    watch.wait_for_stderr("Watching 1 files")?;
+
+    temp.child("board.zen")
+        .write_str("print(\"second build\")\n")?;
+    assert_eq!(watch.next_stdout()?, CLEAR_SCREEN);
+    watch.wait_for_stderr("second build")?;
+
+    Ok(())
+}
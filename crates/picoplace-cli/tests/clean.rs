@@ -0,0 +1,110 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+use picoplace_kicad_exporter::utils;
+
+/// A design whose generated layout lives in `layout/`, with one resistor on
+/// the board
+fn design_with_layout() -> Result<TempDir> {
+    let temp = TempDir::new()?;
+    temp.child("board.zen")
+        .write_str("add_property(\"layout_path\", \"layout\")\n")?;
+
+    let layout = temp.child("layout");
+    layout.child("default.net").write_str("")?;
+    layout.child("layout.kicad_sch").write_str("")?;
+    layout.child("layout.kicad_pcb").write_str(
+        r#"(kicad_pcb (version 20240108) (generator "pcbnew")
+  (footprint "Resistor_SMD:R_0603_1608Metric" (layer "F.Cu")
+    (at 10 20)
+    (property "Reference" "R1" (at 0 -1.43 0) (layer "F.SilkS"))
+  )
+)
+"#,
+    )?;
+    layout.child("snapshot.layout.json").write_str(
+        r#"{"footprints": [{"reference": "R1", "position": {"x": 10000000, "y": 20000000}, "orientation": 0}]}"#,
+    )?;
+    utils::record_pcb_hash(&utils::get_layout_paths(layout.path()))?;
+    Ok(temp)
+}
+
+fn clean(temp: &TempDir) -> Result<std::process::Output> {
+    Ok(Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["clean", "--keep-cache"])
+        .output()?)
+}
+
+#[test]
+fn test_clean_removes_unedited_pcb() -> Result<()> {
+    let temp = design_with_layout()?;
+
+    let output = clean(&temp)?;
+    assert!(output.status.success());
+
+    let layout = temp.child("layout");
+    assert!(!layout.child("default.net").path().exists());
+    assert!(!layout.child("layout.kicad_sch").path().exists());
+    assert!(!layout.child("layout.kicad_pcb").path().exists());
+    assert!(!layout.child("snapshot.layout.json").path().exists());
+    assert!(!layout.child("layout.kicad_pcb.hash").path().exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_clean_keeps_edited_pcb() -> Result<()> {
+    // A track was routed by hand since the layout was generated, without
+    // moving any footprint
+    let temp = design_with_layout()?;
+    let pcb = temp.child("layout/layout.kicad_pcb");
+    let contents = std::fs::read_to_string(pcb.path())?;
+    pcb.write_str(&contents.replace(
+        "\n)\n",
+        "\n  (segment (start 10 20) (end 15 20) (width 0.25) (layer \"F.Cu\") (net 1))\n)\n",
+    ))?;
+
+    let output = clean(&temp)?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("it was edited since it was generated"));
+
+    let layout = temp.child("layout");
+    assert!(!layout.child("default.net").path().exists());
+    assert!(!layout.child("layout.kicad_sch").path().exists());
+    assert!(layout.child("layout.kicad_pcb").path().exists());
+    assert!(layout.child("snapshot.layout.json").path().exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_clean_reports_eval_failures() -> Result<()> {
+    let temp = design_with_layout()?;
+    temp.child("board.zen")
+        .write_str("undefined_function()\n")?;
+
+    let output = clean(&temp)?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("failed to evaluate"));
+
+    // Without the design we can't tell where its layout is, so it is left alone
+    assert!(temp.child("layout/layout.kicad_pcb").path().exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_clean_keeps_pcb_without_recorded_hash() -> Result<()> {
+    // Without a hash there is no telling whether the layout was edited
+    let temp = design_with_layout()?;
+    std::fs::remove_file(temp.child("layout/layout.kicad_pcb.hash").path())?;
+
+    let output = clean(&temp)?;
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("No hash of the generated layout"));
+    assert!(temp.child("layout/layout.kicad_pcb").path().exists());
+
+    Ok(())
+}
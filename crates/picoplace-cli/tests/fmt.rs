@@ -0,0 +1,97 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+const UNFORMATTED: &str = "x=[1,2,\n3]\n";
+
+#[test]
+fn test_fmt_check_reports_without_writing() -> Result<()> {
+    let temp = TempDir::new()?;
+    let zen_file = temp.child("board.zen");
+    zen_file.write_str(UNFORMATTED)?;
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["fmt", "--check"])
+        .output()?;
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("board.zen"));
+    zen_file.assert(UNFORMATTED);
+
+    Ok(())
+}
+
+#[test]
+fn test_fmt_check_passes_after_format() -> Result<()> {
+    let temp = TempDir::new()?;
+    temp.child("board.zen").write_str(UNFORMATTED)?;
+
+    Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .arg("fmt")
+        .assert()
+        .success();
+
+    Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["fmt", "--check"])
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_fmt_diff_previews_without_writing() -> Result<()> {
+    let temp = TempDir::new()?;
+    let zen_file = temp.child("board.zen");
+    zen_file.write_str(UNFORMATTED)?;
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["fmt", "--diff"])
+        .output()?;
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("needs formatting"));
+    zen_file.assert(UNFORMATTED);
+
+    Ok(())
+}
+
+#[test]
+fn test_fmt_parallel_formats_every_file() -> Result<()> {
+    let temp = TempDir::new()?;
+    for i in 0..32 {
+        let group = i % 4;
+        temp.child(format!("nested/{group}/board_{group}_{i:02}.zen"))
+            .write_str(UNFORMATTED)?;
+    }
+
+    Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["fmt", "--jobs", "4"])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["fmt", "--check", "--jobs", "4"])
+        .output()?;
+    assert!(output.status.success());
+
+    // Results are printed in path order regardless of completion order
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let printed: Vec<_> = stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().last())
+        .collect();
+    let mut sorted = printed.clone();
+    sorted.sort();
+    assert_eq!(printed.len(), 32);
+    assert_eq!(printed, sorted);
+
+    Ok(())
+}
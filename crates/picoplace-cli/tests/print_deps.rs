@@ -0,0 +1,57 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+#[test]
+fn test_print_deps_json() -> Result<()> {
+    let temp = TempDir::new()?;
+    temp.child("consts.zen").write_str("VOLTAGE = 3.3\n")?;
+    temp.child("helper.zen").write_str(
+        "load(\"consts.zen\", \"VOLTAGE\")\n\ndef double():\n    return VOLTAGE * 2\n",
+    )?;
+    let top = temp.child("top.zen");
+    top.write_str("load(\"helper.zen\", \"double\")\n\ndouble()\n")?;
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["build", "--print-deps", "json"])
+        .arg(top.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let root = temp.path().canonicalize()?;
+    let path = |name: &str| root.join(name).display().to_string();
+    let graph: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        graph,
+        serde_json::json!({
+            (path("top.zen")): [path("helper.zen")],
+            (path("helper.zen")): [path("consts.zen")],
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_print_deps_dot() -> Result<()> {
+    let temp = TempDir::new()?;
+    let top = temp.child("top.zen");
+    top.write_str("x = 1\n")?;
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["build", "--print-deps", "dot"])
+        .arg(top.path())
+        .output()?;
+    assert!(output.status.success());
+
+    let top_path = top.path().canonicalize()?.display().to_string();
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!("digraph deps {{\n  {top_path:?};\n}}\n")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use assert_fs::TempDir;
+
+#[test]
+fn test_visualize_rejects_annealing_flags_with_grid_placer() -> Result<()> {
+    let temp = TempDir::new()?;
+    temp.child("board.zen").write_str("")?;
+
+    let output = Command::cargo_bin("picoplace")?
+        .current_dir(temp.path())
+        .args(["visualize", "--no-open", "--seed", "7", "--grid", "1.27"])
+        .arg("board.zen")
+        .output()?;
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(
+        stderr.contains("--seed, --grid can only be used with the annealing placer"),
+        "{stderr}"
+    );
+    assert!(!temp.child("board.svg").path().exists());
+
+    Ok(())
+}
@@ -1,10 +1,21 @@
 use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Seek, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
 
-use crate::{FileProvider, LoadResolver, LoadSpec};
+use crate::{
+    DefaultFileProvider, EvalContext, EvalOutput, FileProvider, InputMap, LoadResolver, LoadSpec,
+    WithDiagnostics,
+};
+
+/// Name of the manifest file at the root of a bundle archive
+pub const MANIFEST_FILE: &str = "bundle.toml";
 
 /// Runtime representation of a bundle with additional metadata
 #[derive(Debug, Clone)]
@@ -64,6 +75,92 @@ impl Bundle {
             manifest: BundleManifest::default(),
         }
     }
+
+    /// Write the manifest and every file under `bundle_path` to a zip archive.
+    pub fn write_zip(&self, output_path: &Path) -> Result<()> {
+        let file = File::create(output_path)
+            .with_context(|| format!("Failed to create output file: {}", output_path.display()))?;
+        let mut zip = ZipWriter::new(file);
+
+        let manifest_toml =
+            toml::to_string(&self.manifest).context("Failed to serialize bundle manifest")?;
+        zip.start_file(MANIFEST_FILE, FileOptions::<()>::default())
+            .context("Failed to start writing bundle.toml")?;
+        zip.write_all(manifest_toml.as_bytes())
+            .context("Failed to write bundle.toml")?;
+
+        add_directory_to_zip(&mut zip, &self.bundle_path, &self.bundle_path)?;
+
+        zip.finish().context("Failed to finalize zip file")?;
+        Ok(())
+    }
+
+    /// Extract a bundle archive written by [`Bundle::write_zip`] into `dest`.
+    pub fn open_zip(archive_path: &Path, dest: &Path) -> Result<Self> {
+        let file = File::open(archive_path)
+            .with_context(|| format!("Failed to open bundle: {}", archive_path.display()))?;
+        ZipArchive::new(file)
+            .and_then(|mut archive| archive.extract(dest))
+            .with_context(|| format!("Failed to extract bundle: {}", archive_path.display()))?;
+
+        let manifest_toml = fs::read_to_string(dest.join(MANIFEST_FILE))
+            .context("Bundle is missing bundle.toml")?;
+        let manifest: BundleManifest =
+            toml::from_str(&manifest_toml).context("Failed to parse bundle manifest")?;
+
+        Ok(Self::new(dest.canonicalize()?, manifest))
+    }
+
+    /// Evaluate the bundle's entry point, resolving every load from the
+    /// bundle's own files so no network or workspace access is needed.
+    pub fn eval(&self) -> WithDiagnostics<EvalOutput> {
+        let file_provider = Arc::new(DefaultFileProvider);
+        let bundle = Self {
+            bundle_path: file_provider
+                .canonicalize(&self.bundle_path)
+                .unwrap_or_else(|_| self.bundle_path.clone()),
+            manifest: self.manifest.clone(),
+        };
+        let entry_point = bundle.bundle_path.join(&bundle.manifest.entry_point);
+
+        EvalContext::new()
+            .set_file_provider(file_provider)
+            .set_load_resolver(Arc::new(BundleLoadResolver::new(bundle)))
+            .set_source_path(entry_point)
+            .set_module_name("<root>")
+            .set_inputs(InputMap::new())
+            .eval()
+    }
+}
+
+/// Recursively add the files under `dir` to a zip archive, skipping any
+/// manifest left at the root by a previous extraction.
+fn add_directory_to_zip<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    dir: &Path,
+    base_dir: &Path,
+) -> Result<()> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+
+    for path in entries {
+        let relative_path = path.strip_prefix(base_dir)?;
+
+        if path.is_dir() {
+            add_directory_to_zip(zip, &path, base_dir)?;
+        } else if relative_path != Path::new(MANIFEST_FILE) {
+            // Always use forward slashes in ZIP archives (ZIP standard)
+            let file_name = relative_path.to_string_lossy().replace('\\', "/");
+            zip.start_file(file_name, FileOptions::<()>::default())?;
+
+            let mut file = File::open(&path)?;
+            std::io::copy(&mut file, zip)?;
+        }
+    }
+
+    Ok(())
 }
 
 /// A LoadResolver that uses a Bundle's load map
@@ -84,8 +181,12 @@ impl LoadResolver for BundleLoadResolver {
         spec: &LoadSpec,
         current_file: &Path,
     ) -> Result<PathBuf> {
-        // Convert current_file to a string key for the load map
-        let current_file_str = current_file.to_string_lossy().to_string();
+        // The load map is keyed by paths relative to the bundle root
+        let current_file_str = current_file
+            .strip_prefix(&self.bundle.bundle_path)
+            .unwrap_or(current_file)
+            .to_string_lossy()
+            .replace('\\', "/");
 
         // Convert the spec to a load string to use as the inner key
         let load_spec_str = spec.to_load_string();
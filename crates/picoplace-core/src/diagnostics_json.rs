@@ -0,0 +1,91 @@
+//! The JSON format of diagnostics shared by `build --json` and the WASM
+//! bindings.
+
+use starlark::errors::EvalSeverity;
+
+use crate::Diagnostic;
+
+/// A position in a file, as in the Language Server Protocol: 0-based line and
+/// column.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JsonPosition {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JsonRange {
+    pub start: JsonPosition,
+    pub end: JsonPosition,
+}
+
+/// One message of a [`Diagnostic`] chain, shaped like an LSP diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct JsonDiagnostic {
+    pub path: String,
+    pub range: Option<JsonRange>,
+    /// `error`, `warning`, `hint` or `information`.
+    pub severity: String,
+    pub message: String,
+    pub code: Option<String>,
+    /// The nested children of the diagnostic, outermost first. Only set on
+    /// top-level entries.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<JsonDiagnostic>>,
+}
+
+impl JsonDiagnostic {
+    /// `diag` alone, without its children.
+    fn message(diag: &Diagnostic) -> Self {
+        Self {
+            path: diag.path.clone(),
+            range: diag.span.map(|span| JsonRange {
+                start: JsonPosition {
+                    line: span.begin.line,
+                    character: span.begin.column,
+                },
+                end: JsonPosition {
+                    line: span.end.line,
+                    character: span.end.column,
+                },
+            }),
+            severity: match diag.severity {
+                EvalSeverity::Error => "error",
+                EvalSeverity::Warning => "warning",
+                EvalSeverity::Advice => "hint",
+                EvalSeverity::Disabled => "information",
+            }
+            .to_owned(),
+            message: diag.body.clone(),
+            code: diag.code.clone(),
+            children: None,
+        }
+    }
+}
+
+impl From<&Diagnostic> for JsonDiagnostic {
+    /// `diag` with its nested children flattened into `children`.
+    fn from(diag: &Diagnostic) -> Self {
+        Self {
+            children: Some(
+                diag.flatten()
+                    .into_iter()
+                    .skip(1)
+                    .map(Self::message)
+                    .collect(),
+            ),
+            ..Self::message(diag)
+        }
+    }
+}
+
+/// Serialize `diagnostics` as a JSON array for editors and CI.
+///
+/// Each entry has the `path`, LSP-style 0-based `range` (or `null`),
+/// `severity`, `message` and `code` of a diagnostic. Its nested children
+/// are flattened into a `children` array, outermost first, each with the same
+/// fields.
+pub fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> String {
+    let entries: Vec<JsonDiagnostic> = diagnostics.iter().map(JsonDiagnostic::from).collect();
+    serde_json::to_string(&entries).expect("diagnostics serialize to JSON")
+}
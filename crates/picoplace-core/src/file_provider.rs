@@ -3,14 +3,32 @@ use std::path::{Path, PathBuf};
 
 use crate::{FileProvider, FileProviderError};
 
+/// A modification made to an [`InMemoryFileProvider`] after it was created.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "kind", content = "path", rename_all = "lowercase")]
+pub enum FileChange {
+    /// The file was created or its contents replaced
+    Written(PathBuf),
+    /// The file was removed
+    Removed(PathBuf),
+}
+
 /// In-memory file provider that stores files in a HashMap.
 /// Useful for testing and WASM environments where file system access is not available.
+///
+/// Files added or removed after creation are recorded in a change log, see
+/// [`InMemoryFileProvider::changes`]. The log keeps the most recent
+/// [`InMemoryFileProvider::MAX_CHANGES`] entries.
 #[derive(Clone, Debug)]
 pub struct InMemoryFileProvider {
     files: HashMap<PathBuf, String>,
+    changes: Vec<FileChange>,
 }
 
 impl InMemoryFileProvider {
+    /// Number of entries the change log holds before dropping the oldest
+    pub const MAX_CHANGES: usize = 4096;
+
     /// Create a new InMemoryFileProvider with the given files.
     /// Keys should be file paths (can be relative or absolute).
     /// Relative paths will be converted to absolute paths with "/" as root.
@@ -18,34 +36,35 @@ impl InMemoryFileProvider {
         let mut path_files = HashMap::new();
         for (path, content) in files {
             // Ensure all paths are stored as absolute paths
-            let path_buf = PathBuf::from(path);
-            let absolute_path = if path_buf.is_absolute() {
-                path_buf
-            } else {
-                // Convert relative paths to absolute by prepending /
-                PathBuf::from("/").join(path_buf)
-            };
-            path_files.insert(absolute_path, content);
+            path_files.insert(absolute(PathBuf::from(path)), content);
+        }
+        Self {
+            files: path_files,
+            changes: Vec::new(),
         }
-        Self { files: path_files }
     }
 
     /// Create an empty InMemoryFileProvider
     pub fn empty() -> Self {
         Self {
             files: HashMap::new(),
+            changes: Vec::new(),
         }
     }
 
-    /// Add a file to the provider
+    /// Add a file to the provider, replacing any existing contents
     pub fn add_file(&mut self, path: impl Into<PathBuf>, content: String) {
-        let path_buf: PathBuf = path.into();
-        let absolute_path = if path_buf.is_absolute() {
-            path_buf
-        } else {
-            PathBuf::from("/").join(path_buf)
-        };
-        self.files.insert(absolute_path, content);
+        let path = absolute(path.into());
+        self.files.insert(path.clone(), content);
+        self.record(FileChange::Written(path));
+    }
+
+    /// Store a copy of a file read from elsewhere (e.g. fetched over the
+    /// network), replacing any existing contents. Unlike
+    /// [`add_file`](Self::add_file), this is not a user edit and is not
+    /// recorded in the change log.
+    pub fn cache_file(&mut self, path: impl Into<PathBuf>, content: String) {
+        self.files.insert(absolute(path.into()), content);
     }
 
     /// Get a reference to all files
@@ -53,15 +72,42 @@ impl InMemoryFileProvider {
         &self.files
     }
 
-    /// Remove a file from the provider
-    pub fn remove_file(&mut self, path: impl Into<PathBuf>) {
-        let path_buf: PathBuf = path.into();
-        let absolute_path = if path_buf.is_absolute() {
-            path_buf
-        } else {
-            PathBuf::from("/").join(path_buf)
-        };
-        self.files.remove(&absolute_path);
+    /// Remove a file from the provider. Returns `false` if it did not exist.
+    pub fn remove_file(&mut self, path: impl Into<PathBuf>) -> bool {
+        let path = absolute(path.into());
+        let removed = self.files.remove(&path).is_some();
+        if removed {
+            self.record(FileChange::Removed(path));
+        }
+        removed
+    }
+
+    fn record(&mut self, change: FileChange) {
+        if self.changes.len() == Self::MAX_CHANGES {
+            self.changes.remove(0);
+        }
+        self.changes.push(change);
+    }
+
+    /// Every [`add_file`](Self::add_file) and successful
+    /// [`remove_file`](Self::remove_file) since creation or the last
+    /// [`take_changes`](Self::take_changes), oldest first
+    pub fn changes(&self) -> &[FileChange] {
+        &self.changes
+    }
+
+    /// Return the change log and start a new one
+    pub fn take_changes(&mut self) -> Vec<FileChange> {
+        std::mem::take(&mut self.changes)
+    }
+}
+
+/// Make a relative path absolute by rooting it at `/`
+fn absolute(path: PathBuf) -> PathBuf {
+    if path.is_absolute() {
+        path
+    } else {
+        PathBuf::from("/").join(path)
     }
 }
 
@@ -273,4 +319,58 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_add_remove_read_cycle() {
+        let mut provider = InMemoryFileProvider::new(HashMap::from([(
+            "board.zen".to_string(),
+            "# initial".to_string(),
+        )]));
+        assert!(provider.changes().is_empty());
+
+        provider.add_file("lib/parts.zen", "# parts".to_string());
+        assert_eq!(
+            provider.read_file(Path::new("/lib/parts.zen")).unwrap(),
+            "# parts"
+        );
+
+        provider.add_file("/board.zen", "# edited".to_string());
+        assert_eq!(
+            provider.read_file(Path::new("/board.zen")).unwrap(),
+            "# edited"
+        );
+
+        assert!(provider.remove_file("lib/parts.zen"));
+        assert!(!provider.remove_file("lib/parts.zen"));
+        assert!(provider.read_file(Path::new("/lib/parts.zen")).is_err());
+        assert!(!provider.exists(Path::new("/lib")));
+
+        assert_eq!(
+            provider.take_changes(),
+            vec![
+                FileChange::Written(PathBuf::from("/lib/parts.zen")),
+                FileChange::Written(PathBuf::from("/board.zen")),
+                FileChange::Removed(PathBuf::from("/lib/parts.zen")),
+            ]
+        );
+        assert!(provider.changes().is_empty());
+    }
+
+    #[test]
+    fn test_change_log_skips_cache_fills_and_is_capped() {
+        let mut provider = InMemoryFileProvider::empty();
+        provider.cache_file("vendor/lib.zen", "# fetched".to_string());
+        assert_eq!(
+            provider.read_file(Path::new("/vendor/lib.zen")).unwrap(),
+            "# fetched"
+        );
+        assert!(provider.changes().is_empty());
+
+        for i in 0..=InMemoryFileProvider::MAX_CHANGES {
+            provider.add_file(format!("file{i}.zen"), String::new());
+        }
+        let changes = provider.changes();
+        assert_eq!(changes.len(), InMemoryFileProvider::MAX_CHANGES);
+        assert_eq!(changes[0], FileChange::Written(PathBuf::from("/file1.zen")));
+    }
 }
@@ -1,12 +1,20 @@
+use std::path::Path;
+
 use starlark::environment::GlobalsBuilder;
+use starlark::errors::{EvalMessage, EvalSeverity};
+use starlark::eval::Evaluator;
 use starlark::starlark_module;
 use starlark::values::Value;
 
+use crate::lang::evaluator_ext::EvaluatorExt;
+use crate::Diagnostic;
+
 /// Miscellaneous built-in Starlark helpers used by Diode.
 ///
 /// Currently this exposes:
 ///  • error(msg): unconditionally raises a runtime error with the provided message.
 ///  • check(cond, msg): raises an error with `msg` when `cond` is false.
+///  • warn(msg, code = None): reports a non-fatal warning and carries on.
 #[starlark_module]
 pub(crate) fn assert_globals(builder: &mut GlobalsBuilder) {
     /// Raise a runtime error with the given message.
@@ -25,4 +33,34 @@ pub(crate) fn assert_globals(builder: &mut GlobalsBuilder) {
             Err(anyhow::anyhow!(msg))
         }
     }
+
+    /// Report a warning with the given message without stopping evaluation.
+    ///
+    /// `code` identifies the warning so it can be silenced at the call site
+    /// with a `# pcb: allow(<code>)` comment.
+    fn warn<'v>(
+        #[starlark(require = pos)] msg: String,
+        #[starlark(require = named)] code: Option<String>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<Value<'v>> {
+        let message = match eval.call_stack_top_location() {
+            Some(call_site) => {
+                let mut message =
+                    EvalMessage::from_any_error(Path::new(call_site.filename()), &msg);
+                message.span = Some(call_site.resolve_span());
+                message
+            }
+            None => EvalMessage::from_any_error(
+                Path::new(&eval.source_path().unwrap_or_default()),
+                &msg,
+            ),
+        };
+
+        let mut diagnostic = Diagnostic::from_eval_message(message);
+        diagnostic.severity = EvalSeverity::Warning;
+        diagnostic.code = code;
+        eval.add_diagnostic(diagnostic);
+
+        Ok(Value::new_none())
+    }
 }
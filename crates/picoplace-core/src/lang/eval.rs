@@ -1,9 +1,13 @@
 use std::{
     cell::RefCell,
-    collections::{HashMap, HashSet},
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::{Hash, Hasher},
     ops::Deref,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 use anyhow::anyhow;
@@ -11,9 +15,10 @@ use starlark::collections::SmallMap;
 use starlark::typing::Interface;
 use starlark::{
     any::ProvidesStaticType,
+    codemap::FileSpanRef,
     environment::{GlobalsBuilder, LibraryExtension},
     errors::EvalMessage,
-    eval::{Evaluator, FileLoader},
+    eval::{BeforeStmtFuncDyn, Evaluator, FileLoader},
     syntax::{AstModule, Dialect},
     typing::TypeMap,
     values::{
@@ -26,7 +31,7 @@ use starlark::{
 use crate::lang::component::{build_component_factory_from_symbol, component_globals};
 use crate::lang::file::file_globals;
 use crate::lang::input::{InputMap, InputValue};
-use crate::{file_extensions, lang::assert::assert_globals};
+use crate::{codes, file_extensions, lang::assert::assert_globals};
 use crate::{Diagnostic, WithDiagnostics};
 
 #[cfg(feature = "native")]
@@ -45,17 +50,21 @@ use super::{
     context::{ContextValue, FrozenContextValue},
     interface::interface_globals,
     module::{module_globals, FrozenModuleValue, ModuleLoader},
+    net::FrozenNetValue,
 };
 
-/// A PrintHandler that collects all print output into a vector
+/// A PrintHandler that collects all print output into a vector, and into the
+/// shared state so that output of nested and failed evaluations is kept too.
 struct CollectingPrintHandler {
     output: RefCell<Vec<String>>,
+    state: Arc<Mutex<EvalContextState>>,
 }
 
 impl CollectingPrintHandler {
-    fn new() -> Self {
+    fn new(state: Arc<Mutex<EvalContextState>>) -> Self {
         Self {
             output: RefCell::new(Vec::new()),
+            state,
         }
     }
 
@@ -66,7 +75,9 @@ impl CollectingPrintHandler {
 
 impl PrintHandler for CollectingPrintHandler {
     fn println(&self, text: &str) -> starlark::Result<()> {
-        eprintln!("{text}");
+        if let Ok(mut state) = self.state.lock() {
+            state.print_output.push(text.to_string());
+        }
         self.output.borrow_mut().push(text.to_string());
         Ok(())
     }
@@ -127,6 +138,44 @@ pub struct EvalOutput {
     pub print_output: Vec<String>,
 }
 
+impl EvalOutput {
+    /// The top-level bindings holding a `Module()` loader, e.g. each board
+    /// variant in `Lite = Module("lite.zen")`, with the file the loader
+    /// instantiates. Sorted by name.
+    pub fn module_loaders(&self) -> Vec<(String, PathBuf)> {
+        let mut loaders: Vec<(String, PathBuf)> = self
+            .star_module
+            .names()
+            .filter_map(|name| {
+                let value = self.star_module.get_option(name.as_str()).ok()??;
+                let loader = value.value().downcast_ref::<ModuleLoader>()?;
+                Some((
+                    name.as_str().to_string(),
+                    PathBuf::from(&loader.source_path),
+                ))
+            })
+            .collect();
+        loaders.sort();
+        loaders
+    }
+
+    /// The top-level bindings holding a `Net`, e.g. `GND = Net("GND")`, with
+    /// the name of the net. Sorted by binding name.
+    pub fn net_bindings(&self) -> Vec<(String, String)> {
+        let mut nets: Vec<(String, String)> = self
+            .star_module
+            .names()
+            .filter_map(|name| {
+                let value = self.star_module.get_option(name.as_str()).ok()??;
+                let net = value.value().downcast_ref::<FrozenNetValue>()?;
+                Some((name.as_str().to_string(), net.name().to_string()))
+            })
+            .collect();
+        nets.sort();
+        nets
+    }
+}
+
 #[derive(Debug, Default)]
 struct EvalContextState {
     /// In-memory contents of files that are currently open/edited. Keyed by canonical path.
@@ -148,13 +197,32 @@ struct EvalContextState {
     /// Cache of previously loaded modules keyed by their canonical absolute path. This
     /// ensures that repeated `load()` calls for the same file return the *same* frozen
     /// module instance so that type identities remain consistent across the evaluation
-    /// graph (e.g. record types defined in that module).
-    load_cache: HashMap<PathBuf, starlark::environment::FrozenModule>,
+    /// graph (e.g. record types defined in that module). Entries are reused across
+    /// evaluations for as long as the module and everything it loads keep the same
+    /// content hash.
+    load_cache: HashMap<PathBuf, CachedModule>,
+
+    /// Map of `module.zen` → set of files it pulled in via `load()`. Used to invalidate
+    /// cached modules when one of their dependencies changes.
+    load_deps: HashMap<PathBuf, HashSet<PathBuf>>,
+
+    /// Cached modules already found fresh during the current top-level evaluation, so that
+    /// each dependency tree is only re-hashed once per evaluation rather than on every
+    /// `load()` that reaches it.
+    fresh_modules: HashSet<PathBuf>,
+
+    /// Number of evaluations currently running against this state. An evaluation that
+    /// starts while this is zero is a top-level one and resets `fresh_modules`.
+    active_evals: usize,
 
     /// Map of `module.zen` → set of files referenced via `load()`. Used by the LSP to
     /// propagate diagnostics when a dependency changes.
     module_deps: HashMap<PathBuf, HashSet<PathBuf>>,
 
+    /// Every path resolved through `load()` during evaluation, whether or not
+    /// the load succeeded. Used by callers that need to watch a design's inputs.
+    loaded_files: HashSet<PathBuf>,
+
     /// Cache of type maps for each module.
     #[allow(dead_code)]
     type_cache: HashMap<PathBuf, TypeMap>,
@@ -169,6 +237,120 @@ struct EvalContextState {
     /// Map of paths that we are currently loading to the source file that triggered the load.
     /// This is used to detect cyclic imports and to skip in-flight files when loading directories.
     load_in_progress: HashMap<PathBuf, PathBuf>,
+
+    /// Lines written by `print()` during the current top-level evaluation, including those
+    /// of nested evaluations and of evaluations that failed.
+    print_output: Vec<String>,
+}
+
+/// A frozen module produced by `load()`, along with what it was evaluated from.
+#[derive(Debug)]
+struct CachedModule {
+    frozen: starlark::environment::FrozenModule,
+    /// Hash of the module's source (or directory listing) when it was evaluated.
+    content_hash: u64,
+    /// Hash of the [`EvalContext::set_globals`] values it was evaluated with.
+    globals_hash: u64,
+    /// Files loaded while evaluating the module.
+    deps: HashSet<PathBuf>,
+}
+
+/// Hash the current contents of `path`, preferring in-memory buffers over the file provider.
+/// Directories hash their listing plus every module and symbol file inside them, since
+/// those are evaluated eagerly when the directory is loaded.
+fn source_hash(
+    state: &EvalContextState,
+    file_provider: &dyn crate::FileProvider,
+    path: &Path,
+) -> Option<u64> {
+    let hash_file = |path: &Path, hasher: &mut DefaultHasher| -> Option<()> {
+        match state.file_contents.get(path) {
+            Some(contents) => contents.hash(hasher),
+            None => file_provider.read_file(path).ok()?.hash(hasher),
+        }
+        Some(())
+    };
+
+    let mut hasher = DefaultHasher::new();
+    if file_provider.is_directory(path) {
+        let mut entries = file_provider.list_directory(path).ok()?;
+        entries.sort();
+        for entry in entries {
+            entry.hash(&mut hasher);
+            if file_extensions::is_starlark_file(entry.extension())
+                || file_extensions::is_kicad_symbol_file(entry.extension())
+            {
+                hash_file(&entry, &mut hasher)?;
+            }
+        }
+    } else {
+        hash_file(path, &mut hasher)?;
+    }
+    Some(hasher.finish())
+}
+
+/// Hash of the globals set through [`EvalContext::set_globals`], independent of their order.
+fn globals_hash(globals: &HashMap<String, InputValue>) -> u64 {
+    let sorted: BTreeMap<&String, &InputValue> = globals.iter().collect();
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(&sorted)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether the cached entry for `path` was evaluated with the same globals and still
+/// matches its source and the sources of everything it (transitively) loaded. Entries in
+/// `fresh_modules` are trusted without re-hashing; when the result is `true`, every path
+/// in `visited` has been checked.
+fn is_cache_fresh(
+    state: &EvalContextState,
+    file_provider: &dyn crate::FileProvider,
+    path: &Path,
+    globals_hash: u64,
+    visited: &mut HashSet<PathBuf>,
+) -> bool {
+    let Some(entry) = state.load_cache.get(path) else {
+        return false;
+    };
+    if entry.globals_hash != globals_hash {
+        return false;
+    }
+    if state.fresh_modules.contains(path) || !visited.insert(path.to_path_buf()) {
+        return true;
+    }
+    source_hash(state, file_provider, path) == Some(entry.content_hash)
+        && entry
+            .deps
+            .iter()
+            .all(|dep| is_cache_fresh(state, file_provider, dep, globals_hash, visited))
+}
+
+/// RAII guard that tracks a running evaluation, starting a fresh `fresh_modules` memo for
+/// each top-level evaluation.
+struct EvalGuard {
+    state: Arc<Mutex<EvalContextState>>,
+}
+
+impl EvalGuard {
+    fn new(state: Arc<Mutex<EvalContextState>>) -> Self {
+        if let Ok(mut s) = state.lock() {
+            if s.active_evals == 0 {
+                s.fresh_modules.clear();
+                s.print_output.clear();
+            }
+            s.active_evals += 1;
+        }
+        Self { state }
+    }
+}
+
+impl Drop for EvalGuard {
+    fn drop(&mut self) {
+        if let Ok(mut s) = self.state.lock() {
+            s.active_evals = s.active_evals.saturating_sub(1);
+        }
+    }
 }
 
 /// RAII guard that automatically removes a path from the load_in_progress set when dropped.
@@ -251,6 +433,10 @@ pub struct EvalContext {
     /// The inputs of the module we are evaluating.
     pub(crate) inputs: Option<InputMap>,
 
+    /// Environment-style globals exposed as module-level variables, see
+    /// [`EvalContext::set_globals`]. Inherited by child contexts.
+    globals: HashMap<String, InputValue>,
+
     /// Optional map of custom properties to attach to the root `ModuleValue` before the
     /// module body is executed. Populated by `ModuleLoader` when the caller passes the
     /// `properties = {...}` keyword argument.
@@ -265,6 +451,10 @@ pub struct EvalContext {
 
     /// Load resolver for resolving load() paths
     pub(crate) load_resolver: Option<Arc<dyn crate::LoadResolver>>,
+
+    /// Cooperative cancellation flag, shared with child contexts. Checked at the start of
+    /// each evaluation, at every `load()` boundary and before every statement.
+    cancel_token: Arc<AtomicBool>,
 }
 
 impl Default for EvalContext {
@@ -297,10 +487,12 @@ impl EvalContext {
             contents: None,
             name: None,
             inputs: None,
+            globals: HashMap::new(),
             properties: None,
             diagnostics: RefCell::new(Vec::new()),
             file_provider: None,
             load_resolver: None,
+            cancel_token: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -347,13 +539,36 @@ impl EvalContext {
             contents: None,
             name: None,
             inputs: None,
+            globals: self.globals.clone(),
             properties: None,
             diagnostics: RefCell::new(Vec::new()),
             file_provider: self.file_provider.clone(),
             load_resolver: self.load_resolver.clone(),
+            cancel_token: self.cancel_token.clone(),
         }
     }
 
+    /// Use `token` to cancel this evaluation (and any nested ones). Setting the flag makes
+    /// the evaluator abort with a [`crate::Cancelled`] diagnostic at its next checkpoint.
+    pub fn set_cancellation_token(mut self, token: Arc<AtomicBool>) -> Self {
+        self.cancel_token = token;
+        self
+    }
+
+    /// The cancellation flag observed by this context.
+    pub fn cancellation_token(&self) -> Arc<AtomicBool> {
+        self.cancel_token.clone()
+    }
+
+    /// Whether cancellation has been requested for this evaluation.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_token.load(Ordering::Relaxed)
+    }
+
+    fn check_cancelled(&self) -> starlark::Result<()> {
+        check_cancelled(&self.cancel_token)
+    }
+
     fn dialect(&self) -> Dialect {
         let mut dialect = Dialect::Extended;
         dialect.enable_f_strings = true;
@@ -363,6 +578,41 @@ impl EvalContext {
     /// Construct the `Globals` used when evaluating modules. Kept in one place so the
     /// configuration stays consistent between the main evaluator and nested `load()`s.
     fn build_globals() -> starlark::environment::Globals {
+        Self::globals_builder().build()
+    }
+
+    /// [`Self::build_globals`] plus the values passed to [`Self::set_globals`]. These
+    /// are builtins rather than module variables, so a module can shadow them and
+    /// `load()` never re-exports them.
+    fn build_globals_with(
+        user_globals: &HashMap<String, InputValue>,
+    ) -> starlark::Result<starlark::environment::Globals> {
+        let mut builder = Self::globals_builder();
+        if user_globals.is_empty() {
+            return Ok(builder.build());
+        }
+
+        let module = starlark::environment::Module::new();
+        {
+            let mut eval = Evaluator::new(&module);
+            for (name, iv) in user_globals {
+                let value = iv.to_value(&mut eval, None)?;
+                module.set(name, value);
+            }
+        }
+        let frozen = module.freeze().map_err(starlark::Error::from)?;
+
+        builder.frozen_heap().add_reference(frozen.frozen_heap());
+        for name in user_globals.keys() {
+            if let Some(owned) = frozen.get_option(name).ok().flatten() {
+                // SAFETY: the builder's heap holds a reference to `frozen`.
+                builder.set(name, unsafe { owned.unchecked_frozen_value() });
+            }
+        }
+        Ok(builder.build())
+    }
+
+    fn globals_builder() -> GlobalsBuilder {
         GlobalsBuilder::extended_by(&[
             LibraryExtension::RecordType,
             LibraryExtension::EnumType,
@@ -379,7 +629,6 @@ impl EvalContext {
         .with(interface_globals)
         .with(assert_globals)
         .with(file_globals)
-        .build()
     }
 
     /// Record that `from` references `to` via a `Module()` call.
@@ -536,6 +785,7 @@ impl EvalContext {
                             body: format!("Failed to resolve load path '{file_load_path}': {e}"),
                             call_stack: None,
                             child: None,
+                            code: Some(codes::UNRESOLVED_LOAD_PATH.to_owned()),
                         };
                         errors_by_symbol
                             .entry(symbol_name.clone())
@@ -724,6 +974,7 @@ impl EvalContext {
                             body: format!("Failed to resolve load path '{file_load_path}': {e}"),
                             call_stack: None,
                             child: None,
+                            code: Some(codes::UNRESOLVED_LOAD_PATH.to_owned()),
                         };
                         errors_by_symbol
                             .entry(symbol_name.clone())
@@ -757,6 +1008,7 @@ impl EvalContext {
                         body: format!("Failed to load component from {file_load_path}: {e}"),
                         call_stack: None,
                         child: None,
+                        code: Some(codes::COMPONENT_LOAD_FAILED.to_owned()),
                     };
                     errors_by_symbol
                         .entry(symbol_name.clone())
@@ -773,7 +1025,6 @@ impl EvalContext {
 
     /// Provide the raw contents of the Starlark module. When omitted, the contents
     /// will be read from `source_path` during [`Context::eval`].
-    #[allow(dead_code)]
     pub fn set_source_contents<S: Into<String>>(mut self, contents: S) -> Self {
         self.contents = Some(contents.into());
         self
@@ -799,6 +1050,18 @@ impl EvalContext {
         self
     }
 
+    /// Expose `name → value` pairs as module-level globals, e.g. build-time
+    /// feature flags. They are visible in this module and every module it
+    /// loads or instantiates.
+    ///
+    /// Globals are separate from [`set_inputs`](Self::set_inputs): `io()` and
+    /// `config()` only ever read inputs, never globals. A global is shadowed by
+    /// any top-level assignment to the same name in the module itself.
+    pub fn set_globals(mut self, globals: HashMap<String, InputValue>) -> Self {
+        self.globals = globals;
+        self
+    }
+
     /// Specify a map of `name → value` pairs that should be attached as custom
     /// properties on the module value *before* the Starlark file is executed.
     pub fn set_properties(mut self, props: SmallMap<String, InputValue>) -> Self {
@@ -806,10 +1069,27 @@ impl EvalContext {
         self
     }
 
+    /// Evaluate in-memory `source` as the contents of `virtual_path`, which does
+    /// not need to exist. Relative `load()`s resolve against `virtual_path` and
+    /// diagnostics point at it. The module name defaults to `<root>` when not set
+    /// with [`set_module_name`](Self::set_module_name).
+    pub fn eval_string(self, source: &str, virtual_path: &Path) -> WithDiagnostics<EvalOutput> {
+        let name = self.name.clone().unwrap_or_else(|| "<root>".to_string());
+        self.set_source_contents(source)
+            .set_source_path(virtual_path.to_path_buf())
+            .set_module_name(name)
+            .eval()
+    }
+
     /// Evaluate the configured module. All required fields must be provided
     /// beforehand via the corresponding setters. When a required field is
     /// missing this function returns a failed [`WithDiagnostics`].
     pub fn eval(mut self) -> WithDiagnostics<EvalOutput> {
+        if let Err(err) = self.check_cancelled() {
+            return WithDiagnostics::failure(vec![Diagnostic::from_error(err)]);
+        }
+        let _eval_guard = EvalGuard::new(self.state.clone());
+
         // Make sure a source path is set.
         let source_path = match self.source_path {
             Some(ref path) => path,
@@ -826,32 +1106,41 @@ impl EvalContext {
             .clone()
             .unwrap_or_else(|| default_file_provider());
 
-        // Fetch contents: prefer explicit override, otherwise read from disk.
+        // Fetch contents: prefer explicit override, then an open buffer, otherwise read
+        // from disk.
         let contents_owned = match &self.contents {
-            Some(c) => c.clone(),
-            None => match file_provider.read_file(source_path) {
-                Ok(c) => {
-                    // Cache the read contents for subsequent accesses.
-                    self.contents = Some(c.clone());
-                    c
+            Some(c) => {
+                // Cache provided contents in `open_files` so that nested `load()` calls see
+                // the latest buffer state rather than potentially stale on-disk contents.
+                if let Ok(mut state) = self.state.lock() {
+                    state.file_contents.insert(source_path.clone(), c.clone());
                 }
-                Err(err) => {
-                    let diag = crate::Diagnostic::from_error(starlark::Error::new_other(
-                        anyhow::anyhow!("Failed to read file: {}", err),
-                    ));
-                    return WithDiagnostics::failure(vec![diag]);
+                c.clone()
+            }
+            None => {
+                // Contents read from disk are not recorded as a buffer, so that later edits
+                // on disk are still seen by `load()` and its freshness check.
+                let buffered = self
+                    .state
+                    .lock()
+                    .ok()
+                    .and_then(|state| state.file_contents.get(source_path).cloned());
+                match buffered.map_or_else(|| file_provider.read_file(source_path), Ok) {
+                    Ok(c) => {
+                        // Cache the read contents for subsequent accesses.
+                        self.contents = Some(c.clone());
+                        c
+                    }
+                    Err(err) => {
+                        let diag = crate::Diagnostic::from_error(starlark::Error::new_other(
+                            anyhow::anyhow!("Failed to read file: {}", err),
+                        ));
+                        return WithDiagnostics::failure(vec![diag]);
+                    }
                 }
-            },
+            }
         };
 
-        // Cache provided contents in `open_files` so that nested `load()` calls see the
-        // latest buffer state rather than potentially stale on-disk contents.
-        if let Ok(mut state) = self.state.lock() {
-            state
-                .file_contents
-                .insert(source_path.clone(), contents_owned.clone());
-        }
-
         let ast_res = AstModule::parse(
             source_path.to_str().expect("path is not a string"),
             contents_owned.to_string(),
@@ -867,13 +1156,16 @@ impl EvalContext {
 
         eval_res.flat_map(|ast| {
             // Create a print handler to collect output
-            let print_handler = CollectingPrintHandler::new();
+            let print_handler = CollectingPrintHandler::new(self.state.clone());
 
             let eval_result = {
                 let mut eval = Evaluator::new(&self.module);
                 eval.enable_static_typechecking(true);
                 eval.set_loader(&self);
                 eval.set_print_handler(&print_handler);
+                eval.before_stmt_for_dap(Box::new(CancellationCheckpoint(
+                    self.cancel_token.clone(),
+                )));
 
                 // Attach a `ContextValue` so user code can access evaluation context.
                 self.module
@@ -901,11 +1193,16 @@ impl EvalContext {
                     }
                 }
 
-                let globals = Self::build_globals();
+                let globals = match Self::build_globals_with(&self.globals) {
+                    Ok(globals) => globals,
+                    Err(e) => return WithDiagnostics::failure(vec![Diagnostic::from_error(e)]),
+                };
 
                 // We are only interested in whether evaluation succeeded, not in the
-                // value of the final expression, so map the result to `()`.
-                eval.eval_module(ast.clone(), &globals).map(|_| ())
+                // value of the final expression, so map the result to `()`. A cancellation
+                // requested while the body ran discards the partial result.
+                eval.eval_module(ast.clone(), &globals)
+                    .and_then(|_| self.check_cancelled())
             };
 
             // Collect print output after evaluation
@@ -1025,6 +1322,33 @@ impl EvalContext {
         }
     }
 
+    /// Remember a module evaluated by `load()` so later evaluations can reuse it.
+    fn cache_module(
+        &self,
+        path: PathBuf,
+        frozen: starlark::environment::FrozenModule,
+        content_hash: u64,
+        deps: HashSet<PathBuf>,
+    ) {
+        if let Ok(mut state) = self.state.lock() {
+            state.load_cache.insert(
+                path,
+                CachedModule {
+                    frozen,
+                    content_hash,
+                    globals_hash: globals_hash(&self.globals),
+                    deps,
+                },
+            );
+        }
+    }
+
+    /// Get the frozen module cached for a file loaded via `load()`, if any.
+    pub fn get_cached_module(&self, path: &Path) -> Option<starlark::environment::FrozenModule> {
+        let state = self.state.lock().ok()?;
+        state.load_cache.get(path).map(|entry| entry.frozen.clone())
+    }
+
     /// Get the file contents from the in-memory cache
     pub fn get_file_contents(&self, path: &Path) -> Option<String> {
         if let Ok(state) = self.state.lock() {
@@ -1068,6 +1392,43 @@ impl EvalContext {
         }
     }
 
+    /// Return every file this context (or any of its children) has pulled in
+    /// through `load()` or `Module()`, not including the root source file.
+    pub fn get_loaded_files(&self) -> HashSet<PathBuf> {
+        if let Ok(state) = self.state.lock() {
+            let mut files = state.loaded_files.clone();
+            files.extend(state.module_deps.values().flatten().cloned());
+            files
+        } else {
+            HashSet::new()
+        }
+    }
+
+    /// Every line written by `print()` during the last top-level evaluation of this context,
+    /// including output of `load()`ed modules, `Module()` instances and evaluations that
+    /// failed before producing an [`EvalOutput`].
+    pub fn get_print_output(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .map(|state| state.print_output.clone())
+            .unwrap_or_default()
+    }
+
+    /// Every `load()` and `Module()` edge recorded by this context (or any of its
+    /// children), as a map from each referencing file to the files it references.
+    pub fn get_dependency_graph(&self) -> BTreeMap<PathBuf, BTreeSet<PathBuf>> {
+        let mut graph: BTreeMap<PathBuf, BTreeSet<PathBuf>> = BTreeMap::new();
+        if let Ok(state) = self.state.lock() {
+            for (from, to) in state.load_deps.iter().chain(&state.module_deps) {
+                graph
+                    .entry(from.clone())
+                    .or_default()
+                    .extend(to.iter().cloned());
+            }
+        }
+        graph
+    }
+
     /// Parse and analyze a file, updating the symbol index and metadata
     pub fn parse_and_analyze_file(
         &self,
@@ -1313,6 +1674,28 @@ impl EvalContext {
     }
 }
 
+fn check_cancelled(token: &AtomicBool) -> starlark::Result<()> {
+    if token.load(Ordering::Relaxed) {
+        Err(starlark::Error::new_other(crate::Cancelled))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks the cancellation flag before every statement so that long loops
+/// can be interrupted, not just `load()`s.
+struct CancellationCheckpoint(Arc<AtomicBool>);
+
+impl<'a, 'e: 'a> BeforeStmtFuncDyn<'a, 'e> for CancellationCheckpoint {
+    fn call<'v>(
+        &mut self,
+        _span: FileSpanRef,
+        _eval: &mut Evaluator<'v, 'a, 'e>,
+    ) -> starlark::Result<()> {
+        check_cancelled(&self.0)
+    }
+}
+
 // Add FileLoader implementation so that Starlark `load()` works when evaluating modules.
 impl FileLoader for EvalContext {
     fn load(&self, path: &str) -> starlark::Result<starlark::environment::FrozenModule> {
@@ -1321,6 +1704,8 @@ impl FileLoader for EvalContext {
             self.source_path
         );
 
+        self.check_cancelled()?;
+
         // Get or create default providers if none were set
         let file_provider = self
             .file_provider
@@ -1352,6 +1737,17 @@ impl FileLoader for EvalContext {
             .canonicalize(&absolute_path)
             .unwrap_or(absolute_path.clone());
 
+        if let Ok(mut state) = self.state.lock() {
+            state.loaded_files.insert(canonical_path.clone());
+            if let Some(source_path) = &self.source_path {
+                state
+                    .load_deps
+                    .entry(source_path.clone())
+                    .or_default()
+                    .insert(canonical_path.clone());
+            }
+        }
+
         // Create a LoadGuard to prevent cyclic imports
         let source_path = self
             .source_path
@@ -1359,12 +1755,26 @@ impl FileLoader for EvalContext {
             .unwrap_or_else(|| PathBuf::from("<unknown>"));
         let _guard = LoadGuard::new(self.state.clone(), canonical_path.clone(), source_path)?;
 
-        // Fast path: if we've already loaded (and frozen) this module once
-        // within the current evaluation context, simply return the cached
-        // instance so that callers share the same definitions.
-        if let Some(frozen) = self.state.lock().unwrap().load_cache.get(&canonical_path) {
-            return Ok(frozen.clone());
-        }
+        // Fast path: if we've already loaded (and frozen) this module and
+        // neither it nor anything it loads has changed since, simply return
+        // the cached instance so that callers share the same definitions.
+        let content_hash = {
+            let mut state = self.state.lock().unwrap();
+            let mut visited = HashSet::new();
+            if is_cache_fresh(
+                &state,
+                file_provider.deref(),
+                &canonical_path,
+                globals_hash(&self.globals),
+                &mut visited,
+            ) {
+                state.fresh_modules.extend(visited);
+                return Ok(state.load_cache[&canonical_path].frozen.clone());
+            }
+            state.load_cache.remove(&canonical_path);
+            state.load_deps.remove(&canonical_path);
+            source_hash(&state, file_provider.deref(), &canonical_path)
+        };
 
         // Special-case: if the load path refers to a *directory* treat it as a
         // namespace that exports one `ModuleLoader` per `.zen` file found
@@ -1440,6 +1850,7 @@ impl FileLoader for EvalContext {
                                 body: format!("Error loading module `{error_path}`"),
                                 call_stack: None,
                                 child: Some(Box::new(error.clone())),
+                                code: Some(codes::MODULE_LOAD_FAILED.to_owned()),
                             };
 
                             // Wrap in DiagnosticError and pass through anyhow
@@ -1465,6 +1876,7 @@ impl FileLoader for EvalContext {
                         body: format!("Error loading module `{error_path}`"),
                         call_stack: None,
                         child: Some(Box::new(error.clone())),
+                        code: Some(codes::MODULE_LOAD_FAILED.to_owned()),
                     };
                     let diag_err = crate::DiagnosticError(parent_diag);
                     let load_err = crate::LoadError {
@@ -1476,11 +1888,18 @@ impl FileLoader for EvalContext {
                 }
             }
 
-            self.state
-                .lock()
-                .unwrap()
-                .load_cache
-                .insert(canonical_path.clone(), frozen.clone());
+            if let Some(content_hash) = content_hash {
+                let mut deps = HashSet::new();
+                if let Ok(state) = self.state.lock() {
+                    for entry in file_provider
+                        .list_directory(&canonical_path)
+                        .unwrap_or_default()
+                    {
+                        deps.extend(state.load_deps.get(&entry).into_iter().flatten().cloned());
+                    }
+                }
+                self.cache_module(canonical_path, frozen.clone(), content_hash, deps);
+            }
             return Ok(frozen);
         }
 
@@ -1506,6 +1925,7 @@ impl FileLoader for EvalContext {
                         body: format!("Error loading module `{path}`"),
                         call_stack: None,
                         child: Some(Box::new(first_error.clone())),
+                        code: Some(codes::MODULE_LOAD_FAILED.to_owned()),
                     };
 
                     // Wrap in DiagnosticError and pass through anyhow
@@ -1529,6 +1949,7 @@ impl FileLoader for EvalContext {
                         body: format!("Failed to load module `{path}`"),
                         call_stack: None,
                         child: None,
+                        code: Some(codes::MODULE_LOAD_FAILED.to_owned()),
                     };
                     let diag_err = crate::DiagnosticError(diag);
                     let load_err = crate::LoadError {
@@ -1544,11 +1965,15 @@ impl FileLoader for EvalContext {
         // Cache the result if successful
         if let Some(output) = result.output {
             let frozen = output.star_module;
-            self.state
-                .lock()
-                .unwrap()
-                .load_cache
-                .insert(canonical_path, frozen.clone());
+            if let Some(content_hash) = content_hash {
+                let deps = self
+                    .state
+                    .lock()
+                    .ok()
+                    .and_then(|state| state.load_deps.get(&canonical_path).cloned())
+                    .unwrap_or_default();
+                self.cache_module(canonical_path, frozen.clone(), content_hash, deps);
+            }
             Ok(frozen)
         } else {
             // No specific error diagnostic but evaluation failed
@@ -1565,6 +1990,7 @@ impl FileLoader for EvalContext {
                         body: format!("Failed to load module `{path}`"),
                         call_stack: None,
                         child: None,
+                        code: Some(codes::MODULE_LOAD_FAILED.to_owned()),
                     };
                     let diag_err = crate::DiagnosticError(diag);
                     let load_err = crate::LoadError {
@@ -1587,6 +2013,7 @@ impl FileLoader for EvalContext {
                 body: format!("Failed to load module `{path}`"),
                 call_stack: None,
                 child: None,
+                code: Some(codes::MODULE_LOAD_FAILED.to_owned()),
             };
             let diag_err = crate::DiagnosticError(diag);
             let load_err = crate::LoadError {
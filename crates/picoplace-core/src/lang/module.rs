@@ -26,12 +26,12 @@ use crate::lang::context::ContextValue;
 use crate::lang::eval::EvalContext;
 use crate::lang::evaluator_ext::EvaluatorExt;
 use crate::lang::input::{convert_from_starlark, InputMap};
-use crate::Diagnostic;
+use crate::{codes, Diagnostic};
 use starlark::values::dict::DictRef;
 
 use super::net::{generate_net_id, NetValue};
 use crate::lang::context::FrozenContextValue;
-use starlark::errors::{EvalMessage, EvalSeverity};
+use starlark::errors::EvalMessage;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -346,15 +346,22 @@ where
                 );
                 let mut diag = EvalMessage::from_any_error(Path::new(call_site.filename()), &msg);
                 diag.span = Some(call_site.resolve_span());
-                eval.add_diagnostic(crate::Diagnostic::from_eval_message(diag));
+                eval.add_diagnostic(
+                    crate::Diagnostic::from_eval_message(diag)
+                        .with_code(codes::MISSING_MODULE_NAME),
+                );
             } else {
                 let msg = format!(
                     "Missing required argument `name` when instantiating module {}",
                     self.name
                 );
-                eval.add_diagnostic(crate::Diagnostic::from_eval_message(
-                    EvalMessage::from_any_error(Path::new(&self.source_path), &msg),
-                ));
+                eval.add_diagnostic(
+                    crate::Diagnostic::from_eval_message(EvalMessage::from_any_error(
+                        Path::new(&self.source_path),
+                        &msg,
+                    ))
+                    .with_code(codes::MISSING_MODULE_NAME),
+                );
             }
 
             // Use the file-stem derived name from the loader as a fallback.
@@ -402,13 +409,15 @@ where
         for child in result.diagnostics.into_iter() {
             let diag_to_add = if let Some(cs) = &call_site {
                 // Build a new primary message pointing at this ModuleLoader call-site.
+                // It keeps the child's severity so warnings in submodules stay warnings.
                 Diagnostic {
                     path: cs.filename().to_string(),
                     span: Some(cs.resolve_span()),
-                    severity: EvalSeverity::Error,
+                    severity: child.severity,
                     body: format!("Error instantiating `{}`", self.name),
                     call_stack: Some(eval.call_stack().clone()),
                     child: Some(Box::new(child)),
+                    code: Some(codes::MODULE_INSTANTIATION_FAILED.to_owned()),
                 }
             } else {
                 child
@@ -456,11 +465,18 @@ where
                         let mut unused_diag =
                             EvalMessage::from_any_error(Path::new(cs.filename()), &msg);
                         unused_diag.span = Some(cs.resolve_span());
-                        context.add_diagnostic(crate::Diagnostic::from_eval_message(unused_diag));
+                        context.add_diagnostic(
+                            crate::Diagnostic::from_eval_message(unused_diag)
+                                .with_code(codes::UNKNOWN_MODULE_ARGUMENT),
+                        );
                     } else {
-                        context.add_diagnostic(crate::Diagnostic::from_eval_message(
-                            EvalMessage::from_any_error(Path::new(&self.source_path), &msg),
-                        ));
+                        context.add_diagnostic(
+                            crate::Diagnostic::from_eval_message(EvalMessage::from_any_error(
+                                Path::new(&self.source_path),
+                                &msg,
+                            ))
+                            .with_code(codes::UNKNOWN_MODULE_ARGUMENT),
+                        );
                     }
                     // Continue execution without raising an error.
                 }
@@ -475,7 +491,10 @@ where
                         let mut call_diag =
                             EvalMessage::from_any_error(Path::new(call_site.filename()), &msg);
                         call_diag.span = Some(call_site.resolve_span());
-                        context.add_diagnostic(crate::Diagnostic::from_eval_message(call_diag));
+                        context.add_diagnostic(
+                            crate::Diagnostic::from_eval_message(call_diag)
+                                .with_code(codes::MODULE_INSTANTIATION_FAILED),
+                        );
                     }
                 }
                 Ok(Value::new_none())
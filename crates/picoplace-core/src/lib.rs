@@ -4,6 +4,7 @@ use std::{
     fmt::Display,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
 use serde::ser::SerializeStruct;
@@ -15,6 +16,7 @@ use starlark::{
 
 pub mod bundle;
 pub mod convert;
+mod diagnostics_json;
 mod file_provider;
 pub mod lang;
 pub mod load_spec;
@@ -22,10 +24,12 @@ pub mod load_spec;
 // Re-export commonly used types
 pub use lang::eval::{EvalContext, EvalOutput};
 pub use lang::input::{InputMap, InputValue};
-pub use load_spec::LoadSpec;
+pub use load_spec::{LoadSpec, LoadSpecError};
+
+pub use diagnostics_json::{diagnostics_to_json, JsonDiagnostic, JsonPosition, JsonRange};
 
 // Re-export file provider types
-pub use file_provider::InMemoryFileProvider;
+pub use file_provider::{FileChange, InMemoryFileProvider};
 
 // Re-export types needed by pcb-zen
 pub use lang::component::FrozenComponentValue;
@@ -51,6 +55,11 @@ impl std::error::Error for DiagnosticError {
     }
 }
 
+/// Error raised when an evaluation is aborted through its cancellation token.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Evaluation cancelled")]
+pub struct Cancelled;
+
 /// Wrapper error that has DiagnosticError as its source, allowing it to be
 /// discovered through the error chain.
 #[derive(Debug)]
@@ -84,6 +93,23 @@ pub struct Diagnostic {
     /// reconstruct a chain of diagnostics across module/evaluation boundaries
     /// without needing to rely on parsing rendered strings.
     pub child: Option<Box<Diagnostic>>,
+
+    /// Stable identifier for the kind of diagnostic: one of the [`codes`] for
+    /// diagnostics raised by the tooling, or the code a design passed to
+    /// `warn()`. Used to suppress it with a `# pcb: allow(<code>)` comment.
+    /// Diagnostics raised by starlark itself carry no code.
+    pub code: Option<String>,
+}
+
+/// One frame of a [`Diagnostic`]'s call stack.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct StackFrame {
+    /// Name of the called function
+    pub function: String,
+    /// File of the call site, if known
+    pub file: Option<String>,
+    /// 1-based line of the call site, if known
+    pub line: Option<usize>,
 }
 
 impl serde::Serialize for Diagnostic {
@@ -91,7 +117,7 @@ impl serde::Serialize for Diagnostic {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("Diagnostic", 6)?;
+        let mut state = serializer.serialize_struct("Diagnostic", 8)?;
         state.serialize_field("path", &self.path)?;
         state.serialize_field("span", &self.span.map(|span| span.to_string()))?;
         state.serialize_field("severity", &self.severity)?;
@@ -100,7 +126,9 @@ impl serde::Serialize for Diagnostic {
             "call_stack",
             &self.call_stack.as_ref().map(|stack| stack.to_string()),
         )?;
+        state.serialize_field("call_stack_frames", &self.call_stack_frames())?;
         state.serialize_field("child", &self.child)?;
+        state.serialize_field("code", &self.code)?;
         state.end()
     }
 }
@@ -114,6 +142,7 @@ impl Diagnostic {
             body: msg.description,
             call_stack: None,
             child: None,
+            code: None,
         }
     }
 
@@ -141,6 +170,7 @@ impl Diagnostic {
             body: err.kind().to_string(),
             call_stack: Some(err.call_stack().clone()),
             child: None,
+            code: None,
         }
     }
 
@@ -151,6 +181,58 @@ impl Diagnostic {
         }
     }
 
+    /// Tag the diagnostic with a stable `code` that suppressions can match on.
+    pub fn with_code(self, code: impl Into<String>) -> Self {
+        Self {
+            code: Some(code.into()),
+            ..self
+        }
+    }
+
+    /// The frames of the call stack, outermost call first. Empty if the
+    /// diagnostic carries no call stack.
+    pub fn call_stack_frames(&self) -> Vec<StackFrame> {
+        let Some(stack) = &self.call_stack else {
+            return Vec::new();
+        };
+        stack
+            .frames
+            .iter()
+            .map(|frame| StackFrame {
+                function: frame.name.clone(),
+                file: frame
+                    .location
+                    .as_ref()
+                    .map(|location| location.filename().to_string()),
+                line: frame
+                    .location
+                    .as_ref()
+                    .map(|location| location.resolve_span().begin.line + 1),
+            })
+            .collect()
+    }
+
+    /// This diagnostic followed by each of its nested children, outermost first.
+    pub fn flatten(&self) -> Vec<&Diagnostic> {
+        let mut chain = vec![self];
+        let mut current = self.child.as_deref();
+        while let Some(diag) = current {
+            chain.push(diag);
+            current = diag.child.as_deref();
+        }
+        chain
+    }
+
+    /// The code of the outermost diagnostic in the chain that has one.
+    ///
+    /// Wrappers such as [`codes::MODULE_LOAD_FAILED`] carry their own code
+    /// while the nested cause may carry none or a different one.
+    pub fn outermost_code(&self) -> Option<&str> {
+        self.flatten()
+            .into_iter()
+            .find_map(|diag| diag.code.as_deref())
+    }
+
     /// Return `true` if the diagnostic severity is `Error`.
     pub fn is_error(&self) -> bool {
         matches!(self.severity, EvalSeverity::Error)
@@ -159,22 +241,15 @@ impl Diagnostic {
 
 impl Display for Diagnostic {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Format: "Error: path:line:col-line:col message"
-        write!(f, "{}: ", self.severity)?;
-
-        if !self.path.is_empty() {
-            write!(f, "{}", self.path)?;
-            if let Some(span) = &self.span {
-                write!(f, ":{span}")?;
+        // Format: "Error[code]: path:line:col-line:col message"
+        for (i, diag) in self.flatten().into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match &diag.code {
+                Some(code) => write!(f, "{}[{code}]: ", diag.severity)?,
+                None => write!(f, "{}: ", diag.severity)?,
             }
-            write!(f, " ")?;
-        }
-
-        write!(f, "{}", self.body)?;
-
-        let mut current = &self.child;
-        while let Some(diag) = current {
-            write!(f, "\n{}: ", diag.severity)?;
 
             if !diag.path.is_empty() {
                 write!(f, "{}", diag.path)?;
@@ -185,7 +260,6 @@ impl Display for Diagnostic {
             }
 
             write!(f, "{}", diag.body)?;
-            current = &diag.child;
         }
         Ok(())
     }
@@ -238,6 +312,28 @@ impl<T> WithDiagnostics<T> {
         self.diagnostics.iter().any(|d| d.is_error())
     }
 
+    /// Iterate over the error-level diagnostics.
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.is_error())
+    }
+
+    /// Iterate over the warning-level diagnostics.
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| matches!(d.severity, EvalSeverity::Warning))
+    }
+
+    /// Number of diagnostics per severity. Severities without any
+    /// diagnostics are absent from the map.
+    pub fn count_by_severity(&self) -> HashMap<EvalSeverity, usize> {
+        let mut counts = HashMap::new();
+        for diagnostic in &self.diagnostics {
+            *counts.entry(diagnostic.severity).or_insert(0) += 1;
+        }
+        counts
+    }
+
     /// Return `true` if evaluation produced an output **and** did not emit
     /// any error-level diagnostics.
     pub fn is_success(&self) -> bool {
@@ -439,12 +535,42 @@ pub trait LoadResolver: Send + Sync {
         load_path: &str,
         current_file: &Path,
     ) -> Result<PathBuf, anyhow::Error> {
-        let spec = LoadSpec::parse(load_path)
-            .ok_or_else(|| anyhow::anyhow!("Invalid load spec: {}", load_path))?;
+        let spec =
+            LoadSpec::parse(load_path).map_err(|e| anyhow::anyhow!("Invalid load spec: {}", e))?;
         self.resolve_spec(file_provider, &spec, current_file)
     }
 }
 
+/// Codes of the diagnostics raised by the evaluator itself
+pub mod codes {
+    /// A `load()` path could not be resolved
+    pub const UNRESOLVED_LOAD_PATH: &str = "E0001";
+
+    /// A component factory could not be created from a loaded file
+    pub const COMPONENT_LOAD_FAILED: &str = "E0002";
+
+    /// A loaded module failed to evaluate
+    pub const MODULE_LOAD_FAILED: &str = "E0003";
+
+    /// A module was instantiated without a `name`
+    pub const MISSING_MODULE_NAME: &str = "E0004";
+
+    /// A module was instantiated with arguments it does not declare
+    pub const UNKNOWN_MODULE_ARGUMENT: &str = "E0005";
+
+    /// A module instance failed to evaluate
+    pub const MODULE_INSTANTIATION_FAILED: &str = "E0006";
+
+    /// The evaluated design could not be converted to a schematic
+    pub const SCHEMATIC_CONVERSION_FAILED: &str = "E0007";
+
+    /// Two components were given the same reference designator
+    pub const DUPLICATE_REFERENCE_DESIGNATOR: &str = "W0001";
+
+    /// A `# pcb: allow(<code>)` comment did not silence anything
+    pub const UNUSED_SUPPRESSION: &str = "W0002";
+}
+
 /// File extension constants and utilities
 pub mod file_extensions {
     use std::ffi::OsStr;
@@ -478,9 +604,13 @@ pub mod file_extensions {
 
 /// Workspace-related utilities
 pub mod workspace {
-    use super::FileProvider;
+    use super::{file_extensions, FileProvider};
+    use std::collections::{BTreeMap, HashMap};
     use std::path::{Path, PathBuf};
 
+    /// Directory names skipped by [`find_design_files`]
+    pub const DEFAULT_IGNORED_DIRS: &[&str] = &[".git", ".pcb", "target", "node_modules"];
+
     /// Walk up the directory tree starting at `start` until a directory containing
     /// `pcb.toml` is found. Returns `Some(PathBuf)` pointing at that directory or
     /// `None` if we reach the filesystem root without finding one.
@@ -501,6 +631,191 @@ pub mod workspace {
         }
         None
     }
+
+    /// Recursively list all `.zen`/`.star` files below `root`, skipping
+    /// [`DEFAULT_IGNORED_DIRS`]. The result is sorted.
+    pub fn find_design_files(file_provider: &dyn FileProvider, root: &Path) -> Vec<PathBuf> {
+        find_design_files_ignoring(file_provider, root, DEFAULT_IGNORED_DIRS)
+    }
+
+    /// Like [`find_design_files`], skipping directories whose name is in `ignored`.
+    pub fn find_design_files_ignoring(
+        file_provider: &dyn FileProvider,
+        root: &Path,
+        ignored: &[&str],
+    ) -> Vec<PathBuf> {
+        fn walk(
+            file_provider: &dyn FileProvider,
+            dir: &Path,
+            ignored: &[&str],
+            files: &mut Vec<PathBuf>,
+        ) {
+            let Ok(entries) = file_provider.list_directory(dir) else {
+                return;
+            };
+            for entry in entries {
+                if file_provider.is_directory(&entry) {
+                    let skip = entry
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| ignored.contains(&name));
+                    if !skip {
+                        walk(file_provider, &entry, ignored, files);
+                    }
+                } else if file_extensions::is_starlark_file(entry.extension()) {
+                    files.push(entry);
+                }
+            }
+        }
+
+        let mut files = Vec::new();
+        walk(file_provider, root, ignored, &mut files);
+        files.sort();
+        files
+    }
+
+    /// Read the `[workspace].members` glob patterns from `root/pcb.toml` and
+    /// expand them to the matching member directories below `root`.
+    ///
+    /// Patterns are matched against paths relative to `root` using `/` as the
+    /// separator, e.g. `boards/*`. Returns an empty list if `pcb.toml` is
+    /// missing or has no `[workspace]` section. The result is sorted.
+    pub fn read_members(file_provider: &dyn FileProvider, root: &Path) -> Vec<PathBuf> {
+        #[derive(Debug, serde::Deserialize)]
+        struct WorkspaceRoot {
+            workspace: Option<WorkspaceSection>,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        struct WorkspaceSection {
+            #[serde(default)]
+            members: Vec<String>,
+        }
+
+        fn walk(
+            file_provider: &dyn FileProvider,
+            root: &Path,
+            dir: &Path,
+            members: &globset::GlobSet,
+            found: &mut Vec<PathBuf>,
+        ) {
+            let Ok(entries) = file_provider.list_directory(dir) else {
+                return;
+            };
+            for entry in entries {
+                if !file_provider.is_directory(&entry) {
+                    continue;
+                }
+                let skip = entry
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| DEFAULT_IGNORED_DIRS.contains(&name));
+                if skip {
+                    continue;
+                }
+                if let Ok(relative) = entry.strip_prefix(root) {
+                    let relative = relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    if members.is_match(&relative) {
+                        found.push(entry.clone());
+                    }
+                }
+                walk(file_provider, root, &entry, members, found);
+            }
+        }
+
+        let Ok(contents) = file_provider.read_file(&root.join("pcb.toml")) else {
+            return Vec::new();
+        };
+        let patterns = match toml::from_str::<WorkspaceRoot>(&contents) {
+            Ok(WorkspaceRoot {
+                workspace: Some(section),
+            }) => section.members,
+            Ok(_) => return Vec::new(),
+            Err(e) => {
+                log::warn!("Failed to parse {}: {e}", root.join("pcb.toml").display());
+                return Vec::new();
+            }
+        };
+        if patterns.is_empty() {
+            return Vec::new();
+        }
+
+        let mut builder = globset::GlobSetBuilder::new();
+        for pattern in &patterns {
+            let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+            match globset::GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+            {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => log::warn!("Invalid workspace member pattern '{pattern}': {e}"),
+            }
+        }
+        let Ok(members) = builder.build() else {
+            return Vec::new();
+        };
+
+        let mut found = Vec::new();
+        walk(file_provider, root, root, &members, &mut found);
+        found.sort();
+        found
+    }
+
+    /// Read the `[refdes]` table from `root/pcb.toml`, mapping component types
+    /// to reference designator prefixes (e.g. `crystal = "Y"`).
+    ///
+    /// Returns an empty map if `pcb.toml` is missing or has no such table.
+    pub fn read_refdes_prefixes(
+        file_provider: &dyn FileProvider,
+        root: &Path,
+    ) -> HashMap<String, String> {
+        #[derive(Debug, serde::Deserialize)]
+        struct RefdesRoot {
+            refdes: Option<HashMap<String, String>>,
+        }
+
+        file_provider
+            .read_file(&root.join("pcb.toml"))
+            .ok()
+            .and_then(|contents| toml::from_str::<RefdesRoot>(&contents).ok())
+            .and_then(|parsed| parsed.refdes)
+            .unwrap_or_default()
+    }
+
+    /// Read the `[lint].ignore` table from `root/pcb.toml`, mapping file globs
+    /// (relative to `root`) to the diagnostic codes or messages to hide in the
+    /// files they match, e.g. `"vendor/**" = ["W0001"]`.
+    ///
+    /// Returns an empty map if `pcb.toml` is missing or has no such table.
+    pub fn read_lint_ignores(
+        file_provider: &dyn FileProvider,
+        root: &Path,
+    ) -> BTreeMap<String, Vec<String>> {
+        #[derive(Debug, serde::Deserialize)]
+        struct LintRoot {
+            lint: Option<Lint>,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        struct Lint {
+            #[serde(default)]
+            ignore: BTreeMap<String, Vec<String>>,
+        }
+
+        file_provider
+            .read_file(&root.join("pcb.toml"))
+            .ok()
+            .and_then(|contents| toml::from_str::<LintRoot>(&contents).ok())
+            .and_then(|parsed| parsed.lint)
+            .map(|lint| lint.ignore)
+            .unwrap_or_default()
+    }
 }
 
 /// Normalize a path by resolving .. and . components
@@ -533,6 +848,35 @@ pub struct CoreLoadResolver {
     /// Maps resolved paths to their original LoadSpecs
     /// This allows us to resolve relative paths from remote files correctly
     path_to_spec: Arc<Mutex<HashMap<PathBuf, LoadSpec>>>,
+    /// Reject local paths that resolve outside `workspace_root`
+    confine_to_workspace: bool,
+    /// Remote specs fetched so far, keyed by their resolved spec
+    fetched: Arc<Mutex<HashMap<LoadSpec, FetchedRemote>>>,
+    /// How long a fetch of a mutable rev (branch, tag, package) is reused
+    remote_ttl: Duration,
+}
+
+/// How long a fetched branch or tag is reused before asking the
+/// [`RemoteFetcher`] again.
+pub const DEFAULT_REMOTE_TTL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone)]
+struct FetchedRemote {
+    path: PathBuf,
+    /// `None` where no clock is available, so the entry is never fresh
+    fetched_at: Option<Instant>,
+}
+
+/// Current time, or `None` on targets without a monotonic clock.
+fn now() -> Option<Instant> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        Some(Instant::now())
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        None
+    }
 }
 
 impl CoreLoadResolver {
@@ -547,6 +891,9 @@ impl CoreLoadResolver {
             remote_fetcher,
             workspace_root,
             path_to_spec: Arc::new(Mutex::new(HashMap::new())),
+            confine_to_workspace: false,
+            fetched: Arc::new(Mutex::new(HashMap::new())),
+            remote_ttl: DEFAULT_REMOTE_TTL,
         }
     }
 
@@ -562,6 +909,85 @@ impl CoreLoadResolver {
             remote_fetcher,
             workspace_root,
             path_to_spec: Arc::new(Mutex::new(HashMap::new())),
+            confine_to_workspace: false,
+            fetched: Arc::new(Mutex::new(HashMap::new())),
+            remote_ttl: DEFAULT_REMOTE_TTL,
+        }
+    }
+
+    /// When set, local loads (relative, absolute and workspace paths) that
+    /// resolve outside the workspace root are rejected, as are all local loads
+    /// if there is no workspace root. Remote packages are not affected.
+    ///
+    /// Use this when evaluating untrusted designs.
+    pub fn set_confine_to_workspace(mut self, confine: bool) -> Self {
+        self.confine_to_workspace = confine;
+        self
+    }
+
+    /// How long a fetch of a branch, tag or package is reused before the
+    /// [`RemoteFetcher`] is asked again. Specs pinned to a full commit SHA
+    /// (see [`LoadSpec::is_immutable_rev`]) are fetched once and cached for
+    /// the lifetime of the resolver. Defaults to [`DEFAULT_REMOTE_TTL`].
+    pub fn set_remote_ttl(mut self, ttl: Duration) -> Self {
+        self.remote_ttl = ttl;
+        self
+    }
+
+    /// Fetch `spec`, reusing an earlier fetch if it is still valid.
+    fn fetch_remote_cached(&self, spec: &LoadSpec) -> Result<PathBuf, anyhow::Error> {
+        let now = now();
+
+        if let Some(entry) = self.fetched.lock().unwrap().get(spec) {
+            let fresh = spec.is_immutable_rev()
+                || matches!(
+                    (now, entry.fetched_at),
+                    (Some(now), Some(at)) if now.duration_since(at) < self.remote_ttl
+                );
+            if fresh {
+                return Ok(entry.path.clone());
+            }
+        }
+
+        let path = self
+            .remote_fetcher
+            .fetch_remote(spec, self.workspace_root.as_deref())?;
+        self.fetched.lock().unwrap().insert(
+            spec.clone(),
+            FetchedRemote {
+                path: path.clone(),
+                fetched_at: now,
+            },
+        );
+        Ok(path)
+    }
+
+    /// Check `path` against the workspace root if confinement is enabled.
+    fn confine(
+        &self,
+        file_provider: &dyn FileProvider,
+        path: PathBuf,
+    ) -> Result<PathBuf, anyhow::Error> {
+        if !self.confine_to_workspace {
+            return Ok(path);
+        }
+
+        let workspace_root = self.workspace_root.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot load '{}': loads are confined to the workspace but there is no workspace root",
+                path.display()
+            )
+        })?;
+        let canonical_root = file_provider.canonicalize(workspace_root)?;
+
+        if path.starts_with(&canonical_root) {
+            Ok(path)
+        } else {
+            Err(anyhow::anyhow!(
+                "Cannot load '{}': path is outside the workspace root {}",
+                path.display(),
+                canonical_root.display()
+            ))
         }
     }
 
@@ -718,9 +1144,7 @@ impl LoadResolver for CoreLoadResolver {
         match &resolved_spec {
             // Remote specs need to be fetched
             LoadSpec::Package { .. } | LoadSpec::Github { .. } | LoadSpec::Gitlab { .. } => {
-                let resolved_path = self
-                    .remote_fetcher
-                    .fetch_remote(&resolved_spec, self.workspace_root.as_deref())?;
+                let resolved_path = self.fetch_remote_cached(&resolved_spec)?;
 
                 let canonical_resolved_path = file_provider.canonicalize(&resolved_path)?;
 
@@ -749,7 +1173,7 @@ impl LoadResolver for CoreLoadResolver {
                 let canonical_path = file_provider.canonicalize(&resolved_path)?;
 
                 if file_provider.exists(&canonical_path) {
-                    Ok(canonical_path)
+                    self.confine(file_provider, canonical_path)
                 } else {
                     Err(anyhow::anyhow!(
                         "File not found: {}",
@@ -765,7 +1189,7 @@ impl LoadResolver for CoreLoadResolver {
                     let canonical_path = file_provider.canonicalize(path)?;
 
                     if file_provider.exists(&canonical_path) {
-                        Ok(canonical_path)
+                        self.confine(file_provider, canonical_path)
                     } else {
                         Err(anyhow::anyhow!(
                             "File not found: {}",
@@ -788,7 +1212,7 @@ impl LoadResolver for CoreLoadResolver {
                     let canonical_path = file_provider.canonicalize(&resolved_path)?;
 
                     if file_provider.exists(&canonical_path) {
-                        Ok(canonical_path)
+                        self.confine(file_provider, canonical_path)
                     } else {
                         Err(anyhow::anyhow!(
                             "File not found: {}",
@@ -807,7 +1231,7 @@ impl LoadResolver for CoreLoadResolver {
                     let canonical_path = file_provider.canonicalize(&resolved_path)?;
 
                     if file_provider.exists(&canonical_path) {
-                        Ok(canonical_path)
+                        self.confine(file_provider, canonical_path)
                     } else {
                         Err(anyhow::anyhow!(
                             "File not found: {}",
@@ -819,3 +1243,55 @@ impl LoadResolver for CoreLoadResolver {
         }
     }
 }
+
+/// Load resolver decorator that memoizes successful resolutions.
+///
+/// Results are keyed by the loading file and the spec, so the same spec loaded
+/// from different files is resolved separately. Failed resolutions are not
+/// cached and are retried on the next call.
+pub struct CachingLoadResolver<R: LoadResolver> {
+    inner: R,
+    cache: Mutex<HashMap<(PathBuf, LoadSpec), PathBuf>>,
+}
+
+impl<R: LoadResolver> CachingLoadResolver<R> {
+    /// Wrap `inner`, starting with an empty cache.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The wrapped resolver.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Drop every cached resolution made from `path` or resolving to `path`.
+    pub fn invalidate(&self, path: &Path) {
+        self.cache
+            .lock()
+            .unwrap()
+            .retain(|(current_file, _), resolved| current_file != path && resolved != path);
+    }
+}
+
+impl<R: LoadResolver> LoadResolver for CachingLoadResolver<R> {
+    fn resolve_spec(
+        &self,
+        file_provider: &dyn FileProvider,
+        spec: &LoadSpec,
+        current_file: &Path,
+    ) -> Result<PathBuf, anyhow::Error> {
+        let key = (current_file.to_path_buf(), spec.clone());
+        if let Some(resolved) = self.cache.lock().unwrap().get(&key) {
+            return Ok(resolved.clone());
+        }
+
+        // Resolve without holding the lock so the inner resolver may recurse
+        let resolved = self.inner.resolve_spec(file_provider, spec, current_file)?;
+        self.cache.lock().unwrap().insert(key, resolved.clone());
+        Ok(resolved)
+    }
+}
@@ -14,6 +14,21 @@ pub const DEFAULT_GITHUB_REV: &str = "HEAD";
 /// spec, e.g. `@gitlab/user/repo/path.zen`.
 pub const DEFAULT_GITLAB_REV: &str = "HEAD";
 
+/// Reason a `load()` string could not be parsed into a [`LoadSpec`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LoadSpecError {
+    #[error("load path is empty")]
+    EmptyPath,
+    #[error("`{spec}` is missing a package name, expected `@<package>[:<tag>]/<path>`")]
+    EmptyPackage { spec: String },
+    #[error("`{spec}` has an empty tag after `:`")]
+    EmptyTag { spec: String },
+    #[error("`{spec}` is missing a repository, expected `@{host}/<user>/<repo>[:<rev>]/<path>`")]
+    IncompleteRepository { host: &'static str, spec: String },
+    #[error("`{spec}` uses unsupported scheme `{scheme}://`, use `@github/...`, `@gitlab/...` or a file path")]
+    UnknownScheme { scheme: String, spec: String },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LoadSpec {
     Package {
@@ -80,9 +95,24 @@ impl LoadSpec {
     ///   Examples: `"./math.zen"`, `"../utils/helper.zen"`, `"/absolute/path/file.zen"`.
     ///
     /// The function does not touch the filesystem – it only performs syntactic
-    /// parsing.
-    pub fn parse(s: &str) -> Option<LoadSpec> {
-        if let Some(rest) = s.strip_prefix("@github/") {
+    /// parsing. On failure the returned [`LoadSpecError`] says which part of the
+    /// spec is malformed.
+    pub fn parse(s: &str) -> Result<LoadSpec, LoadSpecError> {
+        let incomplete = |host| LoadSpecError::IncompleteRepository {
+            host,
+            spec: s.to_string(),
+        };
+        let empty_tag = || LoadSpecError::EmptyTag {
+            spec: s.to_string(),
+        };
+
+        if s.is_empty() {
+            Err(LoadSpecError::EmptyPath)
+        } else if s == "@github" {
+            Err(incomplete("github"))
+        } else if s == "@gitlab" {
+            Err(incomplete("gitlab"))
+        } else if let Some(rest) = s.strip_prefix("@github/") {
             // GitHub: @github/user/repo:rev/path  (must come before generic "@pkg" handling)
             let mut user_repo_rev_and_path = rest.splitn(3, '/');
             let user = user_repo_rev_and_path.next().unwrap_or("").to_string();
@@ -91,7 +121,7 @@ impl LoadSpec {
 
             // Validate that we have both user and repo
             if user.is_empty() || repo_and_rev.is_empty() {
-                return None;
+                return Err(incomplete("github"));
             }
 
             let (repo, rev) = if let Some((repo, rev)) = repo_and_rev.split_once(':') {
@@ -102,10 +132,13 @@ impl LoadSpec {
 
             // Ensure repo name is not empty
             if repo.is_empty() {
-                return None;
+                return Err(incomplete("github"));
+            }
+            if rev.is_empty() {
+                return Err(empty_tag());
             }
 
-            Some(LoadSpec::Github {
+            Ok(LoadSpec::Github {
                 user,
                 repo,
                 rev,
@@ -122,19 +155,26 @@ impl LoadSpec {
                 let project_part = &rest[..colon_pos];
                 let after_colon = &rest[colon_pos + 1..];
 
+                if project_part.split('/').filter(|p| !p.is_empty()).count() < 2 {
+                    return Err(incomplete("gitlab"));
+                }
+                if after_colon.is_empty() || after_colon.starts_with('/') {
+                    return Err(empty_tag());
+                }
+
                 // Find the first slash after the colon to separate rev from path
                 if let Some(slash_pos) = after_colon.find('/') {
                     let rev = after_colon[..slash_pos].to_string();
                     let file_path = after_colon[slash_pos + 1..].to_string();
 
-                    Some(LoadSpec::Gitlab {
+                    Ok(LoadSpec::Gitlab {
                         project_path: project_part.to_string(),
                         rev,
                         path: PathBuf::from(file_path),
                     })
                 } else {
                     // No file path after revision
-                    Some(LoadSpec::Gitlab {
+                    Ok(LoadSpec::Gitlab {
                         project_path: project_part.to_string(),
                         rev: after_colon.to_string(),
                         path: PathBuf::new(),
@@ -143,17 +183,17 @@ impl LoadSpec {
             } else {
                 // No revision specified, assume first 2 parts are the project path
                 let parts: Vec<&str> = rest.splitn(3, '/').collect();
-                if parts.len() >= 2 {
+                if parts.len() >= 2 && !parts[0].is_empty() && !parts[1].is_empty() {
                     let project_path = format!("{}/{}", parts[0], parts[1]);
                     let file_path = parts.get(2).unwrap_or(&"").to_string();
 
-                    Some(LoadSpec::Gitlab {
+                    Ok(LoadSpec::Gitlab {
                         project_path,
                         rev: DEFAULT_GITLAB_REV.to_string(),
                         path: PathBuf::from(file_path),
                     })
                 } else {
-                    None
+                    Err(incomplete("gitlab"))
                 }
             }
         } else if let Some(rest) = s.strip_prefix('@') {
@@ -165,7 +205,9 @@ impl LoadSpec {
 
             // Validate that we have a non-empty package name
             if pkg_and_tag.is_empty() {
-                return None;
+                return Err(LoadSpecError::EmptyPackage {
+                    spec: s.to_string(),
+                });
             }
 
             let (package, tag) = if let Some((pkg, tag)) = pkg_and_tag.split_once(':') {
@@ -176,32 +218,55 @@ impl LoadSpec {
 
             // Ensure package name is not empty
             if package.is_empty() {
-                return None;
+                return Err(LoadSpecError::EmptyPackage {
+                    spec: s.to_string(),
+                });
+            }
+            if tag.is_empty() {
+                return Err(empty_tag());
             }
 
             // Reject invalid GitHub/GitLab specs that don't have the proper format
-            if package == "github" || package == "gitlab" {
-                return None;
+            if package == "github" {
+                return Err(incomplete("github"));
+            }
+            if package == "gitlab" {
+                return Err(incomplete("gitlab"));
             }
 
-            Some(LoadSpec::Package {
+            Ok(LoadSpec::Package {
                 package,
                 tag,
                 path: PathBuf::from(rel_path),
             })
         } else if let Some(workspace_path) = s.strip_prefix("//") {
             // Workspace-relative path: //path/to/file.zen
-            Some(LoadSpec::WorkspacePath {
+            Ok(LoadSpec::WorkspacePath {
                 path: PathBuf::from(workspace_path),
             })
+        } else if let Some((scheme, _)) = s.split_once("://").filter(|(scheme, _)| {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }) {
+            Err(LoadSpecError::UnknownScheme {
+                scheme: scheme.to_string(),
+                spec: s.to_string(),
+            })
         } else {
             // Raw file path (relative or absolute)
-            Some(LoadSpec::Path {
+            Ok(LoadSpec::Path {
                 path: PathBuf::from(s),
             })
         }
     }
 
+    /// Like [`LoadSpec::parse`], but discards the reason a spec is invalid.
+    pub fn parse_opt(s: &str) -> Option<LoadSpec> {
+        Self::parse(s).ok()
+    }
+
     /// Default package aliases that are always available
     pub fn default_package_aliases() -> HashMap<String, String> {
         let mut map = HashMap::new();
@@ -252,66 +317,66 @@ impl LoadSpec {
 
                 if let Some(target) = aliases.get(package) {
                     // Parse the alias target
-                    if let Some(mut resolved_spec) = LoadSpec::parse(target) {
-                        // If caller explicitly specified a tag (non-default), override the alias's tag
-                        if tag != DEFAULT_PKG_TAG {
-                            match &mut resolved_spec {
-                                LoadSpec::Package { tag: alias_tag, .. } => {
-                                    *alias_tag = tag.clone();
-                                }
-                                LoadSpec::Github { rev: alias_rev, .. } => {
-                                    *alias_rev = tag.clone();
-                                }
-                                LoadSpec::Gitlab { rev: alias_rev, .. } => {
-                                    *alias_rev = tag.clone();
-                                }
-                                // Path and WorkspacePath specs don't support tags
-                                LoadSpec::Path { .. } | LoadSpec::WorkspacePath { .. } => {
-                                    return Err(anyhow::anyhow!(
-                                        "Cannot apply tag '{}' to path-based alias target '{}'",
-                                        tag,
-                                        target
-                                    ));
-                                }
+                    let mut resolved_spec = LoadSpec::parse(target).map_err(|e| {
+                        anyhow::anyhow!(
+                            "Invalid alias target for package '{}': '{}': {}",
+                            package,
+                            target,
+                            e
+                        )
+                    })?;
+
+                    // If caller explicitly specified a tag (non-default), override the alias's tag
+                    if tag != DEFAULT_PKG_TAG {
+                        match &mut resolved_spec {
+                            LoadSpec::Package { tag: alias_tag, .. } => {
+                                *alias_tag = tag.clone();
+                            }
+                            LoadSpec::Github { rev: alias_rev, .. } => {
+                                *alias_rev = tag.clone();
+                            }
+                            LoadSpec::Gitlab { rev: alias_rev, .. } => {
+                                *alias_rev = tag.clone();
+                            }
+                            // Path and WorkspacePath specs don't support tags
+                            LoadSpec::Path { .. } | LoadSpec::WorkspacePath { .. } => {
+                                return Err(anyhow::anyhow!(
+                                    "Cannot apply tag '{}' to path-based alias target '{}'",
+                                    tag,
+                                    target
+                                ));
                             }
                         }
+                    }
 
-                        // Append the path if needed
-                        if !path.as_os_str().is_empty() {
-                            match &mut resolved_spec {
-                                LoadSpec::Package {
-                                    path: alias_path, ..
-                                } => {
-                                    *alias_path = alias_path.join(path);
-                                }
-                                LoadSpec::Github {
-                                    path: alias_path, ..
-                                } => {
-                                    *alias_path = alias_path.join(path);
-                                }
-                                LoadSpec::Gitlab {
-                                    path: alias_path, ..
-                                } => {
-                                    *alias_path = alias_path.join(path);
-                                }
-                                LoadSpec::Path { path: alias_path } => {
-                                    *alias_path = alias_path.join(path);
-                                }
-                                LoadSpec::WorkspacePath { path: alias_path } => {
-                                    *alias_path = alias_path.join(path);
-                                }
+                    // Append the path if needed
+                    if !path.as_os_str().is_empty() {
+                        match &mut resolved_spec {
+                            LoadSpec::Package {
+                                path: alias_path, ..
+                            } => {
+                                *alias_path = alias_path.join(path);
+                            }
+                            LoadSpec::Github {
+                                path: alias_path, ..
+                            } => {
+                                *alias_path = alias_path.join(path);
+                            }
+                            LoadSpec::Gitlab {
+                                path: alias_path, ..
+                            } => {
+                                *alias_path = alias_path.join(path);
+                            }
+                            LoadSpec::Path { path: alias_path } => {
+                                *alias_path = alias_path.join(path);
+                            }
+                            LoadSpec::WorkspacePath { path: alias_path } => {
+                                *alias_path = alias_path.join(path);
                             }
                         }
-
-                        Ok(resolved_spec)
-                    } else {
-                        // Invalid alias target
-                        Err(anyhow::anyhow!(
-                            "Invalid alias target for package '{}': '{}'",
-                            package,
-                            target
-                        ))
                     }
+
+                    Ok(resolved_spec)
                 } else {
                     // No alias found, return original spec
                     Ok(self.clone())
@@ -331,6 +396,18 @@ impl LoadSpec {
         )
     }
 
+    /// Check if this LoadSpec pins a remote revision that can never change.
+    /// Returns true only for Github and Gitlab specs whose rev is a full
+    /// 40-character commit SHA; branches, tags and packages may move.
+    pub fn is_immutable_rev(&self) -> bool {
+        match self {
+            LoadSpec::Github { rev, .. } | LoadSpec::Gitlab { rev, .. } => {
+                rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit())
+            }
+            _ => false,
+        }
+    }
+
     /// Convert the LoadSpec back to a load string representation.
     /// This is useful for error messages and debugging.
     pub fn to_load_string(&self) -> String {
@@ -443,7 +520,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_package_no_tag() {
-        let spec = LoadSpec::parse("@stdlib/math.zen");
+        let spec = LoadSpec::parse_opt("@stdlib/math.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Package {
@@ -456,7 +533,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_package_with_tag() {
-        let spec = LoadSpec::parse("@stdlib:1.2.3/math.zen");
+        let spec = LoadSpec::parse_opt("@stdlib:1.2.3/math.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Package {
@@ -469,7 +546,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_github_no_rev() {
-        let spec = LoadSpec::parse("@github/foo/bar/scripts/build.zen");
+        let spec = LoadSpec::parse_opt("@github/foo/bar/scripts/build.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Github {
@@ -483,7 +560,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_github_with_rev() {
-        let spec = LoadSpec::parse("@github/foo/bar:abc123/scripts/build.zen");
+        let spec = LoadSpec::parse_opt("@github/foo/bar:abc123/scripts/build.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Github {
@@ -497,7 +574,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_github_empty_path() {
-        let spec = LoadSpec::parse("@github/foo/bar:abc123/");
+        let spec = LoadSpec::parse_opt("@github/foo/bar:abc123/");
         assert_eq!(
             spec,
             Some(LoadSpec::Github {
@@ -511,7 +588,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_github_no_path() {
-        let spec = LoadSpec::parse("@github/foo/bar:abc123");
+        let spec = LoadSpec::parse_opt("@github/foo/bar:abc123");
         assert_eq!(
             spec,
             Some(LoadSpec::Github {
@@ -525,7 +602,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_gitlab_with_rev() {
-        let spec = LoadSpec::parse("@gitlab/foo/bar:abc123/src/lib.zen");
+        let spec = LoadSpec::parse_opt("@gitlab/foo/bar:abc123/src/lib.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -538,7 +615,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_gitlab_no_rev() {
-        let spec = LoadSpec::parse("@gitlab/foo/bar/src/lib.zen");
+        let spec = LoadSpec::parse_opt("@gitlab/foo/bar/src/lib.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -551,7 +628,8 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_gitlab_nested_groups_with_rev() {
-        let spec = LoadSpec::parse("@gitlab/kicad/libraries/kicad-symbols:main/Device.kicad_sym");
+        let spec =
+            LoadSpec::parse_opt("@gitlab/kicad/libraries/kicad-symbols:main/Device.kicad_sym");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -565,7 +643,7 @@ mod tests {
     #[test]
     fn test_parse_load_spec_gitlab_sha() {
         let sha = "a1b2c3d4e5f6789012345678901234567890abcd";
-        let spec = LoadSpec::parse(&format!("@gitlab/foo/bar:{sha}/src/lib.zen"));
+        let spec = LoadSpec::parse_opt(&format!("@gitlab/foo/bar:{sha}/src/lib.zen"));
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -578,7 +656,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_gitlab_nested_groups_no_rev() {
-        let spec = LoadSpec::parse("@gitlab/user/repo/src/lib.zen");
+        let spec = LoadSpec::parse_opt("@gitlab/user/repo/src/lib.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -591,7 +669,8 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_gitlab_nested_groups_with_tag() {
-        let spec = LoadSpec::parse("@gitlab/kicad/libraries/kicad-symbols:v7.0.0/Device.kicad_sym");
+        let spec =
+            LoadSpec::parse_opt("@gitlab/kicad/libraries/kicad-symbols:v7.0.0/Device.kicad_sym");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -604,7 +683,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_workspace_path() {
-        let spec = LoadSpec::parse("//src/components/resistor.zen");
+        let spec = LoadSpec::parse_opt("//src/components/resistor.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::WorkspacePath {
@@ -615,7 +694,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_workspace_path_root() {
-        let spec = LoadSpec::parse("//math.zen");
+        let spec = LoadSpec::parse_opt("//math.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::WorkspacePath {
@@ -626,7 +705,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_workspace_path_empty() {
-        let spec = LoadSpec::parse("//");
+        let spec = LoadSpec::parse_opt("//");
         assert_eq!(
             spec,
             Some(LoadSpec::WorkspacePath {
@@ -637,7 +716,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_relative_path() {
-        let spec = LoadSpec::parse("./math.zen");
+        let spec = LoadSpec::parse_opt("./math.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Path {
@@ -648,7 +727,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_relative_path_parent() {
-        let spec = LoadSpec::parse("../utils/helper.zen");
+        let spec = LoadSpec::parse_opt("../utils/helper.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Path {
@@ -659,7 +738,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_absolute_path() {
-        let spec = LoadSpec::parse("/absolute/path/file.zen");
+        let spec = LoadSpec::parse_opt("/absolute/path/file.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Path {
@@ -670,7 +749,7 @@ mod tests {
 
     #[test]
     fn test_parse_load_spec_simple_filename() {
-        let spec = LoadSpec::parse("math.zen");
+        let spec = LoadSpec::parse_opt("math.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Path {
@@ -682,7 +761,7 @@ mod tests {
     #[test]
     fn test_parse_load_spec_invalid() {
         // These should still return Some(LoadSpec::Path) since we now handle all strings
-        let spec = LoadSpec::parse("not_a_load_spec");
+        let spec = LoadSpec::parse_opt("not_a_load_spec");
         assert_eq!(
             spec,
             Some(LoadSpec::Path {
@@ -691,10 +770,61 @@ mod tests {
         );
 
         // Invalid @ specs should still return None
-        assert_eq!(LoadSpec::parse("@"), None);
-        assert_eq!(LoadSpec::parse("@github"), None);
-        assert_eq!(LoadSpec::parse("@github/"), None);
-        assert_eq!(LoadSpec::parse("@github/user"), None);
+        assert_eq!(LoadSpec::parse_opt("@"), None);
+        assert_eq!(LoadSpec::parse_opt("@github"), None);
+        assert_eq!(LoadSpec::parse_opt("@github/"), None);
+        assert_eq!(LoadSpec::parse_opt("@github/user"), None);
+    }
+
+    #[test]
+    fn test_parse_load_spec_error_reasons() {
+        let err = |s: &str| LoadSpec::parse(s).unwrap_err();
+
+        assert_eq!(err(""), LoadSpecError::EmptyPath);
+        assert_eq!(
+            err("@:1.0/math.zen"),
+            LoadSpecError::EmptyPackage {
+                spec: "@:1.0/math.zen".to_string()
+            }
+        );
+        assert_eq!(
+            err("@stdlib:/math.zen"),
+            LoadSpecError::EmptyTag {
+                spec: "@stdlib:/math.zen".to_string()
+            }
+        );
+        assert_eq!(
+            err("@github/foo/bar:"),
+            LoadSpecError::EmptyTag {
+                spec: "@github/foo/bar:".to_string()
+            }
+        );
+        assert_eq!(
+            err("@github/user"),
+            LoadSpecError::IncompleteRepository {
+                host: "github",
+                spec: "@github/user".to_string()
+            }
+        );
+        assert_eq!(
+            err("@gitlab/foo:main/lib.zen"),
+            LoadSpecError::IncompleteRepository {
+                host: "gitlab",
+                spec: "@gitlab/foo:main/lib.zen".to_string()
+            }
+        );
+        assert_eq!(
+            err("https://example.com/lib.zen"),
+            LoadSpecError::UnknownScheme {
+                scheme: "https".to_string(),
+                spec: "https://example.com/lib.zen".to_string()
+            }
+        );
+
+        assert_eq!(
+            err("@github").to_string(),
+            "`@github` is missing a repository, expected `@github/<user>/<repo>[:<rev>]/<path>`"
+        );
     }
 
     #[test]
@@ -839,6 +969,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_immutable_rev() {
+        let github = |rev: &str| LoadSpec::Github {
+            user: "user".to_string(),
+            repo: "repo".to_string(),
+            rev: rev.to_string(),
+            path: PathBuf::from("lib.zen"),
+        };
+
+        assert!(github("0123456789abcdef0123456789ABCDEF01234567").is_immutable_rev());
+        assert!(!github("main").is_immutable_rev());
+        assert!(!github("v1.0.0").is_immutable_rev());
+        assert!(!github("0123456").is_immutable_rev());
+        assert!(!github("0123456789abcdef0123456789abcdef0123456g").is_immutable_rev());
+
+        let gitlab = LoadSpec::Gitlab {
+            project_path: "group/project".to_string(),
+            rev: "a".repeat(40),
+            path: PathBuf::from("lib.zen"),
+        };
+        assert!(gitlab.is_immutable_rev());
+
+        let package = LoadSpec::Package {
+            package: "stdlib".to_string(),
+            tag: "a".repeat(40),
+            path: PathBuf::from("math.zen"),
+        };
+        assert!(!package.is_immutable_rev());
+    }
+
     // Tests for resolve_load_spec function
     mod resolve_load_spec_tests {
         use super::*;
@@ -0,0 +1,94 @@
+use picoplace_core::{
+    CachingLoadResolver, FileProvider, InMemoryFileProvider, LoadResolver, LoadSpec,
+};
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Resolver that joins relative paths onto the loading file's directory and
+/// counts how often it is asked
+#[derive(Default)]
+struct CountingResolver {
+    calls: AtomicUsize,
+}
+
+impl CountingResolver {
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+impl LoadResolver for CountingResolver {
+    fn resolve_spec(
+        &self,
+        _file_provider: &dyn FileProvider,
+        spec: &LoadSpec,
+        current_file: &Path,
+    ) -> Result<PathBuf, anyhow::Error> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match spec {
+            LoadSpec::Path { path } if !path.starts_with("missing") => {
+                Ok(current_file.parent().unwrap().join(path))
+            }
+            _ => Err(anyhow::anyhow!("cannot resolve {spec:?}")),
+        }
+    }
+}
+
+#[test]
+fn test_caches_successful_resolutions() {
+    let provider = InMemoryFileProvider::empty();
+    let resolver = CachingLoadResolver::new(CountingResolver::default());
+    let board = Path::new("/project/board.zen");
+
+    for _ in 0..3 {
+        let resolved = resolver.resolve_path(&provider, "lib.zen", board).unwrap();
+        assert_eq!(resolved, PathBuf::from("/project/lib.zen"));
+    }
+    assert_eq!(resolver.inner().calls(), 1);
+
+    // The same spec from another file is a separate entry
+    let resolved = resolver
+        .resolve_path(&provider, "lib.zen", Path::new("/other/board.zen"))
+        .unwrap();
+    assert_eq!(resolved, PathBuf::from("/other/lib.zen"));
+    assert_eq!(resolver.inner().calls(), 2);
+}
+
+#[test]
+fn test_errors_are_not_cached() {
+    let provider = InMemoryFileProvider::empty();
+    let resolver = CachingLoadResolver::new(CountingResolver::default());
+    let board = Path::new("/project/board.zen");
+
+    assert!(resolver
+        .resolve_path(&provider, "missing.zen", board)
+        .is_err());
+    assert!(resolver
+        .resolve_path(&provider, "missing.zen", board)
+        .is_err());
+    assert_eq!(resolver.inner().calls(), 2);
+}
+
+#[test]
+fn test_invalidate() {
+    let provider = InMemoryFileProvider::empty();
+    let resolver = CachingLoadResolver::new(CountingResolver::default());
+    let board = Path::new("/project/board.zen");
+    let lib = Path::new("/project/lib.zen");
+
+    resolver.resolve_path(&provider, "lib.zen", board).unwrap();
+    resolver.resolve_path(&provider, "util.zen", lib).unwrap();
+    assert_eq!(resolver.inner().calls(), 2);
+
+    // Invalidating the resolved path drops the entry loaded from board.zen
+    resolver.invalidate(lib);
+    resolver.resolve_path(&provider, "lib.zen", board).unwrap();
+    resolver.resolve_path(&provider, "util.zen", lib).unwrap();
+    assert_eq!(resolver.inner().calls(), 4);
+
+    // Entries for unrelated files stay cached
+    resolver.invalidate(Path::new("/project/unrelated.zen"));
+    resolver.resolve_path(&provider, "lib.zen", board).unwrap();
+    assert_eq!(resolver.inner().calls(), 4);
+}
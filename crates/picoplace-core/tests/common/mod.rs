@@ -1,8 +1,11 @@
 #![allow(dead_code)]
 
-use picoplace_core::{FileProvider, FileProviderError};
+use picoplace_core::{
+    CoreLoadResolver, EvalContext, FileProvider, FileProviderError, NoopRemoteFetcher,
+};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// In-memory file provider for tests
 #[derive(Clone)]
@@ -177,6 +180,20 @@ impl FileProvider for InMemoryFileProvider {
     }
 }
 
+/// Evaluation context that reads `files` (path -> contents) from memory and
+/// resolves loads against `/`
+pub fn eval_context(files: HashMap<String, String>) -> EvalContext {
+    let file_provider = Arc::new(picoplace_core::InMemoryFileProvider::new(files));
+    let load_resolver = Arc::new(CoreLoadResolver::new(
+        file_provider.clone(),
+        Arc::new(NoopRemoteFetcher),
+        Some(PathBuf::from("/")),
+    ));
+    EvalContext::new()
+        .set_file_provider(file_provider)
+        .set_load_resolver(load_resolver)
+}
+
 /// Macro to create a test that evaluates Starlark code and compares the output to a snapshot.
 ///
 /// # Example
@@ -517,3 +517,126 @@ fn test_resolve_workspace_path_from_remote_with_mapping() {
         _ => panic!("Expected GitHub spec for utils.zen"),
     }
 }
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn test_confine_to_workspace() {
+    let file_provider = Arc::new(MockFileProvider::new());
+    let remote_fetcher = Arc::new(MockRemoteFetcher::new());
+
+    file_provider.add_file("/workspace/boards/main.zen", "# Main board");
+    file_provider.add_file("/workspace/modules/power.zen", "# Power module");
+    file_provider.add_file("/etc/passwd", "root:x:0:0");
+
+    let resolver = CoreLoadResolver::new(
+        file_provider.clone(),
+        remote_fetcher,
+        Some(PathBuf::from("/workspace")),
+    )
+    .set_confine_to_workspace(true);
+    let current_file = PathBuf::from("/workspace/boards/main.zen");
+
+    // Climbing up is fine as long as we stay inside the workspace
+    let in_bounds = resolver
+        .resolve_path(
+            file_provider.as_ref(),
+            "../modules/power.zen",
+            &current_file,
+        )
+        .unwrap();
+    assert_eq!(in_bounds, PathBuf::from("/workspace/modules/power.zen"));
+
+    let escaping = resolver
+        .resolve_path(file_provider.as_ref(), "../../etc/passwd", &current_file)
+        .unwrap_err();
+    assert!(escaping.to_string().contains("outside the workspace root"));
+
+    assert!(resolver
+        .resolve_path(file_provider.as_ref(), "/etc/passwd", &current_file)
+        .is_err());
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn test_escaping_load_allowed_without_confinement() {
+    let file_provider = Arc::new(MockFileProvider::new());
+    let remote_fetcher = Arc::new(MockRemoteFetcher::new());
+
+    file_provider.add_file("/workspace/boards/main.zen", "# Main board");
+    file_provider.add_file("/etc/passwd", "root:x:0:0");
+
+    let resolver = CoreLoadResolver::new(
+        file_provider.clone(),
+        remote_fetcher,
+        Some(PathBuf::from("/workspace")),
+    );
+
+    let resolved = resolver
+        .resolve_path(
+            file_provider.as_ref(),
+            "../../etc/passwd",
+            Path::new("/workspace/boards/main.zen"),
+        )
+        .unwrap();
+    assert_eq!(resolved, PathBuf::from("/etc/passwd"));
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn test_remote_fetch_caching() {
+    let file_provider = Arc::new(MockFileProvider::new());
+    let remote_fetcher = Arc::new(MockRemoteFetcher::new());
+
+    let github = |rev: &str| LoadSpec::Github {
+        user: "diodeinc".to_string(),
+        repo: "stdlib".to_string(),
+        rev: rev.to_string(),
+        path: PathBuf::from("zen/generics/Resistor.zen"),
+    };
+    let pinned = github("0123456789abcdef0123456789abcdef01234567");
+    let branch = github("main");
+
+    for (spec, cache_path) in [
+        (&pinned, "/cache/github/diodeinc/stdlib/pinned/Resistor.zen"),
+        (&branch, "/cache/github/diodeinc/stdlib/main/Resistor.zen"),
+    ] {
+        remote_fetcher.add_fetch_result(&spec.to_load_string(), Path::new(cache_path));
+        file_provider.add_file(cache_path, "# Resistor implementation");
+    }
+
+    // With a zero TTL branch refs are re-fetched on every load, while the
+    // pinned SHA is only fetched once.
+    let resolver = CoreLoadResolver::new(
+        file_provider.clone(),
+        remote_fetcher.clone(),
+        Some(PathBuf::from("/workspace")),
+    )
+    .set_remote_ttl(std::time::Duration::ZERO);
+
+    let current_file = PathBuf::from("/workspace/main.zen");
+    for _ in 0..3 {
+        for spec in [&pinned, &branch] {
+            resolver
+                .resolve_spec(file_provider.as_ref(), spec, &current_file)
+                .unwrap();
+        }
+    }
+
+    let calls = remote_fetcher.get_fetch_calls();
+    assert_eq!(calls.iter().filter(|(spec, _)| spec == &pinned).count(), 1);
+    assert_eq!(calls.iter().filter(|(spec, _)| spec == &branch).count(), 3);
+
+    // Within the default TTL the branch ref is reused as well.
+    let resolver = CoreLoadResolver::new(
+        file_provider.clone(),
+        remote_fetcher.clone(),
+        Some(PathBuf::from("/workspace")),
+    );
+    for _ in 0..3 {
+        resolver
+            .resolve_spec(file_provider.as_ref(), &branch, &current_file)
+            .unwrap();
+    }
+    let calls = remote_fetcher.get_fetch_calls();
+    assert_eq!(calls.iter().filter(|(spec, _)| spec == &branch).count(), 4);
+}
@@ -0,0 +1,127 @@
+mod common;
+use common::eval_context;
+
+use picoplace_core::{codes, Diagnostic, InputMap, WithDiagnostics};
+use starlark::errors::EvalSeverity;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn diagnostic(severity: EvalSeverity, body: &str) -> Diagnostic {
+    Diagnostic {
+        path: "/test.zen".to_string(),
+        span: None,
+        severity,
+        body: body.to_string(),
+        call_stack: None,
+        child: None,
+        code: None,
+    }
+}
+
+#[test]
+fn test_filter_by_severity() {
+    let result = WithDiagnostics::success(
+        (),
+        vec![
+            diagnostic(EvalSeverity::Warning, "unused variable"),
+            diagnostic(EvalSeverity::Error, "undefined name"),
+            diagnostic(EvalSeverity::Advice, "prefer f-string"),
+            diagnostic(EvalSeverity::Warning, "shadowed name"),
+            diagnostic(EvalSeverity::Error, "type mismatch"),
+            diagnostic(EvalSeverity::Error, "missing pin"),
+        ],
+    );
+
+    let errors: Vec<&str> = result.errors().map(|d| d.body.as_str()).collect();
+    assert_eq!(errors, ["undefined name", "type mismatch", "missing pin"]);
+
+    let warnings: Vec<&str> = result.warnings().map(|d| d.body.as_str()).collect();
+    assert_eq!(warnings, ["unused variable", "shadowed name"]);
+
+    let counts = result.count_by_severity();
+    assert_eq!(counts[&EvalSeverity::Error], 3);
+    assert_eq!(counts[&EvalSeverity::Warning], 2);
+    assert_eq!(counts[&EvalSeverity::Advice], 1);
+    assert!(!counts.contains_key(&EvalSeverity::Disabled));
+}
+
+#[test]
+fn test_count_by_severity_empty() {
+    let result = WithDiagnostics::<()>::failure(Vec::new());
+    assert!(result.count_by_severity().is_empty());
+    assert_eq!(result.errors().count(), 0);
+    assert_eq!(result.warnings().count(), 0);
+}
+
+#[test]
+fn test_flatten_child_chain() {
+    let chain = diagnostic(EvalSeverity::Error, "outer").with_child(
+        diagnostic(EvalSeverity::Error, "middle")
+            .with_child(diagnostic(EvalSeverity::Warning, "inner")),
+    );
+
+    let flattened: Vec<&str> = chain.flatten().iter().map(|d| d.body.as_str()).collect();
+    assert_eq!(flattened, ["outer", "middle", "inner"]);
+
+    let rendered = chain.to_string();
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with("/test.zen outer"));
+    assert!(lines[2].ends_with("/test.zen inner"));
+}
+
+#[test]
+fn test_display_code() {
+    let chain = diagnostic(EvalSeverity::Error, "outer")
+        .with_code(codes::MODULE_LOAD_FAILED)
+        .with_child(diagnostic(EvalSeverity::Error, "inner"));
+
+    assert_eq!(
+        chain.to_string(),
+        "Error[E0003]: /test.zen outer\nError: /test.zen inner"
+    );
+}
+
+#[test]
+fn test_outermost_code() {
+    let chain = diagnostic(EvalSeverity::Error, "outer").with_child(
+        diagnostic(EvalSeverity::Error, "middle")
+            .with_code(codes::MODULE_LOAD_FAILED)
+            .with_child(
+                diagnostic(EvalSeverity::Error, "inner").with_code(codes::UNRESOLVED_LOAD_PATH),
+            ),
+    );
+    assert_eq!(chain.outermost_code(), Some(codes::MODULE_LOAD_FAILED));
+    assert_eq!(
+        diagnostic(EvalSeverity::Error, "plain").outermost_code(),
+        None
+    );
+}
+
+#[test]
+fn test_serialize_call_stack_frames() {
+    let source = "def inner():\n    error(\"boom\")\n\ndef outer():\n    inner()\n\nouter()\n";
+    let files = HashMap::from([("/test.zen".to_string(), source.to_string())]);
+    let result = eval_context(files)
+        .set_source_path(PathBuf::from("/test.zen"))
+        .set_module_name("<root>")
+        .set_inputs(InputMap::new())
+        .eval();
+    let diagnostic = result.errors().next().expect("evaluation should fail");
+
+    let json = serde_json::to_value(diagnostic).unwrap();
+    assert!(json["call_stack"].is_string());
+
+    let frames = json["call_stack_frames"].as_array().unwrap();
+    let functions: Vec<&str> = frames
+        .iter()
+        .map(|frame| frame["function"].as_str().unwrap())
+        .collect();
+    assert_eq!(functions[..2], ["outer", "inner"]);
+
+    // Each frame points at the line calling its function
+    assert_eq!(frames[0]["file"], "/test.zen");
+    assert_eq!(frames[0]["line"], 7);
+    assert_eq!(frames[1]["line"], 5);
+}
@@ -0,0 +1,30 @@
+mod common;
+use common::eval_context;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+#[test]
+fn test_eval_string_loads_relative_to_virtual_path() {
+    let files = HashMap::from([(
+        "/project/lib.zen".to_string(),
+        "GREETING = \"hello\"\n".to_string(),
+    )]);
+
+    // Nothing exists at the virtual path itself
+    let result = eval_context(files).eval_string(
+        "load(\"lib.zen\", \"GREETING\")\nprint(GREETING, 1 + 2)\n",
+        Path::new("/project/scratch.zen"),
+    );
+
+    assert!(!result.has_errors(), "{:?}", result.diagnostics);
+    assert_eq!(result.output.unwrap().print_output, ["hello 3"]);
+}
+
+#[test]
+fn test_eval_string_reports_virtual_path() {
+    let result = eval_context(HashMap::new()).eval_string("x = (\n", Path::new("/virtual.zen"));
+
+    let error = result.errors().next().expect("syntax error expected");
+    assert_eq!(error.path, "/virtual.zen");
+}
@@ -0,0 +1,94 @@
+mod common;
+use common::eval_context;
+
+use picoplace_core::{EvalContext, InputMap, InputValue};
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+fn eval_with_globals(globals: HashMap<String, InputValue>) -> Vec<String> {
+    let files = HashMap::from([
+        (
+            "/variant.zen".to_string(),
+            "def variant():\n    return \"wifi\" if ENABLE_WIFI else \"lite\"\n".to_string(),
+        ),
+        (
+            "/top.zen".to_string(),
+            "load(\"variant.zen\", \"variant\")\nprint(BOARD, variant())\n".to_string(),
+        ),
+    ]);
+    let result = eval_context(files)
+        .set_source_path(PathBuf::from("/top.zen"))
+        .set_module_name("<root>")
+        .set_inputs(InputMap::new())
+        .set_globals(globals)
+        .eval();
+    assert!(!result.has_errors(), "{:?}", result.diagnostics);
+    result.output.unwrap().print_output
+}
+
+#[test]
+fn test_globals_visible_in_loaded_modules() {
+    let globals = |enable_wifi| {
+        HashMap::from([
+            ("BOARD".to_string(), InputValue::String("rev-b".to_string())),
+            ("ENABLE_WIFI".to_string(), InputValue::Bool(enable_wifi)),
+        ])
+    };
+
+    assert_eq!(eval_with_globals(globals(true)), ["rev-b wifi"]);
+    assert_eq!(eval_with_globals(globals(false)), ["rev-b lite"]);
+}
+
+fn context(files: &[(&str, &str)]) -> EvalContext {
+    eval_context(
+        files
+            .iter()
+            .map(|(path, contents)| (path.to_string(), contents.to_string()))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_cached_modules_follow_globals() {
+    let ctx = context(&[
+        ("/board.zen", "board = BOARD\n"),
+        ("/top.zen", "load(\"board.zen\", \"board\")\nprint(board)\n"),
+    ]);
+    let eval = |board: &str| {
+        let result = ctx
+            .child_context()
+            .set_source_path(PathBuf::from("/top.zen"))
+            .set_module_name("<root>")
+            .set_inputs(InputMap::new())
+            .set_globals(HashMap::from([(
+                "BOARD".to_string(),
+                InputValue::String(board.to_string()),
+            )]))
+            .eval();
+        assert!(!result.has_errors(), "{:?}", result.diagnostics);
+        result.output.unwrap().print_output
+    };
+
+    assert_eq!(eval("rev-a"), ["rev-a"]);
+    assert_eq!(eval("rev-b"), ["rev-b"]);
+    assert_eq!(eval("rev-a"), ["rev-a"]);
+}
+
+#[test]
+fn test_globals_are_not_exported() {
+    let ctx = context(&[
+        ("/lib.zen", "value = 1\n"),
+        ("/top.zen", "load(\"lib.zen\", \"BOARD\")\n"),
+    ]);
+    let result = ctx
+        .set_source_path(PathBuf::from("/top.zen"))
+        .set_module_name("<root>")
+        .set_inputs(InputMap::new())
+        .set_globals(HashMap::from([(
+            "BOARD".to_string(),
+            InputValue::String("rev-b".to_string()),
+        )]))
+        .eval();
+    assert!(result.has_errors());
+}
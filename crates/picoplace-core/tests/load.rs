@@ -125,3 +125,324 @@ snapshot_eval!(module_loader_attrs, {
         check(Module.TestExport == "test", "TestExport should be 'test'")
     "#
 });
+
+#[test]
+fn cancelled_evaluation_reports_diagnostic() {
+    use common::InMemoryFileProvider;
+    use picoplace_core::{CoreLoadResolver, EvalContext, InputMap, NoopRemoteFetcher};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    let files = std::collections::HashMap::from([("test.zen".to_string(), "x = 1\n".to_string())]);
+    let file_provider = Arc::new(InMemoryFileProvider::new(files));
+    let load_resolver = Arc::new(CoreLoadResolver::new(
+        file_provider.clone(),
+        Arc::new(NoopRemoteFetcher::default()),
+        Some(std::path::PathBuf::from("/")),
+    ));
+
+    // Cancelled before evaluation even starts
+    let token = Arc::new(AtomicBool::new(true));
+
+    let result = EvalContext::new()
+        .set_file_provider(file_provider)
+        .set_load_resolver(load_resolver)
+        .set_cancellation_token(token)
+        .set_source_path(std::path::PathBuf::from("/test.zen"))
+        .set_module_name("<root>")
+        .set_inputs(InputMap::new())
+        .eval();
+
+    assert!(!result.is_success());
+    assert!(result
+        .diagnostics
+        .iter()
+        .any(|d| d.body.contains("Evaluation cancelled")));
+}
+
+#[test]
+fn cancelling_during_evaluation_stops_at_next_load() {
+    use common::InMemoryFileProvider;
+    use picoplace_core::{
+        CoreLoadResolver, EvalContext, FileProvider, FileProviderError, InputMap, NoopRemoteFetcher,
+    };
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Requests cancellation as soon as `a.zen` is read, i.e. while the root
+    /// module is already running.
+    struct CancelOnRead {
+        inner: InMemoryFileProvider,
+        token: Arc<AtomicBool>,
+        reads: Mutex<Vec<PathBuf>>,
+    }
+
+    impl FileProvider for CancelOnRead {
+        fn read_file(&self, path: &Path) -> Result<String, FileProviderError> {
+            self.reads.lock().unwrap().push(path.to_path_buf());
+            if path.ends_with("a.zen") {
+                self.token.store(true, Ordering::Relaxed);
+            }
+            self.inner.read_file(path)
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            self.inner.exists(path)
+        }
+
+        fn is_directory(&self, path: &Path) -> bool {
+            self.inner.is_directory(path)
+        }
+
+        fn list_directory(&self, path: &Path) -> Result<Vec<PathBuf>, FileProviderError> {
+            self.inner.list_directory(path)
+        }
+
+        fn canonicalize(&self, path: &Path) -> Result<PathBuf, FileProviderError> {
+            self.inner.canonicalize(path)
+        }
+    }
+
+    let files = std::collections::HashMap::from([
+        (
+            "test.zen".to_string(),
+            "load(\"a.zen\", \"A\")\nload(\"b.zen\", \"B\")\n".to_string(),
+        ),
+        ("a.zen".to_string(), "A = 1\n".to_string()),
+        ("b.zen".to_string(), "B = 2\n".to_string()),
+    ]);
+    let token = Arc::new(AtomicBool::new(false));
+    let file_provider = Arc::new(CancelOnRead {
+        inner: InMemoryFileProvider::new(files),
+        token: token.clone(),
+        reads: Mutex::new(Vec::new()),
+    });
+    let load_resolver = Arc::new(CoreLoadResolver::new(
+        file_provider.clone(),
+        Arc::new(NoopRemoteFetcher::default()),
+        Some(std::path::PathBuf::from("/")),
+    ));
+
+    let result = EvalContext::new()
+        .set_file_provider(file_provider.clone())
+        .set_load_resolver(load_resolver)
+        .set_cancellation_token(token)
+        .set_source_path(std::path::PathBuf::from("/test.zen"))
+        .set_module_name("<root>")
+        .set_inputs(InputMap::new())
+        .eval();
+
+    assert!(!result.is_success());
+    assert!(result
+        .diagnostics
+        .iter()
+        .flat_map(|d| d.flatten())
+        .any(|d| d.body.contains("Evaluation cancelled")));
+    // The evaluation stopped before it got to `b.zen`
+    assert!(!file_provider
+        .reads
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|path| path.ends_with("b.zen")));
+}
+
+#[test]
+fn editing_a_module_invalidates_only_its_dependents() {
+    use common::InMemoryFileProvider;
+    use picoplace_core::{CoreLoadResolver, EvalContext, NoopRemoteFetcher};
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    let files = std::collections::HashMap::from([
+        ("leaf.zen".to_string(), "value = [1]\n".to_string()),
+        (
+            "mid.zen".to_string(),
+            "load(\"leaf.zen\", \"value\")\nmid = [value[0]]\n".to_string(),
+        ),
+        ("other.zen".to_string(), "other = [3]\n".to_string()),
+        (
+            "top.zen".to_string(),
+            "load(\"mid.zen\", \"mid\")\nload(\"other.zen\", \"other\")\n".to_string(),
+        ),
+    ]);
+    let file_provider = Arc::new(InMemoryFileProvider::new(files));
+    let load_resolver = Arc::new(CoreLoadResolver::new(
+        file_provider.clone(),
+        Arc::new(NoopRemoteFetcher::default()),
+        Some(PathBuf::from("/")),
+    ));
+    let ctx = EvalContext::new()
+        .set_file_provider(file_provider)
+        .set_load_resolver(load_resolver);
+
+    let top = PathBuf::from("/top.zen");
+    let top_contents = "load(\"mid.zen\", \"mid\")\nload(\"other.zen\", \"other\")\n";
+    let cached = |path: &str, name: &str| {
+        ctx.get_cached_module(Path::new(path))
+            .expect("module should be cached")
+            .get(name)
+            .unwrap()
+    };
+
+    assert!(ctx
+        .parse_and_analyze_file(top.clone(), top_contents.to_string())
+        .is_success());
+    let mid_before = cached("/mid.zen", "mid");
+    let other_before = cached("/other.zen", "other");
+
+    // Re-evaluating without changes reuses every loaded module
+    assert!(ctx
+        .parse_and_analyze_file(top.clone(), top_contents.to_string())
+        .is_success());
+    assert!(cached("/mid.zen", "mid").value().ptr_eq(mid_before.value()));
+
+    // Editing the leaf re-evaluates it and its dependents, but nothing else
+    ctx.set_file_contents(PathBuf::from("/leaf.zen"), "value = [2]\n".to_string());
+    assert!(ctx
+        .parse_and_analyze_file(top, top_contents.to_string())
+        .is_success());
+
+    let mid_after = cached("/mid.zen", "mid");
+    assert!(!mid_after.value().ptr_eq(mid_before.value()));
+    assert_eq!(mid_after.value().to_string(), "[2]");
+    assert!(cached("/other.zen", "other")
+        .value()
+        .ptr_eq(other_before.value()));
+}
+
+#[test]
+fn shared_dependencies_are_hashed_once_per_evaluation() {
+    use common::InMemoryFileProvider;
+    use picoplace_core::{
+        CoreLoadResolver, EvalContext, FileProvider, FileProviderError, NoopRemoteFetcher,
+    };
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Counts reads of `/leaf.zen`
+    struct LeafReads {
+        inner: InMemoryFileProvider,
+        reads: AtomicUsize,
+    }
+
+    impl FileProvider for LeafReads {
+        fn read_file(&self, path: &Path) -> Result<String, FileProviderError> {
+            if path == Path::new("/leaf.zen") {
+                self.reads.fetch_add(1, Ordering::SeqCst);
+            }
+            self.inner.read_file(path)
+        }
+
+        fn exists(&self, path: &Path) -> bool {
+            self.inner.exists(path)
+        }
+
+        fn is_directory(&self, path: &Path) -> bool {
+            self.inner.is_directory(path)
+        }
+
+        fn list_directory(&self, path: &Path) -> Result<Vec<PathBuf>, FileProviderError> {
+            self.inner.list_directory(path)
+        }
+
+        fn canonicalize(&self, path: &Path) -> Result<PathBuf, FileProviderError> {
+            self.inner.canonicalize(path)
+        }
+    }
+
+    let top_contents = "load(\"a.zen\", \"a\")\nload(\"b.zen\", \"b\")\n";
+    let files = std::collections::HashMap::from([
+        ("leaf.zen".to_string(), "value = 1\n".to_string()),
+        (
+            "a.zen".to_string(),
+            "load(\"leaf.zen\", \"value\")\na = value\n".to_string(),
+        ),
+        (
+            "b.zen".to_string(),
+            "load(\"leaf.zen\", \"value\")\nb = value\n".to_string(),
+        ),
+        ("top.zen".to_string(), top_contents.to_string()),
+    ]);
+    let file_provider = Arc::new(LeafReads {
+        inner: InMemoryFileProvider::new(files),
+        reads: AtomicUsize::new(0),
+    });
+    let load_resolver = Arc::new(CoreLoadResolver::new(
+        file_provider.clone(),
+        Arc::new(NoopRemoteFetcher::default()),
+        Some(PathBuf::from("/")),
+    ));
+    let ctx = EvalContext::new()
+        .set_file_provider(file_provider.clone())
+        .set_load_resolver(load_resolver);
+
+    let top = PathBuf::from("/top.zen");
+    assert!(ctx
+        .parse_and_analyze_file(top.clone(), top_contents.to_string())
+        .is_success());
+
+    // Both `a.zen` and `b.zen` are served from the cache, but the leaf they
+    // share is only checked for changes once
+    file_provider.reads.store(0, Ordering::SeqCst);
+    assert!(ctx
+        .parse_and_analyze_file(top, top_contents.to_string())
+        .is_success());
+    assert_eq!(file_provider.reads.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn cancelling_stops_a_running_loop() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let token = Arc::new(AtomicBool::new(false));
+    let canceller = {
+        let token = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            token.store(true, Ordering::Relaxed);
+        })
+    };
+
+    // Far too many iterations to finish on its own, and no load() to stop at
+    let result = common::eval_context(std::collections::HashMap::new())
+        .set_cancellation_token(token)
+        .eval_string(
+            "total = 0\nfor i in range(1000000000):\n    total += i\n",
+            std::path::Path::new("/test.zen"),
+        );
+    canceller.join().unwrap();
+
+    assert!(!result.is_success());
+    assert!(result
+        .diagnostics
+        .iter()
+        .flat_map(|d| d.flatten())
+        .any(|d| d.body.contains("Evaluation cancelled")));
+}
+
+#[test]
+fn print_output_includes_loaded_modules_and_failed_evaluations() {
+    let files = std::collections::HashMap::from([(
+        "sub.zen".to_string(),
+        "print(\"hello from sub\")\nvalue = 1\n".to_string(),
+    )]);
+    let ctx = common::eval_context(files);
+
+    // Nested and failed evaluations never produce an EvalOutput to carry their
+    // output, so it is collected in the state shared with child contexts
+    let result = ctx.child_context().eval_string(
+        "load(\"sub.zen\", \"value\")\nprint(\"broken board\")\nfail(\"boom\")\n",
+        std::path::Path::new("/test.zen"),
+    );
+
+    assert!(!result.is_success());
+    assert_eq!(
+        ctx.get_print_output(),
+        vec!["hello from sub".to_string(), "broken board".to_string()]
+    );
+}
@@ -0,0 +1,118 @@
+use picoplace_core::workspace::{
+    find_design_files, find_design_files_ignoring, read_members, read_refdes_prefixes,
+};
+use picoplace_core::InMemoryFileProvider;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn provider(paths: &[&str]) -> InMemoryFileProvider {
+    let files: HashMap<String, String> = paths
+        .iter()
+        .map(|path| (path.to_string(), String::new()))
+        .collect();
+    InMemoryFileProvider::new(files)
+}
+
+#[test]
+fn test_find_design_files() {
+    let provider = provider(&[
+        "/ws/pcb.toml",
+        "/ws/board.zen",
+        "/ws/modules/power/regulator.zen",
+        "/ws/modules/lib.star",
+        "/ws/modules/README.md",
+        "/ws/.git/hooks/pre-commit.star",
+        "/ws/.pcb/cache/kicad/resistor.zen",
+    ]);
+
+    let files = find_design_files(&provider, Path::new("/ws"));
+    assert_eq!(
+        files,
+        vec![
+            PathBuf::from("/ws/board.zen"),
+            PathBuf::from("/ws/modules/lib.star"),
+            PathBuf::from("/ws/modules/power/regulator.zen"),
+        ]
+    );
+}
+
+#[test]
+fn test_find_design_files_custom_ignore_list() {
+    let provider = provider(&[
+        "/ws/board.zen",
+        "/ws/vendor/part.zen",
+        "/ws/.pcb/cache/resistor.zen",
+    ]);
+
+    let files = find_design_files_ignoring(&provider, Path::new("/ws"), &["vendor"]);
+    assert_eq!(
+        files,
+        vec![
+            PathBuf::from("/ws/.pcb/cache/resistor.zen"),
+            PathBuf::from("/ws/board.zen"),
+        ]
+    );
+
+    assert!(find_design_files(&provider, Path::new("/missing")).is_empty());
+}
+
+#[test]
+fn test_read_members_expands_globs() {
+    let mut files: HashMap<String, String> = [
+        "/ws/boards/main/board.zen",
+        "/ws/boards/sensor/board.zen",
+        "/ws/boards/README.md",
+        "/ws/modules/power/regulator.zen",
+        "/ws/modules/power/ldo/ldo.zen",
+        "/ws/.pcb/cache/boards/cached/board.zen",
+    ]
+    .iter()
+    .map(|path| (path.to_string(), String::new()))
+    .collect();
+    files.insert(
+        "/ws/pcb.toml".to_string(),
+        "[workspace]\nmembers = [\"boards/*\", \"modules/power\"]\n".to_string(),
+    );
+    let provider = InMemoryFileProvider::new(files);
+
+    assert_eq!(
+        read_members(&provider, Path::new("/ws")),
+        vec![
+            PathBuf::from("/ws/boards/main"),
+            PathBuf::from("/ws/boards/sensor"),
+            PathBuf::from("/ws/modules/power"),
+        ]
+    );
+}
+
+#[test]
+fn test_read_members_without_workspace_section() {
+    let mut files = HashMap::new();
+    files.insert(
+        "/ws/pcb.toml".to_string(),
+        "[packages]\nstdlib = \"@github/example/stdlib\"\n".to_string(),
+    );
+    files.insert("/ws/boards/main/board.zen".to_string(), String::new());
+    let provider = InMemoryFileProvider::new(files);
+
+    assert!(read_members(&provider, Path::new("/ws")).is_empty());
+    assert!(read_members(&provider, Path::new("/missing")).is_empty());
+}
+
+#[test]
+fn test_read_refdes_prefixes() {
+    let mut files = HashMap::new();
+    files.insert(
+        "/ws/pcb.toml".to_string(),
+        "[refdes]\ncrystal = \"X\"\nthermistor = \"RT\"\n".to_string(),
+    );
+    let provider = InMemoryFileProvider::new(files);
+
+    let prefixes = read_refdes_prefixes(&provider, Path::new("/ws"));
+    assert_eq!(prefixes.len(), 2);
+    assert_eq!(prefixes["crystal"], "X");
+    assert_eq!(prefixes["thermistor"], "RT");
+
+    assert!(read_refdes_prefixes(&provider, Path::new("/missing")).is_empty());
+}
@@ -1,4 +1,4 @@
-use crate::{Part, Pin, Symbol};
+use crate::{Part, Pin, PinAlternate, PinType, Symbol};
 use anyhow::Result;
 use picoplace_sexpr::{parse, Sexpr};
 use serde::Serialize;
@@ -41,6 +41,23 @@ impl KicadSymbol {
 pub(super) struct KicadPin {
     pub(super) name: String,
     pub(super) number: String,
+    pub(super) electrical_type: PinType,
+    pub(super) position: (f64, f64, f64),
+    pub(super) unit: u32,
+    pub(super) alternates: Vec<PinAlternate>,
+}
+
+impl From<KicadPin> for Pin {
+    fn from(pin: KicadPin) -> Self {
+        Pin {
+            name: pin.name,
+            number: pin.number,
+            electrical_type: pin.electrical_type,
+            position: pin.position,
+            unit: pin.unit,
+            alternates: pin.alternates,
+        }
+    }
 }
 
 impl From<KicadSymbol> for Symbol {
@@ -55,14 +72,7 @@ impl From<KicadSymbol> for Symbol {
             description: symbol.description,
             distributors: symbol.distributors,
             properties: symbol.properties,
-            pins: symbol
-                .pins
-                .into_iter()
-                .map(|pin| Pin {
-                    name: pin.name,
-                    number: pin.number,
-                })
-                .collect(),
+            pins: symbol.pins.into_iter().map(Pin::from).collect(),
             raw_sexp: symbol.raw_sexp,
         }
     }
@@ -131,11 +141,7 @@ pub(super) fn parse_symbol(symbol_data: &[Sexpr]) -> Result<KicadSymbol> {
                     }
                     "in_bom" => parse_in_bom(&mut symbol, prop_list),
                     "property" => parse_property(&mut symbol, prop_list),
-                    "pin" => {
-                        if let Some(pin) = parse_pin(prop_list) {
-                            symbol.pins.push(pin)
-                        }
-                    }
+                    "pin" => symbol.pins.push(parse_pin(prop_list, (0.0, 0.0))),
                     _ if prop_name.starts_with("symbol") => {
                         // This is the nested symbol section which may contain pins
                         parse_symbol_section(&mut symbol, prop_list);
@@ -151,11 +157,16 @@ pub(super) fn parse_symbol(symbol_data: &[Sexpr]) -> Result<KicadSymbol> {
 
 // New function to parse the nested symbol section which contains pins in new format
 fn parse_symbol_section(symbol: &mut KicadSymbol, section_data: &[Sexpr]) {
+    let offset = parse_at(section_data).map_or((0.0, 0.0), |(x, y, _)| (x, y));
+    let unit = parse_unit(section_data);
     for item in section_data {
         if let Sexpr::List(pin_data) = item {
             if let Some(Sexpr::Symbol(type_name)) = pin_data.first() {
                 if type_name == "pin" {
-                    if let Some(pin) = parse_pin_from_section(pin_data) {
+                    let mut pin = parse_pin(pin_data, offset);
+                    pin.unit = unit;
+                    // Only keep the pin if we have both name and number
+                    if !pin.name.is_empty() && !pin.number.is_empty() {
                         symbol.pins.push(pin);
                     }
                 }
@@ -164,36 +175,143 @@ fn parse_symbol_section(symbol: &mut KicadSymbol, section_data: &[Sexpr]) {
     }
 }
 
-// New function to parse pins from the nested symbol section
-fn parse_pin_from_section(pin_data: &[Sexpr]) -> Option<KicadPin> {
-    // Format: (pin unspecified line (at X Y Z) (length L) (name "Name") (number "N"))
-    let mut pin = KicadPin::default();
-
-    // Extract name and number from the pin data
-    for item in pin_data {
-        if let Sexpr::List(attr_data) = item {
-            if attr_data.len() >= 2 {
-                if let Some(Sexpr::Symbol(attr_name)) = attr_data.first() {
-                    if attr_name == "name" && attr_data.len() >= 2 {
-                        if let Some(Sexpr::String(name)) = attr_data.get(1) {
-                            pin.name = name.clone();
-                        }
-                    } else if attr_name == "number" && attr_data.len() >= 2 {
-                        if let Some(Sexpr::String(number)) = attr_data.get(1) {
-                            pin.number = number.clone();
-                        }
-                    }
+/// Find a pin by name or number in a raw `(symbol ...)` expression.
+///
+/// Pins directly inside `symbol` are checked before nested sub-symbols, and
+/// the returned position includes the offsets of any sub-symbols it is
+/// nested in.
+pub fn find_pin(symbol: &Sexpr, pin: &str) -> Option<Pin> {
+    find_pin_with_offset(symbol, pin, (0.0, 0.0), 0).map(Pin::from)
+}
+
+/// Byte offset in the text of a `.kicad_sym` file of the `(pin ...)`
+/// expression declaring `pin` (by name or number), e.g. to jump to it from an
+/// editor. The first match in the file wins.
+pub fn find_pin_offset(content: &str, pin: &str) -> Option<usize> {
+    content.match_indices("(pin ").find_map(|(start, _)| {
+        let end = start + sexpr_len(&content[start..])?;
+        let pin_sexpr = parse(&content[start..end]).ok()?;
+        find_pin(&Sexpr::List(vec![pin_sexpr]), pin).map(|_| start)
+    })
+}
+
+/// Like [`find_pin_offset`], but only considers the pins of the top-level
+/// symbol named `symbol`, or of the symbol it extends.
+pub fn find_symbol_pin_offset(content: &str, symbol: &str, pin: &str) -> Option<usize> {
+    // Derived symbols inherit their pins, possibly through several levels
+    let mut symbol = symbol.to_owned();
+    for _ in 0..MAX_EXTENDS_DEPTH {
+        let start = content.find(&format!("(symbol \"{symbol}\""))?;
+        let body = &content[start..start + sexpr_len(&content[start..])?];
+        if let Some(offset) = find_pin_offset(body, pin) {
+            return Some(start + offset);
+        }
+        let (_, extended) = body.split_once("(extends \"")?;
+        symbol = extended.split_once('"')?.0.to_owned();
+    }
+    None
+}
+
+/// Most `extends` links followed, so a cycle in a broken library terminates
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// Length in bytes of the balanced s-expression at the start of `text`
+fn sexpr_len(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in text.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i + 1);
                 }
             }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn find_pin_with_offset(
+    sexpr: &Sexpr,
+    pin: &str,
+    offset: (f64, f64),
+    unit: u32,
+) -> Option<KicadPin> {
+    let items = sexpr.as_list()?;
+
+    // First, attempt to match a pin at this level
+    for item in items {
+        let Some(pin_data) = item.as_list() else {
+            continue;
+        };
+        if pin_data.first().and_then(Sexpr::as_atom) == Some("pin") {
+            let mut candidate = parse_pin(pin_data, offset);
+            if candidate.name == pin || candidate.number == pin {
+                candidate.unit = unit;
+                return Some(candidate);
+            }
         }
     }
 
-    // Only return the pin if we have both name and number
-    if !pin.name.is_empty() && !pin.number.is_empty() {
-        Some(pin)
-    } else {
-        None
+    // If not found, recurse into nested symbols / lists
+    for item in items {
+        let Some(item_data) = item.as_list() else {
+            continue;
+        };
+        let (nested_offset, nested_unit) =
+            if item_data.first().and_then(Sexpr::as_atom) == Some("symbol") {
+                let (x, y, _) = parse_at(item_data).unwrap_or_default();
+                ((offset.0 + x, offset.1 + y), parse_unit(item_data))
+            } else {
+                (offset, unit)
+            };
+        if let Some(found) = find_pin_with_offset(item, pin, nested_offset, nested_unit) {
+            return Some(found);
+        }
     }
+
+    None
+}
+
+/// Unit number from a sub-symbol named `Name_<unit>_<style>`; 0 if missing
+fn parse_unit(section_data: &[Sexpr]) -> u32 {
+    section_data
+        .get(1)
+        .and_then(Sexpr::as_atom)
+        .and_then(|name| name.rsplit('_').nth(1))
+        .and_then(|unit| unit.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Parse `(at X Y [ANGLE])` among the direct children of `data`
+fn parse_at(data: &[Sexpr]) -> Option<(f64, f64, f64)> {
+    data.iter().find_map(|item| {
+        let at = item.as_list()?;
+        if at.first().and_then(Sexpr::as_atom) != Some("at") {
+            return None;
+        }
+        let coord = |i: usize| at.get(i).and_then(Sexpr::as_atom)?.parse::<f64>().ok();
+        // Angle is optional
+        Some((coord(1)?, coord(2)?, coord(3).unwrap_or(0.0)))
+    })
 }
 
 fn parse_in_bom(symbol: &mut KicadSymbol, prop_list: &[Sexpr]) {
@@ -221,7 +339,7 @@ fn parse_property(symbol: &mut KicadSymbol, prop_list: &[Sexpr]) {
             "Datasheet" => symbol.datasheet_url = Some(value.clone()),
             "Manufacturer_Name" => symbol.manufacturer = Some(value.clone()),
             "Manufacturer_Part_Number" => symbol.mpn = Some(value.clone()),
-            "ki_description" => symbol.description = Some(value.clone()),
+            "ki_description" | "Description" => symbol.description = Some(value.clone()),
             "LCSC Part" => {
                 if symbol.mpn.is_none() {
                     symbol.mpn = Some(value.clone());
@@ -258,8 +376,20 @@ fn parse_property(symbol: &mut KicadSymbol, prop_list: &[Sexpr]) {
     }
 }
 
-fn parse_pin(pin_list: &[Sexpr]) -> Option<KicadPin> {
-    let mut pin = KicadPin::default();
+// Format: (pin TYPE STYLE (at X Y ANGLE) (length L) (name "Name") (number "N"))
+fn parse_pin(pin_list: &[Sexpr], offset: (f64, f64)) -> KicadPin {
+    let mut pin = KicadPin {
+        electrical_type: pin_list
+            .get(1)
+            .and_then(Sexpr::as_atom)
+            .and_then(|t| t.parse().ok())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    if let Some((x, y, angle)) = parse_at(pin_list) {
+        pin.position = (x + offset.0, y + offset.1, angle);
+    }
 
     for item in pin_list {
         if let Sexpr::List(prop_list) = item {
@@ -269,11 +399,20 @@ fn parse_pin(pin_list: &[Sexpr]) -> Option<KicadPin> {
                 match prop_name.as_str() {
                     "name" => pin.name = value.clone(),
                     "number" => pin.number = value.clone(),
+                    // Format: (alternate "Name" TYPE STYLE)
+                    "alternate" => pin.alternates.push(PinAlternate {
+                        name: value.clone(),
+                        electrical_type: prop_list
+                            .get(2)
+                            .and_then(Sexpr::as_atom)
+                            .and_then(|t| t.parse().ok())
+                            .unwrap_or_default(),
+                    }),
                     _ => {}
                 }
             }
         }
     }
 
-    Some(pin)
-}
\ No newline at end of file
+    pin
+}
@@ -3,10 +3,11 @@ pub mod kicad;
 use anyhow::Result;
 use kicad::symbol::KicadSymbol;
 use kicad::symbol_library::KicadSymbolLibrary;
-use picoplace_sexpr::Sexpr;
+use picoplace_sexpr::{format_sexpr, Sexpr};
 use serde::Serialize;
 
 use std::collections::HashMap;
+use std::fs;
 use std::io;
 use std::path::Path;
 use std::str::FromStr;
@@ -37,6 +38,109 @@ pub struct Part {
 pub struct Pin {
     pub name: String,
     pub number: String,
+    pub electrical_type: PinType,
+    /// `(x, y, angle)` of the pin's connection point in symbol coordinates
+    /// (KiCad convention, +Y up), including any sub-symbol offset.
+    pub position: (f64, f64, f64),
+    /// Unit the pin belongs to; 0 means it is shared by all units
+    pub unit: u32,
+    /// Alternate functions declared with `(alternate ...)`, e.g. for pin muxing
+    pub alternates: Vec<PinAlternate>,
+}
+
+/// An alternate function of a pin, such as `SCL` on a muxed MCU pin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PinAlternate {
+    pub name: String,
+    pub electrical_type: PinType,
+}
+
+impl Pin {
+    /// Whether `function` is the pin's primary name or one of its alternates
+    pub fn has_function(&self, function: &str) -> bool {
+        self.name == function || self.alternates.iter().any(|alt| alt.name == function)
+    }
+
+    /// Electrical type of the pin when used as `function`
+    pub fn function_type(&self, function: &str) -> Option<PinType> {
+        if self.name == function {
+            return Some(self.electrical_type);
+        }
+        self.alternates
+            .iter()
+            .find(|alt| alt.name == function)
+            .map(|alt| alt.electrical_type)
+    }
+}
+
+/// One unit (gate) of a multi-unit symbol, e.g. a single amplifier of a quad op-amp
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolUnit {
+    /// 1-based unit number, as in KiCad's `Name_<unit>_<style>` sub-symbols
+    pub number: u32,
+    /// Pins drawn in this unit, including pins shared by all units
+    pub pins: Vec<Pin>,
+}
+
+/// Electrical type of a symbol pin, as used by electrical rule checks
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinType {
+    Input,
+    Output,
+    Bidirectional,
+    TriState,
+    Passive,
+    Free,
+    #[default]
+    Unspecified,
+    PowerIn,
+    PowerOut,
+    OpenCollector,
+    OpenEmitter,
+    NoConnect,
+}
+
+impl PinType {
+    /// The KiCad keyword for this pin type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PinType::Input => "input",
+            PinType::Output => "output",
+            PinType::Bidirectional => "bidirectional",
+            PinType::TriState => "tri_state",
+            PinType::Passive => "passive",
+            PinType::Free => "free",
+            PinType::Unspecified => "unspecified",
+            PinType::PowerIn => "power_in",
+            PinType::PowerOut => "power_out",
+            PinType::OpenCollector => "open_collector",
+            PinType::OpenEmitter => "open_emitter",
+            PinType::NoConnect => "no_connect",
+        }
+    }
+}
+
+impl FromStr for PinType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "input" => Ok(PinType::Input),
+            "output" => Ok(PinType::Output),
+            "bidirectional" => Ok(PinType::Bidirectional),
+            "tri_state" => Ok(PinType::TriState),
+            "passive" => Ok(PinType::Passive),
+            "free" => Ok(PinType::Free),
+            "unspecified" => Ok(PinType::Unspecified),
+            "power_in" => Ok(PinType::PowerIn),
+            "power_out" => Ok(PinType::PowerOut),
+            "open_collector" => Ok(PinType::OpenCollector),
+            "open_emitter" => Ok(PinType::OpenEmitter),
+            "no_connect" => Ok(PinType::NoConnect),
+            _ => Err(anyhow::anyhow!("Unknown pin type: {}", s)),
+        }
+    }
 }
 
 impl Symbol {
@@ -59,8 +163,164 @@ impl Symbol {
     pub fn raw_sexp(&self) -> Option<&Sexpr> {
         self.raw_sexp.as_ref()
     }
+
+    /// Split `pins` into the symbol's units.
+    ///
+    /// Pins shared by all units (unit 0) are included in every unit, and pins
+    /// repeated by alternate body styles are only listed once. Symbols without
+    /// unit-specific pins have a single unit 1 holding every pin.
+    pub fn units(&self) -> Vec<SymbolUnit> {
+        let mut numbers: Vec<u32> = self
+            .pins
+            .iter()
+            .map(|pin| pin.unit)
+            .filter(|unit| *unit > 0)
+            .collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        if numbers.is_empty() {
+            numbers.push(1);
+        }
+
+        numbers
+            .into_iter()
+            .map(|number| {
+                let mut pins: Vec<Pin> = Vec::new();
+                for pin in &self.pins {
+                    if (pin.unit == number || pin.unit == 0)
+                        && !pins.iter().any(|p| p.number == pin.number)
+                    {
+                        pins.push(pin.clone());
+                    }
+                }
+                SymbolUnit { number, pins }
+            })
+            .collect()
+    }
+
+    /// Look up any `(property "Key" "Value")` captured from the source symbol,
+    /// including the standard KiCad fields backing the typed accessors above.
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.get(key).map(String::as_str)
+    }
+
+    /// Rebuild the symbol's S-expression from `raw_sexp`, patched with the
+    /// current `footprint` and `properties`.
+    ///
+    /// Existing properties keep their position and effects, new ones are
+    /// appended with default effects, and properties no longer present in
+    /// `properties` are dropped. Graphics and pins are written unchanged.
+    pub fn to_sexpr(&self) -> Result<Sexpr> {
+        let mut sexpr = self.raw_sexp.clone().ok_or_else(|| {
+            anyhow::anyhow!("Symbol '{}' has no raw S-expression to write", self.name)
+        })?;
+
+        let mut properties = self.properties.clone();
+        let stored_footprint = properties
+            .get("Footprint")
+            .map(String::as_str)
+            .unwrap_or_default();
+        let (library, stored_name) = match stored_footprint.split_once(':') {
+            Some((library, name)) => (Some(library), name),
+            None => (None, stored_footprint),
+        };
+        if stored_name != self.footprint && stored_footprint != self.footprint {
+            // `footprint` is usually the bare name, so keep the library of the
+            // footprint it replaces to write a full `lib:name` ID
+            let footprint = match library {
+                Some(library) if !self.footprint.is_empty() && !self.footprint.contains(':') => {
+                    format!("{library}:{}", self.footprint)
+                }
+                _ => self.footprint.clone(),
+            };
+            properties.insert("Footprint".to_string(), footprint);
+        }
+
+        let items = sexpr
+            .as_list_mut()
+            .ok_or_else(|| anyhow::anyhow!("Symbol '{}' is not a list", self.name))?;
+
+        items.retain_mut(|item| {
+            let Some(prop) = item.as_list_mut() else {
+                return true;
+            };
+            if prop.first().and_then(Sexpr::as_atom) != Some("property") {
+                return true;
+            }
+            let Some(key) = prop.get(1).and_then(Sexpr::as_atom) else {
+                return true;
+            };
+            match properties.remove(key) {
+                Some(value) => {
+                    if prop.len() > 2 {
+                        prop[2] = Sexpr::string(value);
+                    } else {
+                        prop.push(Sexpr::string(value));
+                    }
+                    true
+                }
+                None => false,
+            }
+        });
+
+        // Append new properties after the existing ones, in a stable order
+        let insert_at = items
+            .iter()
+            .rposition(|item| {
+                item.as_list()
+                    .and_then(|l| l.first())
+                    .and_then(Sexpr::as_atom)
+                    == Some("property")
+            })
+            .map_or(items.len().min(2), |i| i + 1);
+        let mut new_properties: Vec<_> = properties.into_iter().collect();
+        new_properties.sort();
+        for (offset, (key, value)) in new_properties.into_iter().enumerate() {
+            items.insert(insert_at + offset, new_property_sexpr(&key, &value));
+        }
+
+        Ok(sexpr)
+    }
+
+    /// Write the symbol to a `.kicad_sym` file containing just this symbol
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        SymbolLibrary {
+            symbols: vec![self.clone()],
+        }
+        .write_to_file(path)
+    }
+}
+
+/// Hidden property with KiCad's default placement and font
+fn new_property_sexpr(key: &str, value: &str) -> Sexpr {
+    Sexpr::list(vec![
+        Sexpr::symbol("property"),
+        Sexpr::string(key),
+        Sexpr::string(value),
+        Sexpr::list(vec![
+            Sexpr::symbol("at"),
+            Sexpr::symbol("0"),
+            Sexpr::symbol("0"),
+            Sexpr::symbol("0"),
+        ]),
+        Sexpr::list(vec![
+            Sexpr::symbol("effects"),
+            Sexpr::list(vec![
+                Sexpr::symbol("font"),
+                Sexpr::list(vec![
+                    Sexpr::symbol("size"),
+                    Sexpr::symbol("1.27"),
+                    Sexpr::symbol("1.27"),
+                ]),
+            ]),
+            Sexpr::symbol("hide"),
+        ]),
+    ])
 }
 
+/// File format version written into the header of serialized symbol libraries
+const KICAD_SYMBOL_LIB_VERSION: &str = "20211014";
+
 /// A symbol library that can contain multiple symbols
 pub struct SymbolLibrary {
     symbols: Vec<Symbol>,
@@ -114,4 +374,34 @@ impl SymbolLibrary {
     pub fn first_symbol(&self) -> Option<&Symbol> {
         self.symbols.first()
     }
-}
\ No newline at end of file
+
+    /// Serialize the library back into `.kicad_sym` format.
+    ///
+    /// Each symbol is reconstructed from its stored `raw_sexp` (see
+    /// [`Symbol::to_sexpr`]), so symbols without one cannot be written and
+    /// produce an error.
+    pub fn to_string(&self) -> Result<String> {
+        let mut items = vec![
+            Sexpr::symbol("kicad_symbol_lib"),
+            Sexpr::list(vec![
+                Sexpr::symbol("version"),
+                Sexpr::symbol(KICAD_SYMBOL_LIB_VERSION),
+            ]),
+            Sexpr::list(vec![Sexpr::symbol("generator"), Sexpr::string("picoplace")]),
+        ];
+
+        for symbol in &self.symbols {
+            items.push(symbol.to_sexpr()?);
+        }
+
+        let mut output = format_sexpr(&Sexpr::list(items), 0);
+        output.push('\n');
+        Ok(output)
+    }
+
+    /// Write the library to a `.kicad_sym` file
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_string()?)?;
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
 mod test_utils;
 
-use test_utils::setup_symbol;
+use test_utils::{setup_symbol, setup_test_env};
 
-use picoplace_eda::{Part, Symbol};
+use picoplace_eda::kicad::symbol::{find_pin, find_pin_offset, find_symbol_pin_offset};
+use picoplace_eda::{Part, PinAlternate, PinType, Symbol, SymbolLibrary};
 use std::collections::HashMap;
 
 fn test_symbol_property(symbol_name: &str, property: impl Fn(&Symbol) -> String, expected: &str) {
@@ -95,6 +96,66 @@ fn test_pcm2903cdb_pin_names() {
     }
 }
 
+#[test]
+fn test_pcm2903cdb_pin_types_and_positions() {
+    let symbol = setup_symbol("PCM2903CDB");
+    let pin = |number: &str| symbol.pins.iter().find(|p| p.number == number).unwrap();
+
+    assert_eq!(pin("1").electrical_type, PinType::Bidirectional);
+    assert_eq!(pin("3").electrical_type, PinType::PowerIn);
+    assert_eq!(pin("5").electrical_type, PinType::Input);
+    assert_eq!(pin("2").position, (0.0, -2.54, 0.0));
+
+    let raw = symbol.raw_sexp().unwrap();
+    let found = find_pin(raw, "VBUS").unwrap();
+    assert_eq!(found.number, "3");
+    assert_eq!(found.position, (0.0, -5.08, 0.0));
+    assert_eq!(find_pin(raw, "6").unwrap().name, "HID1");
+    assert!(find_pin(raw, "NOPE").is_none());
+}
+
+#[test]
+fn test_find_pin_offset() {
+    let content = include_str!("resources/kicad/PCM2903CDB/PCM2903CDB.kicad_sym");
+
+    for pin in ["VBUS", "3"] {
+        let offset = find_pin_offset(content, pin).unwrap();
+        let declaration = &content[offset..];
+        assert!(declaration.starts_with("(pin "));
+        let end = declaration.find("(number").unwrap();
+        assert!(declaration[..end].contains("\"VBUS\""));
+    }
+    assert!(find_pin_offset(content, "NOPE").is_none());
+}
+
+#[test]
+fn test_find_symbol_pin_offset() {
+    let content = r#"(kicad_symbol_lib
+  (symbol "A"
+    (symbol "A_1_1"
+      (pin passive line (at 0 0 0) (length 2.54) (name "1") (number "1"))
+    )
+  )
+  (symbol "B"
+    (symbol "B_1_1"
+      (pin passive line (at 0 0 0) (length 2.54) (name "1") (number "1"))
+    )
+  )
+  (symbol "C" (extends "B"))
+)
+"#;
+    let pin_of = |symbol: &str| find_symbol_pin_offset(content, symbol, "1");
+
+    // Pins are only looked up in the named symbol, not the first that has one
+    let b = pin_of("B").unwrap();
+    assert!(b > content.find("(symbol \"B\"").unwrap());
+    assert!(pin_of("A").unwrap() < b);
+    // Derived symbols have the pins of their parent
+    assert_eq!(pin_of("C"), Some(b));
+    assert!(pin_of("D").is_none());
+    assert!(find_symbol_pin_offset(content, "A", "2").is_none());
+}
+
 #[test]
 fn test_pcm2903cdb_manufacturer() {
     test_symbol_option_property(
@@ -445,3 +506,228 @@ fn test_lan9252ti_pin_names() {
     assert_eq!(pin_map.get("64"), Some(&"VDD3TXRX2".to_string()));
     assert_eq!(pin_map.get("EPAD"), Some(&"VSS".to_string()));
 }
+
+#[test]
+fn test_symbol_library_roundtrip() {
+    let temp_dir = setup_test_env();
+    let lib_path = temp_dir
+        .path()
+        .join("kicad/PCM2903CDB/PCM2903CDB.kicad_sym");
+
+    let original = SymbolLibrary::from_file(&lib_path).unwrap();
+    let out_path = temp_dir.path().join("roundtrip.kicad_sym");
+    original.write_to_file(&out_path).unwrap();
+    let reparsed = SymbolLibrary::from_file(&out_path).unwrap();
+
+    assert_eq!(original.symbol_names(), reparsed.symbol_names());
+    for (a, b) in original.symbols().iter().zip(reparsed.symbols()) {
+        assert_eq!(a.footprint, b.footprint);
+        assert_eq!(a.in_bom, b.in_bom);
+        assert_eq!(a.datasheet, b.datasheet);
+        assert_eq!(a.mpn, b.mpn);
+        assert_eq!(a.properties, b.properties);
+        assert_eq!(a.raw_sexp, b.raw_sexp);
+        let pins = |s: &Symbol| {
+            s.pins
+                .iter()
+                .map(|p| (p.number.clone(), p.name.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(pins(a), pins(b));
+    }
+}
+
+#[test]
+fn test_symbol_write_roundtrip() {
+    let temp_dir = setup_test_env();
+    let mut symbol = setup_symbol("PCM2903CDB");
+    symbol.footprint = "SSOP-28_5.3x10.2mm_P0.65mm".to_string();
+    symbol
+        .properties
+        .insert("LCSC".to_string(), "C9937".to_string());
+
+    let out_path = temp_dir.path().join("PCM2903CDB_edited.kicad_sym");
+    symbol.to_file(&out_path).unwrap();
+    let reparsed = Symbol::from_file(&out_path).unwrap();
+
+    assert_eq!(reparsed.name, symbol.name);
+    assert_eq!(reparsed.footprint, "SSOP-28_5.3x10.2mm_P0.65mm");
+    assert_eq!(reparsed.property("LCSC"), Some("C9937"));
+    assert_eq!(reparsed.datasheet, symbol.datasheet);
+    for (key, value) in &symbol.properties {
+        if key != "Footprint" {
+            assert_eq!(reparsed.property(key), Some(value.as_str()), "{key}");
+        }
+    }
+
+    let pins = |s: &Symbol| {
+        s.pins
+            .iter()
+            .map(|p| (p.number.clone(), p.name.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(pins(&reparsed), pins(&symbol));
+}
+
+#[test]
+fn test_symbol_write_keeps_footprint_library() {
+    let contents = r#"(kicad_symbol_lib
+        (version 20211014)
+        (generator "test")
+        (symbol "R_0402"
+            (in_bom yes)
+            (property "Reference" "R" (at 0 0 0))
+            (property "Footprint" "Resistor_SMD:R_0402_1005Metric" (at 0 0 0))
+        )
+    )"#;
+    let temp_dir = tempfile::tempdir().unwrap();
+    let out_path = temp_dir.path().join("R_0402.kicad_sym");
+    let written_footprint = |footprint: &str| {
+        let mut symbol = Symbol::from_string(contents, "kicad_sym").unwrap();
+        symbol.footprint = footprint.to_string();
+        symbol.to_file(&out_path).unwrap();
+        let reparsed = Symbol::from_file(&out_path).unwrap();
+        reparsed.property("Footprint").unwrap().to_string()
+    };
+
+    assert_eq!(
+        written_footprint("R_0402_1005Metric"),
+        "Resistor_SMD:R_0402_1005Metric"
+    );
+    assert_eq!(
+        written_footprint("R_0603_1608Metric"),
+        "Resistor_SMD:R_0603_1608Metric"
+    );
+    assert_eq!(
+        written_footprint("Custom:R_0603_1608Metric"),
+        "Custom:R_0603_1608Metric"
+    );
+}
+
+#[test]
+fn test_custom_properties_are_captured() {
+    let contents = r#"(kicad_symbol_lib
+        (version 20211014)
+        (generator "test")
+        (symbol "R_0402"
+            (in_bom yes)
+            (on_board yes)
+            (property "Reference" "R" (at 0 0 0))
+            (property "Value" "10k" (at 0 0 0))
+            (property "Footprint" "Resistor_SMD:R_0402_1005Metric" (at 0 0 0))
+            (property "Datasheet" "https://example.com/r.pdf" (at 0 0 0))
+            (property "LCSC" "C25744" (at 0 0 0))
+        )
+    )"#;
+
+    let symbol = Symbol::from_string(contents, "kicad_sym").unwrap();
+
+    assert_eq!(symbol.property("LCSC"), Some("C25744"));
+    assert_eq!(symbol.property("Reference"), Some("R"));
+    assert_eq!(symbol.property("Value"), Some("10k"));
+    assert_eq!(
+        symbol.property("Datasheet"),
+        Some("https://example.com/r.pdf")
+    );
+    assert_eq!(symbol.datasheet.as_deref(), symbol.property("Datasheet"));
+    assert_eq!(
+        symbol.property("Footprint"),
+        Some("Resistor_SMD:R_0402_1005Metric")
+    );
+    assert_eq!(symbol.footprint, "R_0402_1005Metric");
+}
+
+#[test]
+fn test_multi_unit_symbol() {
+    let contents = r#"(kicad_symbol_lib
+        (version 20211014)
+        (generator "test")
+        (symbol "LM358"
+            (in_bom yes)
+            (property "Reference" "U" (at 0 0 0))
+            (symbol "LM358_0_1"
+                (pin power_in line (at 0 5 270) (length 2.54) (name "V+") (number "8"))
+                (pin power_in line (at 0 -5 90) (length 2.54) (name "V-") (number "4"))
+            )
+            (symbol "LM358_1_1"
+                (pin input line (at -7.62 2.54 0) (length 2.54) (name "+") (number "3"))
+                (pin input line (at -7.62 -2.54 0) (length 2.54) (name "-") (number "2"))
+                (pin output line (at 7.62 0 180) (length 2.54) (name "~") (number "1"))
+            )
+            (symbol "LM358_1_2"
+                (pin output line (at 7.62 0 180) (length 2.54) (name "~") (number "1"))
+            )
+            (symbol "LM358_2_1"
+                (pin input line (at -7.62 2.54 0) (length 2.54) (name "+") (number "5"))
+                (pin input line (at -7.62 -2.54 0) (length 2.54) (name "-") (number "6"))
+                (pin output line (at 7.62 0 180) (length 2.54) (name "~") (number "7"))
+            )
+        )
+    )"#;
+
+    let symbol = Symbol::from_string(contents, "kicad_sym").unwrap();
+    assert_eq!(symbol.pins.len(), 9);
+
+    let units = symbol.units();
+    let numbers = |i: usize| {
+        let mut numbers: Vec<_> = units[i].pins.iter().map(|p| p.number.as_str()).collect();
+        numbers.sort();
+        numbers
+    };
+    assert_eq!(units.len(), 2);
+    assert_eq!(units[0].number, 1);
+    assert_eq!(numbers(0), vec!["1", "2", "3", "4", "8"]);
+    assert_eq!(units[1].number, 2);
+    assert_eq!(numbers(1), vec!["4", "5", "6", "7", "8"]);
+}
+
+#[test]
+fn test_single_unit_symbol() {
+    let symbol = setup_symbol("PCM2903CDB");
+    let units = symbol.units();
+    assert_eq!(units.len(), 1);
+    assert_eq!(units[0].number, 1);
+    assert_eq!(units[0].pins.len(), symbol.pins.len());
+}
+
+#[test]
+fn test_pin_alternates() {
+    let contents = r#"(kicad_symbol_lib
+        (version 20211014)
+        (generator "test")
+        (symbol "MCU"
+            (in_bom yes)
+            (symbol "MCU_1_1"
+                (pin bidirectional line (at -10.16 0 0) (length 2.54)
+                    (name "PA9") (number "30")
+                    (alternate "I2C1_SCL" bidirectional line)
+                    (alternate "USART1_TX" output line)
+                )
+                (pin power_in line (at 0 10.16 270) (length 2.54) (name "VDD") (number "1"))
+            )
+        )
+    )"#;
+
+    let symbol = Symbol::from_string(contents, "kicad_sym").unwrap();
+    let pa9 = symbol.pins.iter().find(|p| p.number == "30").unwrap();
+    assert_eq!(
+        pa9.alternates,
+        vec![
+            PinAlternate {
+                name: "I2C1_SCL".to_string(),
+                electrical_type: PinType::Bidirectional,
+            },
+            PinAlternate {
+                name: "USART1_TX".to_string(),
+                electrical_type: PinType::Output,
+            },
+        ]
+    );
+    assert!(pa9.has_function("PA9"));
+    assert!(pa9.has_function("I2C1_SCL"));
+    assert!(!pa9.has_function("SPI1_MOSI"));
+    assert_eq!(pa9.function_type("USART1_TX"), Some(PinType::Output));
+
+    let vdd = symbol.pins.iter().find(|p| p.number == "1").unwrap();
+    assert!(vdd.alternates.is_empty());
+}
@@ -0,0 +1,146 @@
+//! KiCad PCB Reader
+//!
+//! Recovers footprint placements from an existing `.kicad_pcb` file so that
+//! positions a user arranged by hand in KiCad can seed a new layout run.
+
+use crate::Point;
+use anyhow::{Context, Result};
+use picoplace_sexpr::Sexpr;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Read the placement of every footprint in a `.kicad_pcb` file, keyed by
+/// reference designator. See [`parse_placements`].
+pub fn read_placements(path: &Path) -> Result<HashMap<String, (Point, f64)>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_placements(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Extract each footprint's reference designator and `(at x y rot)` from the
+/// contents of a `.kicad_pcb` file.
+///
+/// Positions are the footprint origins in KiCad's board coordinates
+/// (millimetres, y pointing down) and rotations are in degrees, 0 when
+/// omitted. Both the `(footprint ...)` form of KiCad 6+ and the older
+/// `(module ...)` form are understood, with the reference read from either a
+/// `Reference` property or an `fp_text reference` item. Footprints without a
+/// reference are skipped.
+pub fn parse_placements(content: &str) -> Result<HashMap<String, (Point, f64)>> {
+    let root = picoplace_sexpr::parse(content)?;
+    let items = root
+        .as_list()
+        .filter(|items| head(items) == Some("kicad_pcb"))
+        .context("Not a KiCad PCB: expected a top-level (kicad_pcb ...) list")?;
+
+    let mut placements = HashMap::new();
+    for footprint in items.iter().filter_map(Sexpr::as_list) {
+        if !matches!(head(footprint), Some("footprint" | "module")) {
+            continue;
+        }
+
+        let Some(reference) = footprint_reference(footprint) else {
+            continue;
+        };
+        let at = child(footprint, "at")
+            .with_context(|| format!("Footprint {reference} has no (at ...) position"))?;
+        let coord = |index: usize| -> Result<Option<f64>> {
+            at.get(index)
+                .map(|value| {
+                    let value = value.as_atom().unwrap_or_default();
+                    value.parse::<f64>().with_context(|| {
+                        format!("Footprint {reference} has an invalid coordinate `{value}`")
+                    })
+                })
+                .transpose()
+        };
+
+        let (Some(x), Some(y)) = (coord(1)?, coord(2)?) else {
+            anyhow::bail!("Footprint {reference} has an incomplete (at ...) position");
+        };
+        let rotation = coord(3)?.unwrap_or(0.0);
+        placements.insert(reference.to_string(), (Point { x, y }, rotation));
+    }
+
+    Ok(placements)
+}
+
+/// The symbol a list starts with, e.g. `footprint` for `(footprint ...)`
+fn head(items: &[Sexpr]) -> Option<&str> {
+    match items.first() {
+        Some(Sexpr::Symbol(name)) => Some(name),
+        _ => None,
+    }
+}
+
+/// The first direct child list of `items` starting with `name`
+fn child<'a>(items: &'a [Sexpr], name: &str) -> Option<&'a [Sexpr]> {
+    items
+        .iter()
+        .filter_map(Sexpr::as_list)
+        .find(|list| head(list) == Some(name))
+}
+
+fn footprint_reference(footprint: &[Sexpr]) -> Option<&str> {
+    footprint
+        .iter()
+        .filter_map(Sexpr::as_list)
+        .find_map(|list| match (head(list)?, list.get(1)?.as_atom()?) {
+            ("property", "Reference") | ("fp_text", "reference") => list.get(2)?.as_atom(),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = r#"(kicad_pcb (version 20240108) (generator "pcbnew")
+  (layers (0 "F.Cu" signal))
+  (footprint "Resistor_SMD:R_0603_1608Metric" (layer "F.Cu")
+    (uuid "5c1f2c6e-0000-0000-0000-000000000001")
+    (at 101.5 48.25 90)
+    (property "Reference" "R1" (at 0 -1.43 90) (layer "F.SilkS"))
+    (property "Value" "10k" (at 0 1.43 90) (layer "F.Fab"))
+    (pad "1" smd roundrect (at -0.7875 0 90) (size 0.875 0.95) (layers "F.Cu"))
+  )
+  (footprint "Capacitor_SMD:C_0402_1005Metric" (layer "B.Cu")
+    (at 90 60)
+    (property "Reference" "C3" (at 0 -1.16) (layer "B.SilkS"))
+  )
+  (module Package_SO:SOIC-8 (layer F.Cu)
+    (at 120.1 75.3 180)
+    (fp_text reference U2 (at 0 -3.4) (layer F.SilkS))
+  )
+  (gr_line (start 0 0) (end 10 0) (layer "Edge.Cuts"))
+)
+"#;
+
+    #[test]
+    fn test_parse_placements() {
+        let placements = parse_placements(BOARD).unwrap();
+
+        let mut found: Vec<(&str, f64, f64, f64)> = placements
+            .iter()
+            .map(|(reference, (at, rotation))| (reference.as_str(), at.x, at.y, *rotation))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(b.0));
+        assert_eq!(
+            found,
+            vec![
+                ("C3", 90.0, 60.0, 0.0),
+                ("R1", 101.5, 48.25, 90.0),
+                ("U2", 120.1, 75.3, 180.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_placements_errors() {
+        assert!(parse_placements("(kicad_sch (version 20231120))").is_err());
+
+        let bad = r#"(kicad_pcb (footprint "R" (at 1 oops) (property "Reference" "R1")))"#;
+        let err = parse_placements(bad).unwrap_err();
+        assert!(err.to_string().contains("R1"), "{err}");
+    }
+}
@@ -8,11 +8,12 @@
 
 use anyhow::{Context, Result};
 use picoplace_netlist::{Instance, InstanceKind, InstanceRef, Schematic};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use svg::node::element::{Line, Rectangle, Text};
+use svg::node::element::{Circle, Group, Line, Rectangle, Text, Title};
 use svg::Document;
 
+pub mod kicad_pcb;
 pub mod placer_sa;
 pub mod router;
 
@@ -32,11 +33,103 @@ pub struct Rect {
     pub height: f64,
 }
 
+impl Rect {
+    pub fn max_x(&self) -> f64 {
+        self.x + self.width
+    }
+
+    pub fn max_y(&self) -> f64 {
+        self.y + self.height
+    }
+
+    /// Whether the two rectangles overlap with a non-zero area. Rectangles
+    /// that only share an edge do not intersect.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection_area(other) > 0.0
+    }
+
+    /// Area of the overlapping region, or 0.0 if the rectangles are disjoint.
+    pub fn intersection_area(&self, other: &Rect) -> f64 {
+        let x_overlap = self.max_x().min(other.max_x()) - self.x.max(other.x);
+        let y_overlap = self.max_y().min(other.max_y()) - self.y.max(other.y);
+
+        if x_overlap > 0.0 && y_overlap > 0.0 {
+            x_overlap * y_overlap
+        } else {
+            0.0
+        }
+    }
+
+    /// Whether `p` lies inside the rectangle or on its boundary.
+    pub fn contains_point(&self, p: Point) -> bool {
+        p.x >= self.x && p.x <= self.max_x() && p.y >= self.y && p.y <= self.max_y()
+    }
+
+    /// Whether `other` lies entirely inside this rectangle (edges may touch).
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.max_x() <= self.max_x()
+            && other.max_y() <= self.max_y()
+    }
+
+    /// Smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect {
+            x,
+            y,
+            width: self.max_x().max(other.max_x()) - x,
+            height: self.max_y().max(other.max_y()) - y,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PlacedComponent<'a> {
     pub instance: &'a Instance,
     pub instance_ref: &'a InstanceRef,
+    /// Axis-aligned footprint of the component after rotation.
     pub bounds: Rect,
+    /// Counter-clockwise rotation in degrees (0, 90, 180 or 270), as in KiCad.
+    pub rotation: f64,
+}
+
+impl PlacedComponent<'_> {
+    /// Center of the component's bounds.
+    pub fn center(&self) -> Point {
+        Point {
+            x: self.bounds.x + self.bounds.width / 2.0,
+            y: self.bounds.y + self.bounds.height / 2.0,
+        }
+    }
+
+    /// Absolute position of a pin given its offset from the component center
+    /// in the unrotated frame.
+    pub fn pin_position(&self, offset: Point) -> Point {
+        let rotated = rotate_offset(offset, self.rotation);
+        let center = self.center();
+        Point {
+            x: center.x + rotated.x,
+            y: center.y + rotated.y,
+        }
+    }
+}
+
+/// Rotate a y-down screen offset by `rotation` degrees, counter-clockwise as
+/// seen on screen like the KiCad schematic and PCB.
+fn rotate_offset(offset: Point, rotation: f64) -> Point {
+    let (x, y) = picoplace_netlist::rotate_screen_offset((offset.x, offset.y), rotation);
+    Point { x, y }
+}
+
+/// Footprint size after rotation; quarter turns swap width and height.
+pub(crate) fn rotated_size(width: f64, height: f64, rotation: f64) -> (f64, f64) {
+    match rotation.rem_euclid(360.0) as i32 {
+        90 | 270 => (height, width),
+        _ => (width, height),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +139,128 @@ pub struct Layout<'a> {
     pub height: f64,
 }
 
+impl Layout<'_> {
+    /// Summarize how well `schematic` is connected in this layout.
+    pub fn stats(&self, schematic: &Schematic) -> LayoutStats {
+        let mut stats = LayoutStats {
+            components: self.components.len(),
+            nets: schematic.nets.len(),
+            ..LayoutStats::default()
+        };
+
+        let centers = component_centers(self);
+        for nets in schematic.merged_nets().into_values() {
+            let points = net_points(schematic, &nets, &centers);
+            stats.ratsnest_length += ratsnest(&points)
+                .into_iter()
+                .map(|(a, b)| distance(a, b))
+                .sum::<f64>();
+            if points.len() == 2 {
+                stats.unrouted_two_pin_nets += 1;
+            }
+        }
+
+        stats
+    }
+}
+
+/// Quick quality summary of a placed layout, see [`Layout::stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+pub struct LayoutStats {
+    /// Number of components placed
+    pub components: usize,
+    /// Number of nets in the schematic
+    pub nets: usize,
+    /// Total length of the ratsnest, the minimum spanning tree joining the
+    /// components of each net (mm)
+    pub ratsnest_length: f64,
+    /// Nets joining exactly two placed components; nothing is routed yet, so
+    /// each still needs a trace
+    pub unrouted_two_pin_nets: usize,
+}
+
+impl std::fmt::Display for LayoutStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} components, {} nets, {:.1}mm ratsnest, {} unrouted two-pin nets",
+            self.components, self.nets, self.ratsnest_length, self.unrouted_two_pin_nets
+        )
+    }
+}
+
+/// Map of reference designator -> component center. Pins are approximated by
+/// the center of their component.
+fn component_centers(layout: &Layout) -> HashMap<String, Point> {
+    layout
+        .components
+        .iter()
+        .filter_map(|comp| {
+            let refdes = comp.instance.reference_designator.clone()?;
+            Some((refdes, comp.center()))
+        })
+        .collect()
+}
+
+/// Positions of the distinct placed components that `nets` connect, in port
+/// order. Pass a group from [`Schematic::merged_nets`] so tied nets share a
+/// ratsnest.
+fn net_points(
+    schematic: &Schematic,
+    nets: &[&picoplace_netlist::Net],
+    centers: &HashMap<String, Point>,
+) -> Vec<Point> {
+    let mut seen = HashSet::new();
+    nets.iter()
+        .flat_map(|net| &net.ports)
+        .filter_map(|port_ref| {
+            let (_, component) = schematic.component_of_port(port_ref)?;
+            let refdes = component.reference_designator.as_ref()?;
+            let center = centers.get(refdes).copied()?;
+            seen.insert(refdes).then_some(center)
+        })
+        .collect()
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+/// Edges of the minimum spanning tree over `points` (Prim's algorithm), i.e.
+/// the shortest set of airwires that connects them all.
+fn ratsnest(points: &[Point]) -> Vec<(Point, Point)> {
+    let Some(&first) = points.first() else {
+        return Vec::new();
+    };
+    // Closest tree point for every point not yet in the tree
+    let mut pending: Vec<(Point, Point)> = points[1..].iter().map(|&p| (p, first)).collect();
+    let mut edges = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let next = (0..pending.len())
+            .min_by(|&a, &b| {
+                let (pa, ta) = pending[a];
+                let (pb, tb) = pending[b];
+                distance(pa, ta).total_cmp(&distance(pb, tb))
+            })
+            .unwrap();
+        let (added, parent) = pending.swap_remove(next);
+        edges.push((parent, added));
+        for (point, closest) in &mut pending {
+            if distance(*point, added) < distance(*point, *closest) {
+                *closest = added;
+            }
+        }
+    }
+    edges
+}
+
+/// Trace width for a group of tied nets: the widest any of them asks for.
+fn trace_width(nets: &[&picoplace_netlist::Net]) -> f64 {
+    nets.iter()
+        .map(|net| net.effective_trace_width())
+        .fold(0.0, f64::max)
+}
+
 // --- Placer ---
 
 pub mod placer {
@@ -78,14 +293,14 @@ pub mod placer {
             let col = i % grid_size;
 
             // For now, assume a fixed size for all components
-            let comp_width = 30.0;
-            let comp_height = 20.0;
+            let rotation = instance.rotation();
+            let (comp_width, comp_height) = rotated_size(30.0, 20.0, rotation);
 
             let x = margin + (col as f64 * cell_size);
             let y = margin + (row as f64 * cell_size);
 
             placed_components.push(PlacedComponent {
-                instance, // Pass the reference
+                instance,     // Pass the reference
                 instance_ref, // Pass the reference
                 bounds: Rect {
                     x,
@@ -93,6 +308,7 @@ pub mod placer {
                     width: comp_width,
                     height: comp_height,
                 },
+                rotation,
             });
         }
 
@@ -109,67 +325,88 @@ pub mod placer {
 pub mod svg_generator {
     use super::*;
 
-    /// Generates an SVG document from a layout.
+    /// Generates an SVG document from a layout and writes it to `output_path`.
     pub fn run(layout: &Layout, schematic: &Schematic, output_path: &Path) -> Result<()> {
-        let mut document = Document::new()
-            .set("width", format!("{}mm", layout.width))
-            .set("height", format!("{}mm", layout.height))
-            .set(
-                "viewBox",
-                (0, 0, layout.width as u32, layout.height as u32),
-            );
+        let document = build_document(layout, schematic);
 
-        // --- Draw Ratsnest Lines ---
-        // Create a map of component ref -> pin positions for easy lookup
-        let mut pin_positions: HashMap<String, Point> = HashMap::new();
-        for comp in &layout.components {
-            // For now, let's just place pins at the center for simplicity
-            // A real implementation would parse the footprint to get exact pin locations
-            let center_x = comp.bounds.x + comp.bounds.width / 2.0;
-            let center_y = comp.bounds.y + comp.bounds.height / 2.0;
+        svg::save(output_path, &document)
+            .with_context(|| format!("Failed to save SVG to {}", output_path.display()))?;
 
-            if let Some(refdes) = &comp.instance.reference_designator {
-                pin_positions.insert(refdes.clone(), Point { x: center_x, y: center_y });
-            }
+        Ok(())
+    }
+
+    /// Renders a layout to SVG text without touching the filesystem.
+    pub fn render(layout: &Layout, schematic: &Schematic) -> String {
+        build_document(layout, schematic).to_string()
+    }
+
+    /// Component fills, cycled through by top-level submodule.
+    const MODULE_PALETTE: [&str; 8] = [
+        "lightblue",
+        "lightgreen",
+        "khaki",
+        "lightsalmon",
+        "plum",
+        "lightpink",
+        "paleturquoise",
+        "wheat",
+    ];
+
+    /// Height of one legend row below the board.
+    const LEGEND_ROW: f64 = 6.0;
+
+    /// Name of the top-level submodule a component belongs to; parts placed
+    /// directly in the root module are grouped as `(top)`.
+    fn top_module<'a>(comp: &PlacedComponent<'a>) -> &'a str {
+        match comp.instance_ref.instance_path.as_slice() {
+            [module, _, ..] => module,
+            _ => "(top)",
         }
+    }
 
-        for net in schematic.nets.values() {
-            let mut points_to_connect = Vec::new();
-            for port_ref in &net.ports {
-                // Find the parent component of this port
-                let mut comp_path = port_ref.instance_path.clone();
-                if comp_path.pop().is_none() {
-                    continue;
-                }
+    /// A `<title>` child, shown by browsers as a hover tooltip.
+    fn title(text: &str) -> Title {
+        Title::new().add(svg::node::Text::new(text))
+    }
 
-                let comp_inst_ref = picoplace_netlist::InstanceRef {
-                    module: port_ref.module.clone(),
-                    instance_path: comp_path,
-                };
+    fn build_document(layout: &Layout, schematic: &Schematic) -> Document {
+        // Assign colors in name order so renders are stable
+        let mut modules: Vec<&str> = layout.components.iter().map(top_module).collect();
+        modules.sort_unstable();
+        modules.dedup();
+        let colors: HashMap<&str, &str> = modules
+            .iter()
+            .zip(MODULE_PALETTE.iter().cycle())
+            .map(|(module, color)| (*module, *color))
+            .collect();
 
-                // Find the instance in the schematic that matches this reference
-                if let Some(comp_instance) = schematic.instances.get(&comp_inst_ref) {
-                    if let Some(refdes) = &comp_instance.reference_designator {
-                        if let Some(pos) = pin_positions.get(refdes) {
-                            points_to_connect.push(pos);
-                        }
-                    }
-                }
-            }
+        let height = layout.height + LEGEND_ROW * modules.len() as f64;
+        let mut document = Document::new()
+            .set("width", format!("{}mm", layout.width))
+            .set("height", format!("{}mm", height))
+            .set("viewBox", (0, 0, layout.width as u32, height.ceil() as u32));
 
-            if points_to_connect.len() > 1 {
-                for i in 0..points_to_connect.len() - 1 {
-                    let p1 = points_to_connect[i];
-                    let p2 = points_to_connect[i + 1];
-                    let line = Line::new()
-                        .set("x1", p1.x)
-                        .set("y1", p1.y)
-                        .set("x2", p2.x)
-                        .set("y2", p2.y)
-                        .set("stroke", "gray")
-                        .set("stroke-width", 0.2);
-                    document = document.add(line);
-                }
+        // --- Draw Ratsnest Lines ---
+        // For now, let's just place pins at the center for simplicity
+        // A real implementation would parse the footprint to get exact pin locations
+        let pin_positions = component_centers(layout);
+
+        for nets in schematic.merged_nets().into_values() {
+            let points_to_connect = net_points(schematic, &nets, &pin_positions);
+            // Tooltip naming every net tied into this ratsnest
+            let net_names: Vec<&str> = nets.iter().map(|net| net.name.as_str()).collect();
+            let net_names = net_names.join(", ");
+
+            for (p1, p2) in ratsnest(&points_to_connect) {
+                let line = Line::new()
+                    .set("x1", p1.x)
+                    .set("y1", p1.y)
+                    .set("x2", p2.x)
+                    .set("y2", p2.y)
+                    .set("stroke", "gray")
+                    .set("stroke-width", 0.2)
+                    .add(title(&net_names));
+                document = document.add(line);
             }
         }
 
@@ -180,25 +417,487 @@ pub mod svg_generator {
                 .set("y", comp.bounds.y)
                 .set("width", comp.bounds.width)
                 .set("height", comp.bounds.height)
-                .set("fill", "lightblue")
+                .set("fill", colors[top_module(comp)])
                 .set("stroke", "blue")
                 .set("stroke-width", 0.5);
 
-            document = document.add(rect);
+            // Group the component's shapes so the refdes tooltip covers them all
+            let mut group = Group::new();
+            if let Some(refdes) = &comp.instance.reference_designator {
+                group = group.add(title(refdes));
+            }
+            group = group.add(rect);
+
+            // Offsets below are in the unrotated footprint, so markers and
+            // labels turn with the component.
+            let (width, height) =
+                rotated_size(comp.bounds.width, comp.bounds.height, comp.rotation);
+
+            // Pin-1 marker in the top-left corner
+            let pin_one = comp.pin_position(Point {
+                x: 1.5 - width / 2.0,
+                y: 1.5 - height / 2.0,
+            });
+            let dot = Circle::new()
+                .set("cx", pin_one.x)
+                .set("cy", pin_one.y)
+                .set("r", 0.75)
+                .set("fill", "blue");
+            group = group.add(dot);
+
+            // Polarity marker on the pin-1 side
+            if comp.instance.is_polarized() {
+                let anchor = comp.pin_position(Point {
+                    x: 2.0 - width / 2.0,
+                    y: height / 2.0 - 2.0,
+                });
+                group = group.add(
+                    Text::new()
+                        .set("x", anchor.x)
+                        .set("y", anchor.y)
+                        .set("font-size", "4px")
+                        .add(svg::node::Text::new("+")),
+                );
+            }
 
             if let Some(refdes) = &comp.instance.reference_designator {
-                let text = Text::new()
-                    .set("x", comp.bounds.x + 2.0)
-                    .set("y", comp.bounds.y + 5.0)
+                // Anchor the label at the top-left corner
+                let anchor = comp.pin_position(Point {
+                    x: 2.0 - width / 2.0,
+                    y: 5.0 - height / 2.0,
+                });
+                let mut text = Text::new()
+                    .set("x", anchor.x)
+                    .set("y", anchor.y)
                     .set("font-size", "4px")
                     .add(svg::node::Text::new(refdes));
-                document = document.add(text);
+                if comp.rotation != 0.0 {
+                    text = text.set(
+                        "transform",
+                        // SVG rotates clockwise, so negate for our counter-clockwise angle
+                        format!("rotate({} {} {})", -comp.rotation, anchor.x, anchor.y),
+                    );
+                }
+                group = group.add(text);
             }
+
+            document = document.add(group);
         }
 
-        svg::save(output_path, &document)
-            .with_context(|| format!("Failed to save SVG to {}", output_path.display()))?;
+        // --- Draw Module Legend ---
+        for (row, module) in modules.iter().enumerate() {
+            let y = layout.height + LEGEND_ROW * row as f64 + 1.0;
+            let swatch = Rectangle::new()
+                .set("x", 2.0)
+                .set("y", y)
+                .set("width", 4.0)
+                .set("height", 4.0)
+                .set("fill", colors[module])
+                .set("stroke", "blue")
+                .set("stroke-width", 0.5);
+            let label = Text::new()
+                .set("x", 8.0)
+                .set("y", y + 3.5)
+                .set("font-size", "4px")
+                .add(svg::node::Text::new(*module));
+            document = document.add(swatch).add(label);
+        }
 
-        Ok(())
+        document
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use picoplace_netlist::ModuleRef;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_rect_intersection() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(5.0, 5.0, 10.0, 10.0);
+        let touching = rect(10.0, 0.0, 5.0, 5.0);
+
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection_area(&b), 25.0);
+        assert!(!a.intersects(&touching));
+        assert_eq!(a.intersection_area(&touching), 0.0);
+    }
+
+    #[test]
+    fn test_rect_containment_and_union() {
+        let outer = rect(0.0, 0.0, 20.0, 20.0);
+        let inner = rect(5.0, 5.0, 5.0, 5.0);
+        let straddling = rect(15.0, 15.0, 10.0, 10.0);
+
+        assert!(outer.contains_point(Point { x: 20.0, y: 0.0 }));
+        assert!(!outer.contains_point(Point { x: 20.1, y: 0.0 }));
+        assert!(outer.contains_rect(&inner));
+        assert!(!outer.contains_rect(&straddling));
+
+        let union = outer.union(&straddling);
+        assert_eq!(
+            (union.x, union.y, union.width, union.height),
+            (0.0, 0.0, 25.0, 25.0)
+        );
+    }
+
+    #[test]
+    fn test_rotated_pin_position() {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let inst_ref = InstanceRef::new(mod_ref.clone(), vec!["r1".into()]);
+        let instance = Instance::component(mod_ref).with_attribute("rotation", "90".to_string());
+
+        let rotation = instance.rotation();
+        let (width, height) = rotated_size(30.0, 20.0, rotation);
+        let comp = PlacedComponent {
+            instance: &instance,
+            instance_ref: &inst_ref,
+            bounds: Rect {
+                x: 0.0,
+                y: 0.0,
+                width,
+                height,
+            },
+            rotation,
+        };
+
+        assert_eq!((width, height), (20.0, 30.0));
+
+        // A pin 10mm right of and 4mm below the center ends up 10mm above and
+        // 4mm right of it once the part is turned a quarter counter-clockwise.
+        let pin = comp.pin_position(Point { x: 10.0, y: 4.0 });
+        assert_eq!((pin.x, pin.y), (10.0 + 4.0, 15.0 - 10.0));
+    }
+
+    #[test]
+    fn test_rotated_pin_matches_kicad_schematic() {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let inst_ref = InstanceRef::new(mod_ref.clone(), vec!["u1".into()]);
+        let instance = Instance::component(mod_ref).with_attribute("rotation", "90".to_string());
+        let comp = PlacedComponent {
+            instance: &instance,
+            instance_ref: &inst_ref,
+            bounds: Rect {
+                x: 0.0,
+                y: 0.0,
+                width: 20.0,
+                height: 30.0,
+            },
+            rotation: instance.rotation(),
+        };
+
+        // A symbol pin at (10, 4) in KiCad's +Y-up symbol space. The schematic
+        // places it at `position + (x, -y)` after rotating it about the origin.
+        let (pin_x, pin_y) = (10.0, 4.0);
+        let (x, y) = picoplace_netlist::rotate_point((pin_x, pin_y), comp.rotation);
+        let schematic_offset = (x, -y);
+        assert_eq!(schematic_offset, (-4.0, -10.0));
+
+        // The placer sees the same pin at (10, -4) from the unrotated center
+        let pin = comp.pin_position(Point {
+            x: pin_x,
+            y: -pin_y,
+        });
+        let center = comp.center();
+        assert_eq!((pin.x - center.x, pin.y - center.y), schematic_offset);
+    }
+
+    #[test]
+    fn test_layout_stats() {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let mut schematic = Schematic::new();
+        let mut port = |component: &str| {
+            let comp_ref = InstanceRef::new(mod_ref.clone(), vec![component.into()]);
+            if !schematic.instances.contains_key(&comp_ref) {
+                schematic.add_instance(
+                    comp_ref,
+                    Instance::component(mod_ref.clone())
+                        .with_reference_designator(component.to_uppercase()),
+                );
+            }
+            let port_ref = InstanceRef::new(mod_ref.clone(), vec![component.into(), "P".into()]);
+            schematic.add_instance(port_ref.clone(), Instance::port(mod_ref.clone()));
+            port_ref
+        };
+        let nets = [
+            ("TWO_PIN", vec![port("r1"), port("r2")]),
+            ("THREE_PIN", vec![port("r1"), port("r2"), port("r3")]),
+            ("DANGLING", vec![port("r3")]),
+        ];
+        for (name, ports) in nets {
+            schematic.add_net(picoplace_netlist::Net {
+                kind: picoplace_netlist::NetKind::Normal,
+                name: name.to_string(),
+                ports,
+                properties: HashMap::new(),
+            });
+        }
+
+        // r1, r2 and r3 in a row, 30mm apart center to center
+        let mut components: Vec<PlacedComponent> = schematic
+            .instances
+            .iter()
+            .filter(|(_, inst)| inst.kind == InstanceKind::Component)
+            .map(|(instance_ref, instance)| {
+                let x = match instance.reference_designator.as_deref() {
+                    Some("R1") => 0.0,
+                    Some("R2") => 30.0,
+                    _ => 60.0,
+                };
+                PlacedComponent {
+                    instance,
+                    instance_ref,
+                    bounds: rect(x, 0.0, 10.0, 10.0),
+                    rotation: 0.0,
+                }
+            })
+            .collect();
+        components.sort_by(|a, b| a.bounds.x.total_cmp(&b.bounds.x));
+        let layout = Layout {
+            components,
+            width: 100.0,
+            height: 100.0,
+        };
+
+        let stats = layout.stats(&schematic);
+        assert_eq!(
+            stats,
+            LayoutStats {
+                components: 3,
+                nets: 3,
+                ratsnest_length: 30.0 + 60.0,
+                unrouted_two_pin_nets: 1,
+            }
+        );
+        assert_eq!(
+            stats.to_string(),
+            "3 components, 3 nets, 90.0mm ratsnest, 1 unrouted two-pin nets"
+        );
+    }
+
+    /// Resistors `r1`, `r2`, ... (refdes `R1`, `R2`, ...) joined by `nets`,
+    /// each a name and the `(component, pin)` ports it connects
+    fn resistors(nets: &[(&str, &[(&str, &str)])]) -> Schematic {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let mut schematic = Schematic::new();
+        for (name, ports) in nets {
+            let ports = ports
+                .iter()
+                .map(|(component, pin)| {
+                    let comp_ref = InstanceRef::new(mod_ref.clone(), vec![component.to_string()]);
+                    schematic.add_instance(
+                        comp_ref,
+                        Instance::component(mod_ref.clone())
+                            .with_reference_designator(component.to_uppercase()),
+                    );
+                    let port_ref = InstanceRef::new(
+                        mod_ref.clone(),
+                        vec![component.to_string(), pin.to_string()],
+                    );
+                    schematic.add_instance(port_ref.clone(), Instance::port(mod_ref.clone()));
+                    port_ref
+                })
+                .collect();
+            schematic.add_net(picoplace_netlist::Net {
+                kind: picoplace_netlist::NetKind::Normal,
+                name: name.to_string(),
+                ports,
+                properties: HashMap::new(),
+            });
+        }
+        schematic
+    }
+
+    /// The components of `schematic` in a row, R1 at the left and 30mm apart
+    /// center to center
+    fn row_layout(schematic: &Schematic) -> Layout<'_> {
+        let components = schematic
+            .instances
+            .iter()
+            .filter(|(_, inst)| inst.kind == InstanceKind::Component)
+            .map(|(instance_ref, instance)| {
+                let index: f64 = instance.reference_designator.as_deref().unwrap()[1..]
+                    .parse()
+                    .unwrap();
+                PlacedComponent {
+                    instance,
+                    instance_ref,
+                    bounds: rect((index - 1.0) * 30.0, 0.0, 10.0, 10.0),
+                    rotation: 0.0,
+                }
+            })
+            .collect();
+        Layout {
+            components,
+            width: 100.0,
+            height: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_layout_stats_counts_distinct_components() {
+        let schematic = resistors(&[
+            // Three pins, but only two components to join
+            ("GND", &[("r1", "1"), ("r1", "2"), ("r2", "1")]),
+            // Listed out of board order: r1, r3, r2
+            ("VCC", &[("r1", "3"), ("r3", "1"), ("r2", "2")]),
+        ]);
+        let layout = row_layout(&schematic);
+
+        let stats = layout.stats(&schematic);
+        assert_eq!(stats.unrouted_two_pin_nets, 1);
+        // GND needs one 30mm airwire and VCC spans r1..r3 in 60mm, whatever
+        // order its ports are listed in
+        assert_eq!(stats.ratsnest_length, 30.0 + 60.0);
+
+        let svg = svg_generator::render(&layout, &schematic);
+        assert_eq!(svg.matches("<line").count(), 3, "{svg}");
+    }
+
+    #[test]
+    fn test_layout_stats_with_net_ties() {
+        let mut schematic = resistors(&[
+            ("TWO_PIN", &[("r1", "P"), ("r2", "P")]),
+            ("THREE_PIN", &[("r1", "P"), ("r2", "P"), ("r3", "P")]),
+            ("DANGLING", &[("r3", "P")]),
+        ]);
+        // Tying the dangling net to the two-pin one turns both into a single
+        // three-pin ratsnest
+        schematic.add_net_alias("DANGLING", "TWO_PIN");
+        let layout = row_layout(&schematic);
+
+        assert_eq!(
+            layout.stats(&schematic),
+            LayoutStats {
+                components: 3,
+                nets: 3,
+                ratsnest_length: 60.0 + 60.0,
+                unrouted_two_pin_nets: 0,
+            }
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_svg_titles() {
+        let mut schematic = resistors(&[
+            ("TWO_PIN", &[("r1", "P"), ("r2", "P")]),
+            ("DANGLING", &[("r3", "P")]),
+        ]);
+        schematic.add_net_alias("DANGLING", "TWO_PIN");
+        let layout = row_layout(&schematic);
+
+        // Hovering shows the refdes of a component and the nets of a ratsnest
+        let svg = svg_generator::render(&layout, &schematic).replace('\n', "");
+        assert!(svg.contains("<title>R1</title>"), "{svg}");
+        assert!(svg.contains("<title>DANGLING, TWO_PIN</title>"), "{svg}");
+    }
+
+    #[test]
+    fn test_svg_markers() {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let cap_ref = InstanceRef::new(mod_ref.clone(), vec!["c1".into()]);
+        let cap = Instance::component(mod_ref.clone())
+            .with_attribute("footprint", "Capacitor_SMD:CP_Elec_4x5.4".to_string())
+            .with_attribute("rotation", "180".to_string());
+        let res_ref = InstanceRef::new(mod_ref.clone(), vec!["r1".into()]);
+        let res = Instance::component(mod_ref)
+            .with_attribute("footprint", "Resistor_SMD:R_0603_1608Metric".to_string());
+        assert!(cap.is_polarized());
+        assert!(!res.is_polarized());
+
+        let layout = Layout {
+            components: vec![
+                PlacedComponent {
+                    instance: &cap,
+                    instance_ref: &cap_ref,
+                    bounds: rect(0.0, 0.0, 30.0, 20.0),
+                    rotation: 180.0,
+                },
+                PlacedComponent {
+                    instance: &res,
+                    instance_ref: &res_ref,
+                    bounds: rect(50.0, 0.0, 30.0, 20.0),
+                    rotation: 0.0,
+                },
+            ],
+            width: 100.0,
+            height: 100.0,
+        };
+        let svg = svg_generator::render(&layout, &Schematic::new());
+
+        // The rotated part has its pin-1 dot in the bottom-right corner
+        assert!(svg.contains(r#"<circle cx="28.5" cy="18.5""#), "{svg}");
+        assert!(svg.contains(r#"<circle cx="51.5" cy="1.5""#), "{svg}");
+        // Neither part has a label, so the only text is the capacitor's `+`
+        // and the one-entry module legend
+        assert_eq!(svg.matches("<text").count(), 2);
+    }
+
+    #[test]
+    fn test_svg_module_colors() {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let instance = Instance::component(mod_ref.clone());
+        let refs: Vec<InstanceRef> = [
+            vec!["power", "u1"],
+            vec!["mcu", "u2"],
+            vec!["power", "c1"],
+            vec!["r1"],
+        ]
+        .into_iter()
+        .map(|path| {
+            InstanceRef::new(
+                mod_ref.clone(),
+                path.into_iter().map(String::from).collect(),
+            )
+        })
+        .collect();
+
+        let layout = Layout {
+            components: refs
+                .iter()
+                .enumerate()
+                .map(|(i, instance_ref)| PlacedComponent {
+                    instance: &instance,
+                    instance_ref,
+                    bounds: rect(i as f64 * 20.0, 0.0, 10.0, 10.0),
+                    rotation: 0.0,
+                })
+                .collect(),
+            width: 100.0,
+            height: 50.0,
+        };
+        let svg = svg_generator::render(&layout, &Schematic::new());
+
+        // Modules are colored in name order: (top), mcu, power
+        let fills: Vec<&str> = svg
+            .split(r#"fill=""#)
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .filter(|fill| *fill != "blue")
+            .collect();
+        assert_eq!(
+            fills,
+            [
+                "khaki",
+                "lightgreen",
+                "khaki",
+                "lightblue",
+                // Legend swatches
+                "lightblue",
+                "lightgreen",
+                "khaki"
+            ]
+        );
+        assert!(svg.contains(r#"height="68mm""#), "{svg}");
+    }
+}
@@ -2,20 +2,43 @@
 //!
 //! This module implements a simulated annealing algorithm for component placement.
 //! It optimizes the placement by minimizing a cost function that considers:
-//! - Total wire length (Manhattan distance)
+//! - Total wire length (half-perimeter or minimum spanning tree, see [`CostModel`])
 //! - Component overlap
 //! - Adherence to AI placement suggestions (if provided)
+//! - Distance between components that should be placed as a pair
 
-use crate::{Layout, PlacedComponent, Point, Rect};
-use picoplace_netlist::{Instance, InstanceKind, InstanceRef, Schematic};
+use crate::{rotated_size, Layout, PlacedComponent, Point, Rect};
+use anyhow::Result;
+use picoplace_netlist::{Instance, InstanceKind, InstanceRef, Net, Schematic};
 use std::collections::HashMap;
 
-/// Configuration for the simulated annealing algorithm
+/// How the wire length of each net is estimated in the cost function.
+///
+/// HPWL is the standard placement metric and only needs one pass over a net's
+/// pins, so it stays cheap on large designs; it underestimates nets with many
+/// pins, which need more wire than their bounding box suggests. The MST
+/// estimate tracks the routed length more closely but costs O(pins²) per net
+/// on every move.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CostModel {
+    /// Half-perimeter of the bounding box of each net's pins
+    #[default]
+    Hpwl,
+    /// Length of each net's rectilinear minimum spanning tree
+    Mst,
+}
+
+/// Configuration for the simulated annealing algorithm.
+///
+/// The cooling schedule (`initial_temperature`, `cooling_rate`,
+/// `iterations_per_temp` and `min_temperature`) trades runtime for quality:
+/// a slower schedule explores more placements before settling.
 #[derive(Debug, Clone)]
 pub struct PlacerConfig {
     /// Initial temperature for simulated annealing
     pub initial_temperature: f64,
-    /// Cooling rate (0.0 to 1.0)
+    /// Factor the temperature is multiplied by after each round; must be
+    /// strictly between 0.0 and 1.0
     pub cooling_rate: f64,
     /// Number of iterations at each temperature
     pub iterations_per_temp: usize,
@@ -23,12 +46,76 @@ pub struct PlacerConfig {
     pub min_temperature: f64,
     /// Weight for wire length in the cost function
     pub wire_length_weight: f64,
+    /// How wire length is estimated
+    pub cost_model: CostModel,
     /// Weight for component overlap in the cost function
     pub overlap_weight: f64,
     /// Weight for AI hint adherence in the cost function
     pub ai_hint_weight: f64,
+    /// Weight for the distance between paired components in the cost function
+    pub pair_weight: f64,
+    /// Seed for the random number generator. Runs with the same seed and
+    /// configuration produce the same placement; `None` seeds randomly.
+    ///
+    /// The annealer draws from `fastrand`'s wyrand generator, which yields the
+    /// same stream on every platform, and visits components and nets in a fixed
+    /// order, so a seeded placement is reproducible across Linux, macOS and
+    /// Windows and independent of `HashMap` iteration order.
+    pub seed: Option<u64>,
+    /// Stop once the best cost has not improved for this many consecutive
+    /// iterations; `None` always runs the full cooling schedule. Stopping
+    /// depends only on the costs seen, so a seeded run stays reproducible.
+    pub patience: Option<usize>,
+    /// Pitch of the grid component positions are snapped to (e.g. 1.27 for a
+    /// 50 mil grid). Every candidate move is snapped before it is costed, so
+    /// snapping never introduces overlaps the annealer did not see. `0.0`
+    /// disables snapping.
+    pub grid_pitch: f64,
+    /// Record the best cost after every iteration, see
+    /// [`SimulatedAnnealingPlacer::cost_history`]. Off by default to avoid
+    /// the allocation.
+    pub track_history: bool,
+}
+
+impl PlacerConfig {
+    /// Check that the cooling schedule actually cools and ends, and that the
+    /// grid is usable.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.cooling_rate > 0.0 && self.cooling_rate < 1.0) {
+            anyhow::bail!(
+                "cooling_rate must be between 0 and 1 (exclusive), got {}",
+                self.cooling_rate
+            );
+        }
+        // Cooling only approaches zero, so a non-positive floor is never reached
+        if !(self.min_temperature > 0.0 && self.min_temperature.is_finite()) {
+            anyhow::bail!(
+                "min_temperature must be a positive number, got {}",
+                self.min_temperature
+            );
+        }
+        // Starting at or below the floor would stop before the first move
+        if !(self.initial_temperature > self.min_temperature
+            && self.initial_temperature.is_finite())
+        {
+            anyhow::bail!(
+                "initial_temperature must be a number above min_temperature ({}), got {}",
+                self.min_temperature,
+                self.initial_temperature
+            );
+        }
+        if !(self.grid_pitch >= 0.0 && self.grid_pitch.is_finite()) {
+            anyhow::bail!(
+                "grid_pitch must be a non-negative number, got {}",
+                self.grid_pitch
+            );
+        }
+        Ok(())
+    }
 }
 
+/// The default schedule: start at 100, cool by 5% per round of 100 moves and
+/// stop below 0.1, i.e. 135 rounds.
 impl Default for PlacerConfig {
     fn default() -> Self {
         Self {
@@ -37,47 +124,257 @@ impl Default for PlacerConfig {
             iterations_per_temp: 100,
             min_temperature: 0.1,
             wire_length_weight: 1.0,
+            cost_model: CostModel::Hpwl,
             overlap_weight: 10.0,
             ai_hint_weight: 5.0,
+            pair_weight: 20.0,
+            seed: None,
+            patience: None,
+            grid_pitch: 0.0,
+            track_history: false,
         }
     }
 }
 
-/// AI placement suggestions
-pub type PlacementHints = HashMap<String, Point>;
+/// Placement guidance for the annealer, keyed by reference designator
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct PlacementHints {
+    /// Suggested component positions, e.g. from the AI engine
+    #[serde(default)]
+    pub positions: HashMap<String, Point>,
+    /// Components that should sit next to each other, such as a decoupling
+    /// capacitor and its IC or the two halves of a differential pair
+    #[serde(default)]
+    pub pairs: Vec<(String, String)>,
+}
+
+impl From<HashMap<String, Point>> for PlacementHints {
+    fn from(positions: HashMap<String, Point>) -> Self {
+        Self {
+            positions,
+            pairs: Vec::new(),
+        }
+    }
+}
+
+/// Place `schematic` using simulated annealing with the default configuration.
+pub fn run(schematic: &Schematic) -> Layout<'_> {
+    SimulatedAnnealingPlacer::new(schematic, PlacerConfig::default(), None)
+        .expect("default config is valid")
+        .run()
+}
+
+/// Place `schematic` using simulated annealing, steering components towards
+/// the positions suggested in `hints`.
+pub fn run_with_hints(schematic: &Schematic, hints: PlacementHints) -> Layout<'_> {
+    SimulatedAnnealingPlacer::new(schematic, PlacerConfig::default(), Some(hints))
+        .expect("default config is valid")
+        .run()
+}
+
+/// Place `schematic` using simulated annealing with a custom configuration
+/// and optional placement hints. Fails if `config` is invalid.
+pub fn run_with_config(
+    schematic: &Schematic,
+    config: PlacerConfig,
+    hints: Option<PlacementHints>,
+) -> Result<Layout<'_>> {
+    Ok(SimulatedAnnealingPlacer::new(schematic, config, hints)?.run())
+}
+
+/// Compute a connectivity-aware starting placement for `schematic`.
+///
+/// Components are grouped greedily by how strongly they are connected: each
+/// net contributes `1 / (pins - 1)` to every pair of components on it, so a
+/// two-pin net binds its ends tightly while a ground net barely registers.
+/// Starting from the most connected component, the next one placed is always
+/// the one most strongly connected to the current cluster; a new cluster
+/// starts once nothing left connects to it. The resulting order is laid out
+/// on the same grid as the default initial placement, snaking row by row so
+/// consecutive components stay adjacent.
+///
+/// Pass the result to [`run_with_hints`] (or [`run_with_config`], with
+/// `ai_hint_weight` at 0.0 to use it purely as a starting point).
+/// Components without a reference designator are left out.
+pub fn seed_placement(schematic: &Schematic) -> PlacementHints {
+    let mut refdes: Vec<&str> = schematic
+        .instances
+        .values()
+        .filter(|inst| inst.kind == InstanceKind::Component)
+        .filter_map(|inst| inst.reference_designator.as_deref())
+        .collect();
+    refdes.sort_unstable();
+    refdes.dedup();
+    if refdes.is_empty() {
+        return PlacementHints::default();
+    }
+    let index: HashMap<&str, usize> = refdes.iter().enumerate().map(|(i, r)| (*r, i)).collect();
+
+    // Pairwise connection strength between components, counting tied nets as one
+    let mut weights = vec![vec![0.0; refdes.len()]; refdes.len()];
+    for nets in schematic.merged_nets().into_values() {
+        let mut members: Vec<usize> = nets
+            .iter()
+            .flat_map(|net| &net.ports)
+            .filter_map(|port_ref| {
+                let (_, component) = schematic.component_of_port(port_ref)?;
+                index
+                    .get(component.reference_designator.as_deref()?)
+                    .copied()
+            })
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+        if members.len() < 2 {
+            continue;
+        }
+
+        let weight = 1.0 / (members.len() - 1) as f64;
+        for (i, &a) in members.iter().enumerate() {
+            for &b in &members[i + 1..] {
+                weights[a][b] += weight;
+                weights[b][a] += weight;
+            }
+        }
+    }
+
+    // Greedily order components so strongly connected ones end up adjacent
+    let mut placed = vec![false; refdes.len()];
+    let mut order = Vec::with_capacity(refdes.len());
+    let mut affinity = vec![0.0; refdes.len()];
+    let totals: Vec<f64> = weights.iter().map(|row| row.iter().sum()).collect();
+    while order.len() < refdes.len() {
+        let strongest = |scores: &[f64]| {
+            (0..refdes.len())
+                .filter(|&i| !placed[i])
+                .fold(None, |best: Option<usize>, i| match best {
+                    Some(b) if scores[b] >= scores[i] => Some(b),
+                    _ => Some(i),
+                })
+                .expect("an unplaced component remains")
+        };
+
+        let next = strongest(&affinity);
+        let next = if affinity[next] > 0.0 {
+            next
+        } else {
+            // Nothing connects to the current cluster; start a new one
+            affinity.iter_mut().for_each(|a| *a = 0.0);
+            strongest(&totals)
+        };
+
+        placed[next] = true;
+        order.push(next);
+        for (a, w) in affinity.iter_mut().zip(&weights[next]) {
+            *a += w;
+        }
+    }
+
+    // Lay the order out on the default grid, reversing every other row
+    let grid_size = (refdes.len() as f64).sqrt().ceil() as usize;
+    let cell_size = 50.0;
+    let margin = 20.0;
+    let positions = order
+        .into_iter()
+        .enumerate()
+        .map(|(i, component)| {
+            let row = i / grid_size;
+            let col = if row % 2 == 1 {
+                grid_size - 1 - i % grid_size
+            } else {
+                i % grid_size
+            };
+            let position = Point {
+                x: margin + col as f64 * cell_size,
+                y: margin + row as f64 * cell_size,
+            };
+            (refdes[component].to_string(), position)
+        })
+        .collect::<HashMap<_, _>>();
+
+    positions.into()
+}
+
+/// Half-perimeter of the bounding box of `points`
+fn half_perimeter(points: &[Point]) -> f64 {
+    let (min_x, max_x) = points
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+            (lo.min(p.x), hi.max(p.x))
+        });
+    let (min_y, max_y) = points
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+            (lo.min(p.y), hi.max(p.y))
+        });
+    (max_x - min_x) + (max_y - min_y)
+}
 
 /// Simulated annealing placer
 pub struct SimulatedAnnealingPlacer<'a> {
     schematic: &'a Schematic,
     config: PlacerConfig,
     placement_hints: Option<PlacementHints>,
+    /// Nets grouped by the net they are tied to (see
+    /// [`Schematic::merged_nets`]), sorted by name so cost sums are
+    /// accumulated in a stable order
+    nets: Vec<Vec<&'a Net>>,
+    /// Iterations (proposed moves) performed by the last call to `run`
+    iterations_run: usize,
+    /// Best cost after each iteration of the last run, if tracked
+    cost_history: Vec<f64>,
     board_width: f64,
     board_height: f64,
 }
 
 impl<'a> SimulatedAnnealingPlacer<'a> {
+    /// Create a placer, failing if `config` is invalid (see [`PlacerConfig::validate`]).
     pub fn new(
         schematic: &'a Schematic,
         config: PlacerConfig,
         placement_hints: Option<PlacementHints>,
-    ) -> Self {
-        Self {
+    ) -> Result<Self> {
+        config.validate()?;
+
+        let nets = schematic.merged_nets().into_values().collect();
+
+        Ok(Self {
             schematic,
             config,
             placement_hints,
-            board_width: 100.0,  // Default board size
+            nets,
+            iterations_run: 0,
+            cost_history: Vec::new(),
+            board_width: 100.0, // Default board size
             board_height: 100.0,
-        }
+        })
+    }
+
+    /// Number of iterations the last [`Self::run`] performed, which is less
+    /// than the full schedule if it stopped early (see [`PlacerConfig::patience`]).
+    pub fn iterations_run(&self) -> usize {
+        self.iterations_run
+    }
+
+    /// Best cost after each iteration of the last [`Self::run`], for plotting
+    /// convergence. Empty unless [`PlacerConfig::track_history`] is set.
+    pub fn cost_history(&self) -> &[f64] {
+        &self.cost_history
     }
 
     /// Run the simulated annealing algorithm
     pub fn run(&mut self) -> Layout<'a> {
-        let components: Vec<(&InstanceRef, &Instance)> = self
+        self.iterations_run = 0;
+        self.cost_history.clear();
+
+        let mut components: Vec<(&InstanceRef, &Instance)> = self
             .schematic
             .instances
             .iter()
             .filter(|(_inst_ref, inst)| inst.kind == InstanceKind::Component)
             .collect();
+        // Fix the order so a seed always perturbs the same components
+        components.sort_by_cached_key(|(inst_ref, _)| inst_ref.to_string());
 
         if components.is_empty() {
             return Layout {
@@ -89,32 +386,47 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
 
         // Initialize with grid placement
         let mut current_placement = self.initialize_placement(&components);
+        for comp in &mut current_placement {
+            self.snap_to_grid(&mut comp.bounds);
+        }
         let mut current_cost = self.calculate_cost(&current_placement);
 
         let mut best_placement = current_placement.clone();
         let mut best_cost = current_cost;
 
         let mut temperature = self.config.initial_temperature;
-        let mut rng = fastrand::Rng::new();
+        let mut rng = match self.config.seed {
+            Some(seed) => fastrand::Rng::with_seed(seed),
+            None => fastrand::Rng::new(),
+        };
+
+        let mut since_improvement = 0;
 
         // Simulated annealing loop
-        while temperature > self.config.min_temperature {
+        'anneal: while temperature > self.config.min_temperature {
             for _ in 0..self.config.iterations_per_temp {
+                self.iterations_run += 1;
+
                 // Generate a neighbor solution by randomly moving a component
                 let mut new_placement = current_placement.clone();
                 let len = new_placement.len();
-                if let Some(comp) = new_placement.get_mut(rng.usize(0..len)) {
+                // Draw a u64 rather than a usize so 32- and 64-bit targets
+                // consume the same random stream
+                let index = rng.u64(0..len as u64) as usize;
+                if let Some(comp) = new_placement.get_mut(index) {
                     // Random perturbation
                     let dx = (rng.f64() - 0.5) * 20.0;
                     let dy = (rng.f64() - 0.5) * 20.0;
-                    comp.bounds.x = (comp.bounds.x + dx).max(0.0).min(self.board_width - comp.bounds.width);
-                    comp.bounds.y = (comp.bounds.y + dy).max(0.0).min(self.board_height - comp.bounds.height);
+                    comp.bounds.x += dx;
+                    comp.bounds.y += dy;
+                    self.snap_to_board(&mut comp.bounds);
                 }
 
                 let new_cost = self.calculate_cost(&new_placement);
                 let delta_cost = new_cost - current_cost;
 
                 // Accept or reject the new solution
+                let mut improved = false;
                 if delta_cost < 0.0 || rng.f64() < (-delta_cost / temperature).exp() {
                     current_placement = new_placement;
                     current_cost = new_cost;
@@ -122,8 +434,27 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
                     if current_cost < best_cost {
                         best_placement = current_placement.clone();
                         best_cost = current_cost;
+                        improved = true;
                     }
                 }
+
+                if self.config.track_history {
+                    self.cost_history.push(best_cost);
+                }
+
+                // Stop early once the best cost has plateaued
+                since_improvement = if improved { 0 } else { since_improvement + 1 };
+                if self
+                    .config
+                    .patience
+                    .is_some_and(|patience| since_improvement >= patience)
+                {
+                    log::debug!(
+                        "Annealing converged after {} iterations",
+                        self.iterations_run
+                    );
+                    break 'anneal;
+                }
             }
 
             temperature *= self.config.cooling_rate;
@@ -139,8 +470,41 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
         }
     }
 
+    /// Snap a component's origin to the nearest multiple of the configured
+    /// grid pitch.
+    fn snap_to_grid(&self, bounds: &mut Rect) {
+        let pitch = self.config.grid_pitch;
+        if pitch <= 0.0 {
+            return;
+        }
+
+        bounds.x = (bounds.x / pitch).round() * pitch;
+        bounds.y = (bounds.y / pitch).round() * pitch;
+    }
+
+    /// Snap a moved component to the grid (see [`Self::snap_to_grid`]), then
+    /// pull it back onto the board. Positions are clamped to
+    /// the last grid line that still fits, so they stay on the grid.
+    fn snap_to_board(&self, bounds: &mut Rect) {
+        let pitch = self.config.grid_pitch;
+        let snap = |coord: f64, limit: f64| {
+            if pitch <= 0.0 {
+                coord.max(0.0).min(limit)
+            } else {
+                let snapped = (coord / pitch).round() * pitch;
+                snapped.max(0.0).min((limit / pitch).floor() * pitch)
+            }
+        };
+
+        bounds.x = snap(bounds.x, self.board_width - bounds.width);
+        bounds.y = snap(bounds.y, self.board_height - bounds.height);
+    }
+
     /// Initialize placement using a simple grid layout
-    fn initialize_placement(&self, components: &[(&'a InstanceRef, &'a Instance)]) -> Vec<PlacedComponent<'a>> {
+    fn initialize_placement(
+        &self,
+        components: &[(&'a InstanceRef, &'a Instance)],
+    ) -> Vec<PlacedComponent<'a>> {
         let num_components = components.len();
         let grid_size = (num_components as f64).sqrt().ceil() as usize;
         let cell_size = 50.0;
@@ -151,8 +515,8 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
             let row = i / grid_size;
             let col = i % grid_size;
 
-            let comp_width = 30.0;
-            let comp_height = 20.0;
+            let rotation = instance.rotation();
+            let (comp_width, comp_height) = rotated_size(30.0, 20.0, rotation);
 
             let mut x = margin + (col as f64 * cell_size);
             let mut y = margin + (row as f64 * cell_size);
@@ -160,7 +524,7 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
             // Use AI hint if available
             if let Some(hints) = &self.placement_hints {
                 if let Some(refdes) = &instance.reference_designator {
-                    if let Some(hint) = hints.get(refdes) {
+                    if let Some(hint) = hints.positions.get(refdes) {
                         x = hint.x;
                         y = hint.y;
                     }
@@ -176,6 +540,7 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
                     width: comp_width,
                     height: comp_height,
                 },
+                rotation,
             });
         }
 
@@ -187,10 +552,12 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
         let wire_length_cost = self.calculate_wire_length(placement);
         let overlap_cost = self.calculate_overlap(placement);
         let ai_hint_cost = self.calculate_ai_hint_cost(placement);
+        let pair_cost = self.calculate_pair_cost(placement);
 
         self.config.wire_length_weight * wire_length_cost
             + self.config.overlap_weight * overlap_cost
             + self.config.ai_hint_weight * ai_hint_cost
+            + self.config.pair_weight * pair_cost
     }
 
     /// Calculate total wire length (Manhattan distance)
@@ -211,21 +578,11 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
             }
         }
 
-        // Calculate wire length for each net
-        for net in self.schematic.nets.values() {
+        // Calculate wire length for each net, treating tied nets as one
+        for nets in &self.nets {
             let mut net_positions = Vec::new();
-            for port_ref in &net.ports {
-                let mut comp_path = port_ref.instance_path.clone();
-                if comp_path.pop().is_none() {
-                    continue;
-                }
-
-                let comp_inst_ref = InstanceRef {
-                    module: port_ref.module.clone(),
-                    instance_path: comp_path,
-                };
-
-                if let Some(comp_instance) = self.schematic.instances.get(&comp_inst_ref) {
+            for port_ref in nets.iter().flat_map(|net| &net.ports) {
+                if let Some((_, comp_instance)) = self.schematic.component_of_port(port_ref) {
                     if let Some(refdes) = &comp_instance.reference_designator {
                         if let Some(pos) = positions.get(refdes) {
                             net_positions.push(*pos);
@@ -234,12 +591,11 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
                 }
             }
 
-            // Calculate minimum spanning tree length (approximation using star topology)
             if net_positions.len() > 1 {
-                let center = self.calculate_centroid(&net_positions);
-                for pos in &net_positions {
-                    total_length += self.manhattan_distance(&center, pos);
-                }
+                total_length += match self.config.cost_model {
+                    CostModel::Hpwl => half_perimeter(&net_positions),
+                    CostModel::Mst => self.mst_length(&net_positions),
+                };
             }
         }
 
@@ -252,17 +608,7 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
 
         for i in 0..placement.len() {
             for j in (i + 1)..placement.len() {
-                let rect1 = &placement[i].bounds;
-                let rect2 = &placement[j].bounds;
-
-                let x_overlap = (rect1.x + rect1.width).min(rect2.x + rect2.width)
-                    - rect1.x.max(rect2.x);
-                let y_overlap = (rect1.y + rect1.height).min(rect2.y + rect2.height)
-                    - rect1.y.max(rect2.y);
-
-                if x_overlap > 0.0 && y_overlap > 0.0 {
-                    overlap += x_overlap * y_overlap;
-                }
+                overlap += placement[i].bounds.intersection_area(&placement[j].bounds);
             }
         }
 
@@ -276,7 +622,7 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
 
             for comp in placement {
                 if let Some(refdes) = &comp.instance.reference_designator {
-                    if let Some(hint) = hints.get(refdes) {
+                    if let Some(hint) = hints.positions.get(refdes) {
                         let center = Point {
                             x: comp.bounds.x + comp.bounds.width / 2.0,
                             y: comp.bounds.y + comp.bounds.height / 2.0,
@@ -292,16 +638,55 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
         }
     }
 
-    /// Calculate centroid of a set of points
-    fn calculate_centroid(&self, points: &[Point]) -> Point {
-        let sum_x: f64 = points.iter().map(|p| p.x).sum();
-        let sum_y: f64 = points.iter().map(|p| p.y).sum();
-        let n = points.len() as f64;
+    /// Calculate the total center distance between paired components
+    fn calculate_pair_cost(&self, placement: &[PlacedComponent<'a>]) -> f64 {
+        let Some(hints) = &self.placement_hints else {
+            return 0.0;
+        };
+        if hints.pairs.is_empty() {
+            return 0.0;
+        }
+
+        let centers: HashMap<&str, Point> = placement
+            .iter()
+            .filter_map(|comp| {
+                let refdes = comp.instance.reference_designator.as_deref()?;
+                Some((refdes, comp.center()))
+            })
+            .collect();
 
-        Point {
-            x: sum_x / n,
-            y: sum_y / n,
+        hints
+            .pairs
+            .iter()
+            .filter_map(|(a, b)| Some((centers.get(a.as_str())?, centers.get(b.as_str())?)))
+            .map(|(a, b)| self.euclidean_distance(a, b))
+            .sum()
+    }
+
+    /// Length of the rectilinear minimum spanning tree over `points` (Prim's algorithm)
+    fn mst_length(&self, points: &[Point]) -> f64 {
+        let mut in_tree = vec![false; points.len()];
+        let mut distance = vec![f64::INFINITY; points.len()];
+        distance[0] = 0.0;
+
+        let mut total = 0.0;
+        for _ in 0..points.len() {
+            let next = (0..points.len())
+                .filter(|&i| !in_tree[i])
+                .min_by(|&a, &b| distance[a].total_cmp(&distance[b]))
+                .expect("a point remains outside the tree");
+            in_tree[next] = true;
+            total += distance[next];
+
+            for i in 0..points.len() {
+                if !in_tree[i] {
+                    distance[i] =
+                        distance[i].min(self.manhattan_distance(&points[next], &points[i]));
+                }
+            }
         }
+
+        total
     }
 
     /// Calculate Manhattan distance between two points
@@ -328,3 +713,466 @@ impl<'a> SimulatedAnnealingPlacer<'a> {
         (max_x + margin, max_y + margin)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use picoplace_netlist::{ModuleRef, Net, NetKind};
+    use std::collections::HashMap;
+
+    fn schematic() -> Schematic {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let mut schematic = Schematic::new();
+        let mut net = Net {
+            kind: NetKind::Normal,
+            name: "N1".to_string(),
+            ports: Vec::new(),
+            properties: HashMap::new(),
+        };
+        for name in ["r1", "r2", "r3"] {
+            schematic.add_instance(
+                InstanceRef::new(mod_ref.clone(), vec![name.into()]),
+                Instance::component(mod_ref.clone()).with_reference_designator(name.to_uppercase()),
+            );
+            let port_ref = InstanceRef::new(mod_ref.clone(), vec![name.into(), "P1".into()]);
+            schematic.add_instance(port_ref.clone(), Instance::port(mod_ref.clone()));
+            net.ports.push(port_ref);
+        }
+        schematic.add_net(net);
+        schematic
+    }
+
+    fn positions(layout: &Layout) -> Vec<(f64, f64)> {
+        layout
+            .components
+            .iter()
+            .map(|c| (c.bounds.x, c.bounds.y))
+            .collect()
+    }
+
+    #[test]
+    fn test_seeded_placement_is_deterministic() {
+        let schematic = schematic();
+        let config = PlacerConfig {
+            seed: Some(42),
+            iterations_per_temp: 10,
+            ..PlacerConfig::default()
+        };
+
+        let first = run_with_config(&schematic, config.clone(), None).unwrap();
+        let second = run_with_config(&schematic, config, None).unwrap();
+
+        assert_eq!(first.components.len(), 3);
+        assert_eq!(positions(&first), positions(&second));
+    }
+
+    #[test]
+    fn test_seeded_placement_is_independent_of_insertion_order() {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let build = |names: &[&str]| {
+            let mut schematic = Schematic::new();
+            for name in names {
+                schematic.add_instance(
+                    InstanceRef::new(mod_ref.clone(), vec![name.to_string()]),
+                    Instance::component(mod_ref.clone())
+                        .with_reference_designator(name.to_uppercase()),
+                );
+            }
+            schematic
+        };
+        let forward = build(&["r1", "r2", "c1", "c2", "u1"]);
+        let reversed = build(&["u1", "c2", "c1", "r2", "r1"]);
+        let config = PlacerConfig {
+            seed: Some(1234),
+            iterations_per_temp: 10,
+            ..PlacerConfig::default()
+        };
+
+        let first = run_with_config(&forward, config.clone(), None).unwrap();
+        let second = run_with_config(&reversed, config, None).unwrap();
+
+        assert_eq!(format!("{first:?}"), format!("{second:?}"));
+    }
+
+    #[test]
+    fn test_invalid_cooling_rate_is_rejected() {
+        let schematic = schematic();
+        for cooling_rate in [0.0, 1.0, 1.5, -0.5, f64::NAN] {
+            let config = PlacerConfig {
+                cooling_rate,
+                ..PlacerConfig::default()
+            };
+            assert!(run_with_config(&schematic, config, None).is_err());
+        }
+        for grid_pitch in [-1.27, f64::NAN, f64::INFINITY] {
+            let config = PlacerConfig {
+                grid_pitch,
+                ..PlacerConfig::default()
+            };
+            assert!(run_with_config(&schematic, config, None).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_min_temperature_is_rejected() {
+        let schematic = schematic();
+        for min_temperature in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            let config = PlacerConfig {
+                min_temperature,
+                ..PlacerConfig::default()
+            };
+            assert!(run_with_config(&schematic, config, None).is_err());
+        }
+    }
+
+    #[test]
+    fn test_invalid_initial_temperature_is_rejected() {
+        let schematic = schematic();
+        // The default floor is 0.1
+        for initial_temperature in [0.1, 0.05, -1.0, f64::NAN, f64::INFINITY] {
+            let config = PlacerConfig {
+                initial_temperature,
+                ..PlacerConfig::default()
+            };
+            assert!(run_with_config(&schematic, config, None).is_err());
+        }
+    }
+
+    #[test]
+    fn test_slower_schedule_finds_lower_cost() {
+        let schematic = schematic();
+        let fast = PlacerConfig {
+            initial_temperature: 1.0,
+            cooling_rate: 0.5,
+            iterations_per_temp: 1,
+            seed: Some(5),
+            ..PlacerConfig::default()
+        };
+        let slow = PlacerConfig {
+            seed: Some(5),
+            ..PlacerConfig::default()
+        };
+
+        let cost = |config: PlacerConfig| {
+            let mut placer = SimulatedAnnealingPlacer::new(&schematic, config, None).unwrap();
+            let layout = placer.run();
+            placer.calculate_cost(&layout.components)
+        };
+
+        let (fast_cost, slow_cost) = (cost(fast), cost(slow));
+        assert!(
+            slow_cost < fast_cost,
+            "slow {slow_cost} >= fast {fast_cost}"
+        );
+    }
+
+    #[test]
+    fn test_stops_early_once_converged() {
+        let schematic = schematic();
+        let config = PlacerConfig {
+            seed: Some(9),
+            patience: Some(500),
+            ..PlacerConfig::default()
+        };
+        let budget = PlacerConfig::default().iterations_per_temp * 135;
+
+        let run = || {
+            let mut placer =
+                SimulatedAnnealingPlacer::new(&schematic, config.clone(), None).unwrap();
+            let layout = placer.run();
+            (placer.iterations_run(), positions(&layout))
+        };
+        let (iterations, first) = run();
+
+        assert!(iterations >= 500);
+        assert!(
+            iterations < budget / 2,
+            "ran {iterations} of {budget} iterations"
+        );
+        // The same seed stops at the same point with the same result
+        assert_eq!(run(), (iterations, first));
+
+        let mut placer =
+            SimulatedAnnealingPlacer::new(&schematic, PlacerConfig::default(), None).unwrap();
+        placer.run();
+        assert_eq!(placer.iterations_run(), budget);
+    }
+
+    #[test]
+    fn test_cost_history_is_non_increasing() {
+        let schematic = schematic();
+        let config = PlacerConfig {
+            seed: Some(11),
+            iterations_per_temp: 10,
+            track_history: true,
+            ..PlacerConfig::default()
+        };
+
+        let mut placer = SimulatedAnnealingPlacer::new(&schematic, config.clone(), None).unwrap();
+        placer.run();
+        let history = placer.cost_history();
+
+        assert_eq!(history.len(), placer.iterations_run());
+        assert!(history.windows(2).all(|w| w[1] <= w[0]));
+        assert!(history.last() < history.first());
+
+        let mut untracked = SimulatedAnnealingPlacer::new(
+            &schematic,
+            PlacerConfig {
+                track_history: false,
+                ..config
+            },
+            None,
+        )
+        .unwrap();
+        untracked.run();
+        assert!(untracked.cost_history().is_empty());
+    }
+
+    #[test]
+    fn test_seed_placement_converges_faster() {
+        // Three three-pin nets, each joining components the grid start
+        // places on a diagonal
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let mut schematic = Schematic::new();
+        for (n, members) in [["r1", "r5", "r9"], ["r2", "r6", "r7"], ["r3", "r4", "r8"]]
+            .iter()
+            .enumerate()
+        {
+            let mut net = Net {
+                kind: NetKind::Normal,
+                name: format!("N{n}"),
+                ports: Vec::new(),
+                properties: HashMap::new(),
+            };
+            for name in members {
+                schematic.add_instance(
+                    InstanceRef::new(mod_ref.clone(), vec![name.to_string()]),
+                    Instance::component(mod_ref.clone())
+                        .with_reference_designator(name.to_uppercase()),
+                );
+                let port_ref =
+                    InstanceRef::new(mod_ref.clone(), vec![name.to_string(), "P1".into()]);
+                schematic.add_instance(port_ref.clone(), Instance::port(mod_ref.clone()));
+                net.ports.push(port_ref);
+            }
+            schematic.add_net(net);
+        }
+
+        let hints = seed_placement(&schematic);
+        assert_eq!(hints.positions.len(), 9);
+        // Each net's components share a row of the seeded grid
+        for net in schematic.nets.values() {
+            let rows: Vec<f64> = net
+                .ports
+                .iter()
+                .map(|port| hints.positions[&port.instance_path[0].to_uppercase()].y)
+                .collect();
+            assert!(
+                rows.iter().all(|&y| y == rows[0]),
+                "{} spans rows {rows:?}",
+                net.name
+            );
+        }
+
+        // Hints only choose the starting point here, they add no cost
+        let config = PlacerConfig {
+            seed: Some(21),
+            iterations_per_temp: 10,
+            ai_hint_weight: 0.0,
+            track_history: true,
+            ..PlacerConfig::default()
+        };
+        let history = |hints: Option<PlacementHints>| {
+            let mut placer =
+                SimulatedAnnealingPlacer::new(&schematic, config.clone(), hints).unwrap();
+            placer.run();
+            placer.cost_history().to_vec()
+        };
+        let grid = history(None);
+        let seeded = history(Some(hints));
+
+        let grid_final = *grid.last().unwrap();
+        let seeded_final = *seeded.last().unwrap();
+        assert!(
+            seeded_final < grid_final,
+            "seeded {seeded_final} >= grid {grid_final}"
+        );
+        let reached = seeded.iter().position(|&cost| cost <= grid_final).unwrap();
+        assert!(
+            reached < grid.len() / 2,
+            "seeded start took {reached} iterations"
+        );
+    }
+
+    #[test]
+    fn test_cost_models_on_large_schematic() {
+        // 50 resistors in a chain of two-pin nets plus a few wide buses
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let mut schematic = Schematic::new();
+        let port = |i: usize, pin: &str| {
+            InstanceRef::new(mod_ref.clone(), vec![format!("r{i}"), pin.to_string()])
+        };
+        for i in 0..50 {
+            schematic.add_instance(
+                InstanceRef::new(mod_ref.clone(), vec![format!("r{i}")]),
+                Instance::component(mod_ref.clone()).with_reference_designator(format!("R{i}")),
+            );
+            for pin in ["1", "2"] {
+                schematic.add_instance(port(i, pin), Instance::port(mod_ref.clone()));
+            }
+        }
+        let mut nets: Vec<(String, Vec<InstanceRef>)> = (0..49)
+            .map(|i| (format!("CHAIN{i}"), vec![port(i, "2"), port(i + 1, "1")]))
+            .collect();
+        nets.extend((0..5).map(|bus| {
+            let ports = (bus..50).step_by(5).map(|i| port(i, "1")).collect();
+            (format!("BUS{bus}"), ports)
+        }));
+        for (name, ports) in nets {
+            schematic.add_net(Net {
+                kind: NetKind::Normal,
+                name,
+                ports,
+                properties: HashMap::new(),
+            });
+        }
+
+        let run = |cost_model: CostModel| {
+            let config = PlacerConfig {
+                seed: Some(50),
+                cost_model,
+                iterations_per_temp: 20,
+                ..PlacerConfig::default()
+            };
+            let mut placer = SimulatedAnnealingPlacer::new(&schematic, config, None).unwrap();
+            let layout = placer.run();
+            assert_eq!(layout.components.len(), 50);
+            (layout, placer)
+        };
+
+        let (layout, hpwl) = run(CostModel::Hpwl);
+        let (_, mst) = run(CostModel::Mst);
+
+        // A spanning tree has to cover the bounding box, so HPWL never
+        // exceeds the MST estimate for the same placement
+        let hpwl_length = hpwl.calculate_wire_length(&layout.components);
+        let mst_length = mst.calculate_wire_length(&layout.components);
+        assert!(hpwl_length > 0.0);
+        assert!(
+            hpwl_length <= mst_length,
+            "HPWL {hpwl_length} > MST {mst_length}"
+        );
+    }
+
+    #[test]
+    fn test_mst_length() {
+        let schematic = schematic();
+        let placer =
+            SimulatedAnnealingPlacer::new(&schematic, PlacerConfig::default(), None).unwrap();
+        let points = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 10.0, y: 0.0 },
+            Point { x: 10.0, y: 5.0 },
+            Point { x: 0.0, y: 20.0 },
+        ];
+
+        // 0-1 (10), 1-2 (5), then the closest link to (0, 20) is from (10, 5)
+        // at 25 or from (0, 0) at 20
+        assert_eq!(placer.mst_length(&points), 35.0);
+        assert_eq!(half_perimeter(&points), 30.0);
+    }
+
+    #[test]
+    fn test_positions_snap_to_grid() {
+        let schematic = schematic();
+        let config = PlacerConfig {
+            seed: Some(3),
+            iterations_per_temp: 10,
+            grid_pitch: 1.27,
+            track_history: true,
+            ..PlacerConfig::default()
+        };
+
+        let mut placer = SimulatedAnnealingPlacer::new(&schematic, config, None).unwrap();
+        let layout = placer.run();
+
+        for (x, y) in positions(&layout) {
+            for coord in [x, y] {
+                let steps = coord / 1.27;
+                assert!(
+                    (steps - steps.round()).abs() < 1e-9,
+                    "{coord} is not on a 1.27 grid"
+                );
+            }
+        }
+        // The annealer costed the snapped positions, so snapping cannot have
+        // added overlap it did not account for
+        let best_cost = *placer.cost_history().last().unwrap();
+        assert_eq!(placer.calculate_cost(&layout.components), best_cost);
+    }
+
+    #[test]
+    fn test_snapping_keeps_components_on_the_board() {
+        let schematic = schematic();
+        let config = PlacerConfig {
+            grid_pitch: 1.27,
+            ..PlacerConfig::default()
+        };
+        let placer = SimulatedAnnealingPlacer::new(&schematic, config, None).unwrap();
+
+        // Rounding to the nearest grid line would push this part past the
+        // right and bottom edges
+        let mut bounds = Rect {
+            x: placer.board_width - 10.0 - 0.1,
+            y: placer.board_height - 10.0 - 0.1,
+            width: 10.0,
+            height: 10.0,
+        };
+        placer.snap_to_board(&mut bounds);
+
+        assert!(bounds.x + bounds.width <= placer.board_width);
+        assert!(bounds.y + bounds.height <= placer.board_height);
+        for coord in [bounds.x, bounds.y] {
+            let steps = coord / 1.27;
+            assert!(
+                (steps - steps.round()).abs() < 1e-9,
+                "{coord} is not on a 1.27 grid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_paired_components_are_placed_together() {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let mut schematic = Schematic::new();
+        for name in ["u1", "u2", "c1", "c2"] {
+            schematic.add_instance(
+                InstanceRef::new(mod_ref.clone(), vec![name.into()]),
+                Instance::component(mod_ref.clone()).with_reference_designator(name.to_uppercase()),
+            );
+        }
+
+        let hints = PlacementHints {
+            pairs: vec![("U1".to_string(), "C2".to_string())],
+            ..PlacementHints::default()
+        };
+        let config = PlacerConfig {
+            seed: Some(7),
+            ..PlacerConfig::default()
+        };
+        let layout = run_with_config(&schematic, config, Some(hints)).unwrap();
+
+        let center = |refdes: &str| {
+            layout
+                .components
+                .iter()
+                .find(|c| c.instance.reference_designator.as_deref() == Some(refdes))
+                .map(|c| c.center())
+                .unwrap()
+        };
+        let (u1, c2) = (center("U1"), center("C2"));
+        let distance = ((u1.x - c2.x).powi(2) + (u1.y - c2.y).powi(2)).sqrt();
+        assert!(distance < 40.0, "paired components are {distance} apart");
+    }
+}
@@ -3,10 +3,10 @@
 //! This module implements an A* search algorithm for routing nets on a PCB.
 //! It routes nets on a grid while avoiding obstacles (components).
 
-use crate::{Layout, Point, Rect};
+use crate::{trace_width, Layout, Point};
 use picoplace_netlist::Schematic;
-use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 /// Grid cell coordinates
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -35,7 +35,10 @@ impl Eq for AStarNode {}
 impl Ord for AStarNode {
     fn cmp(&self, other: &Self) -> Ordering {
         // Reverse ordering for min-heap
-        other.f_cost().partial_cmp(&self.f_cost()).unwrap_or(Ordering::Equal)
+        other
+            .f_cost()
+            .partial_cmp(&self.f_cost())
+            .unwrap_or(Ordering::Equal)
     }
 }
 
@@ -50,6 +53,8 @@ impl PartialOrd for AStarNode {
 pub struct RoutedPath {
     pub net_name: String,
     pub points: Vec<Point>,
+    /// Trace width in millimeters; the widest of the tied nets.
+    pub width: f64,
 }
 
 /// Router configuration
@@ -59,6 +64,15 @@ pub struct RouterConfig {
     pub grid_resolution: f64,
     /// Penalty for routing near components
     pub component_penalty: f64,
+    /// Minimum copper-to-copper spacing between traces and components (mm)
+    pub clearance: f64,
+    /// Penalty for routing closer to another net's trace than its width and
+    /// the clearance allow
+    pub trace_penalty: f64,
+    /// Differential pairs that don't follow the `_P`/`_N` naming convention,
+    /// mapping each positive net to its negative net (see
+    /// [`Schematic::differential_pairs`])
+    pub differential_pairs: HashMap<String, String>,
 }
 
 impl Default for RouterConfig {
@@ -66,10 +80,31 @@ impl Default for RouterConfig {
         Self {
             grid_resolution: 1.0,
             component_penalty: 5.0,
+            clearance: 0.2,
+            trace_penalty: 20.0,
+            differential_pairs: HashMap::new(),
         }
     }
 }
 
+/// Copper laid down by the nets routed so far
+#[derive(Default)]
+struct Traces {
+    /// Each cell a trace passes through, with half the width of the widest
+    /// trace there (mm)
+    cells: HashMap<GridCell, f64>,
+    /// Half the width of the widest trace anywhere (mm)
+    widest: f64,
+}
+
+impl Traces {
+    fn add(&mut self, cell: GridCell, width: f64) {
+        let half_width = self.cells.entry(cell).or_default();
+        *half_width = half_width.max(width / 2.0);
+        self.widest = self.widest.max(width / 2.0);
+    }
+}
+
 /// A* Router
 pub struct AStarRouter<'a> {
     schematic: &'a Schematic,
@@ -110,8 +145,10 @@ impl<'a> AStarRouter<'a> {
         for comp in &self.layout.components {
             let x_start = (comp.bounds.x / self.config.grid_resolution).floor() as i32;
             let y_start = (comp.bounds.y / self.config.grid_resolution).floor() as i32;
-            let x_end = ((comp.bounds.x + comp.bounds.width) / self.config.grid_resolution).ceil() as i32;
-            let y_end = ((comp.bounds.y + comp.bounds.height) / self.config.grid_resolution).ceil() as i32;
+            let x_end =
+                ((comp.bounds.x + comp.bounds.width) / self.config.grid_resolution).ceil() as i32;
+            let y_end =
+                ((comp.bounds.y + comp.bounds.height) / self.config.grid_resolution).ceil() as i32;
 
             for x in x_start..=x_end {
                 for y in y_start..=y_end {
@@ -139,34 +176,47 @@ impl<'a> AStarRouter<'a> {
             }
         }
 
-        // Route nets in priority order
-        let mut nets_to_route: Vec<_> = self.schematic.nets.iter().collect();
-        
-        // Sort by priority if provided
-        if !self.routing_priorities.is_empty() {
-            nets_to_route.sort_by_key(|(net_name, _)| {
-                self.routing_priorities
-                    .iter()
-                    .position(|p| p == *net_name)
-                    .unwrap_or(usize::MAX)
-            });
+        // Route nets in priority order, treating tied nets as one
+        let mut nets_to_route: Vec<_> = self.schematic.merged_nets().into_iter().collect();
+
+        // Keep differential pairs together: the negative net follows its
+        // positive net and the pair takes the better of their two priorities
+        let mut partners: HashMap<String, (String, bool)> = HashMap::new();
+        for pair in self
+            .schematic
+            .differential_pairs(&self.config.differential_pairs)
+        {
+            partners.insert(pair.negative.clone(), (pair.positive.clone(), true));
+            partners.insert(pair.positive.clone(), (pair.negative, false));
         }
+        let priority = |net_name: &str| {
+            self.routing_priorities
+                .iter()
+                .position(|p| p == net_name)
+                .unwrap_or(usize::MAX)
+        };
+        nets_to_route.sort_by_cached_key(|(net_name, _)| match partners.get(*net_name) {
+            Some((partner, is_negative)) => {
+                let anchor = if *is_negative {
+                    partner.as_str()
+                } else {
+                    net_name
+                };
+                (
+                    priority(net_name).min(priority(partner)),
+                    anchor.to_string(),
+                    *is_negative,
+                )
+            }
+            None => (priority(net_name), net_name.to_string(), false),
+        });
 
-        for (net_name, net) in nets_to_route {
+        let mut traces = Traces::default();
+        for (net_name, nets) in nets_to_route {
             let mut net_positions = Vec::new();
 
-            for port_ref in &net.ports {
-                let mut comp_path = port_ref.instance_path.clone();
-                if comp_path.pop().is_none() {
-                    continue;
-                }
-
-                let comp_inst_ref = picoplace_netlist::InstanceRef {
-                    module: port_ref.module.clone(),
-                    instance_path: comp_path,
-                };
-
-                if let Some(comp_instance) = self.schematic.instances.get(&comp_inst_ref) {
+            for port_ref in nets.iter().flat_map(|net| &net.ports) {
+                if let Some((_, comp_instance)) = self.schematic.component_of_port(port_ref) {
                     if let Some(refdes) = &comp_instance.reference_designator {
                         if let Some(pos) = component_positions.get(refdes) {
                             net_positions.push(*pos);
@@ -177,10 +227,15 @@ impl<'a> AStarRouter<'a> {
 
             if net_positions.len() > 1 {
                 // Route using minimum spanning tree approach
-                let path = self.route_net(&net_positions);
+                let width = trace_width(&nets);
+                let path = self.route_net(&net_positions, width, &traces);
+                for point in &path {
+                    traces.add(self.point_to_grid(*point), width);
+                }
                 routed_paths.push(RoutedPath {
-                    net_name: net_name.clone(),
+                    net_name: net_name.to_string(),
                     points: path,
+                    width,
                 });
             }
         }
@@ -188,10 +243,11 @@ impl<'a> AStarRouter<'a> {
         routed_paths
     }
 
-    /// Route a single net connecting multiple points
-    fn route_net(&self, positions: &[Point]) -> Vec<Point> {
+    /// Route a single net connecting multiple points with a trace `width` mm
+    /// wide, keeping clear of the `traces` of other nets
+    fn route_net(&self, positions: &[Point], width: f64, traces: &Traces) -> Vec<Point> {
         let mut path = Vec::new();
-        
+
         if positions.is_empty() {
             return path;
         }
@@ -201,7 +257,7 @@ impl<'a> AStarRouter<'a> {
         path.push(start);
 
         for target in &positions[1..] {
-            if let Some(segment) = self.find_path(start, *target) {
+            if let Some(segment) = self.find_path(start, *target, width, traces) {
                 path.extend(segment);
             }
         }
@@ -210,7 +266,13 @@ impl<'a> AStarRouter<'a> {
     }
 
     /// Find a path between two points using A*
-    fn find_path(&self, start: Point, goal: Point) -> Option<Vec<Point>> {
+    fn find_path(
+        &self,
+        start: Point,
+        goal: Point,
+        width: f64,
+        traces: &Traces,
+    ) -> Option<Vec<Point>> {
         let start_cell = self.point_to_grid(start);
         let goal_cell = self.point_to_grid(goal);
 
@@ -245,13 +307,10 @@ impl<'a> AStarRouter<'a> {
                     continue;
                 }
 
-                let movement_cost = if self.obstacles.contains(&neighbor) {
-                    self.config.component_penalty
-                } else {
-                    1.0
-                };
+                let movement_cost = self.step_cost(neighbor, width, traces);
 
-                let tentative_g_score = g_scores.get(&current.cell).unwrap_or(&f64::INFINITY) + movement_cost;
+                let tentative_g_score =
+                    g_scores.get(&current.cell).unwrap_or(&f64::INFINITY) + movement_cost;
 
                 if tentative_g_score < *g_scores.get(&neighbor).unwrap_or(&f64::INFINITY) {
                     came_from.insert(neighbor, current.cell);
@@ -270,6 +329,59 @@ impl<'a> AStarRouter<'a> {
         None // No path found
     }
 
+    /// Cost of running a trace `width` mm wide through `cell`: entering a
+    /// component's keepout costs [`RouterConfig::component_penalty`] instead
+    /// of 1, and crowding another net's trace adds
+    /// [`RouterConfig::trace_penalty`].
+    fn step_cost(&self, cell: GridCell, width: f64, traces: &Traces) -> f64 {
+        let half_width = width / 2.0;
+        let clearance = self.config.clearance;
+
+        let mut cost = if self.any_within(cell, half_width + clearance, |other| {
+            self.obstacles
+                .contains(&other)
+                .then_some(half_width + clearance)
+        }) {
+            self.config.component_penalty
+        } else {
+            1.0
+        };
+
+        if self.any_within(cell, traces.widest + clearance + half_width, |other| {
+            traces
+                .cells
+                .get(&other)
+                .map(|other_half_width| other_half_width + clearance + half_width)
+        }) {
+            cost += self.config.trace_penalty;
+        }
+
+        cost
+    }
+
+    /// Whether `cell` is too close to copper in a nearby cell: `spacing`
+    /// returns how far (mm) from a cell another trace has to stay, or `None`
+    /// if the cell is free. No spacing may exceed `max_spacing`.
+    fn any_within(
+        &self,
+        cell: GridCell,
+        max_spacing: f64,
+        spacing: impl Fn(GridCell) -> Option<f64>,
+    ) -> bool {
+        let resolution = self.config.grid_resolution;
+        let radius = (max_spacing / resolution).ceil() as i32;
+        (-radius..=radius).any(|dx| {
+            (-radius..=radius).any(|dy| {
+                let other = GridCell {
+                    x: cell.x + dx,
+                    y: cell.y + dy,
+                };
+                let distance = f64::from(dx * dx + dy * dy).sqrt() * resolution;
+                spacing(other).is_some_and(|spacing| distance < spacing)
+            })
+        })
+    }
+
     /// Get neighboring cells
     fn get_neighbors(&self, cell: GridCell) -> Vec<GridCell> {
         let mut neighbors = Vec::new();
@@ -309,7 +421,11 @@ impl<'a> AStarRouter<'a> {
     }
 
     /// Reconstruct path from came_from map
-    fn reconstruct_path(&self, came_from: &HashMap<GridCell, GridCell>, mut current: GridCell) -> Vec<Point> {
+    fn reconstruct_path(
+        &self,
+        came_from: &HashMap<GridCell, GridCell>,
+        mut current: GridCell,
+    ) -> Vec<Point> {
         let mut path = vec![self.grid_to_point(current)];
 
         while let Some(&parent) = came_from.get(&current) {
@@ -321,3 +437,133 @@ impl<'a> AStarRouter<'a> {
         path
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PlacedComponent, Rect};
+    use picoplace_netlist::{Instance, InstanceKind, InstanceRef, ModuleRef, Net, NetKind};
+
+    /// R1 and R2 joined by one net per entry of `nets`, with an optional
+    /// trace width (mm)
+    fn two_resistors(nets: &[(&str, Option<f64>)]) -> Schematic {
+        let mod_ref = ModuleRef::new("/test.zen", "Test");
+        let mut schematic = Schematic::new();
+        for refdes in ["R1", "R2"] {
+            schematic.add_instance(
+                InstanceRef::new(mod_ref.clone(), vec![refdes.to_lowercase()]),
+                Instance::component(mod_ref.clone()).with_reference_designator(refdes.to_string()),
+            );
+        }
+        for (name, width) in nets {
+            let ports = ["r1", "r2"]
+                .into_iter()
+                .map(|component| {
+                    let port_ref =
+                        InstanceRef::new(mod_ref.clone(), vec![component.into(), (*name).into()]);
+                    schematic.add_instance(port_ref.clone(), Instance::port(mod_ref.clone()));
+                    port_ref
+                })
+                .collect();
+            let mut net = Net {
+                kind: NetKind::Normal,
+                name: name.to_string(),
+                ports,
+                properties: HashMap::new(),
+            };
+            if let Some(width) = width {
+                net.set_trace_width(*width);
+            }
+            schematic.add_net(net);
+        }
+        schematic
+    }
+
+    /// R1 and R2 side by side on a 50x20mm board, 20mm apart
+    fn layout(schematic: &Schematic) -> Layout<'_> {
+        let components = schematic
+            .instances
+            .iter()
+            .filter(|(_, inst)| inst.kind == InstanceKind::Component)
+            .map(|(instance_ref, instance)| {
+                let x = match instance.reference_designator.as_deref() {
+                    Some("R1") => 5.0,
+                    _ => 35.0,
+                };
+                PlacedComponent {
+                    instance,
+                    instance_ref,
+                    bounds: Rect {
+                        x,
+                        y: 5.0,
+                        width: 10.0,
+                        height: 10.0,
+                    },
+                    rotation: 0.0,
+                }
+            })
+            .collect();
+        Layout {
+            components,
+            width: 50.0,
+            height: 20.0,
+        }
+    }
+
+    #[test]
+    fn test_overridden_differential_pair_is_routed_together() {
+        // `TX`/`RX` don't follow the naming convention, and `SCL` sorts
+        // between them
+        let schematic = two_resistors(&[("RX", None), ("SCL", None), ("TX", None)]);
+        let layout = layout(&schematic);
+
+        let routed_order = |config: RouterConfig| {
+            AStarRouter::new(&schematic, &layout, config, Vec::new())
+                .route()
+                .into_iter()
+                .map(|path| path.net_name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(routed_order(RouterConfig::default()), ["RX", "SCL", "TX"]);
+
+        let config = RouterConfig {
+            differential_pairs: HashMap::from([("TX".to_string(), "RX".to_string())]),
+            ..RouterConfig::default()
+        };
+        assert_eq!(routed_order(config), ["SCL", "TX", "RX"]);
+    }
+
+    #[test]
+    fn test_traces_keep_clear_by_their_width() {
+        // Closest approach (mm) of the two routed traces between the parts
+        let spacing = |schematic: &Schematic| {
+            let layout = layout(schematic);
+            let paths =
+                AStarRouter::new(schematic, &layout, RouterConfig::default(), Vec::new()).route();
+            let between = |path: &RoutedPath| -> Vec<Point> {
+                path.points
+                    .iter()
+                    .copied()
+                    .filter(|point| point.x > 16.0 && point.x < 34.0)
+                    .collect()
+            };
+            let (first, second) = (between(&paths[0]), between(&paths[1]));
+            assert!(!first.is_empty() && !second.is_empty());
+            first
+                .iter()
+                .flat_map(|a| {
+                    second
+                        .iter()
+                        .map(|b| ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt())
+                })
+                .fold(f64::INFINITY, f64::min)
+        };
+
+        // Default 0.25mm traces only need their own grid cells
+        assert_eq!(spacing(&two_resistors(&[("A", None), ("B", None)])), 1.0);
+        // A 3mm trace pushes its neighbour at least 1.5 + 0.2 + 0.125mm away,
+        // i.e. two cells on the 1mm grid
+        let wide = spacing(&two_resistors(&[("A", Some(3.0)), ("B", None)]));
+        assert!(wide >= 2.0, "{wide}");
+    }
+}
@@ -6,7 +6,7 @@ use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 use picoplace_kicad::PythonScriptBuilder;
-use picoplace_netlist::kicad_netlist::{format_footprint, write_fp_lib_table};
+use picoplace_netlist::kicad_netlist::{write_fp_lib_table, FootprintLibraries};
 
 /// Result of layout generation/update
 #[derive(Debug, Clone)]
@@ -31,6 +31,9 @@ pub enum LayoutError {
 
     #[error("PCB generation error: {0}")]
     PcbGeneration(#[from] anyhow::Error),
+
+    #[error("Components without a footprint: {}", .0.join(", "))]
+    MissingFootprints(Vec<String>),
 }
 
 /// Helper struct for layout file paths
@@ -38,7 +41,12 @@ pub enum LayoutError {
 pub struct LayoutPaths {
     pub netlist: PathBuf,
     pub pcb: PathBuf,
+    /// Schematic written by `export --to kicad-sch`, named after the PCB so
+    /// KiCad treats both as one project
+    pub schematic: PathBuf,
     pub snapshot: PathBuf,
+    /// Hash of the PCB as last generated, present only while nobody has edited it
+    pub pcb_hash: PathBuf,
     pub log: PathBuf,
     pub json_netlist: PathBuf,
 }
@@ -46,26 +54,24 @@ pub struct LayoutPaths {
 /// Process a schematic and generate/update its layout files
 /// This will:
 /// 1. Extract the layout path from the schematic's root instance attributes
-/// 2. Create the layout directory if it doesn't exist
-/// 3. Generate/update the netlist file
-/// 4. Write the footprint library table
-/// 5. Create or update the KiCad PCB file
+/// 2. Check that every component has a footprint
+/// 3. Create the layout directory if it doesn't exist
+/// 4. Generate/update the netlist file
+/// 5. Write the footprint library table
+/// 6. Create or update the KiCad PCB file
 pub fn process_layout(
     schematic: &Schematic,
     source_path: &Path,
 ) -> Result<LayoutResult, LayoutError> {
-    // Extract layout path from schematic
-    let layout_path = utils::extract_layout_path(schematic).ok_or(LayoutError::NoLayoutPath)?;
-
-    // Convert relative path to absolute based on source file location
-    let layout_dir = if layout_path.is_relative() {
-        source_path
-            .parent()
-            .unwrap_or(Path::new("."))
-            .join(&layout_path)
-    } else {
-        layout_path
-    };
+    // Extract layout path from schematic, relative to the source file
+    let layout_dir =
+        utils::resolve_layout_dir(schematic, source_path).ok_or(LayoutError::NoLayoutPath)?;
+
+    // Fail before writing anything rather than deep inside pcbnew
+    let missing = utils::find_missing_footprints(schematic);
+    if !missing.is_empty() {
+        return Err(LayoutError::MissingFootprints(missing));
+    }
 
     // Get all the file paths
     let paths = utils::get_layout_paths(&layout_dir);
@@ -106,6 +112,10 @@ pub fn process_layout(
     // Check if PCB file exists to determine if this is create or update
     let pcb_exists = paths.pcb.exists();
 
+    // An updated layout only counts as generated output if it held no manual
+    // work before the update
+    let unedited = !pcb_exists || utils::pcb_matches_recorded_hash(&paths).unwrap_or(false);
+
     // Update or create the KiCad PCB file using the new API
     if pcb_exists {
         debug!("Updating existing layout file: {}", paths.pcb.display());
@@ -141,6 +151,13 @@ pub fn process_layout(
             )
         })?;
 
+    if unedited {
+        utils::record_pcb_hash(&paths)?;
+    } else if paths.pcb_hash.exists() {
+        fs::remove_file(&paths.pcb_hash)
+            .with_context(|| format!("Failed to remove {}", paths.pcb_hash.display()))?;
+    }
+
     Ok(LayoutResult {
         source_file: source_path.to_path_buf(),
         layout_dir,
@@ -169,35 +186,94 @@ pub mod utils {
         Some(PathBuf::from(layout_path_str))
     }
 
+    /// Layout directory of `schematic`, with a relative layout path resolved
+    /// against the directory of `source_path` (the design's .zen file).
+    pub fn resolve_layout_dir(schematic: &Schematic, source_path: &Path) -> Option<PathBuf> {
+        let layout_path = extract_layout_path(schematic)?;
+        if layout_path.is_relative() {
+            Some(
+                source_path
+                    .parent()
+                    .unwrap_or(Path::new("."))
+                    .join(layout_path),
+            )
+        } else {
+            Some(layout_path)
+        }
+    }
+
+    /// Reference designators of components without a `footprint` attribute,
+    /// in natural order. Components without a designator are listed by their
+    /// instance path.
+    pub fn find_missing_footprints(schematic: &Schematic) -> Vec<String> {
+        let mut missing: Vec<String> = schematic
+            .instances
+            .iter()
+            .filter(|(_, inst)| inst.kind == InstanceKind::Component)
+            .filter(|(_, inst)| {
+                inst.attributes
+                    .get("footprint")
+                    .and_then(AttributeValue::string)
+                    .is_none_or(|fp| fp.trim().is_empty())
+            })
+            .map(|(inst_ref, inst)| {
+                inst.reference_designator
+                    .clone()
+                    .unwrap_or_else(|| inst_ref.instance_path.join("."))
+            })
+            .collect();
+        missing.sort_by(|a, b| picoplace_netlist::natural_cmp(a, b));
+        missing
+    }
+
     /// Get all the file paths that would be generated for a layout
     pub fn get_layout_paths(layout_dir: &Path) -> LayoutPaths {
         LayoutPaths {
             netlist: layout_dir.join("default.net"),
             pcb: layout_dir.join("layout.kicad_pcb"),
+            schematic: layout_dir.join("layout.kicad_sch"),
             snapshot: layout_dir.join("snapshot.layout.json"),
+            pcb_hash: layout_dir.join("layout.kicad_pcb.hash"),
             log: layout_dir.join("layout.log"),
             json_netlist: layout_dir.join("netlist.json"),
         }
     }
 
+    /// Record the hash of the PCB at `paths`, marking its current contents as
+    /// generated output.
+    pub fn record_pcb_hash(paths: &LayoutPaths) -> AnyhowResult<()> {
+        let pcb = fs::read(&paths.pcb)
+            .with_context(|| format!("Failed to read {}", paths.pcb.display()))?;
+        fs::write(&paths.pcb_hash, blake3::hash(&pcb).to_hex().as_str())
+            .with_context(|| format!("Failed to write {}", paths.pcb_hash.display()))
+    }
+
+    /// Whether the PCB at `paths` is byte-for-byte what was last generated,
+    /// i.e. nobody has edited it since. Fails if no hash was recorded.
+    pub fn pcb_matches_recorded_hash(paths: &LayoutPaths) -> AnyhowResult<bool> {
+        let recorded = fs::read_to_string(&paths.pcb_hash).with_context(|| {
+            format!(
+                "No hash of the generated layout at {}",
+                paths.pcb_hash.display()
+            )
+        })?;
+        let pcb = fs::read(&paths.pcb)
+            .with_context(|| format!("Failed to read {}", paths.pcb.display()))?;
+        Ok(blake3::hash(&pcb).to_hex().as_str() == recorded.trim())
+    }
+
+    /// Footprint libraries referenced by component `footprint` attributes,
+    /// keyed by library nickname (see [`FootprintLibraries`]).
+    pub fn collect_footprint_libraries(schematic: &Schematic) -> HashMap<String, PathBuf> {
+        FootprintLibraries::collect(schematic).into_libraries()
+    }
+
     /// Write footprint library table for a layout
     pub fn write_footprint_library_table(
         layout_dir: &Path,
         schematic: &Schematic,
     ) -> AnyhowResult<()> {
-        let mut fp_libs: HashMap<String, PathBuf> = HashMap::new();
-
-        for inst in schematic.instances.values() {
-            if inst.kind != InstanceKind::Component {
-                continue;
-            }
-
-            if let Some(AttributeValue::String(fp_attr)) = inst.attributes.get("footprint") {
-                if let (_, Some((lib_name, dir))) = format_footprint(fp_attr) {
-                    fp_libs.entry(lib_name).or_insert(dir);
-                }
-            }
-        }
+        let fp_libs = collect_footprint_libraries(schematic);
 
         // Canonicalize the layout directory to avoid symlink issues on macOS
         let canonical_layout_dir = layout_dir
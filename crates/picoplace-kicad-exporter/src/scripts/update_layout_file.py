@@ -32,6 +32,7 @@ Pipeline Overview
 
 import argparse
 import logging
+import math
 import os
 import os.path
 import re
@@ -78,11 +79,12 @@ class JsonNetlistParser:
     class Part:
         """Represents a component part from the netlist."""
 
-        def __init__(self, ref, value, footprint, sheetpath):
+        def __init__(self, ref, value, footprint, sheetpath, rotation=0.0):
             self.ref = ref
             self.value = value
             self.footprint = footprint
             self.sheetpath = sheetpath
+            self.rotation = rotation  # Counter-clockwise degrees, as in KiCad
             self.properties = []
 
     class Module:
@@ -118,6 +120,22 @@ class JsonNetlistParser:
         self.nets = []
         self.modules = {}  # Dict of module path -> Module instance
 
+    @staticmethod
+    def parse_rotation(attributes):
+        """Rotation from the `rotation` attribute, snapped to a quarter turn.
+
+        Mirrors `Instance::rotation` in picoplace-netlist."""
+        attr = attributes.get("rotation", {})
+        try:
+            degrees = float(attr.get("Number", attr.get("String", 0)))
+        except (TypeError, ValueError):
+            return 0.0
+        if not math.isfinite(degrees):
+            return 0.0
+        # Round half away from zero like Rust's `f64::round`
+        quarter_turns = math.copysign(math.floor(abs(degrees) / 90.0 + 0.5), degrees)
+        return (quarter_turns * 90.0) % 360.0
+
     @staticmethod
     def parse_netlist(json_path):
         """Parse a JSON netlist file and return a netlist object compatible with kinparse."""
@@ -230,7 +248,13 @@ class JsonNetlistParser:
             sheetpath = JsonNetlistParser.SheetPath(hier_name, ts_uuid)
 
             # Create part
-            part = JsonNetlistParser.Part(ref, value, footprint, sheetpath)
+            part = JsonNetlistParser.Part(
+                ref,
+                value,
+                footprint,
+                sheetpath,
+                JsonNetlistParser.parse_rotation(instance["attributes"]),
+            )
 
             # Add properties from attributes
             for attr_name, attr_value in instance["attributes"].items():
@@ -1254,6 +1278,18 @@ class ImportNetlist(Step):
         if os.path.exists(local_fp_lib_table_path):
             _load_fp_lib_table(local_fp_lib_table_path)
 
+    def _footprint_lib_uri(self, fp_lib):
+        """Look up a library URI, matching nicknames case-insensitively like the Rust fp-lib-table writer."""
+        if fp_lib in self.footprint_lib_map:
+            return self.footprint_lib_map[fp_lib]
+
+        wanted = fp_lib.strip().lower()
+        for nickname, uri in self.footprint_lib_map.items():
+            if nickname.lower() == wanted:
+                return uri
+
+        raise KeyError(fp_lib)
+
     def _sync_footprints(self):
         """Remove footprints from the board that are not in the netlist, and add new ones that are missing from the board."""
         netlist_footprint_ids = set(
@@ -1308,7 +1344,7 @@ class ImportNetlist(Step):
 
             # Load footprint from library
             fp_lib, fp_name = part.footprint.split(":")
-            lib_uri = self.footprint_lib_map[fp_lib]
+            lib_uri = self._footprint_lib_uri(fp_lib)
 
             # (Deal with Windows extended path prefix)
             lib_uri = lib_uri.replace("\\\\?\\", "")
@@ -1335,6 +1371,9 @@ class ImportNetlist(Step):
 
             fp.SetParent(self.board)
             _configure_footprint(fp, part)
+            # Only new footprints take the design's rotation; footprints already
+            # on the board keep the orientation chosen in the layout.
+            fp.SetOrientationDegrees(part.rotation)
 
             self.board.Add(fp)
             self.state.track_footprint_added(fp)
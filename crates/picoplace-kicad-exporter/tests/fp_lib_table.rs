@@ -0,0 +1,40 @@
+use picoplace_kicad_exporter::utils::collect_footprint_libraries;
+use picoplace_netlist::kicad_netlist::to_kicad_netlist;
+use picoplace_netlist::{Instance, InstanceRef, ModuleRef, Schematic};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn test_footprint_libraries_deduplicated_case_insensitively() {
+    let mod_ref = ModuleRef::from_path(Path::new("/board.zen"), "<root>");
+    let mut schematic = Schematic::new();
+
+    for (name, footprint) in [
+        ("u1", "/libs/MyLib.kicad_mod"),
+        ("u2", "/libs/mylib.kicad_mod"),
+        ("u3", "/libs/MyLib.kicad_mod"),
+        ("r1", "/libs/Passives.kicad_mod"),
+        ("r2", "Resistor_SMD:R_0402_1005Metric"),
+    ] {
+        schematic.add_instance(
+            InstanceRef::new(mod_ref.clone(), vec![name.to_string()]),
+            Instance::component(mod_ref.clone()).with_attribute("footprint", footprint.to_string()),
+        );
+    }
+
+    assert_eq!(
+        collect_footprint_libraries(&schematic),
+        HashMap::from([
+            ("MyLib".to_string(), PathBuf::from("/libs")),
+            ("Passives".to_string(), PathBuf::from("/libs")),
+        ])
+    );
+
+    // Footprints from the dropped spelling must point at the kept nickname
+    let netlist = to_kicad_netlist(&schematic);
+    assert!(netlist.contains("(footprint \"MyLib:mylib\")"));
+    assert!(netlist.contains("(footprint \"MyLib:MyLib\")"));
+    assert!(netlist.contains("(footprint \"Passives:Passives\")"));
+    assert!(netlist.contains("(footprint \"Resistor_SMD:R_0402_1005Metric\")"));
+    assert!(!netlist.contains("mylib:mylib"));
+}
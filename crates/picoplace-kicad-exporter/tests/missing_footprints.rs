@@ -0,0 +1,56 @@
+use assert_fs::TempDir;
+use picoplace_kicad_exporter::{process_layout, utils, LayoutError};
+use picoplace_netlist::{Instance, InstanceRef, ModuleRef, Schematic, ATTR_LAYOUT_PATH};
+use std::path::Path;
+
+fn schematic_with_components(components: &[(&str, Option<&str>)]) -> Schematic {
+    let mod_ref = ModuleRef::from_path(Path::new("/board.zen"), "<root>");
+    let root_ref = InstanceRef::new(mod_ref.clone(), vec![]);
+    let mut root =
+        Instance::module(mod_ref.clone()).with_attribute(ATTR_LAYOUT_PATH, "layout".to_string());
+    let mut schematic = Schematic::new();
+
+    for (refdes, footprint) in components {
+        let inst_ref = InstanceRef::new(mod_ref.clone(), vec![refdes.to_lowercase()]);
+        let mut inst = Instance::component(mod_ref.clone()).with_reference_designator(*refdes);
+        if let Some(footprint) = footprint {
+            inst = inst.with_attribute("footprint", footprint.to_string());
+        }
+        root.add_child(refdes.to_lowercase(), inst_ref.clone());
+        schematic.add_instance(inst_ref, inst);
+    }
+
+    schematic.add_instance(root_ref.clone(), root);
+    schematic.set_root_ref(root_ref);
+    schematic
+}
+
+#[test]
+fn test_find_missing_footprints() {
+    let schematic = schematic_with_components(&[
+        ("R10", None),
+        ("R2", Some("Resistor_SMD:R_0402_1005Metric")),
+        ("U1", Some("")),
+        ("R1", None),
+    ]);
+
+    assert_eq!(
+        utils::find_missing_footprints(&schematic),
+        ["R1", "R10", "U1"]
+    );
+}
+
+#[test]
+fn test_process_layout_fails_fast_without_footprints() {
+    let temp = TempDir::new().unwrap();
+    let source = temp.path().join("board.zen");
+    let schematic = schematic_with_components(&[
+        ("C1", Some("Capacitor_SMD:C_0402_1005Metric")),
+        ("U1", None),
+    ]);
+
+    let err = process_layout(&schematic, &source).unwrap_err();
+    assert!(matches!(&err, LayoutError::MissingFootprints(refs) if refs == &["U1"]));
+    assert_eq!(err.to_string(), "Components without a footprint: U1");
+    assert!(!temp.path().join("layout").exists());
+}
@@ -1,6 +1,5 @@
 use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::Write;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
@@ -10,8 +9,6 @@ use picoplace_core::workspace::find_workspace_root;
 use picoplace_core::{
     CoreLoadResolver, DefaultFileProvider, EvalContext, FileProvider, InputMap, LoadResolver,
 };
-use zip::write::FileOptions;
-use zip::ZipWriter;
 
 use crate::load::DefaultRemoteFetcher;
 
@@ -215,6 +212,18 @@ fn copy_dir_all(src: &Path, dst: &Path) -> Result<()> {
 
 /// Create a bundle from a Starlark file, discovering all dependencies
 pub fn create_bundle(input_path: &Path, output_path: &Path) -> Result<()> {
+    // Gather the bundle in a temporary directory
+    let temp_dir = tempfile::tempdir()?;
+    let bundle = bundle_from_eval(input_path, temp_dir.path())?;
+
+    bundle.write_zip(output_path)
+}
+
+/// Evaluate `input_path` and copy its source directory plus every file its
+/// loads resolved to (including fetched remote packages) into `bundle_dir`.
+///
+/// The returned [`Bundle`] evaluates identically offline; see [`Bundle::eval`].
+pub fn bundle_from_eval(input_path: &Path, bundle_dir: &Path) -> Result<Bundle> {
     // Get the canonical path of the input file
     let canonical_input = input_path
         .canonicalize()
@@ -233,9 +242,7 @@ pub fn create_bundle(input_path: &Path, output_path: &Path) -> Result<()> {
     let workspace_root = find_workspace_root(file_provider.as_ref(), &canonical_input)
         .unwrap_or_else(|| source_dir.clone());
 
-    // Create a temporary directory for the bundle
-    let temp_dir = tempfile::tempdir()?;
-    let bundle_dir = temp_dir.path().to_path_buf();
+    let bundle_dir = bundle_dir.to_path_buf();
 
     // Copy the entire source directory to the bundle
     copy_dir_all(&source_dir, &bundle_dir).with_context(|| {
@@ -293,57 +300,10 @@ pub fn create_bundle(input_path: &Path, output_path: &Path) -> Result<()> {
         .map_err(|_| anyhow::anyhow!("Failed to unwrap tracking resolver Arc"))?
         .into_bundle(entry_point);
 
-    // Write the bundle to a zip file
-    write_bundle_zip(&bundle, &bundle_dir, output_path)?;
-
-    Ok(())
+    Ok(bundle)
 }
 
 /// Write a bundle to a zip archive
 pub fn write_bundle_zip(bundle: &Bundle, bundle_dir: &Path, output_path: &Path) -> Result<()> {
-    let file = File::create(output_path)
-        .with_context(|| format!("Failed to create output file: {}", output_path.display()))?;
-    let mut zip = ZipWriter::new(file);
-
-    // Write the bundle manifest
-    let manifest_toml =
-        toml::to_string(&bundle.manifest).context("Failed to serialize bundle manifest")?;
-    zip.start_file("bundle.toml", FileOptions::<()>::default())
-        .context("Failed to start writing bundle.toml")?;
-    zip.write_all(manifest_toml.as_bytes())
-        .context("Failed to write bundle.toml")?;
-
-    // Add all files from the bundle directory
-    add_directory_to_zip(&mut zip, bundle_dir, bundle_dir)?;
-
-    zip.finish().context("Failed to finalize zip file")?;
-    Ok(())
-}
-
-/// Recursively add a directory to a zip archive
-fn add_directory_to_zip<W: Write + std::io::Seek>(
-    zip: &mut ZipWriter<W>,
-    dir: &Path,
-    base_dir: &Path,
-) -> Result<()> {
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        let relative_path = path.strip_prefix(base_dir)?;
-
-        if path.is_dir() {
-            // Recursively add subdirectory
-            add_directory_to_zip(zip, &path, base_dir)?;
-        } else {
-            // Add file to zip
-            // Always use forward slashes in ZIP archives (ZIP standard)
-            let file_name = relative_path.to_string_lossy().replace('\\', "/");
-            zip.start_file(file_name, FileOptions::<()>::default())?;
-
-            let mut file = File::open(&path)?;
-            std::io::copy(&mut file, zip)?;
-        }
-    }
-
-    Ok(())
+    Bundle::new(bundle_dir.to_path_buf(), bundle.manifest.clone()).write_zip(output_path)
 }
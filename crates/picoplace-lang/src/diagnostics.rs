@@ -85,6 +85,9 @@ pub fn render_diagnostic(diag: &Diagnostic) {
             .with_message(&deepest_error_msg.body)
             .with_color(red),
     );
+    if let Some(code) = diag.outermost_code() {
+        report = report.with_code(code);
+    }
 
     // Add all other messages in the chain (except the deepest) in yellow.
     for (idx, msg) in messages.iter().enumerate().rev() {
@@ -6,20 +6,27 @@ pub mod load;
 pub mod lsp;
 pub mod suppression;
 
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::load::DefaultRemoteFetcher;
 use picoplace_netlist::Schematic;
 use picoplace_core::convert::ToSchematic;
-use picoplace_core::workspace::find_workspace_root;
-use picoplace_core::{CoreLoadResolver, DefaultFileProvider, EvalContext, InputMap};
+use picoplace_core::workspace::{find_workspace_root, read_lint_ignores, read_refdes_prefixes};
+use picoplace_core::{
+    codes, CoreLoadResolver, DefaultFileProvider, EvalContext, FileProvider, FrozenModuleValue,
+    InputMap,
+};
 use starlark::errors::EvalMessage;
 
 pub use diagnostics::render_diagnostic;
 pub use picoplace_core::bundle::{Bundle, BundleMetadata};
 pub use picoplace_core::file_extensions;
-pub use picoplace_core::{Diagnostic, WithDiagnostics};
+pub use picoplace_core::lang::type_info::{ParameterInfo, TypeInfo};
+pub use picoplace_core::{
+    diagnostics_to_json, Diagnostic, EvalOutput, JsonDiagnostic, WithDiagnostics,
+};
 pub use starlark::errors::EvalSeverity;
 
 /// Create an evaluation context with proper load resolver setup for a given workspace.
@@ -56,12 +63,130 @@ pub fn create_eval_context(workspace_root: &Path) -> EvalContext {
         .set_load_resolver(load_resolver)
 }
 
+/// Evaluate `file` and return the full [`EvalOutput`].
+///
+/// Unlike [`run`], this keeps everything the evaluator computed, including the
+/// module's parameter signature and any `print()` output.
+pub fn run_full(file: &Path) -> WithDiagnostics<EvalOutput> {
+    eval_root(file).0
+}
+
 /// Evaluate `file` and return a [`Schematic`].
 pub fn run(file: &Path) -> WithDiagnostics<Schematic> {
     let abs_path = file
         .canonicalize()
         .expect("failed to canonicalise input path");
 
+    let (eval_result, _) = eval_root(&abs_path);
+    into_schematic(&abs_path, eval_result)
+}
+
+/// Evaluate `file` like [`run`], additionally returning every file the
+/// evaluation depended on and the lines written by `print()`.
+///
+/// Dependencies are the root file itself plus all resolved `load()` and
+/// `Module()` targets. Paths are canonical and sorted.
+pub fn run_with_dependencies(
+    file: &Path,
+) -> (WithDiagnostics<Schematic>, Vec<PathBuf>, Vec<String>) {
+    let abs_path = file
+        .canonicalize()
+        .expect("failed to canonicalise input path");
+
+    let (eval_result, deps_ctx) = eval_root(&abs_path);
+
+    let mut dependencies: Vec<PathBuf> = deps_ctx.get_loaded_files().into_iter().collect();
+    dependencies.push(abs_path.clone());
+    dependencies.sort();
+    dependencies.dedup();
+
+    // Includes output of submodules and of evaluations that failed
+    let print_output = deps_ctx.get_print_output();
+
+    (
+        into_schematic(&abs_path, eval_result),
+        dependencies,
+        print_output,
+    )
+}
+
+/// Evaluate `file` like [`run`], additionally returning the graph of
+/// `load()` and `Module()` edges between the files it depended on, keyed by
+/// canonical path. Files that reference nothing have no entry.
+pub fn run_with_dependency_graph(
+    file: &Path,
+) -> (
+    WithDiagnostics<Schematic>,
+    BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+) {
+    let abs_path = file
+        .canonicalize()
+        .expect("failed to canonicalise input path");
+
+    let (eval_result, deps_ctx) = eval_root(&abs_path);
+    let graph = deps_ctx.get_dependency_graph();
+
+    (into_schematic(&abs_path, eval_result), graph)
+}
+
+/// Evaluate every design variant declared in `file`. Each module the file
+/// instantiates at the top level (e.g. `Board(name = "Lite", wifi = False)`)
+/// is a variant keyed by its instance name, built with the inputs it was
+/// instantiated with. Each top-level binding that holds a `Module()` loader
+/// the file never instantiates (e.g. `Lite = Module("lite.zen")`) is built on
+/// its own, keyed by binding name. Returns the schematics along with the
+/// diagnostics of `file` and of every variant. Variants that fail to evaluate
+/// are reported through the diagnostics and left out of the map.
+pub fn run_variants(file: &Path) -> WithDiagnostics<HashMap<String, Schematic>> {
+    let abs_path = file
+        .canonicalize()
+        .expect("failed to canonicalise input path");
+
+    let (eval_result, _) = eval_root(&abs_path);
+    let mut diagnostics = eval_result.diagnostics;
+    let Some(output) = eval_result.output else {
+        return WithDiagnostics::failure(diagnostics);
+    };
+
+    let mut variants = HashMap::new();
+    let mut instantiated = HashSet::new();
+    for child in output.sch_module.children() {
+        let Some(module) = child.downcast_ref::<FrozenModuleValue>() else {
+            continue;
+        };
+        instantiated.insert(PathBuf::from(module.source_path()));
+        let variant = finish_schematic(&abs_path, module.to_schematic(), Vec::new());
+        diagnostics.extend(variant.diagnostics);
+        if let Some(schematic) = variant.output {
+            variants.insert(module.name().to_string(), schematic);
+        }
+    }
+
+    for (name, path) in output.module_loaders() {
+        if instantiated.contains(&path) {
+            continue;
+        }
+        let (variant_result, _) = eval_root(&path);
+        let variant = into_schematic(&path, variant_result);
+        diagnostics.extend(variant.diagnostics);
+        if let Some(schematic) = variant.output {
+            variants.insert(name, schematic);
+        }
+    }
+
+    WithDiagnostics::success(variants, diagnostics)
+}
+
+/// Evaluate the root module at `file`, returning the result alongside a context
+/// that shares state with the evaluation so callers can inspect what it loaded.
+///
+/// Diagnostics silenced by `# pcb: allow(<code>)` comments in workspace files
+/// are removed (see [`suppression::apply_suppression_comments`]).
+fn eval_root(file: &Path) -> (WithDiagnostics<EvalOutput>, EvalContext) {
+    let abs_path = file
+        .canonicalize()
+        .expect("failed to canonicalise input path");
+
     // Create a file provider for finding workspace root
     let file_provider = DefaultFileProvider;
 
@@ -70,40 +195,104 @@ pub fn run(file: &Path) -> WithDiagnostics<Schematic> {
         .unwrap_or_else(|| abs_path.parent().unwrap().to_path_buf());
 
     let ctx = create_eval_context(&workspace_root);
+    let deps_ctx = ctx.child_context();
 
     // For now we don't inject any external inputs.
     let inputs = InputMap::new();
-    let eval_result = ctx
+    let mut eval_result = ctx
         .set_source_path(abs_path.clone())
         .set_module_name("<root>".to_string())
         .set_inputs(inputs)
         .eval();
 
-    // Collect diagnostics emitted during evaluation.
-    let diagnostics = eval_result.diagnostics;
-    let schematic = eval_result.output.map(|m| m.sch_module.to_schematic());
+    // Only honour suppressions in the workspace, not in fetched packages.
+    let mut files: Vec<PathBuf> = deps_ctx
+        .get_loaded_files()
+        .into_iter()
+        .filter(|path| path.starts_with(&workspace_root))
+        .collect();
+    files.push(abs_path);
+    files.sort();
+    files.dedup();
+    eval_result.diagnostics = suppression::apply_suppression_comments(
+        std::mem::take(&mut eval_result.diagnostics),
+        &files,
+        |path| file_provider.read_file(path).ok(),
+    );
+    eval_result.diagnostics = suppression::apply_config_suppressions(
+        std::mem::take(&mut eval_result.diagnostics),
+        &read_lint_ignores(&file_provider, &workspace_root),
+        &workspace_root,
+    );
 
+    (eval_result, deps_ctx)
+}
+
+/// Convert the output of evaluating `file` into a [`Schematic`] with
+/// reference designators assigned, see [`finish_schematic`].
+fn into_schematic(
+    file: &Path,
+    eval_result: WithDiagnostics<EvalOutput>,
+) -> WithDiagnostics<Schematic> {
     // Determine the overall outcome.  Even if the evaluation emitted error
     // diagnostics we still return `success` as long as a schematic was
     // produced so that callers (e.g. the CLI) can decide based on
     // `has_errors()` whether to treat the build as failed.
+    match eval_result.output {
+        Some(output) => finish_schematic(
+            file,
+            output.sch_module.to_schematic(),
+            eval_result.diagnostics,
+        ),
+        None => WithDiagnostics::failure(eval_result.diagnostics),
+    }
+}
+
+/// Assign reference designators to a schematic converted from `file`,
+/// appending to `diagnostics`. Explicit designators are kept; clashes between
+/// them are reported as warnings. Prefixes can be customized per component
+/// type in the `[refdes]` table of the workspace `pcb.toml`.
+fn finish_schematic(
+    file: &Path,
+    schematic: anyhow::Result<Schematic>,
+    mut diagnostics: Vec<Diagnostic>,
+) -> WithDiagnostics<Schematic> {
     match schematic {
-        Some(Ok(mut schematic)) => {
-            schematic.assign_reference_designators();
+        Ok(mut schematic) => {
+            let prefixes = find_workspace_root(&DefaultFileProvider, file)
+                .map(|root| read_refdes_prefixes(&DefaultFileProvider, &root))
+                .unwrap_or_default();
+            let (ref_map, conflicts) =
+                schematic.assign_reference_designators_preserving_with_prefixes(&prefixes);
+            for conflict in conflicts {
+                diagnostics.push(Diagnostic {
+                    path: file.to_string_lossy().to_string(),
+                    span: None,
+                    severity: EvalSeverity::Warning,
+                    body: format!(
+                        "Reference designator {} of `{}` is already used by `{}`; assigned {} instead",
+                        conflict.designator,
+                        conflict.reassigned.instance_path.join("."),
+                        conflict.kept.instance_path.join("."),
+                        ref_map[&conflict.reassigned],
+                    ),
+                    call_stack: None,
+                    child: None,
+                    code: Some(codes::DUPLICATE_REFERENCE_DESIGNATOR.to_owned()),
+                });
+            }
             WithDiagnostics::success(schematic, diagnostics)
         }
-        Some(Err(e)) => {
+        Err(e) => {
             // Convert the schematic conversion error into a Starlark diagnostic and append it
             // to the existing list so that callers can surface it to users.
-            let mut diagnostics_with_error = diagnostics;
             let st_error: starlark::Error = e.into();
-            diagnostics_with_error.push(Diagnostic::from_eval_message(EvalMessage::from_error(
-                abs_path.as_path(),
-                &st_error,
-            )));
-            WithDiagnostics::failure(diagnostics_with_error)
+            diagnostics.push(
+                Diagnostic::from_eval_message(EvalMessage::from_error(file, &st_error))
+                    .with_code(codes::SCHEMATIC_CONVERSION_FAILED),
+            );
+            WithDiagnostics::failure(diagnostics)
         }
-        None => WithDiagnostics::failure(diagnostics),
     }
 }
 
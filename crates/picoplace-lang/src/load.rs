@@ -1,7 +1,8 @@
-use picoplace_core::LoadSpec;
+use picoplace_core::{LoadSpec, DEFAULT_REMOTE_TTL};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
 
 #[cfg(unix)]
 use std::os::unix::fs as unix_fs;
@@ -26,10 +27,10 @@ fn materialise_remote(spec: &LoadSpec, workspace_root: Option<&Path>) -> anyhow:
         LoadSpec::Package { package, tag, path } => {
             let cache_root = cache_dir()?.join("packages").join(package).join(tag);
 
-            // Ensure package tarball is present/unpacked.
-            if !cache_root.exists() {
-                download_and_unpack_package(package, tag, &cache_root)?;
-            }
+            // Ensure package tarball is present/unpacked and not stale.
+            ensure_cached(spec, &cache_root, |dest| {
+                download_and_unpack_package(package, tag, dest)
+            })?;
 
             let local_path = if path.as_os_str().is_empty() {
                 cache_root.clone()
@@ -61,10 +62,10 @@ fn materialise_remote(spec: &LoadSpec, workspace_root: Option<&Path>) -> anyhow:
         } => {
             let cache_root = cache_dir()?.join("github").join(user).join(repo).join(rev);
 
-            // Ensure the repo has been fetched & unpacked.
-            if !cache_root.exists() {
-                download_and_unpack_github_repo(user, repo, rev, &cache_root)?;
-            }
+            // Ensure the repo has been fetched & unpacked and is not stale.
+            ensure_cached(spec, &cache_root, |dest| {
+                download_and_unpack_github_repo(user, repo, rev, dest)
+            })?;
 
             let local_path = cache_root.join(path);
             if !local_path.exists() {
@@ -94,10 +95,10 @@ fn materialise_remote(spec: &LoadSpec, workspace_root: Option<&Path>) -> anyhow:
         } => {
             let cache_root = cache_dir()?.join("gitlab").join(project_path).join(rev);
 
-            // Ensure the repo has been fetched & unpacked.
-            if !cache_root.exists() {
-                download_and_unpack_gitlab_repo(project_path, rev, &cache_root)?;
-            }
+            // Ensure the repo has been fetched & unpacked and is not stale.
+            ensure_cached(spec, &cache_root, |dest| {
+                download_and_unpack_gitlab_repo(project_path, rev, dest)
+            })?;
 
             let local_path = cache_root.join(path);
             if !local_path.exists() {
@@ -121,6 +122,62 @@ fn materialise_remote(spec: &LoadSpec, workspace_root: Option<&Path>) -> anyhow:
     }
 }
 
+/// Whether the on-disk checkout at `cache_root` must be fetched again.
+///
+/// Missing checkouts always need a fetch. Checkouts pinned to a full commit SHA
+/// never change, while branches, tags and packages expire after `ttl`, measured
+/// from the last time the checkout directory was written.
+fn cache_needs_fetch(spec: &LoadSpec, cache_root: &Path, ttl: Duration) -> bool {
+    if !cache_root.exists() {
+        return true;
+    }
+    if spec.is_immutable_rev() {
+        return false;
+    }
+    fs::metadata(cache_root)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_none_or(|age| age >= ttl)
+}
+
+/// Populate `cache_root` with `download` if it is missing or stale.
+///
+/// A stale checkout is refreshed into a sibling staging directory and only
+/// swapped in once the download succeeds, so being offline keeps the old copy.
+fn ensure_cached(
+    spec: &LoadSpec,
+    cache_root: &Path,
+    download: impl FnOnce(&Path) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    if !cache_needs_fetch(spec, cache_root, DEFAULT_REMOTE_TTL) {
+        return Ok(());
+    }
+    if !cache_root.exists() {
+        return download(cache_root);
+    }
+
+    let mut staging_name = cache_root.file_name().unwrap_or_default().to_owned();
+    staging_name.push(".refresh");
+    let staging = cache_root.with_file_name(staging_name);
+    let _ = fs::remove_dir_all(&staging);
+
+    match download(&staging) {
+        Ok(()) => {
+            fs::remove_dir_all(cache_root)?;
+            fs::rename(&staging, cache_root)?;
+        }
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            log::warn!(
+                "Failed to refresh {}, using cached copy: {e}",
+                spec.to_load_string()
+            );
+        }
+    }
+    Ok(())
+}
+
 pub fn cache_dir() -> anyhow::Result<PathBuf> {
     // 1. Allow callers to force an explicit location via env var. This is handy in CI
     //    environments where the default XDG cache directory may be read-only or owned
@@ -604,7 +661,7 @@ mod tests {
 
     #[test]
     fn parses_package_without_tag() {
-        let spec = LoadSpec::parse("@stdlib/math.zen");
+        let spec = LoadSpec::parse_opt("@stdlib/math.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Package {
@@ -617,7 +674,7 @@ mod tests {
 
     #[test]
     fn parses_package_with_tag_and_root_path() {
-        let spec = LoadSpec::parse("@stdlib:1.2.3");
+        let spec = LoadSpec::parse_opt("@stdlib:1.2.3");
         assert_eq!(
             spec,
             Some(LoadSpec::Package {
@@ -630,7 +687,7 @@ mod tests {
 
     #[test]
     fn parses_github_with_rev_and_path() {
-        let spec = LoadSpec::parse("@github/foo/bar:abc123/scripts/build.zen");
+        let spec = LoadSpec::parse_opt("@github/foo/bar:abc123/scripts/build.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Github {
@@ -644,7 +701,7 @@ mod tests {
 
     #[test]
     fn parses_github_without_rev() {
-        let spec = LoadSpec::parse("@github/foo/bar/scripts/build.zen");
+        let spec = LoadSpec::parse_opt("@github/foo/bar/scripts/build.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Github {
@@ -658,7 +715,7 @@ mod tests {
 
     #[test]
     fn parses_github_repo_root_with_rev() {
-        let spec = LoadSpec::parse("@github/foo/bar:main");
+        let spec = LoadSpec::parse_opt("@github/foo/bar:main");
         assert_eq!(
             spec,
             Some(LoadSpec::Github {
@@ -674,7 +731,7 @@ mod tests {
     fn parses_github_repo_root_with_long_commit() {
         let sha = "0123456789abcdef0123456789abcdef01234567";
         let input = format!("@github/foo/bar:{sha}");
-        let spec = LoadSpec::parse(&input);
+        let spec = LoadSpec::parse_opt(&input);
         assert_eq!(
             spec,
             Some(LoadSpec::Github {
@@ -688,7 +745,7 @@ mod tests {
 
     #[test]
     fn parses_gitlab_with_rev_and_path() {
-        let spec = LoadSpec::parse("@gitlab/foo/bar:abc123/scripts/build.zen");
+        let spec = LoadSpec::parse_opt("@gitlab/foo/bar:abc123/scripts/build.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -701,7 +758,7 @@ mod tests {
 
     #[test]
     fn parses_gitlab_without_rev() {
-        let spec = LoadSpec::parse("@gitlab/foo/bar/scripts/build.zen");
+        let spec = LoadSpec::parse_opt("@gitlab/foo/bar/scripts/build.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -714,7 +771,7 @@ mod tests {
 
     #[test]
     fn parses_gitlab_repo_root_with_rev() {
-        let spec = LoadSpec::parse("@gitlab/foo/bar:main");
+        let spec = LoadSpec::parse_opt("@gitlab/foo/bar:main");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -729,7 +786,7 @@ mod tests {
     fn parses_gitlab_repo_root_with_long_commit() {
         let sha = "0123456789abcdef0123456789abcdef01234567";
         let input = format!("@gitlab/foo/bar:{sha}");
-        let spec = LoadSpec::parse(&input);
+        let spec = LoadSpec::parse_opt(&input);
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -742,7 +799,8 @@ mod tests {
 
     #[test]
     fn parses_gitlab_nested_groups_with_rev() {
-        let spec = LoadSpec::parse("@gitlab/kicad/libraries/kicad-symbols:main/Device.kicad_sym");
+        let spec =
+            LoadSpec::parse_opt("@gitlab/kicad/libraries/kicad-symbols:main/Device.kicad_sym");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -756,7 +814,7 @@ mod tests {
     #[test]
     fn parses_gitlab_simple_without_rev_with_file_path() {
         // Without revision, first 2 parts are project
-        let spec = LoadSpec::parse("@gitlab/user/repo/src/main.zen");
+        let spec = LoadSpec::parse_opt("@gitlab/user/repo/src/main.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -769,7 +827,7 @@ mod tests {
 
     #[test]
     fn parses_gitlab_nested_groups_no_file() {
-        let spec = LoadSpec::parse("@gitlab/kicad/libraries/kicad-symbols:v7.0.0");
+        let spec = LoadSpec::parse_opt("@gitlab/kicad/libraries/kicad-symbols:v7.0.0");
         assert_eq!(
             spec,
             Some(LoadSpec::Gitlab {
@@ -850,7 +908,7 @@ mod tests {
         // Test that custom tags override the default alias tags
 
         // Test 1: Package alias with tag override
-        let spec = LoadSpec::parse("@stdlib:zen/math.zen");
+        let spec = LoadSpec::parse_opt("@stdlib:zen/math.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Package {
@@ -861,7 +919,7 @@ mod tests {
         );
 
         // Test 2: Verify that default tag is used when not specified
-        let spec = LoadSpec::parse("@stdlib/math.zen");
+        let spec = LoadSpec::parse_opt("@stdlib/math.zen");
         assert_eq!(
             spec,
             Some(LoadSpec::Package {
@@ -872,7 +930,7 @@ mod tests {
         );
 
         // Test 3: KiCad symbols with custom version
-        let spec = LoadSpec::parse("@kicad-symbols:8.0.0/Device.kicad_sym");
+        let spec = LoadSpec::parse_opt("@kicad-symbols:8.0.0/Device.kicad_sym");
         assert_eq!(
             spec,
             Some(LoadSpec::Package {
@@ -882,4 +940,69 @@ mod tests {
             })
         );
     }
+
+    fn github(rev: &str) -> LoadSpec {
+        LoadSpec::Github {
+            user: "user".to_string(),
+            repo: "repo".to_string(),
+            rev: rev.to_string(),
+            path: PathBuf::from("lib.zen"),
+        }
+    }
+
+    fn age_dir(dir: &Path, by: Duration) {
+        let modified = std::time::SystemTime::now() - by;
+        fs::File::open(dir).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn expired_branch_checkout_is_refetched() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_root = tmp.path().join("main");
+        fs::create_dir_all(&cache_root).unwrap();
+        fs::write(cache_root.join("lib.zen"), "old").unwrap();
+
+        let branch = github("main");
+        let pinned = github(&"a".repeat(40));
+        let ttl = Duration::from_secs(60);
+
+        assert!(!cache_needs_fetch(&branch, &cache_root, ttl));
+        age_dir(&cache_root, 2 * ttl);
+        assert!(cache_needs_fetch(&branch, &cache_root, ttl));
+        assert!(!cache_needs_fetch(&pinned, &cache_root, ttl));
+        assert!(cache_needs_fetch(&pinned, &tmp.path().join("missing"), ttl));
+
+        age_dir(&cache_root, 2 * DEFAULT_REMOTE_TTL);
+        ensure_cached(&branch, &cache_root, |dest| {
+            fs::create_dir_all(dest)?;
+            fs::write(dest.join("lib.zen"), "new")?;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(cache_root.join("lib.zen")).unwrap(),
+            "new"
+        );
+        assert!(!cache_needs_fetch(&branch, &cache_root, DEFAULT_REMOTE_TTL));
+    }
+
+    #[test]
+    fn failed_refresh_keeps_cached_checkout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache_root = tmp.path().join("main");
+        fs::create_dir_all(&cache_root).unwrap();
+        fs::write(cache_root.join("lib.zen"), "old").unwrap();
+        age_dir(&cache_root, 2 * DEFAULT_REMOTE_TTL);
+
+        ensure_cached(&github("main"), &cache_root, |dest| {
+            fs::create_dir_all(dest)?;
+            anyhow::bail!("offline")
+        })
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(cache_root.join("lib.zen")).unwrap(),
+            "old"
+        );
+        assert!(!tmp.path().join("main.refresh").exists());
+    }
 }
@@ -2,31 +2,103 @@ pub mod signature;
 
 use lsp_server::ResponseError;
 use lsp_types::{
-    request::Request, Hover, HoverContents, MarkupContent, MarkupKind, ServerCapabilities,
+    request::Request, Hover, HoverContents, MarkupContent, MarkupKind, OneOf, ServerCapabilities,
     SignatureHelpOptions, Url, WorkDoneProgressOptions,
 };
+use once_cell::sync::Lazy;
 use picoplace_starlark_lsp::server::{
-    self, CompletionMeta, LspContext, LspEvalResult, LspUrl, Response, StringLiteralResult,
+    self, CompletionMeta, LspContext, LspEvalResult, LspUrl, Response, SignatureParameter,
+    StringLiteralResult,
 };
-use picoplace_core::workspace::find_workspace_root;
+use picoplace_core::workspace::{find_workspace_root, read_lint_ignores, read_refdes_prefixes};
 use picoplace_core::{
-    CoreLoadResolver, DefaultFileProvider, EvalContext, FileProvider, InputMap, LoadResolver,
+    CoreLoadResolver, DefaultFileProvider, EvalContext, EvalOutput, FileProvider, InputMap,
+    LoadResolver, LoadSpec, NoopRemoteFetcher, SymbolKind, WithDiagnostics,
 };
+use picoplace_eda::kicad::symbol::{find_pin_offset, find_symbol_pin_offset};
+use picoplace_netlist::kicad_schematic::find_kicad_symbol_dirs;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use starlark::codemap::{CodeMap, ResolvedSpan};
 use starlark::docs::DocModule;
-use std::collections::HashMap;
-use std::path::Path;
-use std::sync::Arc;
+use starlark::syntax::ast::{
+    ArgumentP, AssignTargetP, AstArgumentP, AstExprP, AstLiteral, AstPayload, AstStmtP, ExprP,
+    StmtP,
+};
+use starlark::syntax::AstModule;
+use starlark_syntax::syntax::module::AstModuleFields;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use crate::load::DefaultRemoteFetcher;
+use crate::suppression;
+use crate::{ParameterInfo, TypeInfo};
 use picoplace_core::convert::ToSchematic;
 
+/// A `Library:Symbol` reference into one of the KiCad symbol libraries
+static LIBRARY_SYMBOL_REF: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([^:/\\\s]+):([^:/\\]+)$").unwrap());
+
+/// LSP position (UTF-16 columns) of the byte `offset` in `content`
+fn offset_to_position(content: &str, offset: usize) -> lsp_types::Position {
+    let line_start = content[..offset].rfind('\n').map_or(0, |i| i + 1);
+    lsp_types::Position::new(
+        content[..offset].matches('\n').count() as u32,
+        content[line_start..offset].encode_utf16().count() as u32,
+    )
+}
+
 /// Wrapper around EvalContext that implements LspContext
 pub struct LspEvalContext {
     inner: EvalContext,
     builtin_docs: HashMap<LspUrl, String>,
     file_provider: Arc<dyn FileProvider>,
+    /// Hover text for each net variable of a file, keyed by file together
+    /// with a hash of the contents it was computed from.
+    net_hovers: Mutex<HashMap<PathBuf, (blake3::Hash, Arc<HashMap<String, String>>)>>,
+    /// Result of the last completed parse of each document.
+    parse_cache: Mutex<HashMap<LspUrl, CachedParse>>,
+    /// Signature help parameters of each module, keyed by module file.
+    signatures: Mutex<HashMap<PathBuf, CachedSignature>>,
+    /// Directories searched for KiCad symbol libraries before the ones
+    /// from `KICAD_SYMBOL_DIR` and the platform defaults.
+    symbol_dirs: Vec<PathBuf>,
+}
+
+/// A completed parse together with the inputs it was computed from
+#[derive(Clone)]
+struct CachedParse {
+    content_hash: blake3::Hash,
+    /// Every file the document loads (transitively) and the workspace
+    /// `pcb.toml`, with the hash of their contents at parse time. `None` for
+    /// files that could not be read.
+    deps: Vec<(PathBuf, Option<blake3::Hash>)>,
+    result: LspEvalResult,
+}
+
+/// The parameters a module accepts together with the files they were
+/// introspected from
+struct CachedSignature {
+    /// The module file and every file it loads (transitively), with the hash
+    /// of their contents at introspection time
+    deps: Vec<(PathBuf, Option<blake3::Hash>)>,
+    signature: Vec<SignatureParameter>,
+}
+
+/// Hash of a document's contents, used to tell whether cached results are stale
+fn content_hash(contents: &str) -> blake3::Hash {
+    blake3::hash(contents.as_bytes())
+}
+
+/// Workspace root of `file_path`, falling back to its directory
+fn workspace_root_for(file_provider: &dyn FileProvider, file_path: &Path) -> PathBuf {
+    file_path
+        .parent()
+        .and_then(|parent| find_workspace_root(file_provider, parent))
+        .unwrap_or_else(|| file_path.parent().unwrap_or(file_path).to_path_buf())
 }
 
 /// Helper function to create a standard load resolver with remote and workspace support
@@ -34,10 +106,7 @@ fn create_standard_load_resolver(
     file_provider: Arc<dyn FileProvider>,
     file_path: &Path,
 ) -> Arc<CoreLoadResolver> {
-    let workspace_root = file_path
-        .parent()
-        .and_then(|parent| find_workspace_root(file_provider.as_ref(), parent))
-        .unwrap_or_else(|| file_path.parent().unwrap_or(file_path).to_path_buf());
+    let workspace_root = workspace_root_for(file_provider.as_ref(), file_path);
 
     let remote_fetcher = Arc::new(DefaultRemoteFetcher);
     Arc::new(CoreLoadResolver::new(
@@ -47,6 +116,227 @@ fn create_standard_load_resolver(
     ))
 }
 
+/// Signature help entry for a module input, e.g. `voltage: float = 3.3`
+fn module_parameter(param: &ParameterInfo) -> SignatureParameter {
+    let mut label = format!("{}: {}", param.name, type_label(&param.type_info));
+    if let Some(default) = &param.default_value {
+        label.push_str(&format!(" = {default}"));
+    }
+    SignatureParameter {
+        name: param.name.clone(),
+        label,
+        documentation: param.help.clone(),
+    }
+}
+
+/// Short name of a type, as written in `.zen` source
+fn type_label(type_info: &TypeInfo) -> &str {
+    match type_info {
+        TypeInfo::String => "str",
+        TypeInfo::Int => "int",
+        TypeInfo::Float => "float",
+        TypeInfo::Bool => "bool",
+        TypeInfo::List { .. } => "list",
+        TypeInfo::Dict { .. } => "dict",
+        TypeInfo::Net => "Net",
+        TypeInfo::Enum { name, .. }
+        | TypeInfo::Record { name, .. }
+        | TypeInfo::Interface { name, .. } => name,
+        TypeInfo::Unknown { type_name } => type_name,
+    }
+}
+
+/// The path literal of every `Module("...")` call in `ast`, with its span
+fn module_path_literals(ast: &AstModule) -> Vec<(String, ResolvedSpan)> {
+    fn visit<P: AstPayload>(
+        expr: &AstExprP<P>,
+        codemap: &CodeMap,
+        out: &mut Vec<(String, ResolvedSpan)>,
+    ) {
+        if let ExprP::Call(target, args) = &expr.node {
+            let is_module =
+                matches!(&target.node, ExprP::Identifier(ident) if ident.node.ident == "Module");
+            if let (true, Some(ArgumentP::Positional(path))) =
+                (is_module, args.args.first().map(|arg| &arg.node))
+            {
+                if let ExprP::Literal(AstLiteral::String(literal)) = &path.node {
+                    out.push((literal.node.clone(), codemap.resolve_span(path.span)));
+                }
+            }
+        }
+        expr.visit_expr(|child| visit(child, codemap, out));
+    }
+
+    let mut out = Vec::new();
+    ast.statement()
+        .visit_expr(|expr| visit(expr, ast.codemap(), &mut out));
+    out
+}
+
+/// The value of a string literal expression
+fn string_literal<P: AstPayload>(expr: &AstExprP<P>) -> Option<&str> {
+    match &expr.node {
+        ExprP::Literal(AstLiteral::String(literal)) => Some(&literal.node),
+        _ => None,
+    }
+}
+
+/// The name of the function called by `expr` and the arguments it is called with
+fn called_function<P: AstPayload>(expr: &AstExprP<P>) -> Option<(&str, &[AstArgumentP<P>])> {
+    match &expr.node {
+        ExprP::Call(target, args) => match &target.node {
+            ExprP::Identifier(ident) => Some((&ident.node.ident, &args.args)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The argument passed as `name = ...`
+fn named_argument<'a, P: AstPayload>(
+    args: &'a [AstArgumentP<P>],
+    name: &str,
+) -> Option<&'a AstExprP<P>> {
+    args.iter().find_map(|arg| match &arg.node {
+        ArgumentP::Named(arg_name, value) if arg_name.node == name => Some(value),
+        _ => None,
+    })
+}
+
+/// The first positional argument
+fn first_positional_argument<P: AstPayload>(args: &[AstArgumentP<P>]) -> Option<&AstExprP<P>> {
+    args.iter().find_map(|arg| match &arg.node {
+        ArgumentP::Positional(value) => Some(value),
+        _ => None,
+    })
+}
+
+/// Library and, when given, symbol name of a `Symbol(...)` call, e.g.
+/// `Symbol("Device:R")` or `Symbol(library = "./part.kicad_sym", name = "R")`
+fn symbol_call_library<P: AstPayload>(expr: &AstExprP<P>) -> Option<(String, Option<String>)> {
+    let ("Symbol", args) = called_function(expr)? else {
+        return None;
+    };
+    let name = named_argument(args, "name").and_then(string_literal);
+    if let Some(spec) = first_positional_argument(args).and_then(string_literal) {
+        // Like `Symbol` itself, a positional `library:name` names the symbol too
+        return Some(match spec.rsplit_once(':') {
+            Some((library, name)) => (library.to_owned(), Some(name.to_owned())),
+            None => (spec.to_owned(), name.map(str::to_owned)),
+        });
+    }
+    let library = named_argument(args, "library").and_then(string_literal)?;
+    match (LIBRARY_SYMBOL_REF.captures(library), name) {
+        (Some(captures), None) => Some((captures[1].to_owned(), Some(captures[2].to_owned()))),
+        (_, name) => Some((library.to_owned(), name.map(str::to_owned))),
+    }
+}
+
+/// Library and, when given, symbol name of the symbol whose pins the string
+/// literal at `span` names. Only keys of the `pins = {...}` argument of a
+/// `Component(...)` call, or of a call of a factory assigned from
+/// `load_component(...)` in the same file, name pins.
+fn pin_key_symbol(ast: &AstModule, span: ResolvedSpan) -> Option<(String, Option<String>)> {
+    fn visit_stmts<P: AstPayload>(stmt: &AstStmtP<P>, f: &mut impl FnMut(&AstStmtP<P>)) {
+        f(stmt);
+        stmt.visit_stmt(|child| visit_stmts(child, f));
+    }
+
+    fn visit<P: AstPayload>(
+        expr: &AstExprP<P>,
+        codemap: &CodeMap,
+        span: ResolvedSpan,
+        factories: &HashMap<String, String>,
+        found: &mut Option<(String, Option<String>)>,
+    ) {
+        if found.is_some() {
+            return;
+        }
+        if let Some((function, args)) = called_function(expr) {
+            let is_pin_key = match named_argument(args, "pins").map(|pins| &pins.node) {
+                Some(ExprP::Dict(entries)) => entries
+                    .iter()
+                    .any(|(key, _)| codemap.resolve_span(key.span) == span),
+                _ => false,
+            };
+            if is_pin_key {
+                *found = match function {
+                    "Component" => named_argument(args, "symbol").and_then(symbol_call_library),
+                    factory => factories
+                        .get(factory)
+                        .map(|library| (library.clone(), None)),
+                };
+                return;
+            }
+        }
+        expr.visit_expr(|child| visit(child, codemap, span, factories, found));
+    }
+
+    // Factories created by `NAME = load_component("./part.kicad_sym")`
+    let mut factories = HashMap::new();
+    visit_stmts(ast.statement(), &mut |stmt| {
+        if let StmtP::Assign(assign) = &stmt.node {
+            if let (AssignTargetP::Identifier(ident), Some(("load_component", args))) =
+                (&assign.lhs.node, called_function(&assign.rhs))
+            {
+                if let Some(library) = first_positional_argument(args).and_then(string_literal) {
+                    factories.insert(ident.ident.clone(), library.to_owned());
+                }
+            }
+        }
+    });
+
+    let mut found = None;
+    ast.statement()
+        .visit_expr(|expr| visit(expr, ast.codemap(), span, &factories, &mut found));
+    found
+}
+
+/// Most components listed by name in a net's hover text
+const NET_HOVER_MAX_COMPONENTS: usize = 8;
+
+/// e.g. "`GND` — connects 7 pins across C1, R3, U1"
+fn net_hover_text(net_name: &str, pin_count: usize, components: &[String]) -> String {
+    let pins = if pin_count == 1 { "pin" } else { "pins" };
+    let mut text = format!("`{net_name}` — connects {pin_count} {pins}");
+    if !components.is_empty() {
+        let shown = components.len().min(NET_HOVER_MAX_COMPONENTS);
+        text.push_str(&format!(" across {}", components[..shown].join(", ")));
+        if components.len() > shown {
+            text.push_str(&format!(" and {} more", components.len() - shown));
+        }
+    }
+    text
+}
+
+/// Workspace roots announced by the client, falling back to the workspace containing `file_path`
+fn workspace_roots(
+    initialize_params: &lsp_types::InitializeParams,
+    file_provider: &dyn FileProvider,
+    file_path: &Path,
+) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = match &initialize_params.workspace_folders {
+        Some(folders) => folders
+            .iter()
+            .filter_map(|f| f.uri.to_file_path().ok())
+            .collect(),
+        None => initialize_params
+            .root_uri
+            .as_ref()
+            .and_then(|uri| uri.to_file_path().ok())
+            .into_iter()
+            .collect(),
+    };
+    if roots.is_empty() {
+        roots.extend(
+            file_path
+                .parent()
+                .and_then(|parent| find_workspace_root(file_provider, parent)),
+        );
+    }
+    roots
+}
+
 impl Default for LspEvalContext {
     fn default() -> Self {
         // Build builtin documentation map
@@ -79,6 +369,10 @@ impl Default for LspEvalContext {
             inner,
             builtin_docs,
             file_provider,
+            net_hovers: Mutex::new(HashMap::new()),
+            parse_cache: Mutex::new(HashMap::new()),
+            signatures: Mutex::new(HashMap::new()),
+            symbol_dirs: Vec::new(),
         }
     }
 }
@@ -89,6 +383,306 @@ impl LspEvalContext {
         self
     }
 
+    pub fn set_file_provider(mut self, file_provider: Arc<dyn FileProvider>) -> Self {
+        self.inner = self.inner.set_file_provider(file_provider.clone());
+        self.file_provider = file_provider;
+        self
+    }
+
+    pub fn set_symbol_dirs(mut self, symbol_dirs: Vec<PathBuf>) -> Self {
+        self.symbol_dirs = symbol_dirs;
+        self
+    }
+
+    /// Evaluate `path` as a root module, preferring in-memory contents over
+    /// the file on disk.
+    fn eval_root(&self, path: &Path) -> WithDiagnostics<EvalOutput> {
+        let ctx = EvalContext::new()
+            .set_file_provider(self.file_provider.clone())
+            .set_load_resolver(create_standard_load_resolver(
+                self.file_provider.clone(),
+                path,
+            ))
+            .set_source_path(path.to_path_buf())
+            .set_module_name("<root>".to_string())
+            .set_inputs(InputMap::new());
+
+        match self
+            .get_load_contents(&LspUrl::File(path.to_path_buf()))
+            .ok()
+            .flatten()
+        {
+            Some(contents) => ctx.set_source_contents(contents).eval(),
+            None => ctx.eval(),
+        }
+    }
+
+    /// Hash of the current contents of `path`, preferring an open editor
+    /// buffer over the file on disk.
+    fn file_hash(&self, path: &Path) -> Option<blake3::Hash> {
+        self.inner
+            .get_file_contents(path)
+            .or_else(|| self.file_provider.read_file(path).ok())
+            .map(|contents| content_hash(&contents))
+    }
+
+    /// `files` paired with the hash of their current contents.
+    fn fingerprint(
+        &self,
+        files: impl IntoIterator<Item = PathBuf>,
+    ) -> Vec<(PathBuf, Option<blake3::Hash>)> {
+        files
+            .into_iter()
+            .map(|file| {
+                let hash = self.file_hash(&file);
+                (file, hash)
+            })
+            .collect()
+    }
+
+    /// Whether every file in `fingerprint` still has the recorded contents.
+    fn is_unchanged(&self, fingerprint: &[(PathBuf, Option<blake3::Hash>)]) -> bool {
+        fingerprint
+            .iter()
+            .all(|(file, hash)| self.file_hash(file) == *hash)
+    }
+
+    /// Every file `path` loads or instantiates, directly or transitively.
+    fn transitive_deps(&self, path: &Path) -> BTreeSet<PathBuf> {
+        let graph = self.inner.get_dependency_graph();
+        let mut deps = BTreeSet::new();
+        let mut pending = vec![path.to_path_buf()];
+        while let Some(file) = pending.pop() {
+            for dep in graph.get(&file).into_iter().flatten() {
+                if deps.insert(dep.clone()) {
+                    pending.push(dep.clone());
+                }
+            }
+        }
+        deps.remove(path);
+        deps
+    }
+
+    /// Hover text for each top-level net variable of `path`, describing what
+    /// the net connects to. Cached until the contents of `path` change.
+    fn net_hovers(&self, path: &Path) -> Arc<HashMap<String, String>> {
+        let contents = self
+            .get_load_contents(&LspUrl::File(path.to_path_buf()))
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let hash = content_hash(&contents);
+
+        if let Some((cached_hash, hovers)) = self.net_hovers.lock().unwrap().get(path) {
+            if *cached_hash == hash {
+                return hovers.clone();
+            }
+        }
+
+        let hovers = Arc::new(self.compute_net_hovers(path));
+        self.net_hovers
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), (hash, hovers.clone()));
+        hovers
+    }
+
+    fn compute_net_hovers(&self, path: &Path) -> HashMap<String, String> {
+        let Some(output) = self.eval_root(path).output else {
+            return HashMap::new();
+        };
+        let Ok(mut schematic) = output.sch_module.to_schematic() else {
+            return HashMap::new();
+        };
+        let workspace_root = workspace_root_for(self.file_provider.as_ref(), path);
+        let prefixes = read_refdes_prefixes(self.file_provider.as_ref(), &workspace_root);
+        schematic.assign_reference_designators_preserving_with_prefixes(&prefixes);
+
+        output
+            .net_bindings()
+            .into_iter()
+            .filter_map(|(variable, net_name)| {
+                let net = schematic.nets.get(&net_name)?;
+                let mut components: Vec<String> = net
+                    .ports
+                    .iter()
+                    .filter_map(|port| {
+                        let (reference, component) = schematic.component_of_port(port)?;
+                        Some(
+                            component
+                                .reference_designator
+                                .clone()
+                                .unwrap_or_else(|| reference.instance_path.join(".")),
+                        )
+                    })
+                    .collect();
+                components.sort();
+                components.dedup();
+
+                let text = net_hover_text(&net_name, net.ports.len(), &components);
+                Some((variable, text))
+            })
+            .collect()
+    }
+
+    /// Resolve the `pins = {...}` key `pin` at `span` in `current_path` to its
+    /// `(pin ...)` declaration in the symbol given in the same call.
+    fn find_pin_definition(
+        &self,
+        pin: &str,
+        span: ResolvedSpan,
+        current_path: &Path,
+    ) -> Option<StringLiteralResult> {
+        let source = self
+            .inner
+            .get_file_contents(current_path)
+            .or_else(|| self.file_provider.read_file(current_path).ok())?;
+        let ast = starlark::syntax::AstModule::parse(
+            &current_path.to_string_lossy(),
+            source,
+            &starlark::syntax::Dialect::Extended,
+        )
+        .ok()?;
+        let (library, symbol) = pin_key_symbol(&ast, span)?;
+
+        let load_resolver = create_standard_load_resolver(self.file_provider.clone(), current_path);
+        let library = load_resolver
+            .resolve_path(self.file_provider.as_ref(), &library, current_path)
+            .ok()
+            .filter(|path| self.file_provider.exists(path))
+            .or_else(|| self.find_library_in_symbol_dirs(&library))?;
+        let content = self.file_provider.read_file(&library).ok()?;
+        let offset = match symbol {
+            Some(symbol) => find_symbol_pin_offset(&content, &symbol, pin),
+            // A library given without a symbol name holds a single symbol
+            None => find_pin_offset(&content, pin),
+        }?;
+        let position = offset_to_position(&content, offset);
+        Some(StringLiteralResult {
+            url: LspUrl::File(library),
+            location_finder: None,
+            range: Some(lsp_types::Range::new(position, position)),
+        })
+    }
+
+    /// The `.kicad_sym` file of the library named `library_name` in the
+    /// configured symbol directories
+    fn find_library_in_symbol_dirs(&self, library_name: &str) -> Option<PathBuf> {
+        find_kicad_symbol_dirs(&self.symbol_dirs)
+            .into_iter()
+            .map(|dir| dir.join(format!("{library_name}.kicad_sym")))
+            .find(|path| self.file_provider.exists(path))
+    }
+
+    /// Resolve a `Library:Symbol` reference to the `.kicad_sym` file of its
+    /// library, pointing at the symbol's declaration when it can be found.
+    fn find_library_symbol(&self, literal: &str) -> Option<StringLiteralResult> {
+        let captures = LIBRARY_SYMBOL_REF.captures(literal)?;
+        let (library_name, symbol_name) = (&captures[1], &captures[2]);
+
+        let library = self.find_library_in_symbol_dirs(library_name)?;
+
+        let range = self
+            .file_provider
+            .read_file(&library)
+            .ok()
+            .and_then(|content| {
+                let offset = content.find(&format!("(symbol \"{symbol_name}\""))?;
+                let position = offset_to_position(&content, offset);
+                Some(lsp_types::Range::new(position, position))
+            });
+
+        Some(StringLiteralResult {
+            url: LspUrl::File(library),
+            location_finder: None,
+            range,
+        })
+    }
+
+    /// Find every `load()` statement and `Module()` call in the workspace that
+    /// resolves to `target`.
+    pub fn find_module_references(
+        &self,
+        target: &Path,
+        workspace_roots: &[PathBuf],
+    ) -> anyhow::Result<Vec<lsp_types::Location>> {
+        let canonicalize = |path: &Path| {
+            self.file_provider
+                .canonicalize(path)
+                .unwrap_or_else(|_| path.to_path_buf())
+        };
+        let target = canonicalize(target);
+
+        // Remote specs are skipped so that searching never fetches anything.
+        // Resolvers only differ by workspace root, so files share them.
+        let mut load_resolvers: HashMap<PathBuf, CoreLoadResolver> = HashMap::new();
+
+        let mut locations = Vec::new();
+        for path in self.inner.find_workspace_files(workspace_roots)? {
+            let Some(contents) = self
+                .inner
+                .get_file_contents(&path)
+                .or_else(|| self.file_provider.read_file(&path).ok())
+            else {
+                continue;
+            };
+            let Ok(ast) = starlark::syntax::AstModule::parse(
+                &path.to_string_lossy(),
+                contents,
+                &starlark::syntax::Dialect::Extended,
+            ) else {
+                continue;
+            };
+
+            let mut references: Vec<(String, ResolvedSpan)> = ast
+                .loads()
+                .into_iter()
+                .map(|load| (load.module_id.to_string(), load.span.resolve_span()))
+                .collect();
+            references.extend(module_path_literals(&ast));
+
+            let workspace_root = workspace_root_for(self.file_provider.as_ref(), &path);
+            let load_resolver = load_resolvers
+                .entry(workspace_root.clone())
+                .or_insert_with(|| {
+                    CoreLoadResolver::new(
+                        self.file_provider.clone(),
+                        Arc::new(NoopRemoteFetcher),
+                        Some(workspace_root),
+                    )
+                });
+            for (module_id, span) in references {
+                let Ok(spec) = LoadSpec::parse(&module_id) else {
+                    continue;
+                };
+                if spec.is_remote() {
+                    continue;
+                }
+                let Ok(resolved) =
+                    load_resolver.resolve_spec(self.file_provider.as_ref(), &spec, &path)
+                else {
+                    continue;
+                };
+                if canonicalize(&resolved) != target {
+                    continue;
+                }
+
+                locations.push(lsp_types::Location {
+                    uri: Url::from_file_path(&path)
+                        .map_err(|_| anyhow::anyhow!("Invalid file path: {}", path.display()))?,
+                    range: lsp_types::Range::new(
+                        lsp_types::Position::new(span.begin.line as u32, span.begin.column as u32),
+                        lsp_types::Position::new(span.end.line as u32, span.end.column as u32),
+                    ),
+                });
+            }
+        }
+
+        locations
+            .sort_by(|a, b| (a.uri.as_str(), a.range.start).cmp(&(b.uri.as_str(), b.range.start)));
+        Ok(locations)
+    }
+
     fn diagnostic_to_lsp(&self, diag: &picoplace_core::Diagnostic) -> lsp_types::Diagnostic {
         use lsp_types::{
             DiagnosticRelatedInformation, DiagnosticSeverity, Location, Position, Range,
@@ -165,7 +759,9 @@ impl LspEvalContext {
         lsp_types::Diagnostic {
             range,
             severity: Some(severity),
-            code: None,
+            code: diag
+                .outermost_code()
+                .map(|code| lsp_types::NumberOrString::String(code.to_owned())),
             code_description: None,
             source: Some("diode-star".to_string()),
             message: diag.body.clone(),
@@ -190,13 +786,30 @@ impl LspContext for LspEvalContext {
                     work_done_progress: None,
                 },
             }),
+            references_provider: Some(OneOf::Left(true)),
             ..ServerCapabilities::default()
         }
     }
 
     fn parse_file_with_contents(&self, uri: &LspUrl, content: String) -> LspEvalResult {
+        self.parse_file_with_contents_cancellable(uri, content, Arc::new(AtomicBool::new(false)))
+    }
+
+    fn parse_file_with_contents_cancellable(
+        &self,
+        uri: &LspUrl,
+        content: String,
+        cancel: Arc<AtomicBool>,
+    ) -> LspEvalResult {
         match uri {
             LspUrl::File(path) => {
+                let hash = content_hash(&content);
+                if let Some(cached) = self.parse_cache.lock().unwrap().get(uri) {
+                    if cached.content_hash == hash && self.is_unchanged(&cached.deps) {
+                        return cached.result.clone();
+                    }
+                }
+
                 // Create a load resolver for this file
                 let load_resolver =
                     create_standard_load_resolver(self.file_provider.clone(), uri.path());
@@ -206,19 +819,72 @@ impl LspContext for LspEvalContext {
                     .inner
                     .child_context()
                     .set_load_resolver(load_resolver)
+                    .set_cancellation_token(cancel.clone())
                     .parse_and_analyze_file(path.clone(), content.clone());
 
-                // Convert diagnostics to LSP format
-                let diagnostics = result
-                    .diagnostics
+                // Honour `# pcb: allow(...)` comments in workspace files and the
+                // `[lint].ignore` table as the build does, reading this document
+                // from the editor buffer.
+                let workspace_root = workspace_root_for(self.file_provider.as_ref(), path);
+                let mut files: Vec<PathBuf> = self
+                    .inner
+                    .get_loaded_files()
+                    .into_iter()
+                    .filter(|file| file.starts_with(&workspace_root))
+                    .collect();
+                files.push(path.clone());
+                files.sort();
+                files.dedup();
+                let current_path = path.to_string_lossy();
+                let diagnostics =
+                    suppression::apply_suppression_comments(result.diagnostics, &files, |file| {
+                        if file == path.as_path() {
+                            Some(content.clone())
+                        } else {
+                            self.file_provider.read_file(file).ok()
+                        }
+                    });
+                let diagnostics = suppression::apply_config_suppressions(
+                    diagnostics,
+                    &read_lint_ignores(self.file_provider.as_ref(), &workspace_root),
+                    &workspace_root,
+                );
+
+                // Convert diagnostics to LSP format. Unused suppressions in
+                // other files are reported when those files are open.
+                let diagnostics = diagnostics
                     .iter()
+                    .filter(|d| {
+                        d.code.as_deref() != Some(suppression::UNUSED_SUPPRESSION)
+                            || d.path == current_path
+                    })
                     .map(|d| self.diagnostic_to_lsp(d))
                     .collect();
 
-                LspEvalResult {
+                let result = LspEvalResult {
                     diagnostics,
                     ast: result.output.flatten(),
+                };
+
+                // A superseded parse may have stopped early, so only cache
+                // results of parses that ran to completion.
+                if cancel.load(Ordering::Relaxed) {
+                    return result;
                 }
+                let deps = self.fingerprint(
+                    self.transitive_deps(path)
+                        .into_iter()
+                        .chain([workspace_root.join("pcb.toml")]),
+                );
+                self.parse_cache.lock().unwrap().insert(
+                    uri.clone(),
+                    CachedParse {
+                        content_hash: hash,
+                        deps,
+                        result: result.clone(),
+                    },
+                );
+                result
             }
             _ => {
                 // For non-file URLs, return empty result
@@ -292,15 +958,34 @@ impl LspContext for LspEvalContext {
                         return Ok(Some(StringLiteralResult {
                             url: LspUrl::File(resolved),
                             location_finder: None,
+                            range: None,
                         }));
                     }
                 }
-                Ok(None)
+
+                // A `Library:Symbol` reference jumps to the symbol in its library
+                Ok(self.find_library_symbol(literal))
             }
             _ => Ok(None),
         }
     }
 
+    fn resolve_string_literal_at(
+        &self,
+        literal: &str,
+        span: ResolvedSpan,
+        current_file: &LspUrl,
+        workspace_root: Option<&Path>,
+    ) -> anyhow::Result<Option<StringLiteralResult>> {
+        // Keys of a `pins` mapping jump to the pin in the symbol of that call
+        if let LspUrl::File(current_path) = current_file {
+            if let Some(result) = self.find_pin_definition(literal, span, current_path) {
+                return Ok(Some(result));
+            }
+        }
+        self.resolve_string_literal(literal, current_file, workspace_root)
+    }
+
     fn get_load_contents(&self, uri: &LspUrl) -> anyhow::Result<Option<String>> {
         match uri {
             LspUrl::File(path) => {
@@ -384,6 +1069,59 @@ impl LspContext for LspEvalContext {
         }
     }
 
+    fn get_signature(
+        &self,
+        current_file: &LspUrl,
+        symbol: &str,
+    ) -> Option<Vec<SignatureParameter>> {
+        let LspUrl::File(path) = current_file else {
+            return None;
+        };
+        let info = self.inner.get_symbol_info(path, symbol)?;
+        let (SymbolKind::Module, Some(module_path)) = (info.kind, info.source_path) else {
+            return None;
+        };
+
+        // Signature help fires on nearly every keystroke, so only re-evaluate
+        // the module once it or something it loads has changed
+        if let Some(cached) = self.signatures.lock().unwrap().get(&module_path) {
+            if self.is_unchanged(&cached.deps) {
+                return Some(cached.signature.clone());
+            }
+        }
+
+        // Evaluate the module with empty inputs to learn what it accepts
+        let module_name = module_path.file_stem()?.to_string_lossy().into_owned();
+        let inputs = self
+            .inner
+            .child_context()
+            .set_load_resolver(create_standard_load_resolver(
+                self.file_provider.clone(),
+                &module_path,
+            ))
+            .introspect_module_typed(&module_path, &module_name)
+            .output?;
+
+        let mut signature = vec![SignatureParameter {
+            name: "name".to_string(),
+            label: "name: str".to_string(),
+            documentation: Some("Name of the module instance".to_string()),
+        }];
+        signature.extend(inputs.iter().map(module_parameter));
+
+        let deps = self.fingerprint(
+            std::iter::once(module_path.clone()).chain(self.transitive_deps(&module_path)),
+        );
+        self.signatures.lock().unwrap().insert(
+            module_path,
+            CachedSignature {
+                deps,
+                signature: signature.clone(),
+            },
+        );
+        Some(signature)
+    }
+
     fn is_eager(&self) -> bool {
         self.inner.is_eager()
     }
@@ -430,10 +1168,27 @@ impl LspContext for LspEvalContext {
         }
     }
 
+    fn get_custom_hover_for_identifier(
+        &self,
+        name: &str,
+        current_file: &LspUrl,
+    ) -> anyhow::Result<Option<Hover>> {
+        let LspUrl::File(path) = current_file else {
+            return Ok(None);
+        };
+        Ok(self.net_hovers(path).get(name).map(|text| Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: text.clone(),
+            }),
+            range: None,
+        }))
+    }
+
     fn handle_custom_request(
         &self,
         req: &server::Request,
-        _initialize_params: &lsp_types::InitializeParams,
+        initialize_params: &lsp_types::InitializeParams,
     ) -> Option<Response> {
         // Handle signature help requests
         if req.method == "textDocument/signatureHelp" {
@@ -516,35 +1271,58 @@ impl LspContext for LspEvalContext {
             }
         }
 
+        // Handle find-references requests for modules loaded across the workspace
+        if req.method == lsp_types::request::References::METHOD {
+            let result = serde_json::from_value::<lsp_types::ReferenceParams>(req.params.clone())
+                .map_err(|e| format!("Failed to parse params: {e}"))
+                .and_then(|params| {
+                    let uri = params.text_document_position.text_document.uri;
+                    let path = uri
+                        .to_file_path()
+                        .map_err(|_| format!("Invalid URI: {uri}"))?;
+                    let roots =
+                        workspace_roots(initialize_params, self.file_provider.as_ref(), &path);
+                    let mut locations = self
+                        .find_module_references(&path, &roots)
+                        .map_err(|e| format!("{e:#}"))?;
+                    if params.context.include_declaration {
+                        locations.insert(
+                            0,
+                            lsp_types::Location {
+                                uri,
+                                range: lsp_types::Range::default(),
+                            },
+                        );
+                    }
+                    Ok(locations)
+                });
+
+            return Some(match result {
+                Ok(locations) => Response {
+                    id: req.id.clone(),
+                    result: Some(serde_json::to_value(locations).unwrap()),
+                    error: None,
+                },
+                Err(message) => Response {
+                    id: req.id.clone(),
+                    result: None,
+                    error: Some(ResponseError {
+                        code: 0,
+                        message,
+                        data: None,
+                    }),
+                },
+            });
+        }
+
         // Handle viewer/getState requests
         if req.method == ViewerGetStateRequest::METHOD {
             match serde_json::from_value::<ViewerGetStateParams>(req.params.clone()) {
                 Ok(params) => {
                     let state_json: Option<JsonValue> = match &params.uri {
                         LspUrl::File(path_buf) => {
-                            // Get contents from memory or disk
-                            let maybe_contents = self.get_load_contents(&params.uri).ok().flatten();
-
                             // Evaluate the module
-                            let ctx = EvalContext::new()
-                                .set_file_provider(self.file_provider.clone())
-                                .set_load_resolver(create_standard_load_resolver(
-                                    self.file_provider.clone(),
-                                    path_buf,
-                                ));
-
-                            let eval_result = if let Some(contents) = maybe_contents {
-                                ctx.set_source_path(path_buf.clone())
-                                    .set_module_name("<root>".to_string())
-                                    .set_inputs(InputMap::new())
-                                    .set_source_contents(contents)
-                                    .eval()
-                            } else {
-                                ctx.set_source_path(path_buf.clone())
-                                    .set_module_name("<root>".to_string())
-                                    .set_inputs(InputMap::new())
-                                    .eval()
-                            };
+                            let eval_result = self.eval_root(path_buf);
 
                             eval_result.output.and_then(|fmv| {
                                 match fmv.sch_module.to_schematic() {
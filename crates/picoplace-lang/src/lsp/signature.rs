@@ -1,12 +1,14 @@
 use anyhow::Result;
-use lsp_types::{ParameterInformation, ParameterLabel, SignatureHelp, SignatureInformation};
-// MarkupContent etc not used currently but left for future expansion. Removed to suppress warnings.
+use lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, SignatureHelp,
+    SignatureInformation,
+};
 use starlark::codemap::{CodeMap, ResolvedPos, ResolvedSpan};
 use starlark::syntax::ast::*;
 use starlark::syntax::AstModule;
 use starlark_syntax::syntax::module::AstModuleFields;
 
-use picoplace_starlark_lsp::server::{LspContext, LspUrl};
+use picoplace_starlark_lsp::server::{LspContext, LspUrl, SignatureParameter};
 
 use std::collections::HashMap;
 
@@ -218,10 +220,14 @@ pub fn signature_help<T: LspContext>(
     let mut params = find_def_params(ast.statement(), &call.function_name).unwrap_or_default();
 
     // ------------------------------------------------------------------
-    // Fast path: if the Context already knows the parameter list (e.g. for a
+    // Fast path: if the Context already knows the signature (e.g. for a
     // ModuleLoader value) use that first.
     // ------------------------------------------------------------------
-    // Note: We removed the get_signature call since it's not part of LspContext trait
+    if params.is_empty() {
+        if let Some(signature) = ctx.get_signature(current_uri, &call.function_name) {
+            return render_signature_help(&call.function_name, &signature, &call.current_argument);
+        }
+    }
 
     // ------------------------------------------------------------------
     // First try Context symbol index / built-ins.
@@ -273,19 +279,46 @@ pub fn signature_help<T: LspContext>(
         }
     }
 
-    let label = format!("{}({})", call.function_name, params.join(", "));
+    let signature: Vec<SignatureParameter> = params
+        .into_iter()
+        .map(|name| SignatureParameter {
+            label: name.clone(),
+            name,
+            documentation: None,
+        })
+        .collect();
+    render_signature_help(&call.function_name, &signature, &call.current_argument)
+}
+
+/// Build the [`SignatureHelp`] for a call to `function_name`, highlighting the
+/// parameter that `current_argument` fills.
+fn render_signature_help(
+    function_name: &str,
+    signature: &[SignatureParameter],
+    current_argument: &CallArgument,
+) -> SignatureHelp {
+    let labels: Vec<&str> = signature.iter().map(|p| p.label.as_str()).collect();
+    let label = format!("{}({})", function_name, labels.join(", "));
 
-    let parameters: Vec<ParameterInformation> = params
+    let parameters: Vec<ParameterInformation> = signature
         .iter()
         .map(|p| ParameterInformation {
-            label: ParameterLabel::Simple(p.clone()),
-            documentation: None,
+            label: ParameterLabel::Simple(p.label.clone()),
+            documentation: p.documentation.clone().map(|doc| {
+                Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: doc,
+                })
+            }),
         })
         .collect();
 
-    let active_parameter = match &call.current_argument {
+    let active_parameter = match current_argument {
         CallArgument::Positional(i) => Some(*i as u32),
-        CallArgument::Named(name) => params.iter().position(|p| p == name).map(|idx| idx as u32),
+        CallArgument::Named(name) => signature
+            .iter()
+            .position(|p| &p.name == name)
+            .map(|idx| idx as u32),
         CallArgument::None => None,
     };
 
@@ -15,10 +15,14 @@
  * limitations under the License.
  */
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 
 use globset::Glob;
 use globset::GlobMatcher;
+use picoplace_core::Diagnostic;
+use starlark::codemap::{ResolvedPos, ResolvedSpan};
+use starlark::errors::EvalSeverity;
 
 #[derive(Debug, Clone)]
 pub struct GlobLintSuppression {
@@ -39,11 +43,14 @@ impl GlobLintSuppression {
             ("*", rule)
         };
 
-        let rules = rules.split(',').map(|s| s.trim().to_owned()).collect();
+        Self::new(pattern, rules.split(',').map(|s| s.trim().to_owned()))
+    }
 
+    /// Suppress `rules` in files matching the glob `pattern`.
+    pub fn new(pattern: &str, rules: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
         Ok(Self {
             pattern: Glob::new(pattern)?.compile_matcher(),
-            rules,
+            rules: rules.into_iter().collect(),
         })
     }
 
@@ -56,6 +63,256 @@ impl GlobLintSuppression {
     }
 }
 
+pub use picoplace_core::codes::UNUSED_SUPPRESSION;
+
+/// A `# pcb: allow(<code>, ...)` comment in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuppressionComment {
+    /// 0-based line of the comment.
+    pub line: usize,
+    /// 0-based column of the `#` starting the comment.
+    pub column: usize,
+    /// Whether the comment follows code on the same line. Trailing comments
+    /// apply to their own line, standalone ones to the line below.
+    pub trailing: bool,
+    /// Diagnostic codes to suppress.
+    pub codes: Vec<String>,
+}
+
+impl SuppressionComment {
+    /// The 0-based line whose diagnostics this comment suppresses.
+    pub fn target_line(&self) -> usize {
+        if self.trailing {
+            self.line
+        } else {
+            self.line + 1
+        }
+    }
+}
+
+/// Find every `# pcb: allow(<code>, ...)` comment in `source`.
+///
+/// Comments are tokenized with Starlark's string literal rules, so a `#`
+/// inside a string (quoted, triple-quoted or raw) never starts one.
+pub fn parse_suppression_comments(source: &str) -> Vec<SuppressionComment> {
+    let bytes = source.as_bytes();
+    let mut comments = Vec::new();
+    // Closing delimiter of the string literal being skipped, if any.
+    let mut string: Option<&[u8]> = None;
+    let mut line = 0;
+    let mut line_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if rest[0] == b'\n' {
+            // Single-quoted strings cannot span lines; let the parser report it.
+            if string.is_some_and(|quote| quote.len() == 1) {
+                string = None;
+            }
+            line += 1;
+            line_start = i + 1;
+            i += 1;
+            continue;
+        }
+
+        if let Some(quote) = string {
+            if rest[0] == b'\\' {
+                // Escapes (even in raw strings) keep the next character,
+                // including a newline, in the string.
+                if rest.get(1) == Some(&b'\n') {
+                    line += 1;
+                    line_start = i + 2;
+                }
+                i += 2;
+            } else if rest.starts_with(quote) {
+                string = None;
+                i += quote.len();
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+
+        match rest[0] {
+            b'"' | b'\'' => {
+                let quote = if rest.starts_with(b"\"\"\"") || rest.starts_with(b"'''") {
+                    &rest[..3]
+                } else {
+                    &rest[..1]
+                };
+                string = Some(quote);
+                i += quote.len();
+            }
+            b'#' => {
+                let end = rest
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |n| i + n);
+                if let Some(codes) = parse_allow(&source[i + 1..end]) {
+                    comments.push(SuppressionComment {
+                        line,
+                        column: i - line_start,
+                        trailing: !source[line_start..i].trim().is_empty(),
+                        codes,
+                    });
+                }
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+
+    comments
+}
+
+/// The codes of a `pcb: allow(<code>, ...)` comment body (after the `#`).
+fn parse_allow(comment: &str) -> Option<Vec<String>> {
+    let (codes, _) = comment
+        .trim_start()
+        .strip_prefix("pcb:")?
+        .trim_start()
+        .strip_prefix("allow(")?
+        .split_once(')')?;
+    Some(
+        codes
+            .split(',')
+            .map(|code| code.trim().to_owned())
+            .filter(|code| !code.is_empty())
+            .collect(),
+    )
+}
+
+/// Drop the diagnostics silenced by `# pcb: allow(<code>)` comments in `files`
+/// and add an [`UNUSED_SUPPRESSION`] warning for every code that silenced
+/// nothing. `read_source` returns the current contents of a file, which may
+/// differ from disk for documents open in an editor.
+///
+/// A diagnostic is dropped when any message in its chain has a code and a
+/// span on a line that a comment allows that code for, so codes on wrappers
+/// (e.g. [`picoplace_core::codes::MODULE_LOAD_FAILED`] at a `load()`) can be
+/// silenced as well as the ones where the problem was reported.
+pub fn apply_suppression_comments(
+    diagnostics: Vec<Diagnostic>,
+    files: &[PathBuf],
+    read_source: impl Fn(&Path) -> Option<String>,
+) -> Vec<Diagnostic> {
+    let suppressions: Vec<(String, SuppressionComment)> = files
+        .iter()
+        .filter_map(|file| {
+            let source = read_source(file)?;
+            let path = file.to_string_lossy().into_owned();
+            Some(
+                parse_suppression_comments(&source)
+                    .into_iter()
+                    .map(move |comment| (path.clone(), comment)),
+            )
+        })
+        .flatten()
+        .collect();
+    let mut used: HashSet<(usize, String)> = HashSet::new();
+
+    let mut kept: Vec<Diagnostic> = diagnostics
+        .into_iter()
+        .filter(|diag| {
+            let matched = diag.flatten().into_iter().find_map(|message| {
+                let (Some(code), Some(span)) = (&message.code, &message.span) else {
+                    return None;
+                };
+                suppressions
+                    .iter()
+                    .position(|(path, comment)| {
+                        *path == message.path
+                            && comment.target_line() == span.begin.line
+                            && comment.codes.contains(code)
+                    })
+                    .map(|index| (index, code.clone()))
+            });
+            match matched {
+                Some(key) => {
+                    used.insert(key);
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect();
+
+    for (index, (path, comment)) in suppressions.iter().enumerate() {
+        for code in &comment.codes {
+            if used.contains(&(index, code.clone())) {
+                continue;
+            }
+            let position = ResolvedPos {
+                line: comment.line,
+                column: comment.column,
+            };
+            kept.push(Diagnostic {
+                path: path.clone(),
+                span: Some(ResolvedSpan {
+                    begin: position,
+                    end: position,
+                }),
+                severity: EvalSeverity::Warning,
+                body: format!("Unused suppression: no `{code}` diagnostic to allow here"),
+                call_stack: None,
+                child: None,
+                code: Some(UNUSED_SUPPRESSION.to_owned()),
+            });
+        }
+    }
+
+    kept
+}
+
+/// Drop the diagnostics hidden by the `[lint].ignore` table of the workspace
+/// `pcb.toml` (see [`picoplace_core::workspace::read_lint_ignores`]).
+///
+/// A diagnostic is hidden when, for any message in its chain, the file
+/// relative to `workspace_root` matches one of the globs and the code or
+/// message is listed for that glob.
+pub fn apply_config_suppressions(
+    diagnostics: Vec<Diagnostic>,
+    ignores: &BTreeMap<String, Vec<String>>,
+    workspace_root: &Path,
+) -> Vec<Diagnostic> {
+    let rules: Vec<GlobLintSuppression> = ignores
+        .iter()
+        .filter_map(|(pattern, entries)| {
+            match GlobLintSuppression::new(pattern, entries.iter().cloned()) {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    log::warn!("Invalid [lint].ignore pattern '{pattern}': {e}");
+                    None
+                }
+            }
+        })
+        .collect();
+    if rules.is_empty() {
+        return diagnostics;
+    }
+
+    diagnostics
+        .into_iter()
+        .filter(|diag| {
+            !diag.flatten().into_iter().any(|message| {
+                let path = Path::new(&message.path);
+                let file = path
+                    .strip_prefix(workspace_root)
+                    .unwrap_or(path)
+                    .to_string_lossy();
+                rules.iter().any(|rule| {
+                    rule.is_suppressed(&file, &message.body)
+                        || message
+                            .code
+                            .as_deref()
+                            .is_some_and(|code| rule.is_suppressed(&file, code))
+                })
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,4 +333,67 @@ mod tests {
         assert!(suppression.is_suppressed("foo/bar.bzl", "rule1"));
         assert!(!suppression.is_suppressed("foo/bar.bzl", "rule2"));
     }
+
+    #[test]
+    fn test_parse_suppression_comments() {
+        let source = r##"
+# pcb: allow(W9003)
+warn("a", code = "W9003")
+warn("b", code = "x")  # pcb: allow(x, y)
+s = "#"  #pcb:allow( z )
+# unrelated comment
+"##;
+
+        let comments = parse_suppression_comments(source);
+        assert_eq!(
+            comments,
+            vec![
+                SuppressionComment {
+                    line: 1,
+                    column: 0,
+                    trailing: false,
+                    codes: vec!["W9003".to_owned()],
+                },
+                SuppressionComment {
+                    line: 3,
+                    column: 23,
+                    trailing: true,
+                    codes: vec!["x".to_owned(), "y".to_owned()],
+                },
+                SuppressionComment {
+                    line: 4,
+                    column: 9,
+                    trailing: true,
+                    codes: vec!["z".to_owned()],
+                },
+            ]
+        );
+        assert_eq!(comments[0].target_line(), 2);
+        assert_eq!(comments[1].target_line(), 3);
+    }
+
+    #[test]
+    fn test_suppression_comments_in_strings_are_ignored() {
+        let source = r##"
+a = "# pcb: allow(A)"
+b = 'it\'s # pcb: allow(B)'
+c = """
+# pcb: allow(C)
+"""
+d = r"\" # pcb: allow(D)"
+e = "\
+# pcb: allow(E)"
+f = 1  # pcb: allow(F)
+"##;
+
+        assert_eq!(
+            parse_suppression_comments(source),
+            vec![SuppressionComment {
+                line: 9,
+                column: 7,
+                trailing: true,
+                codes: vec!["F".to_owned()],
+            }]
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use picoplace_core::WithDiagnostics;
+use picoplace_core::{DefaultFileProvider, FileProvider, FileProviderError, WithDiagnostics};
 
 /// Utility to build an isolated Starlark project for integration tests.
 ///
@@ -188,3 +189,41 @@ macro_rules! star_snapshot {
         });
     }};
 }
+
+/// File provider that counts how often paths are resolved, which only
+/// happens while a document is analyzed or evaluated
+#[allow(dead_code)]
+#[derive(Default)]
+pub struct CountingFileProvider {
+    resolutions: AtomicUsize,
+}
+
+#[allow(dead_code)]
+impl CountingFileProvider {
+    pub fn resolutions(&self) -> usize {
+        self.resolutions.load(Ordering::SeqCst)
+    }
+}
+
+impl FileProvider for CountingFileProvider {
+    fn read_file(&self, path: &Path) -> Result<String, FileProviderError> {
+        DefaultFileProvider.read_file(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        DefaultFileProvider.exists(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        DefaultFileProvider.is_directory(path)
+    }
+
+    fn list_directory(&self, path: &Path) -> Result<Vec<PathBuf>, FileProviderError> {
+        DefaultFileProvider.list_directory(path)
+    }
+
+    fn canonicalize(&self, path: &Path) -> Result<PathBuf, FileProviderError> {
+        self.resolutions.fetch_add(1, Ordering::SeqCst);
+        DefaultFileProvider.canonicalize(path)
+    }
+}
@@ -0,0 +1,51 @@
+use picoplace_lang::{diagnostics_to_json, Diagnostic, EvalSeverity};
+use serde_json::json;
+use starlark::codemap::{ResolvedPos, ResolvedSpan};
+
+fn diagnostic(path: &str, line: usize, body: &str) -> Diagnostic {
+    Diagnostic {
+        path: path.to_string(),
+        span: Some(ResolvedSpan {
+            begin: ResolvedPos { line, column: 4 },
+            end: ResolvedPos { line, column: 12 },
+        }),
+        severity: EvalSeverity::Error,
+        body: body.to_string(),
+        call_stack: None,
+        child: None,
+        code: None,
+    }
+}
+
+#[test]
+fn test_nested_diagnostic_json() {
+    let nested = diagnostic("/top.zen", 2, "Error loading module `sub.zen`")
+        .with_code("E0003")
+        .with_child(Diagnostic {
+            span: None,
+            severity: EvalSeverity::Warning,
+            ..diagnostic("/sub.zen", 0, "Variable `x` not found")
+        });
+
+    let json: serde_json::Value = serde_json::from_str(&diagnostics_to_json(&[nested])).unwrap();
+    assert_eq!(
+        json,
+        json!([{
+            "path": "/top.zen",
+            "range": {
+                "start": {"line": 2, "character": 4},
+                "end": {"line": 2, "character": 12},
+            },
+            "severity": "error",
+            "message": "Error loading module `sub.zen`",
+            "code": "E0003",
+            "children": [{
+                "path": "/sub.zen",
+                "range": null,
+                "severity": "warning",
+                "message": "Variable `x` not found",
+                "code": null,
+            }],
+        }])
+    );
+}
@@ -0,0 +1,150 @@
+mod common;
+use common::TestProject;
+
+use picoplace_lang::lsp::LspEvalContext;
+use picoplace_starlark_lsp::server::{LspContext, LspUrl};
+use starlark::codemap::{ResolvedPos, ResolvedSpan};
+
+const LIBRARY: &str = r#"(kicad_symbol_lib
+  (version 20211014)
+  (symbol "C"
+    (property "Reference" "C")
+  )
+  (symbol "R"
+    (property "Reference" "R")
+  )
+)
+"#;
+
+#[test]
+fn test_definition_of_symbol_file() {
+    let env = TestProject::new();
+
+    let library = env.add_file("symbols/Device.kicad_sym", LIBRARY);
+    let top = env.add_file(
+        "top.zen",
+        "Symbol(library = \"symbols/Device.kicad_sym\", name = \"R\")\n",
+    );
+
+    let ctx = LspEvalContext::default();
+    let result = ctx
+        .resolve_string_literal("symbols/Device.kicad_sym", &LspUrl::File(top), None)
+        .unwrap()
+        .expect("symbol file resolves");
+
+    assert_eq!(result.url, LspUrl::File(library));
+}
+
+#[test]
+fn test_definition_of_library_reference() {
+    let env = TestProject::new();
+
+    let library = env.add_file("kicad/symbols/Device.kicad_sym", LIBRARY);
+    let top = env.add_file("top.zen", "Symbol(library = \"Device:R\")\n");
+
+    let ctx = LspEvalContext::default().set_symbol_dirs(vec![env.root().join("kicad/symbols")]);
+    let result = ctx
+        .resolve_string_literal("Device:R", &LspUrl::File(top.clone()), None)
+        .unwrap()
+        .expect("library reference resolves");
+
+    assert_eq!(result.url, LspUrl::File(library));
+    // Points at `(symbol "R"`, not the first symbol in the file
+    assert_eq!(result.range.unwrap().start.line, 5);
+
+    let missing = ctx
+        .resolve_string_literal("Device:Q", &LspUrl::File(top), None)
+        .unwrap()
+        .expect("library still resolves without the symbol");
+    assert!(missing.range.is_none());
+}
+
+/// Span of the `nth` occurrence of the string literal `literal` in `source`
+fn literal_span(source: &str, literal: &str, nth: usize) -> ResolvedSpan {
+    let quoted = format!("\"{literal}\"");
+    let (line, column) = source
+        .lines()
+        .enumerate()
+        .flat_map(|(line, text)| {
+            text.match_indices(&quoted)
+                .map(move |(column, _)| (line, column))
+        })
+        .nth(nth)
+        .expect("literal occurs in source");
+    ResolvedSpan {
+        begin: ResolvedPos { line, column },
+        end: ResolvedPos {
+            line,
+            column: column + quoted.len(),
+        },
+    }
+}
+
+const TWO_SYMBOLS: &str = r#"(kicad_symbol_lib
+  (symbol "X"
+    (symbol "X_1_1"
+      (pin input line (at 0 0 0) (length 2.54) (name "IN") (number "1"))
+    )
+  )
+  (symbol "Y"
+    (symbol "Y_1_1"
+      (pin input line (at 0 0 0) (length 2.54) (name "IN") (number "1"))
+    )
+  )
+)
+"#;
+
+const ONE_SYMBOL: &str = r#"(kicad_symbol_lib
+  (symbol "P"
+    (symbol "P_1_1"
+      (pin output line (at 0 0 0) (length 2.54) (name "OUT") (number "1"))
+    )
+  )
+)
+"#;
+
+#[test]
+fn test_definition_of_pin_key() {
+    let env = TestProject::new();
+
+    let two = env.add_file("symbols/Two.kicad_sym", TWO_SYMBOLS);
+    let one = env.add_file("symbols/One.kicad_sym", ONE_SYMBOL);
+    let source = r#"Part = load_component("symbols/One.kicad_sym")
+
+label = "IN"
+
+Component(
+    name = "U1",
+    footprint = "SMD:0805",
+    symbol = Symbol(library = "symbols/Two.kicad_sym", name = "Y"),
+    pins = {"IN": Net("IN")},
+)
+
+Part(name = "P1", pins = {"OUT": Net("OUT")})
+"#;
+    let top = env.add_file("top.zen", source);
+    let ctx = LspEvalContext::default();
+    let resolve = |literal: &str, nth: usize| {
+        ctx.resolve_string_literal_at(
+            literal,
+            literal_span(source, literal, nth),
+            &LspUrl::File(top.clone()),
+            None,
+        )
+        .unwrap()
+    };
+
+    // The pin of the symbol named in the same call, not the first in the library
+    let pin = resolve("IN", 1).expect("pin of Component resolves");
+    assert_eq!(pin.url, LspUrl::File(two));
+    assert_eq!(pin.range.unwrap().start.line, 8);
+
+    let pin = resolve("OUT", 0).expect("pin of load_component factory resolves");
+    assert_eq!(pin.url, LspUrl::File(one));
+    assert_eq!(pin.range.unwrap().start.line, 3);
+
+    // Strings outside of a `pins` mapping are not pins, even if a symbol has
+    // a pin of that name
+    assert!(resolve("IN", 0).is_none());
+    assert!(resolve("IN", 2).is_none());
+}
@@ -0,0 +1,87 @@
+mod common;
+use common::TestProject;
+
+use lsp_types::HoverContents;
+use picoplace_lang::lsp::LspEvalContext;
+use picoplace_starlark_lsp::server::{LspContext, LspUrl};
+
+fn hover_text(ctx: &LspEvalContext, name: &str, file: &LspUrl) -> Option<String> {
+    let hover = ctx.get_custom_hover_for_identifier(name, file).unwrap()?;
+    match hover.contents {
+        HoverContents::Markup(markup) => Some(markup.value),
+        other => panic!("unexpected hover contents: {other:?}"),
+    }
+}
+
+#[test]
+fn test_net_hover_connectivity() {
+    let env = TestProject::new();
+    let top = env.add_file(
+        "top.zen",
+        r#"
+gnd = Net("GND")
+vcc = Net("VCC")
+floating = Net("NC")
+not_a_net = 3
+
+def passive(name, prefix, a, b):
+    Component(
+        name = name,
+        prefix = prefix,
+        footprint = "SMD:0402",
+        symbol = Symbol(definition = [("1", ["1"]), ("2", ["2"])]),
+        pins = {"1": a, "2": b},
+    )
+
+passive("R_PULLUP", "R", vcc, gnd)
+passive("C_BULK", "C", vcc, gnd)
+passive("C_BYPASS", "C", vcc, gnd)
+"#,
+    );
+
+    let ctx = LspEvalContext::default();
+    let uri = LspUrl::File(top);
+
+    assert_eq!(
+        hover_text(&ctx, "gnd", &uri).as_deref(),
+        Some("`GND` — connects 3 pins across C1, C2, R1")
+    );
+    // Served from the cache the second time
+    assert_eq!(hover_text(&ctx, "gnd", &uri), hover_text(&ctx, "gnd", &uri));
+
+    assert_eq!(hover_text(&ctx, "floating", &uri), None);
+    assert_eq!(hover_text(&ctx, "not_a_net", &uri), None);
+}
+
+#[test]
+fn test_net_hover_uses_build_refdes() {
+    let env = TestProject::new();
+    env.add_file("pcb.toml", "[refdes]\ncrystal = \"Y\"\n");
+    let top = env.add_file(
+        "top.zen",
+        r#"
+clk = Net("CLK")
+
+def part(name, kind, pins, properties = {}):
+    Component(
+        name = name,
+        type = kind,
+        footprint = "SMD:0402",
+        symbol = Symbol(definition = [("1", ["1"])]),
+        pins = {"1": pins},
+        properties = properties,
+    )
+
+part("XTAL", "crystal", clk)
+part("R_SERIES", "resistor", clk, properties = {"refdes": "R7"})
+"#,
+    );
+
+    let ctx = LspEvalContext::default();
+    let uri = LspUrl::File(top);
+
+    assert_eq!(
+        hover_text(&ctx, "clk", &uri).as_deref(),
+        Some("`CLK` — connects 2 pins across R7, Y1")
+    );
+}
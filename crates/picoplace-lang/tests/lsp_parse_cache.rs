@@ -0,0 +1,79 @@
+mod common;
+use common::{CountingFileProvider, TestProject};
+
+use picoplace_lang::lsp::LspEvalContext;
+use picoplace_starlark_lsp::server::{LspContext, LspUrl};
+use std::sync::Arc;
+
+#[test]
+fn test_parse_cached_until_contents_change() {
+    let env = TestProject::new();
+    env.add_file("lib.zen", "VALUE = 1\n");
+    let source = "load(\"lib.zen\", \"VALUE\")\nx = VALUE\n";
+    let top = env.add_file("top.zen", source);
+
+    let provider = Arc::new(CountingFileProvider::default());
+    let ctx = LspEvalContext::default().set_file_provider(provider.clone());
+    let uri = LspUrl::File(top);
+
+    let first = ctx.parse_file_with_contents(&uri, source.to_string());
+    let resolutions = provider.resolutions();
+    assert!(resolutions > 0);
+    assert!(first.ast.is_some());
+
+    // Identical contents are served from the cache without re-analyzing
+    let second = ctx.parse_file_with_contents(&uri, source.to_string());
+    assert_eq!(provider.resolutions(), resolutions);
+    assert_eq!(second.diagnostics, first.diagnostics);
+    assert!(second.ast.is_some());
+
+    // Changed contents invalidate the cached result
+    let third = ctx.parse_file_with_contents(&uri, format!("{source}y = VALUE\n"));
+    assert!(provider.resolutions() > resolutions);
+    assert!(third.ast.is_some());
+}
+
+#[test]
+fn test_parse_cache_invalidated_by_dependency_change() {
+    let env = TestProject::new();
+    env.add_file("lib.zen", "VALUE = 1\n");
+    let source = "load(\"lib.zen\", \"VALUE\")\nx = VALUE\n";
+    let top = env.add_file("top.zen", source);
+
+    let ctx = LspEvalContext::default();
+    let uri = LspUrl::File(top);
+    assert!(ctx
+        .parse_file_with_contents(&uri, source.to_string())
+        .diagnostics
+        .is_empty());
+
+    // An edit outside the editor (e.g. a git checkout) is picked up
+    env.add_file("lib.zen", "OTHER = 1\n");
+    assert!(!ctx
+        .parse_file_with_contents(&uri, source.to_string())
+        .diagnostics
+        .is_empty());
+}
+
+#[test]
+fn test_parse_cache_keeps_every_document() {
+    let env = TestProject::new();
+    env.add_file("lib.zen", "VALUE = 1\n");
+    let a_source = "load(\"lib.zen\", \"VALUE\")\na = VALUE\n";
+    let b_source = "load(\"lib.zen\", \"VALUE\")\nb = VALUE\n";
+    let a = LspUrl::File(env.add_file("a.zen", a_source));
+    let b = LspUrl::File(env.add_file("b.zen", b_source));
+
+    let provider = Arc::new(CountingFileProvider::default());
+    let ctx = LspEvalContext::default().set_file_provider(provider.clone());
+
+    ctx.parse_file_with_contents(&a, a_source.to_string());
+    ctx.parse_file_with_contents(&b, b_source.to_string());
+    let resolutions = provider.resolutions();
+    assert!(resolutions > 0);
+
+    // Parsing one document does not evict the other
+    ctx.parse_file_with_contents(&a, a_source.to_string());
+    ctx.parse_file_with_contents(&b, b_source.to_string());
+    assert_eq!(provider.resolutions(), resolutions);
+}
@@ -0,0 +1,54 @@
+mod common;
+use common::TestProject;
+
+use picoplace_lang::lsp::LspEvalContext;
+
+#[test]
+fn test_find_module_references() {
+    let env = TestProject::new();
+
+    let shared = env.add_file("modules/shared.zen", "def helper():\n    pass\n");
+    // Remote specs are never fetched, and never reference a workspace file
+    env.add_file(
+        "top.zen",
+        "load(\"modules/shared.zen\", \"helper\")\n\nhelper()\n\nload(\"@github/acme/parts/modules/shared.zen\", remote = \"helper\")\n",
+    );
+    env.add_file(
+        "boards/board.zen",
+        "# Uses the shared module\nload(\"../modules/shared.zen\", h = \"helper\")\n",
+    );
+    env.add_file(
+        "boards/variant.zen",
+        "Other = Module(\"../modules/other.zen\")\n\ndef make():\n    return Module(\"../modules/shared.zen\")\n",
+    );
+    env.add_file("modules/other.zen", "load(\"other_dep.zen\", \"x\")\n");
+    env.add_file("modules/other_dep.zen", "x = 1\n");
+
+    let ctx = LspEvalContext::default();
+    let locations = ctx
+        .find_module_references(&shared, &[env.root().to_path_buf()])
+        .unwrap();
+
+    let found: Vec<(String, u32)> = locations
+        .iter()
+        .map(|location| {
+            let path = location.uri.to_file_path().unwrap();
+            let rel = path.strip_prefix(env.root()).unwrap();
+            (
+                rel.to_string_lossy().replace('\\', "/"),
+                location.range.start.line,
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("boards/board.zen".to_string(), 1),
+            ("boards/variant.zen".to_string(), 3),
+            ("top.zen".to_string(), 0),
+        ]
+    );
+    // `Module()` references point at the path literal
+    assert_eq!(locations[1].range.start.character, 18);
+    assert_eq!(locations[2].range.start.character, 0);
+}
@@ -0,0 +1,97 @@
+mod common;
+use common::{CountingFileProvider, TestProject};
+
+use lsp_types::ParameterLabel;
+use picoplace_lang::lsp::signature::signature_help;
+use picoplace_lang::lsp::LspEvalContext;
+use picoplace_starlark_lsp::server::{LspContext, LspUrl};
+use std::sync::Arc;
+
+#[test]
+fn test_module_signature_help() {
+    let env = TestProject::new();
+
+    env.add_file(
+        "sub.zen",
+        r#"
+package = config("package", str, default = "0402", help = "Footprint size")
+VCC = io("VCC", Net)
+"#,
+    );
+    let source = "Sub = Module(\"sub.zen\")\nSub(name = \"S1\", VCC = Net(\"VCC\"))\n";
+    let top = env.add_file("top.zen", source);
+
+    let ctx = LspEvalContext::default();
+    let uri = LspUrl::File(top);
+    let ast = ctx
+        .parse_file_with_contents(&uri, source.to_string())
+        .ast
+        .expect("top.zen parses");
+
+    // Cursor on the `VCC = ...` argument
+    let help = signature_help(&ast, 1, 18, &ctx, &uri);
+    let signature = &help.signatures[0];
+    let labels: Vec<&str> = signature
+        .parameters
+        .iter()
+        .flatten()
+        .map(|param| match &param.label {
+            ParameterLabel::Simple(label) => label.as_str(),
+            ParameterLabel::LabelOffsets(_) => unreachable!(),
+        })
+        .collect();
+
+    assert_eq!(labels[0], "name: str");
+    assert!(labels.contains(&"package: str = \"0402\""), "{labels:?}");
+    assert!(labels.contains(&"VCC: Net"), "{labels:?}");
+
+    let active = help.active_parameter.expect("VCC is an input") as usize;
+    assert_eq!(labels[active], "VCC: Net");
+}
+
+#[test]
+fn test_module_signature_cached_until_module_changes() {
+    let env = TestProject::new();
+    env.add_file("lib.zen", "DEFAULT_PACKAGE = \"0402\"\n");
+    env.add_file(
+        "sub.zen",
+        r#"
+load("lib.zen", "DEFAULT_PACKAGE")
+package = config("package", str, default = DEFAULT_PACKAGE)
+"#,
+    );
+    let source = "Sub = Module(\"sub.zen\")\nSub(name = \"S1\")\n";
+    let top = env.add_file("top.zen", source);
+
+    let provider = Arc::new(CountingFileProvider::default());
+    let ctx = LspEvalContext::default().set_file_provider(provider.clone());
+    let uri = LspUrl::File(top);
+    ctx.parse_file_with_contents(&uri, source.to_string());
+
+    let names = |signature: Vec<picoplace_starlark_lsp::server::SignatureParameter>| {
+        signature
+            .into_iter()
+            .map(|param| param.name)
+            .collect::<Vec<_>>()
+    };
+    let first = ctx.get_signature(&uri, "Sub").expect("Sub is a module");
+    let resolutions = provider.resolutions();
+
+    // Asking again without any edits does not re-evaluate the module
+    let second = ctx.get_signature(&uri, "Sub").expect("Sub is a module");
+    assert_eq!(provider.resolutions(), resolutions);
+    assert_eq!(names(second), names(first));
+
+    // Editing the module is picked up
+    env.add_file(
+        "sub.zen",
+        r#"
+load("lib.zen", "DEFAULT_PACKAGE")
+package = config("package", str, default = DEFAULT_PACKAGE)
+VCC = io("VCC", Net)
+"#,
+    );
+    let third = ctx.get_signature(&uri, "Sub").expect("Sub is a module");
+    assert!(provider.resolutions() > resolutions);
+    assert!(names(third).contains(&"VCC".to_string()));
+}
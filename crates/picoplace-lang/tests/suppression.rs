@@ -0,0 +1,172 @@
+mod common;
+use common::TestProject;
+
+use picoplace_lang::lsp::LspEvalContext;
+use picoplace_lang::suppression::UNUSED_SUPPRESSION;
+use picoplace_starlark_lsp::server::{LspContext, LspUrl};
+
+#[test]
+fn test_suppression_comments() {
+    let env = TestProject::new();
+
+    let top = env.add_file(
+        "test.zen",
+        r#"
+# pcb: allow(W9001)
+warn("suppressed on the line above", code = "W9001")
+warn("suppressed on the same line", code = "W9001")  # pcb: allow(W9001)
+warn("not suppressed", code = "W9001")
+
+# pcb: allow(W9002)
+warn("wrong code", code = "W9001")
+"#,
+    );
+
+    let result = picoplace_lang::run(&top);
+    assert!(!result.has_errors());
+
+    let mut warnings: Vec<(&str, Option<&str>, usize)> = result
+        .warnings()
+        .map(|d| {
+            (
+                d.body.as_str(),
+                d.code.as_deref(),
+                d.span.unwrap().begin.line,
+            )
+        })
+        .collect();
+    warnings.sort_by_key(|(_, _, line)| *line);
+
+    assert_eq!(
+        warnings,
+        vec![
+            ("not suppressed", Some("W9001"), 4),
+            (
+                "Unused suppression: no `W9002` diagnostic to allow here",
+                Some(UNUSED_SUPPRESSION),
+                6
+            ),
+            ("wrong code", Some("W9001"), 7),
+        ]
+    );
+}
+
+#[test]
+fn test_suppression_comments_in_lsp() {
+    let env = TestProject::new();
+    let top = env.add_file("top.zen", "");
+
+    // The unsaved editor buffer is what counts, not the file on disk, and
+    // comment-like text inside a string is not a comment
+    let source = r##"
+# pcb: allow(W9001)
+warn("suppressed", code = "W9001")
+s = """
+# pcb: allow(W9001)
+"""; warn("not suppressed", code = "W9001")
+"##;
+    let result =
+        LspEvalContext::default().parse_file_with_contents(&LspUrl::File(top), source.to_string());
+
+    let messages: Vec<&str> = result
+        .diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(messages, ["not suppressed"]);
+}
+
+#[test]
+fn test_suppression_comment_matches_wrapper_code() {
+    let env = TestProject::new();
+
+    env.add_file("sub.zen", "warn(\"noisy submodule\")\n");
+    let top = env.add_file(
+        "top.zen",
+        r#"
+Sub = Module("sub.zen")
+
+# pcb: allow(E0006)
+Sub(name = "S1")
+Sub(name = "S2")
+"#,
+    );
+
+    let result = picoplace_lang::run(&top);
+
+    // Only the instance without a comment reports its wrapped warning
+    let remaining: Vec<(Option<&str>, usize)> = result
+        .diagnostics
+        .iter()
+        .map(|d| (d.outermost_code(), d.span.unwrap().begin.line))
+        .collect();
+    assert_eq!(remaining, vec![(Some("E0006"), 5)]);
+}
+
+#[test]
+fn test_pcb_toml_lint_ignore() {
+    let env = TestProject::new();
+
+    env.add_file(
+        "pcb.toml",
+        r#"
+[lint.ignore]
+"vendor/*.zen" = ["W9001"]
+"*.zen" = ["known benign warning"]
+"#,
+    );
+    env.add_file(
+        "vendor/part.zen",
+        r#"
+warn("vendored footprint", code = "W9001")
+warn("vendored part without MPN", code = "W9002")
+"#,
+    );
+    let top = env.add_file(
+        "top.zen",
+        r#"
+Part = Module("vendor/part.zen")
+Part(name = "P1")
+
+warn("known benign warning")
+warn("local footprint", code = "W9001")
+"#,
+    );
+
+    let result = picoplace_lang::run(&top);
+    assert!(!result.has_errors());
+
+    let mut warnings: Vec<&str> = result
+        .warnings()
+        .map(|d| d.flatten().last().unwrap().body.as_str())
+        .collect();
+    warnings.sort();
+
+    assert_eq!(warnings, ["local footprint", "vendored part without MPN"]);
+}
+
+#[test]
+fn test_pcb_toml_lint_ignore_matches_wrapper_code() {
+    let env = TestProject::new();
+
+    env.add_file(
+        "pcb.toml",
+        r#"
+[lint.ignore]
+"top.zen" = ["E0006"]
+"#,
+    );
+    env.add_file("sub.zen", "warn(\"noisy submodule\")\n");
+    let source = r#"
+Sub = Module("sub.zen")
+Sub(name = "S1")
+"#;
+    let top = env.add_file("top.zen", source);
+
+    let result = picoplace_lang::run(&top);
+    assert!(result.diagnostics.is_empty());
+
+    let lsp_result =
+        LspEvalContext::default().parse_file_with_contents(&LspUrl::File(top), source.to_string());
+    assert!(lsp_result.diagnostics.is_empty());
+}
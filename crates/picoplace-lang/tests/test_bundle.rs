@@ -6,7 +6,10 @@ use std::path::Path;
 use zip::ZipArchive;
 
 use common::TestProject;
+use picoplace_core::bundle::Bundle;
+use picoplace_core::convert::ToSchematic;
 use picoplace_lang::bundle::create_bundle;
+use picoplace_netlist::{InstanceKind, Schematic};
 
 /// Helper to verify bundle contents
 fn verify_bundle_contents(bundle_path: &Path, expected_files: &[&str]) -> anyhow::Result<()> {
@@ -614,3 +617,86 @@ Component(
         ],
     );
 }
+
+/// Sorted component paths and net names, which do not depend on where the
+/// design lives on disk
+fn schematic_summary(schematic: &Schematic) -> (Vec<String>, Vec<String>) {
+    let mut components: Vec<String> = schematic
+        .instances
+        .iter()
+        .filter(|(_, inst)| inst.kind == InstanceKind::Component)
+        .map(|(inst_ref, _)| inst_ref.instance_path.join("."))
+        .collect();
+    components.sort();
+    let mut nets: Vec<String> = schematic.nets.keys().cloned().collect();
+    nets.sort();
+    (components, nets)
+}
+
+#[test]
+fn bundle_round_trip_evaluates_offline() {
+    let env = TestProject::new();
+
+    env.add_file(
+        "utils.zen",
+        r#"
+def net_name(prefix, index):
+    return prefix + str(index)
+"#,
+    );
+
+    env.add_file(
+        "resistor.zen",
+        r#"
+P1 = io("P1", Net)
+P2 = io("P2", Net)
+
+Component(
+    name = "R",
+    footprint = "SMD:0402",
+    pin_defs = {"1": "1", "2": "2"},
+    pins = {"1": P1, "2": P2},
+)
+"#,
+    );
+
+    let main = env.add_file(
+        "main.zen",
+        r#"
+load("./utils.zen", "net_name")
+Resistor = Module("./resistor.zen")
+
+for i in range(2):
+    Resistor(name = "R" + str(i), P1 = Net(net_name("IN", i)), P2 = Net("GND"))
+"#,
+    );
+
+    let expected = picoplace_lang::run(&main);
+    assert!(!expected.has_errors(), "{:?}", expected.diagnostics);
+    let expected = schematic_summary(expected.output.as_ref().unwrap());
+
+    let archive_dir = tempfile::tempdir().unwrap();
+    let archive = archive_dir.path().join("design.bundle");
+    create_bundle(&main, &archive).expect("Failed to create bundle");
+
+    // The original sources are gone; only the bundle is left
+    drop(env);
+
+    let extract_dir = tempfile::tempdir().unwrap();
+    let bundle = Bundle::open_zip(&archive, extract_dir.path()).expect("Failed to open bundle");
+    assert_eq!(bundle.manifest.entry_point, Path::new("main.zen"));
+
+    let result = bundle.eval();
+    assert!(!result.has_errors(), "{:?}", result.diagnostics);
+    let schematic = result.output.unwrap().sch_module.to_schematic().unwrap();
+    assert_eq!(schematic_summary(&schematic), expected);
+
+    // Re-writing the extracted bundle yields an equivalent archive
+    let rewritten = archive_dir.path().join("rewritten.bundle");
+    bundle.write_zip(&rewritten).unwrap();
+    verify_bundle_contents(
+        &rewritten,
+        &["bundle.toml", "main.zen", "resistor.zen", "utils.zen"],
+    )
+    .unwrap();
+}
@@ -0,0 +1,104 @@
+mod common;
+use common::TestProject;
+
+use picoplace_netlist::InstanceKind;
+
+fn resistors(count: usize) -> String {
+    (1..=count)
+        .map(|i| {
+            format!(
+                r#"
+Component(
+    name = "R{i}",
+    footprint = "SMD:0402",
+    symbol = Symbol(definition = [("1", ["1"]), ("2", ["2"])]),
+    pins = {{"1": Net("A"), "2": Net("B")}},
+)
+"#
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_run_variants() {
+    let env = TestProject::new();
+
+    env.add_file("lite.zen", &resistors(1));
+    env.add_file("full.zen", &resistors(3));
+    let top = env.add_file(
+        "boards.zen",
+        r#"
+Lite = Module("lite.zen")
+Full = Module("full.zen")
+REVISION = "b"
+"#,
+    );
+
+    let result = picoplace_lang::run_variants(&top);
+    assert!(!result.has_errors(), "{:?}", result.diagnostics);
+
+    let variants = result.output.unwrap();
+    let mut components: Vec<(&str, usize)> = variants
+        .iter()
+        .map(|(name, schematic)| {
+            let count = schematic
+                .instances
+                .values()
+                .filter(|instance| instance.kind == InstanceKind::Component)
+                .count();
+            (name.as_str(), count)
+        })
+        .collect();
+    components.sort();
+    assert_eq!(components, [("Full", 3), ("Lite", 1)]);
+}
+
+#[test]
+fn test_run_variants_with_inputs() {
+    let env = TestProject::new();
+
+    env.add_file(
+        "board.zen",
+        r#"
+channels = config("channels", int)
+
+def build(count):
+    for i in range(count):
+        Component(
+            name = "R{}".format(i + 1),
+            footprint = "SMD:0402",
+            symbol = Symbol(definition = [("1", ["1"]), ("2", ["2"])]),
+            pins = {"1": Net("A"), "2": Net("B")},
+        )
+
+build(channels)
+"#,
+    );
+    let top = env.add_file(
+        "boards.zen",
+        r#"
+Board = Module("board.zen")
+Board(name = "Lite", channels = 1)
+Board(name = "Pro", channels = 4)
+"#,
+    );
+
+    let result = picoplace_lang::run_variants(&top);
+    assert!(!result.has_errors(), "{:?}", result.diagnostics);
+
+    let variants = result.output.unwrap();
+    let mut components: Vec<(&str, usize)> = variants
+        .iter()
+        .map(|(name, schematic)| {
+            let count = schematic
+                .instances
+                .values()
+                .filter(|instance| instance.kind == InstanceKind::Component)
+                .count();
+            (name.as_str(), count)
+        })
+        .collect();
+    components.sort();
+    assert_eq!(components, [("Lite", 1), ("Pro", 4)]);
+}
@@ -0,0 +1,180 @@
+// Bill-of-materials export for `picoplace_netlist::Schematic`.
+
+use std::collections::BTreeMap;
+
+use crate::{natural_cmp, AttributeValue, Instance, InstanceKind, Schematic};
+
+/// Column header emitted as the first line of every BOM.
+const BOM_HEADER: &str = "References,Quantity,Value,Footprint,MPN,Manufacturer";
+
+/// Key used to group identical parts onto a single BOM line.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct BomKey {
+    mpn: String,
+    value: String,
+    footprint: String,
+}
+
+#[derive(Debug, Default)]
+struct BomLine {
+    references: Vec<String>,
+    manufacturer: String,
+}
+
+/// Return the first string attribute found under any of `keys`.
+fn string_attr(inst: &Instance, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| inst.attributes.get(*key).and_then(AttributeValue::string))
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Export the provided [`Schematic`] as a CSV bill of materials.
+///
+/// Components are grouped by `(mpn, value, footprint)`; each line lists the
+/// reference designators of every component in the group. Components without
+/// an MPN are still included, grouped by their value and footprint alone.
+/// Reference designators should already be assigned (see
+/// [`Schematic::assign_reference_designators`]); components without one fall
+/// back to their hierarchical instance path.
+pub fn to_bom_csv(schematic: &Schematic) -> String {
+    let mut lines: BTreeMap<BomKey, BomLine> = BTreeMap::new();
+
+    for (inst_ref, inst) in &schematic.instances {
+        if inst.kind != InstanceKind::Component {
+            continue;
+        }
+
+        let key = BomKey {
+            mpn: string_attr(inst, &["mpn"]),
+            value: string_attr(inst, &["value", "Value", "Val"]),
+            footprint: string_attr(inst, &["footprint"]),
+        };
+
+        let line = lines.entry(key).or_default();
+        line.references.push(
+            inst.reference_designator
+                .clone()
+                .unwrap_or_else(|| inst_ref.instance_path.join(".")),
+        );
+        if line.manufacturer.is_empty() {
+            line.manufacturer = string_attr(inst, &["manufacturer", "Manufacturer"]);
+        }
+    }
+
+    let mut rows: Vec<(BomKey, BomLine)> = lines.into_iter().collect();
+    for (_, line) in rows.iter_mut() {
+        line.references.sort_by(|a, b| natural_cmp(a, b));
+    }
+    // Order lines by their first reference designator so the output reads
+    // like a typical BOM (C1.., R1.., R2.., R10..).
+    rows.sort_by(|a, b| {
+        let (refs_a, refs_b) = (&a.1.references, &b.1.references);
+        refs_a
+            .iter()
+            .zip(refs_b)
+            .map(|(x, y)| natural_cmp(x, y))
+            .find(|ord| ord.is_ne())
+            .unwrap_or_else(|| refs_a.len().cmp(&refs_b.len()))
+    });
+
+    let mut out = String::new();
+    out.push_str(BOM_HEADER);
+    out.push('\n');
+
+    for (key, line) in rows {
+        let fields = [
+            line.references.join(" "),
+            line.references.len().to_string(),
+            key.value,
+            key.footprint,
+            key.mpn,
+            line.manufacturer,
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InstanceRef, ModuleRef};
+    use std::path::Path;
+
+    fn resistor(mod_ref: &ModuleRef, refdes: &str) -> Instance {
+        Instance::component(mod_ref.clone())
+            .with_attribute("mpn", "RC0402FR-0710KL".to_string())
+            .with_attribute("value", "10k".to_string())
+            .with_attribute("footprint", "R_0402".to_string())
+            .with_attribute("manufacturer", "Yageo".to_string())
+            .with_reference_designator(refdes)
+    }
+
+    #[test]
+    fn groups_identical_components() {
+        let mod_ref = ModuleRef::from_path(Path::new("/test.zen"), "Test");
+        let mut schematic = Schematic::new();
+
+        schematic.add_instance(
+            InstanceRef::new(mod_ref.clone(), vec!["r1".into()]),
+            resistor(&mod_ref, "R1"),
+        );
+        schematic.add_instance(
+            InstanceRef::new(mod_ref.clone(), vec!["r2".into()]),
+            resistor(&mod_ref, "R2"),
+        );
+        schematic.add_instance(
+            InstanceRef::new(mod_ref.clone(), vec!["c1".into()]),
+            Instance::component(mod_ref.clone())
+                .with_attribute("value", "100n, 16V".to_string())
+                .with_attribute("footprint", "C_0402".to_string())
+                .with_reference_designator("C1"),
+        );
+
+        let csv = to_bom_csv(&schematic);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                BOM_HEADER,
+                "C1,1,\"100n, 16V\",C_0402,,",
+                "R1 R2,2,10k,R_0402,RC0402FR-0710KL,Yageo",
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_references_naturally() {
+        let mod_ref = ModuleRef::from_path(Path::new("/test.zen"), "Test");
+        let mut schematic = Schematic::new();
+
+        for refdes in ["R10", "R2", "R1"] {
+            schematic.add_instance(
+                InstanceRef::new(mod_ref.clone(), vec![refdes.to_lowercase()]),
+                resistor(&mod_ref, refdes),
+            );
+        }
+
+        let csv = to_bom_csv(&schematic);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![BOM_HEADER, "R1 R2 R10,3,10k,R_0402,RC0402FR-0710KL,Yageo"]
+        );
+    }
+}
@@ -110,25 +110,115 @@ pub struct Placement {
     pub position: Point, // Position of the item's top-left corner
 }
 
+/// Gaps the layout engine inserts between and around items
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutConfig {
+    /// Padding around a module that contains more than one item
+    pub module_padding: f64,
+    /// Horizontal gap between items placed side by side, also used as the
+    /// padding around single items
+    pub component_gap: f64,
+    /// Vertical gap between items placed above one another
+    pub row_gap: f64,
+}
+
+impl LayoutConfig {
+    /// Same gap everywhere, with double padding around modules
+    pub fn uniform(spacing: f64) -> Self {
+        Self {
+            module_padding: spacing * 2.0,
+            component_gap: spacing,
+            row_gap: spacing,
+        }
+    }
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self::uniform(10.0)
+    }
+}
+
+/// How the packed children of a module are positioned within the module's box
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Alignment {
+    /// Children start at the module's top-left corner
+    #[default]
+    TopLeft,
+    /// Children are centered, leaving equal margins on opposite sides
+    Center,
+    /// Like [`Alignment::Center`], with every position snapped to a grid of
+    /// the given pitch
+    Grid(f64),
+}
+
+/// Tracks how many modules have been laid out and reports each step
+struct LayoutProgress<'a> {
+    processed: usize,
+    total: usize,
+    on_step: &'a mut dyn FnMut(usize, usize),
+}
+
+impl LayoutProgress<'_> {
+    fn step(&mut self) {
+        self.processed += 1;
+        (self.on_step)(self.processed, self.total);
+    }
+}
+
 /// The main hierarchical layout engine
 pub struct HierarchicalLayout {
     /// Component sizes (id -> size)
     component_sizes: HashMap<String, Size>,
     /// Module hierarchy (parent -> children)
     module_hierarchy: HashMap<String, Vec<String>>,
-    /// Spacing between components
-    spacing: f64,
+    /// Gaps between components
+    config: LayoutConfig,
+    /// Gap overrides keyed by hierarchy depth
+    depth_config: HashMap<usize, LayoutConfig>,
+    /// Position of children within their module's box
+    alignment: Alignment,
 }
 
 impl HierarchicalLayout {
     pub fn new(spacing: f64) -> Self {
+        Self::with_config(LayoutConfig::uniform(spacing))
+    }
+
+    pub fn with_config(config: LayoutConfig) -> Self {
         Self {
             component_sizes: HashMap::new(),
             module_hierarchy: HashMap::new(),
-            spacing,
+            config,
+            depth_config: HashMap::new(),
+            alignment: Alignment::default(),
         }
     }
 
+    /// Set how children are positioned within their module's box.
+    /// This only moves children; module sizes are unaffected.
+    pub fn set_alignment(&mut self, alignment: Alignment) {
+        self.alignment = alignment;
+    }
+
+    /// Override the spacing used when packing items at a given hierarchy depth.
+    ///
+    /// Depth 0 is the top level where root items are packed; the children of a
+    /// module are packed one level deeper than the module itself. Depths without
+    /// an override use the configuration the layout was created with.
+    pub fn set_spacing_for_depth(&mut self, depth: usize, spacing: f64) {
+        self.depth_config
+            .insert(depth, LayoutConfig::uniform(spacing));
+    }
+
+    /// Gaps to use when packing items at `depth`
+    fn config_for_depth(&self, depth: usize) -> LayoutConfig {
+        self.depth_config
+            .get(&depth)
+            .copied()
+            .unwrap_or(self.config)
+    }
+
     /// Set the size of a component
     pub fn set_component_size(&mut self, id: String, size: Size) {
         self.component_sizes.insert(id, size);
@@ -149,7 +239,21 @@ impl HierarchicalLayout {
 
     /// Perform the hierarchical layout and return bounding boxes for all items
     pub fn layout(&mut self) -> HashMap<String, BoundingBox> {
+        self.layout_with_progress(|_, _| {})
+    }
+
+    /// Perform the hierarchical layout, calling `on_step(modules_processed, total_modules)`
+    /// each time a module has been laid out.
+    pub fn layout_with_progress(
+        &mut self,
+        mut on_step: impl FnMut(usize, usize),
+    ) -> HashMap<String, BoundingBox> {
         let mut results = HashMap::new();
+        let mut progress = LayoutProgress {
+            processed: 0,
+            total: self.module_hierarchy.len(),
+            on_step: &mut on_step,
+        };
 
         // Find root items (components/modules with no parent)
         let root_items = self.find_root_items();
@@ -161,7 +265,7 @@ impl HierarchicalLayout {
         // Layout each root item recursively to get their sizes
         let mut root_bboxes = Vec::new();
         for root_id in &root_items {
-            let bbox = self.layout_module_recursive(root_id, &mut results);
+            let bbox = self.layout_module_recursive(root_id, 1, &mut results, &mut progress);
             root_bboxes.push((root_id.clone(), bbox));
         }
 
@@ -169,7 +273,7 @@ impl HierarchicalLayout {
         root_bboxes.sort_by(|a, b| b.1.area().partial_cmp(&a.1.area()).unwrap());
 
         // Use corner-tracking to pack root items
-        let _packed_bbox = self.pack_items(&root_bboxes, &mut results);
+        let _packed_bbox = self.pack_items(&root_bboxes, self.config_for_depth(0), &mut results);
 
         // The pack_items function already updates the results with correct positions
         // We just need to update any nested children positions
@@ -211,11 +315,15 @@ impl HierarchicalLayout {
         root_items
     }
 
-    /// Recursively layout a module and return its bounding box
+    /// Recursively layout a module and return its bounding box.
+    ///
+    /// `depth` is the depth at which this module's children are packed.
     fn layout_module_recursive(
         &mut self,
         module_id: &str,
+        depth: usize,
         results: &mut HashMap<String, BoundingBox>,
+        progress: &mut LayoutProgress<'_>,
     ) -> BoundingBox {
         // Check if this is a leaf component
         if let Some(&size) = self.component_sizes.get(module_id) {
@@ -233,7 +341,7 @@ impl HierarchicalLayout {
         // Layout all children
         let mut child_bboxes = Vec::new();
         for child_id in &children {
-            let bbox = self.layout_module_recursive(child_id, results);
+            let bbox = self.layout_module_recursive(child_id, depth + 1, results, progress);
             child_bboxes.push((child_id.clone(), bbox));
         }
 
@@ -241,10 +349,11 @@ impl HierarchicalLayout {
         child_bboxes.sort_by(|a, b| b.1.area().partial_cmp(&a.1.area()).unwrap());
 
         // Pack children using corner-tracking algorithm
-        let packed_bbox = self.pack_items(&child_bboxes, results);
+        let packed_bbox = self.pack_items(&child_bboxes, self.config_for_depth(depth), results);
 
         // Store the module's bounding box
         results.insert(module_id.to_string(), packed_bbox);
+        progress.step();
 
         packed_bbox
     }
@@ -253,6 +362,7 @@ impl HierarchicalLayout {
     fn pack_items(
         &self,
         items: &[(String, BoundingBox)],
+        config: LayoutConfig,
         results: &mut HashMap<String, BoundingBox>,
     ) -> BoundingBox {
         if items.is_empty() {
@@ -275,12 +385,12 @@ impl HierarchicalLayout {
 
                 // Add corners as potential placement points
                 placement_points.push(Point {
-                    x: placed_bbox.max_x() + self.spacing,
+                    x: placed_bbox.max_x() + config.component_gap,
                     y: placed_bbox.min_y(),
                 }); // right side
                 placement_points.push(Point {
                     x: placed_bbox.min_x(),
-                    y: placed_bbox.max_y() + self.spacing,
+                    y: placed_bbox.max_y() + config.row_gap,
                 }); // bottom side
             } else {
                 // Try each placement point and find the best one
@@ -323,12 +433,12 @@ impl HierarchicalLayout {
                     // Update placement points
                     placement_points.retain(|p| *p != position);
                     placement_points.push(Point {
-                        x: placed_bbox.max_x() + self.spacing,
+                        x: placed_bbox.max_x() + config.component_gap,
                         y: placed_bbox.min_y(),
                     }); // right side
                     placement_points.push(Point {
                         x: placed_bbox.min_x(),
-                        y: placed_bbox.max_y() + self.spacing,
+                        y: placed_bbox.max_y() + config.row_gap,
                     }); // bottom side
 
                     // Update group bounds
@@ -341,9 +451,9 @@ impl HierarchicalLayout {
         // Add spacing around the group
         // Use extra padding for modules to create visual separation between hierarchical layers
         let padding = if items.len() > 1 {
-            self.spacing * 2.0 // Double spacing for modules
+            config.module_padding
         } else {
-            self.spacing // Normal spacing for single components
+            config.component_gap // Normal spacing for single components
         };
 
         BoundingBox::new(
@@ -362,16 +472,18 @@ impl HierarchicalLayout {
         results: &mut HashMap<String, BoundingBox>,
     ) {
         if let Some(children) = self.module_hierarchy.get(module_id) {
+            let offset = self.aligned_offset(module_id, children, offset, results);
             for child_id in children {
                 if let Some(child_bbox) = results.get(child_id).cloned() {
                     // Update child position
-                    let new_bbox = BoundingBox::from_position_and_size(
-                        Point {
-                            x: child_bbox.position.x + offset.x,
-                            y: child_bbox.position.y + offset.y,
-                        },
-                        child_bbox.size,
-                    );
+                    let mut position = Point {
+                        x: child_bbox.position.x + offset.x,
+                        y: child_bbox.position.y + offset.y,
+                    };
+                    if let Alignment::Grid(pitch) = self.alignment {
+                        position = snap_to_grid(position, pitch);
+                    }
+                    let new_bbox = BoundingBox::from_position_and_size(position, child_bbox.size);
                     results.insert(child_id.clone(), new_bbox);
 
                     // Recursively update grandchildren
@@ -380,6 +492,47 @@ impl HierarchicalLayout {
             }
         }
     }
+
+    /// Offset that moves a module's children (still in packing coordinates)
+    /// to their final position for the configured alignment
+    fn aligned_offset(
+        &self,
+        module_id: &str,
+        children: &[String],
+        offset: Point,
+        results: &HashMap<String, BoundingBox>,
+    ) -> Point {
+        if self.alignment == Alignment::TopLeft {
+            return offset;
+        }
+        let Some(module_bbox) = results.get(module_id) else {
+            return offset;
+        };
+        let Some(group) = children
+            .iter()
+            .filter_map(|id| results.get(id))
+            .copied()
+            .reduce(|a, b| a.union(&b))
+        else {
+            return offset;
+        };
+
+        Point {
+            x: offset.x - group.min_x() + (module_bbox.size.width - group.size.width) / 2.0,
+            y: offset.y - group.min_y() + (module_bbox.size.height - group.size.height) / 2.0,
+        }
+    }
+}
+
+/// Round a point to the nearest multiple of `pitch` on both axes
+fn snap_to_grid(point: Point, pitch: f64) -> Point {
+    if pitch <= 0.0 {
+        return point;
+    }
+    Point {
+        x: (point.x / pitch).round() * pitch,
+        y: (point.y / pitch).round() * pitch,
+    }
 }
 
 #[cfg(test)]
@@ -482,6 +635,149 @@ mod tests {
         assert!(r1_r2_dist > r2_c2_dist);
     }
 
+    #[test]
+    fn test_layout_progress() {
+        let mut layout = HierarchicalLayout::new(5.0);
+
+        layout.set_component_size("R1".to_string(), Size::new(10.0, 5.0));
+        layout.set_component_size("R2".to_string(), Size::new(10.0, 5.0));
+        layout.set_component_size("R3".to_string(), Size::new(10.0, 5.0));
+
+        layout.add_module(
+            "inner".to_string(),
+            vec!["R1".to_string(), "R2".to_string()],
+        );
+        layout.add_module(
+            "outer".to_string(),
+            vec!["inner".to_string(), "R3".to_string()],
+        );
+
+        let mut steps = Vec::new();
+        let bboxes = layout.layout_with_progress(|done, total| steps.push((done, total)));
+
+        assert_eq!(bboxes.len(), 5);
+        // Children are laid out before their parents
+        assert_eq!(steps, vec![(1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_spacing_for_depth() {
+        fn build(layout: &mut HierarchicalLayout) {
+            for id in ["R1", "R2", "R3", "R4"] {
+                layout.set_component_size(id.to_string(), Size::new(10.0, 5.0));
+            }
+            layout.add_module(
+                "inner".to_string(),
+                vec!["R1".to_string(), "R2".to_string()],
+            );
+            layout.add_module(
+                "top".to_string(),
+                vec!["inner".to_string(), "R3".to_string(), "R4".to_string()],
+            );
+        }
+
+        let mut uniform = HierarchicalLayout::new(2.0);
+        build(&mut uniform);
+        let uniform_bboxes = uniform.layout();
+
+        let mut per_depth = HierarchicalLayout::new(2.0);
+        build(&mut per_depth);
+        per_depth.set_spacing_for_depth(1, 8.0);
+        let per_depth_bboxes = per_depth.layout();
+
+        // The leaf module is packed at depth 2 and keeps the default spacing
+        let uniform_inner = uniform_bboxes["inner"];
+        let per_depth_inner = per_depth_bboxes["inner"];
+        assert_eq!(uniform_inner.size.width, per_depth_inner.size.width);
+        assert_eq!(uniform_inner.size.height, per_depth_inner.size.height);
+
+        // The top module packs its children at depth 1 with the wider spacing
+        let uniform_top = uniform_bboxes["top"];
+        let per_depth_top = per_depth_bboxes["top"];
+        assert!(per_depth_top.size.width > uniform_top.size.width);
+        assert!(per_depth_top.size.height > uniform_top.size.height);
+    }
+
+    #[test]
+    fn test_layout_config_changes_canvas_size() {
+        fn layout_with(config: LayoutConfig) -> BoundingBox {
+            let mut layout = HierarchicalLayout::with_config(config);
+            for id in ["R1", "R2", "C1", "C2"] {
+                layout.set_component_size(id.to_string(), Size::new(10.0, 5.0));
+            }
+            layout.add_module(
+                "power".to_string(),
+                vec!["R1".to_string(), "C1".to_string()],
+            );
+            layout.add_module(
+                "main".to_string(),
+                vec!["power".to_string(), "R2".to_string(), "C2".to_string()],
+            );
+            layout.layout()["main"]
+        }
+
+        let dense = layout_with(LayoutConfig {
+            module_padding: 1.0,
+            component_gap: 1.0,
+            row_gap: 1.0,
+        });
+        let loose = layout_with(LayoutConfig {
+            module_padding: 12.0,
+            component_gap: 8.0,
+            row_gap: 6.0,
+        });
+
+        assert!(loose.area() > dense.area());
+        assert!(loose.size.width > dense.size.width);
+        assert!(loose.size.height > dense.size.height);
+
+        // Uniform spacing keeps the behaviour of `HierarchicalLayout::new`
+        assert_eq!(LayoutConfig::uniform(10.0), LayoutConfig::default());
+    }
+
+    #[test]
+    fn test_alignment() {
+        fn layout_with(alignment: Alignment) -> HashMap<String, BoundingBox> {
+            let mut layout = HierarchicalLayout::new(5.0);
+            layout.set_alignment(alignment);
+            layout.set_component_size("U1".to_string(), Size::new(20.0, 15.0));
+            layout.set_component_size("R1".to_string(), Size::new(10.0, 5.0));
+            layout.set_component_size("R2".to_string(), Size::new(10.0, 5.0));
+            layout.add_module(
+                "main".to_string(),
+                vec!["U1".to_string(), "R1".to_string(), "R2".to_string()],
+            );
+            layout.layout()
+        }
+
+        fn children_bbox(bboxes: &HashMap<String, BoundingBox>) -> BoundingBox {
+            bboxes["U1"].union(&bboxes["R1"]).union(&bboxes["R2"])
+        }
+
+        let top_left = layout_with(Alignment::TopLeft);
+        let main = top_left["main"];
+        let group = children_bbox(&top_left);
+        assert_eq!(group.min_x(), main.min_x());
+        assert_eq!(group.min_y(), main.min_y());
+
+        let center = layout_with(Alignment::Center);
+        let main = center["main"];
+        let group = children_bbox(&center);
+        assert!((group.min_x() - main.min_x() - (main.max_x() - group.max_x())).abs() < 1e-9);
+        assert!((group.min_y() - main.min_y() - (main.max_y() - group.max_y())).abs() < 1e-9);
+
+        // Alignment does not change sizes
+        assert_eq!(center["main"].size.width, top_left["main"].size.width);
+        assert_eq!(center["main"].size.height, top_left["main"].size.height);
+
+        let grid = layout_with(Alignment::Grid(2.54));
+        for id in ["U1", "R1", "R2"] {
+            let position = grid[id].position;
+            assert!((position.x / 2.54 - (position.x / 2.54).round()).abs() < 1e-9);
+            assert!((position.y / 2.54 - (position.y / 2.54).round()).abs() < 1e-9);
+        }
+    }
+
     #[test]
     fn test_no_column_layout() {
         let mut layout = HierarchicalLayout::new(5.0);
@@ -1,16 +1,17 @@
 // Module implementing KiCad net-list export functionality for `picoplace_netlist::Schematic`.
 
 use pathdiff::diff_paths;
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-use crate::{AttributeValue, InstanceKind, InstanceRef, Schematic};
+use crate::{natural_cmp, AttributeValue, InstanceKind, InstanceRef, Schematic};
 
 #[derive(Debug)]
 struct CompInfo<'a> {
-    reference: InstanceRef,
+    reference: &'a InstanceRef,
     instance: &'a crate::Instance,
     hier_name: String, // dot-separated instance path
 }
@@ -33,22 +34,6 @@ struct LibPartInfo {
     pins: Vec<(String, String)>, // (num, name)
 }
 
-// Helper extracting a prefix string for a component.
-fn comp_prefix(inst: &crate::Instance) -> String {
-    // Prefer explicit `prefix` attribute if present.
-    if let Some(AttributeValue::String(s)) = inst.attributes.get("prefix") {
-        return s.clone();
-    }
-    // Derive from component `type` attribute (e.g. `res` ⇒ `R`).
-    if let Some(AttributeValue::String(t)) = inst.attributes.get("type") {
-        if let Some(first) = t.chars().next() {
-            return first.to_ascii_uppercase().to_string();
-        }
-    }
-    // Fallback `U`.
-    "U".to_owned()
-}
-
 /// Escape quotes in a string for KiCad S-expression format.
 /// In S-expressions, quotes within strings are escaped with a backslash.
 fn escape_kicad_string(s: &str) -> String {
@@ -61,33 +46,38 @@ fn escape_kicad_string(s: &str) -> String {
 /// KiCad PCB-new needs to import a net-list.  All footprints are set to a dummy `lib:UNKNOWN`
 /// if the component instance doesn't specify one.
 pub fn to_kicad_netlist(sch: &Schematic) -> String {
+    let fp_libs = FootprintLibraries::collect(sch);
     let mut components: Vec<CompInfo<'_>> = Vec::new();
     for (inst_ref, inst) in &sch.instances {
         if inst.kind == InstanceKind::Component {
             let hier = inst_ref.instance_path.join(".");
             components.push(CompInfo {
-                reference: inst_ref.clone(),
+                reference: inst_ref,
                 instance: inst,
                 hier_name: hier,
             });
         }
     }
     // Ensure deterministic ordering for subsequent reference designator allocation.
-    components.sort_by(|a, b| a.hier_name.cmp(&b.hier_name));
+    components.sort_by(|a, b| natural_cmp(&a.hier_name, &b.hier_name));
 
     //---------------------------------------------------------------------
     // 2. Allocate reference designators (REFs)
     //---------------------------------------------------------------------
-    let mut ref_counts: HashMap<String, u32> = HashMap::new();
-    let mut ref_map: HashMap<&InstanceRef, String> = HashMap::new();
-
-    for comp in &components {
-        let prefix = comp_prefix(comp.instance);
-        let counter = ref_counts.entry(prefix.clone()).or_default();
-        *counter += 1;
-        let refdes = format!("{}{}", prefix, *counter);
-        ref_map.insert(&comp.reference, refdes);
-    }
+    // Use the same assignment as the schematic and BOM: designators already on
+    // the instances (or declared through `refdes`) are kept and the rest are
+    // numbered using the refdes prefix table.
+    let (assigned, _) = sch.clone().assign_reference_designators_preserving();
+    let ref_map: HashMap<&InstanceRef, String> = components
+        .iter()
+        .map(|comp| (comp.reference, assigned[comp.reference].clone()))
+        .collect();
+
+    // Emit components in refdes order (R2 before R10) so the output is stable.
+    components.sort_by(|a, b| {
+        natural_cmp(&ref_map[a.reference], &ref_map[b.reference])
+            .then_with(|| a.hier_name.cmp(&b.hier_name))
+    });
 
     //---------------------------------------------------------------------
     // 3. Collect nets.
@@ -104,15 +94,10 @@ pub fn to_kicad_netlist(sch: &Schematic) -> String {
 
         for port_ref in &net.ports {
             // Determine the component instance that owns this port.
-            let mut comp_path = port_ref.instance_path.clone();
-            if comp_path.pop().is_none() {
-                continue; // malformed – skip
-            }
-            let comp_ref = InstanceRef {
-                module: port_ref.module.clone(),
-                instance_path: comp_path,
+            let Some((comp_ref, _)) = sch.component_of_port(port_ref) else {
+                continue;
             };
-            let refdes = match ref_map.get(&comp_ref) {
+            let refdes = match ref_map.get(comp_ref) {
                 Some(r) => r.clone(),
                 None => continue,
             };
@@ -161,7 +146,7 @@ pub fn to_kicad_netlist(sch: &Schematic) -> String {
     //---------------- components ----------------
     writeln!(out, "  (components").unwrap();
     for comp in &components {
-        let refdes = &ref_map[&comp.reference];
+        let refdes = &ref_map[comp.reference];
         let value_field = comp
             .instance
             .attributes
@@ -183,7 +168,7 @@ pub fn to_kicad_netlist(sch: &Schematic) -> String {
                 _ => None,
             })
             .unwrap_or("UNKNOWN:UNKNOWN");
-        let (fp_string, _lib_info) = format_footprint(fp_attr);
+        let fp_string = fp_libs.footprint_id(fp_attr);
 
         writeln!(out, "    (comp (ref \"{}\")", escape_kicad_string(refdes)).unwrap();
         writeln!(
@@ -274,7 +259,7 @@ pub fn to_kicad_netlist(sch: &Schematic) -> String {
         let entry = libparts.entry(mpn.clone()).or_default();
 
         // Collect pins from children
-        if let Some(ComponentChildren { pins }) = collect_pins_for_component(sch, &comp.reference) {
+        if let Some(ComponentChildren { pins }) = collect_pins_for_component(sch, comp.reference) {
             for (pad, name) in pins {
                 entry.pins.push((pad, name));
             }
@@ -332,12 +317,7 @@ pub fn to_kicad_netlist(sch: &Schematic) -> String {
         // Sort nodes for deterministic ordering.
         let mut sorted_nodes = info.nodes.clone();
         sorted_nodes.sort_by(|a, b| {
-            let ord = a.refdes.cmp(&b.refdes);
-            if ord == std::cmp::Ordering::Equal {
-                a.pad.cmp(&b.pad)
-            } else {
-                ord
-            }
+            natural_cmp(&a.refdes, &b.refdes).then_with(|| natural_cmp(&a.pad, &b.pad))
         });
 
         writeln!(
@@ -423,6 +403,76 @@ pub fn format_footprint(fp: &str) -> (String, Option<(String, PathBuf)>) {
     )
 }
 
+/// Footprint libraries referenced by `.kicad_mod` paths in a schematic.
+///
+/// Nicknames are trimmed and deduplicated case-insensitively since KiCad rejects duplicate
+/// nicknames; when two spellings collide, the first in sort order is kept and a warning is logged.
+/// [`FootprintLibraries::footprint_id`] rewrites footprint identifiers to the kept nickname so the
+/// net-list always matches the generated `fp-lib-table`.
+#[derive(Debug, Default)]
+pub struct FootprintLibraries {
+    libs: HashMap<String, PathBuf>,
+    /// Lower-cased nickname -> nickname kept in `libs`
+    nicknames: HashMap<String, String>,
+}
+
+impl FootprintLibraries {
+    pub fn collect(sch: &Schematic) -> Self {
+        let mut found: BTreeMap<String, PathBuf> = BTreeMap::new();
+        for inst in sch.instances.values() {
+            if inst.kind != InstanceKind::Component {
+                continue;
+            }
+            if let Some(AttributeValue::String(fp_attr)) = inst.attributes.get("footprint") {
+                if let (_, Some((lib_name, dir))) = format_footprint(fp_attr) {
+                    found.entry(lib_name.trim().to_owned()).or_insert(dir);
+                }
+            }
+        }
+
+        let mut result = Self::default();
+        for (lib_name, dir) in found {
+            match result.nicknames.entry(lib_name.to_lowercase()) {
+                Entry::Occupied(kept) => {
+                    log::warn!(
+                        "Footprint library '{}' ({}) differs from '{}' only in case; using '{}' ({})",
+                        lib_name,
+                        dir.display(),
+                        kept.get(),
+                        kept.get(),
+                        result.libs[kept.get()].display()
+                    );
+                }
+                Entry::Vacant(slot) => {
+                    slot.insert(lib_name.clone());
+                    result.libs.insert(lib_name, dir);
+                }
+            }
+        }
+        result
+    }
+
+    /// KiCad `lib:fp` identifier for a `footprint` attribute, using the nickname kept in the table.
+    pub fn footprint_id(&self, fp: &str) -> String {
+        let (id, lib_info) = format_footprint(fp);
+        let Some((lib_name, _)) = lib_info else {
+            return id;
+        };
+        match self.nicknames.get(&lib_name.trim().to_lowercase()) {
+            Some(nickname) => format!("{}:{}", nickname, &id[lib_name.len() + 1..]),
+            None => id,
+        }
+    }
+
+    pub fn libraries(&self) -> &HashMap<String, PathBuf> {
+        &self.libs
+    }
+
+    pub fn into_libraries(self) -> HashMap<String, PathBuf> {
+        self.libs
+    }
+}
+
 /// Determine whether a given string is a KiCad `lib:footprint` reference rather than a file path.
 ///
 /// The heuristic is:
@@ -574,6 +624,114 @@ mod tests {
         assert_eq!(escape_kicad_string("\"\"\""), "\\\"\\\"\\\"");
     }
 
+    fn build_schematic(order: &[usize]) -> Schematic {
+        use crate::{Instance, ModuleRef, Net, NetKind};
+
+        let mod_ref = ModuleRef::from_path(Path::new("/board.zen"), "Board");
+        let mut schematic = Schematic::new();
+        let mut gnd = Net::new(NetKind::Ground, "GND");
+        let mut vcc = Net::new(NetKind::Power, "VCC");
+
+        for &i in order {
+            let comp_ref = InstanceRef::new(mod_ref.clone(), vec![format!("r{i}")]);
+            let comp = Instance::component(mod_ref.clone())
+                .with_attribute("type", "res".to_string())
+                .with_attribute("mpn", "RC0603".to_string())
+                .with_attribute("footprint", "Resistor_SMD:R_0603_1608Metric".to_string());
+            schematic.add_instance(comp_ref.clone(), comp);
+
+            for (pad, net) in [("1", &mut vcc), ("2", &mut gnd)] {
+                let port_ref =
+                    InstanceRef::new(mod_ref.clone(), vec![format!("r{i}"), format!("P{pad}")]);
+                let port = Instance::port(mod_ref.clone()).with_attribute(
+                    "pads",
+                    AttributeValue::Array(vec![AttributeValue::String(pad.to_string())]),
+                );
+                schematic.add_instance(port_ref.clone(), port);
+                net.add_port(port_ref);
+            }
+        }
+
+        schematic.add_net(gnd);
+        schematic.add_net(vcc);
+        schematic
+    }
+
+    #[test]
+    fn test_netlist_ordering_is_deterministic() {
+        let forward: Vec<usize> = (1..=12).collect();
+        let reverse: Vec<usize> = (1..=12).rev().collect();
+
+        let netlist = to_kicad_netlist(&build_schematic(&forward));
+        for _ in 0..3 {
+            assert_eq!(to_kicad_netlist(&build_schematic(&reverse)), netlist);
+        }
+
+        let r2 = netlist.find("(comp (ref \"R2\")").unwrap();
+        let r10 = netlist.find("(comp (ref \"R10\")").unwrap();
+        assert!(r2 < r10);
+
+        insta::assert_snapshot!(netlist);
+    }
+
+    #[test]
+    fn test_netlist_uses_schematic_refdes() {
+        use crate::{Instance, ModuleRef};
+
+        let mod_ref = ModuleRef::from_path(Path::new("/board.zen"), "Board");
+        let mut schematic = Schematic::new();
+        schematic.add_instance(
+            InstanceRef::new(mod_ref.clone(), vec!["xtal".to_string()]),
+            Instance::component(mod_ref.clone()).with_attribute("type", "crystal".to_string()),
+        );
+        schematic.add_instance(
+            InstanceRef::new(mod_ref.clone(), vec!["cap".to_string()]),
+            Instance::component(mod_ref.clone())
+                .with_attribute("type", "capacitor".to_string())
+                .with_attribute("refdes", "C7".to_string()),
+        );
+
+        let netlist = to_kicad_netlist(&schematic);
+        assert!(netlist.contains("(comp (ref \"Y1\")"), "{netlist}");
+        assert!(netlist.contains("(comp (ref \"C7\")"), "{netlist}");
+        assert!(!netlist.contains("(comp (ref \"C1\")"), "{netlist}");
+    }
+
+    #[test]
+    fn test_netlist_includes_ports_nested_in_interfaces() {
+        use crate::{Instance, ModuleRef, Net, NetKind};
+
+        let mod_ref = ModuleRef::from_path(Path::new("/board.zen"), "Board");
+        let at = |path: &[&str]| {
+            InstanceRef::new(
+                mod_ref.clone(),
+                path.iter().map(|s| s.to_string()).collect(),
+            )
+        };
+        let pads = |pad: &str| AttributeValue::Array(vec![AttributeValue::String(pad.to_string())]);
+
+        let mut schematic = Schematic::new();
+        schematic.add_instance(
+            at(&["usb"]),
+            Instance::component(mod_ref.clone()).with_attribute("type", "connector".to_string()),
+        );
+        schematic.add_instance(at(&["usb", "USB"]), Instance::interface(mod_ref.clone()));
+        schematic.add_instance(
+            at(&["usb", "USB", "DP"]),
+            Instance::port(mod_ref.clone()).with_attribute("pads", pads("3")),
+        );
+
+        let mut dp = Net::new(NetKind::Normal, "USB_DP");
+        dp.add_port(at(&["usb", "USB", "DP"]));
+        schematic.add_net(dp);
+
+        let netlist = to_kicad_netlist(&schematic);
+        assert!(
+            netlist.contains("(node (ref \"J1\") (pin \"3\")"),
+            "{netlist}"
+        );
+    }
+
     #[test]
     fn test_is_kicad_lib_fp() {
         // Valid KiCad lib:fp format
@@ -3,12 +3,14 @@
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
-use picoplace_sexpr::{format_sexpr, parse, Sexpr};
+use picoplace_eda::kicad::symbol::find_pin;
+use picoplace_sexpr::{format_sexpr, parse, sexpr, Sexpr};
 use uuid::Uuid;
 
-use crate::hierarchical_layout::{HierarchicalLayout, Size};
-use crate::{Instance, InstanceKind, InstanceRef, Net, Schematic};
+use crate::hierarchical_layout::{HierarchicalLayout, LayoutConfig, Size};
+use crate::{rotate_point, Instance, InstanceKind, InstanceRef, Net, Schematic};
 
 /// Enable debug mode to render component bounding boxes
 /// Set this to true to visualize component bounds, layout allocations, and module boundaries
@@ -32,10 +34,18 @@ pub enum ConversionError {
     #[error("Invalid instance reference: {0}")]
     InvalidInstanceRef(String),
 
-    #[error(
-        "KiCad symbol directory not found. Please set KICAD_SYMBOL_DIR environment variable or install KiCad"
-    )]
-    KiCadSymbolDirNotFound,
+    #[error("Symbol {0} not found in any KiCad symbol directory (searched: {})", display_dirs(.1))]
+    LibrarySymbolNotFound(String, Vec<PathBuf>),
+}
+
+fn display_dirs(dirs: &[PathBuf]) -> String {
+    if dirs.is_empty() {
+        return "none".to_string();
+    }
+    dirs.iter()
+        .map(|dir| dir.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Minimal symbol info needed for creating instances
@@ -50,6 +60,14 @@ struct SymbolInfo {
     origin_offset: (f64, f64),    // Offset from symbol origin to top-left of bounds
 }
 
+/// Rotate `(min_x, min_y, max_x, max_y)` bounds about the symbol origin.
+fn rotate_bounds(bounds: (f64, f64, f64, f64), rotation: f64) -> (f64, f64, f64, f64) {
+    let (min_x, min_y, max_x, max_y) = bounds;
+    let a = rotate_point((min_x, min_y), rotation);
+    let b = rotate_point((max_x, max_y), rotation);
+    (a.0.min(b.0), a.1.min(b.1), a.0.max(b.0), a.1.max(b.1))
+}
+
 /// Stores basic information about a global label that is attached to a component
 /// (position and a very rough size estimate). This is used only for debugging
 /// rectangles/packing calculations – *not* for the schematic output itself,
@@ -65,11 +83,79 @@ struct LabelInfo {
 }
 
 /// Convert a picoplace_netlist::Schematic to a KiCad schematic file
-pub fn to_kicad_schematic(sch: &Schematic, output_path: &Path) -> Result<String, ConversionError> {
-    let mut converter = SchematicConverter::with_debug(DEBUG_MODE);
+///
+/// `Library:Name` symbols are looked up in `symbol_dirs` first, then in the
+/// directories listed in `KICAD_SYMBOL_DIR`, then in the default KiCad install
+/// locations.
+pub fn to_kicad_schematic(
+    sch: &Schematic,
+    output_path: &Path,
+    symbol_dirs: &[PathBuf],
+) -> Result<String, ConversionError> {
+    to_kicad_schematic_with_config(sch, output_path, symbol_dirs, LayoutConfig::default())
+}
+
+/// Convert a picoplace_netlist::Schematic to a KiCad schematic file, laying
+/// out symbols with the given gaps
+pub fn to_kicad_schematic_with_config(
+    sch: &Schematic,
+    output_path: &Path,
+    symbol_dirs: &[PathBuf],
+    config: LayoutConfig,
+) -> Result<String, ConversionError> {
+    let mut converter = SchematicConverter::with_debug(DEBUG_MODE, config);
+    converter.symbol_dirs = symbol_dirs.to_vec();
     converter.convert(sch, output_path)
 }
 
+/// Existing KiCad symbol library directories, in search order: the
+/// `extra_dirs`, each entry of `KICAD_SYMBOL_DIR` (separated like
+/// `PATH`), then the platform's default install locations
+pub fn find_kicad_symbol_dirs(extra_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    // Try different locations based on the platform
+    let possible_paths = if cfg!(target_os = "macos") {
+        vec![
+            PathBuf::from("/Applications/KiCad/KiCad.app/Contents/SharedSupport/symbols"),
+            PathBuf::from("/Library/Application Support/kicad/symbols"),
+            dirs::home_dir()
+                .map(|h| h.join("Library/Application Support/kicad/symbols"))
+                .unwrap_or_default(),
+        ]
+    } else if cfg!(target_os = "windows") {
+        vec![
+            PathBuf::from("C:\\Program Files\\KiCad\\share\\kicad\\symbols"),
+            PathBuf::from("C:\\Program Files (x86)\\KiCad\\share\\kicad\\symbols"),
+            dirs::config_dir()
+                .map(|c| c.join("kicad\\symbols"))
+                .unwrap_or_default(),
+        ]
+    } else {
+        // Linux and other Unix-like systems
+        vec![
+            PathBuf::from("/usr/share/kicad/symbols"),
+            PathBuf::from("/usr/local/share/kicad/symbols"),
+            PathBuf::from("/opt/kicad/share/kicad/symbols"),
+            dirs::home_dir()
+                .map(|h| h.join(".local/share/kicad/symbols"))
+                .unwrap_or_default(),
+        ]
+    };
+
+    let mut paths = extra_dirs.to_vec();
+    if let Some(env_paths) = std::env::var_os("KICAD_SYMBOL_DIR") {
+        paths.extend(std::env::split_paths(&env_paths));
+    }
+    paths.extend(possible_paths);
+
+    let mut existing: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if path.is_dir() && !existing.contains(&path) {
+            existing.push(path);
+        }
+    }
+    existing
+}
+
 struct SchematicConverter {
     /// Map from component instance ref to its KiCad symbol
     symbols: Vec<SchematicSymbol>,
@@ -95,12 +181,18 @@ struct SchematicConverter {
     component_label_positions: HashMap<InstanceRef, Vec<LabelInfo>>,
     /// Debug mode flag - when true, renders component bounding boxes
     debug_mode: bool,
+    /// Extra directories searched for KiCad symbol libraries, before the
+    /// environment and platform defaults
+    symbol_dirs: Vec<PathBuf>,
+    /// Parsed symbol libraries, keyed by file path
+    library_cache: HashMap<PathBuf, Rc<Sexpr>>,
 }
 
 #[derive(Debug)]
 struct SchematicSymbol {
     lib_id: String,
     position: (f64, f64),
+    rotation: f64,
     unit: i32,
     in_bom: bool,
     on_board: bool,
@@ -149,7 +241,7 @@ struct Text {
 }
 
 impl SchematicConverter {
-    fn with_debug(debug_mode: bool) -> Self {
+    fn with_debug(debug_mode: bool, config: LayoutConfig) -> Self {
         Self {
             symbols: Vec::new(),
             uuid_map: HashMap::new(),
@@ -158,53 +250,20 @@ impl SchematicConverter {
             wires: Vec::new(),
             junctions: Vec::new(),
             component_nets: HashMap::new(),
-            layout_engine: HierarchicalLayout::new(10.0),
+            layout_engine: HierarchicalLayout::with_config(config),
             rectangles: Vec::new(),
             texts: Vec::new(),
             component_label_positions: HashMap::new(),
             debug_mode,
+            symbol_dirs: Vec::new(),
+            library_cache: HashMap::new(),
         }
     }
 
-    /// Find the KiCad symbol library directory
-    fn find_kicad_symbol_dir() -> Option<PathBuf> {
-        // Try different locations based on the platform
-        let possible_paths = if cfg!(target_os = "macos") {
-            vec![
-                PathBuf::from("/Applications/KiCad/KiCad.app/Contents/SharedSupport/symbols"),
-                PathBuf::from("/Library/Application Support/kicad/symbols"),
-                dirs::home_dir()
-                    .map(|h| h.join("Library/Application Support/kicad/symbols"))
-                    .unwrap_or_default(),
-            ]
-        } else if cfg!(target_os = "windows") {
-            vec![
-                PathBuf::from("C:\\Program Files\\KiCad\\share\\kicad\\symbols"),
-                PathBuf::from("C:\\Program Files (x86)\\KiCad\\share\\kicad\\symbols"),
-                dirs::config_dir()
-                    .map(|c| c.join("kicad\\symbols"))
-                    .unwrap_or_default(),
-            ]
-        } else {
-            // Linux and other Unix-like systems
-            vec![
-                PathBuf::from("/usr/share/kicad/symbols"),
-                PathBuf::from("/usr/local/share/kicad/symbols"),
-                PathBuf::from("/opt/kicad/share/kicad/symbols"),
-                dirs::home_dir()
-                    .map(|h| h.join(".local/share/kicad/symbols"))
-                    .unwrap_or_default(),
-            ]
-        };
-
-        // Also check KICAD_SYMBOL_DIR environment variable
-        if let Ok(env_path) = std::env::var("KICAD_SYMBOL_DIR") {
-            let mut paths = vec![PathBuf::from(env_path)];
-            paths.extend(possible_paths);
-            return paths.into_iter().find(|p| p.exists());
-        }
-
-        possible_paths.into_iter().find(|p| p.exists())
+    /// Existing KiCad symbol library directories to search, see
+    /// [`find_kicad_symbol_dirs`]
+    fn find_kicad_symbol_dirs(&self) -> Vec<PathBuf> {
+        find_kicad_symbol_dirs(&self.symbol_dirs)
     }
 
     fn convert(&mut self, sch: &Schematic, output_path: &Path) -> Result<String, ConversionError> {
@@ -214,9 +273,9 @@ impl SchematicConverter {
         log::debug!("Collecting component-net associations");
         for (net_name, net) in &sch.nets {
             for port_ref in &net.ports {
-                if let Ok(comp_ref) = self.get_component_ref(port_ref) {
+                if let Some((comp_ref, _)) = sch.component_of_port(port_ref) {
                     self.component_nets
-                        .entry(comp_ref)
+                        .entry(comp_ref.clone())
                         .or_default()
                         .push(net_name.clone());
                 }
@@ -357,18 +416,22 @@ impl SchematicConverter {
                     // Get the symbol info to find the origin offset
                     if let Some(symbol_info) = self.lib_symbols.get(&symbol.lib_id) {
                         // Adjust position by the symbol's origin offset
-                        symbol.position = (
-                            position.0 + symbol_info.origin_offset.0,
-                            position.1 + symbol_info.origin_offset.1,
-                        );
+                        let origin_offset = if symbol.rotation == 0.0 {
+                            symbol_info.origin_offset
+                        } else {
+                            let rotated = rotate_bounds(symbol_info.bounds, symbol.rotation);
+                            (-rotated.0, -rotated.1)
+                        };
+                        symbol.position =
+                            (position.0 + origin_offset.0, position.1 + origin_offset.1);
 
                         log::debug!(
                             "Component {} positioned at ({}, {}) with offset ({}, {})",
                             inst_ref,
                             symbol.position.0,
                             symbol.position.1,
-                            symbol_info.origin_offset.0,
-                            symbol_info.origin_offset.1
+                            origin_offset.0,
+                            origin_offset.1
                         );
 
                         // In debug mode, create a rectangle for the component's actual bounds
@@ -523,7 +586,9 @@ impl SchematicConverter {
         self.uuid_map.insert(inst_ref.clone(), uuid.clone());
 
         // Calculate extended bounds that include space for labels
-        let extended_bounds = self.calculate_extended_bounds(inst_ref, symbol_info.bounds);
+        let rotation = instance.rotation();
+        let extended_bounds =
+            self.calculate_extended_bounds(inst_ref, rotate_bounds(symbol_info.bounds, rotation));
 
         log::debug!(
             "Component {} - Symbol bounds: {:?}, Extended bounds: {:?}",
@@ -543,6 +608,7 @@ impl SchematicConverter {
         let symbol = SchematicSymbol {
             lib_id: lib_id.clone(),
             position: (0.0, 0.0), // Will be updated after layout calculation
+            rotation,
             unit: 1,
             in_bom: true,
             on_board: true,
@@ -615,34 +681,29 @@ impl SchematicConverter {
         let symbol_name = parts[1];
         log::debug!("Looking for symbol '{symbol_name}' in library '{library_name}'");
 
-        // Find the KiCad symbol directory
-        log::debug!("Finding KiCad symbol directory");
-        let kicad_symbol_dir =
-            Self::find_kicad_symbol_dir().ok_or(ConversionError::KiCadSymbolDirNotFound)?;
-        log::debug!("KiCad symbol directory: {kicad_symbol_dir:?}");
-
-        // Construct the path to the KiCad library
-        let kicad_lib_path = kicad_symbol_dir.join(format!("{library_name}.kicad_sym"));
-        log::debug!("Loading symbol file: {kicad_lib_path:?}");
+        // Search each KiCad symbol directory in order
+        let symbol_dirs = self.find_kicad_symbol_dirs();
+        log::debug!("KiCad symbol directories: {symbol_dirs:?}");
 
-        // Read and parse the symbol file
-        let content = fs::read_to_string(&kicad_lib_path)
-            .map_err(|e| ConversionError::SymbolFileReadError(kicad_lib_path.clone(), e))?;
-        log::debug!("Read {} bytes from symbol file", content.len());
+        let mut symbol_info = None;
+        for symbol_dir in &symbol_dirs {
+            // Construct the path to the KiCad library
+            let kicad_lib_path = symbol_dir.join(format!("{library_name}.kicad_sym"));
+            if !kicad_lib_path.exists() {
+                continue;
+            }
+            let sexpr = self.parse_library(&kicad_lib_path)?;
 
-        log::debug!("Parsing symbol file");
-        let sexpr = parse(&content).map_err(|e| {
-            ConversionError::SymbolFileParseError(kicad_lib_path.clone(), e.to_string())
+            // Find the specific symbol in the library
+            log::debug!("Searching for symbol '{symbol_name}' in parsed data");
+            symbol_info = self.find_symbol_in_library(&sexpr, symbol_name);
+            if symbol_info.is_some() {
+                break;
+            }
+        }
+        let symbol_info = symbol_info.ok_or_else(|| {
+            ConversionError::LibrarySymbolNotFound(library_ref.to_string(), symbol_dirs)
         })?;
-        log::debug!("Symbol file parsed successfully");
-
-        // Find the specific symbol in the library
-        log::debug!("Searching for symbol '{symbol_name}' in parsed data");
-        let symbol_info = self
-            .find_symbol_in_library(&sexpr, symbol_name)
-            .ok_or_else(|| {
-                ConversionError::SymbolNotFound(symbol_name.to_string(), kicad_lib_path.clone())
-            })?;
 
         let lib_id = format!("{library_name}:{symbol_name}");
         log::debug!("Symbol loaded successfully with lib_id: {lib_id}");
@@ -659,16 +720,7 @@ impl SchematicConverter {
             .unwrap_or("lib")
             .to_string();
 
-        // Read and parse the symbol file
-        let content = fs::read_to_string(symbol_path)
-            .map_err(|e| ConversionError::SymbolFileReadError(symbol_path.to_path_buf(), e))?;
-        log::debug!("Read {} bytes from symbol file", content.len());
-
-        log::debug!("Parsing symbol file");
-        let sexpr = parse(&content).map_err(|e| {
-            ConversionError::SymbolFileParseError(symbol_path.to_path_buf(), e.to_string())
-        })?;
-        log::debug!("Symbol file parsed successfully");
+        let sexpr = self.parse_library(symbol_path)?;
 
         // Find the first symbol in the library
         log::debug!("Finding first symbol in library");
@@ -681,6 +733,31 @@ impl SchematicConverter {
         Ok((symbol_info, lib_id))
     }
 
+    /// Read and parse the symbol library at `path`. Libraries are cached, so
+    /// each file is only parsed once however many components use it.
+    fn parse_library(&mut self, path: &Path) -> Result<Rc<Sexpr>, ConversionError> {
+        if let Some(sexpr) = self.library_cache.get(path) {
+            log::debug!("Using cached symbol file: {path:?}");
+            return Ok(sexpr.clone());
+        }
+        log::debug!("Loading symbol file: {path:?}");
+
+        // Read and parse the symbol file
+        let content = fs::read_to_string(path)
+            .map_err(|e| ConversionError::SymbolFileReadError(path.to_path_buf(), e))?;
+        log::debug!("Read {} bytes from symbol file", content.len());
+
+        log::debug!("Parsing symbol file");
+        let sexpr = parse(&content).map_err(|e| {
+            ConversionError::SymbolFileParseError(path.to_path_buf(), e.to_string())
+        })?;
+        log::debug!("Symbol file parsed successfully");
+
+        let sexpr = Rc::new(sexpr);
+        self.library_cache.insert(path.to_path_buf(), sexpr.clone());
+        Ok(sexpr)
+    }
+
     fn find_symbol_in_library(&self, sexpr: &Sexpr, symbol_name: &str) -> Option<SymbolInfo> {
         log::debug!("Searching for symbol '{symbol_name}' in S-expression");
         match sexpr {
@@ -968,12 +1045,9 @@ impl SchematicConverter {
         // For each net, create global labels at pin positions
         for port_ref in &net.ports {
             // Get the component that owns this port
-            let comp_ref = match self.get_component_ref(port_ref) {
-                Ok(ref_) => ref_,
-                Err(e) => {
-                    log::warn!("Failed to get component reference for port {port_ref}: {e}");
-                    continue;
-                }
+            let Some((comp_ref, _)) = sch.component_of_port(port_ref) else {
+                log::warn!("No component found for port {port_ref}");
+                continue;
             };
 
             // Try to obtain the pin *number* first (from the port's "pad" attribute).
@@ -994,7 +1068,7 @@ impl SchematicConverter {
             });
 
             // Get the symbol position and lib_id
-            if let Some(symbol_uuid) = self.uuid_map.get(&comp_ref) {
+            if let Some(symbol_uuid) = self.uuid_map.get(comp_ref) {
                 if let Some(symbol) = self.symbols.iter().find(|s| &s.uuid == symbol_uuid) {
                     // Get the symbol definition to find pin position
                     if let Some(symbol_info) = self.lib_symbols.get(&symbol.lib_id) {
@@ -1003,6 +1077,7 @@ impl SchematicConverter {
                             &symbol_info.raw_sexpr,
                             pin_identifier,
                             symbol.position,
+                            symbol.rotation,
                         ) {
                             // Justification based on pin orientation:
                             // 0° (pin points right): label on left side, right-justified
@@ -1113,51 +1188,26 @@ impl SchematicConverter {
         Ok(())
     }
 
-    fn get_component_ref(&self, port_ref: &InstanceRef) -> Result<InstanceRef, ConversionError> {
-        // Extract component reference from port reference
-        let mut comp_path = port_ref.instance_path.clone();
-        if comp_path.is_empty() {
-            return Err(ConversionError::InvalidInstanceRef(port_ref.to_string()));
-        }
-        comp_path.pop(); // Remove the port name
-
-        Ok(InstanceRef {
-            module: port_ref.module.clone(),
-            instance_path: comp_path,
-        })
-    }
-
     fn generate_schematic_sexpr(&self, output_path: &Path) -> String {
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
         let mut schematic_items = vec![
             // Header
-            Sexpr::list(vec![Sexpr::atom("version"), Sexpr::atom("20231120")]),
-            Sexpr::list(vec![Sexpr::atom("generator"), Sexpr::string("diode_sch")]),
-            Sexpr::list(vec![
-                Sexpr::atom("uuid"),
-                Sexpr::atom(Uuid::new_v4().to_string()),
-            ]),
-            Sexpr::list(vec![Sexpr::atom("paper"), Sexpr::string("A4")]),
+            sexpr!((version 20231120)),
+            sexpr!((generator "diode_sch")),
+            sexpr!((uuid {Sexpr::symbol(Uuid::new_v4().to_string())})),
+            sexpr!((paper "A4")),
             // Title block
-            Sexpr::list(vec![
-                Sexpr::atom("title_block"),
-                Sexpr::list(vec![
-                    Sexpr::atom("title"),
-                    Sexpr::string("Converted from Diode"),
-                ]),
-                Sexpr::list(vec![
-                    Sexpr::atom("date"),
-                    Sexpr::string(chrono::Local::now().format("%Y-%m-%d").to_string()),
-                ]),
-            ]),
+            sexpr!((title_block (title "Converted from Diode") (date {date}))),
         ];
 
         // Library symbols - just copy them as-is
         if !self.lib_symbols.is_empty() {
-            let mut lib_symbols_items = vec![Sexpr::atom("lib_symbols")];
-            for symbol_info in self.lib_symbols.values() {
-                lib_symbols_items.push(symbol_info.raw_sexpr.clone());
-            }
-            schematic_items.push(Sexpr::list(lib_symbols_items));
+            schematic_items.push(Sexpr::tagged(
+                "lib_symbols",
+                self.lib_symbols
+                    .values()
+                    .map(|symbol_info| symbol_info.raw_sexpr.clone()),
+            ));
         }
 
         // Junctions
@@ -1191,69 +1241,34 @@ impl SchematicConverter {
         }
 
         // Sheet instances
-        schematic_items.push(Sexpr::list(vec![
-            Sexpr::atom("sheet_instances"),
-            Sexpr::list(vec![
-                Sexpr::atom("path"),
-                Sexpr::string("/"),
-                Sexpr::list(vec![Sexpr::atom("page"), Sexpr::string("1")]),
-            ]),
-        ]));
+        schematic_items.push(sexpr!((sheet_instances (path "/" (page "1")))));
 
         // Build the complete schematic S-expression
-        let schematic_sexpr = Sexpr::list({
-            let mut items = vec![Sexpr::atom("kicad_sch")];
-            items.extend(schematic_items);
-            items
-        });
+        let schematic_sexpr = Sexpr::tagged("kicad_sch", schematic_items);
 
         // Convert to string with proper formatting
         format_sexpr(&schematic_sexpr, 0)
     }
 
     fn junction_to_sexpr(&self, junction: &Junction) -> Sexpr {
-        Sexpr::list(vec![
-            Sexpr::atom("junction"),
-            Sexpr::list(vec![
-                Sexpr::atom("at"),
-                Sexpr::atom(junction.position.0.to_string()),
-                Sexpr::atom(junction.position.1.to_string()),
-            ]),
-            Sexpr::list(vec![Sexpr::atom("diameter"), Sexpr::atom("0")]),
-            Sexpr::list(vec![
-                Sexpr::atom("color"),
-                Sexpr::atom("0"),
-                Sexpr::atom("0"),
-                Sexpr::atom("0"),
-                Sexpr::atom("0"),
-            ]),
-            Sexpr::list(vec![
-                Sexpr::atom("uuid"),
-                Sexpr::atom(junction.uuid.clone()),
-            ]),
-        ])
+        let (x, y) = junction.position;
+        sexpr!((junction
+            (at {x} {y})
+            (diameter 0)
+            (color 0 0 0 0)
+            (uuid {Sexpr::symbol(&junction.uuid)})))
     }
 
     fn wire_to_sexpr(&self, wire: &Wire) -> Sexpr {
-        let mut pts_items = vec![Sexpr::atom("pts")];
-        for point in &wire.points {
-            pts_items.push(Sexpr::list(vec![
-                Sexpr::atom("xy"),
-                Sexpr::atom(point.0.to_string()),
-                Sexpr::atom(point.1.to_string()),
-            ]));
-        }
+        let pts = Sexpr::tagged(
+            "pts",
+            wire.points.iter().map(|&(x, y)| sexpr!((xy {x} {y}))),
+        );
 
-        Sexpr::list(vec![
-            Sexpr::atom("wire"),
-            Sexpr::list(pts_items),
-            Sexpr::list(vec![
-                Sexpr::atom("stroke"),
-                Sexpr::list(vec![Sexpr::atom("width"), Sexpr::atom("0")]),
-                Sexpr::list(vec![Sexpr::atom("type"), Sexpr::atom("default")]),
-            ]),
-            Sexpr::list(vec![Sexpr::atom("uuid"), Sexpr::atom(wire.uuid.clone())]),
-        ])
+        sexpr!((wire
+            {pts}
+            (stroke (width 0) (type default))
+            (uuid {Sexpr::symbol(&wire.uuid)})))
     }
 
     fn global_label_to_sexpr(&self, label: &GlobalLabel) -> Sexpr {
@@ -1271,61 +1286,29 @@ impl SchematicConverter {
             }
         };
 
-        Sexpr::list(vec![
-            Sexpr::atom("global_label"),
-            Sexpr::string(label.text.clone()),
-            Sexpr::list(vec![Sexpr::atom("shape"), Sexpr::atom("input")]),
-            Sexpr::list(vec![
-                Sexpr::atom("at"),
-                Sexpr::atom(label.position.0.to_string()),
-                Sexpr::atom(label.position.1.to_string()),
-                Sexpr::atom(label.angle.to_string()),
-            ]),
-            Sexpr::list(vec![Sexpr::atom("fields_autoplaced")]),
-            Sexpr::list(vec![
-                Sexpr::atom("effects"),
-                Sexpr::list(vec![
-                    Sexpr::atom("font"),
-                    Sexpr::list(vec![
-                        Sexpr::atom("size"),
-                        Sexpr::atom("1.27"),
-                        Sexpr::atom("1.27"),
-                    ]),
-                ]),
-                Sexpr::list(vec![Sexpr::atom("justify"), Sexpr::atom(justify_value)]),
-            ]),
-            Sexpr::list(vec![Sexpr::atom("uuid"), Sexpr::atom(label.uuid.clone())]),
-        ])
+        let (x, y) = label.position;
+        sexpr!((global_label
+            {label.text.as_str()}
+            (shape input)
+            (at {x} {y} {label.angle})
+            (fields_autoplaced)
+            (effects (font (size 1.27 1.27)) (justify {Sexpr::symbol(justify_value)}))
+            (uuid {Sexpr::symbol(&label.uuid)})))
     }
 
     fn symbol_to_sexpr(&self, symbol: &SchematicSymbol, output_path: &Path) -> Sexpr {
+        let yes_no = |flag: bool| Sexpr::symbol(if flag { "yes" } else { "no" });
+        let (x, y) = symbol.position;
         let mut symbol_items = vec![
-            Sexpr::atom("symbol"),
-            Sexpr::list(vec![
-                Sexpr::atom("lib_id"),
-                Sexpr::string(symbol.lib_id.clone()),
-            ]),
-            Sexpr::list(vec![
-                Sexpr::atom("at"),
-                Sexpr::atom(symbol.position.0.to_string()),
-                Sexpr::atom(symbol.position.1.to_string()),
-                Sexpr::atom("0"),
-            ]),
-            Sexpr::list(vec![
-                Sexpr::atom("unit"),
-                Sexpr::atom(symbol.unit.to_string()),
-            ]),
-            Sexpr::list(vec![
-                Sexpr::atom("in_bom"),
-                Sexpr::atom(if symbol.in_bom { "yes" } else { "no" }),
-            ]),
-            Sexpr::list(vec![
-                Sexpr::atom("on_board"),
-                Sexpr::atom(if symbol.on_board { "yes" } else { "no" }),
-            ]),
-            Sexpr::list(vec![Sexpr::atom("dnp"), Sexpr::atom("no")]),
-            Sexpr::list(vec![Sexpr::atom("fields_autoplaced")]),
-            Sexpr::list(vec![Sexpr::atom("uuid"), Sexpr::atom(symbol.uuid.clone())]),
+            Sexpr::symbol("symbol"),
+            sexpr!((lib_id {symbol.lib_id.as_str()})),
+            sexpr!((at {x} {y} {symbol.rotation})),
+            sexpr!((unit {symbol.unit})),
+            sexpr!((in_bom {yes_no(symbol.in_bom)})),
+            sexpr!((on_board {yes_no(symbol.on_board)})),
+            sexpr!((dnp no)),
+            sexpr!((fields_autoplaced)),
+            sexpr!((uuid {Sexpr::symbol(&symbol.uuid)})),
         ];
 
         // Properties
@@ -1369,66 +1352,26 @@ impl SchematicConverter {
         }
 
         // Instances
-        symbol_items.push(Sexpr::list(vec![
-            Sexpr::atom("instances"),
-            Sexpr::list(vec![
-                Sexpr::atom("project"),
-                Sexpr::string(
-                    output_path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or("project"),
-                ),
-                Sexpr::list(vec![
-                    Sexpr::atom("path"),
-                    Sexpr::string(format!("/{}", symbol.uuid)),
-                    Sexpr::list(vec![
-                        Sexpr::atom("reference"),
-                        Sexpr::string(symbol.reference.clone()),
-                    ]),
-                    Sexpr::list(vec![
-                        Sexpr::atom("unit"),
-                        Sexpr::atom(symbol.unit.to_string()),
-                    ]),
-                ]),
-            ]),
-        ]));
+        let project = output_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("project");
+        symbol_items.push(sexpr!((instances
+            (project {project}
+                (path {format!("/{}", symbol.uuid)}
+                    (reference {symbol.reference.as_str()})
+                    (unit {symbol.unit}))))));
 
         Sexpr::list(symbol_items)
     }
 
     fn create_property_sexpr(&self, key: &str, value: &str, x: f64, y: f64, hide: bool) -> Sexpr {
-        let mut property_items = vec![
-            Sexpr::atom("property"),
-            Sexpr::string(key),
-            Sexpr::string(value),
-            Sexpr::list(vec![
-                Sexpr::atom("at"),
-                Sexpr::atom(x.to_string()),
-                Sexpr::atom(y.to_string()),
-                Sexpr::atom("0"),
-            ]),
-        ];
-
-        let mut effects_items = vec![
-            Sexpr::atom("effects"),
-            Sexpr::list(vec![
-                Sexpr::atom("font"),
-                Sexpr::list(vec![
-                    Sexpr::atom("size"),
-                    Sexpr::atom("1.27"),
-                    Sexpr::atom("1.27"),
-                ]),
-            ]),
-        ];
-
+        let mut effects = sexpr!((effects (font (size 1.27 1.27))));
         if hide {
-            effects_items.push(Sexpr::atom("hide"));
+            effects.as_list_mut().unwrap().push(Sexpr::symbol("hide"));
         }
 
-        property_items.push(Sexpr::list(effects_items));
-
-        Sexpr::list(property_items)
+        sexpr!((property {key} {value} (at {x} {y} 0) {effects}))
     }
 
     fn find_pin_position(
@@ -1436,147 +1379,18 @@ impl SchematicConverter {
         symbol_data: &Sexpr,
         pin_name: &str,
         symbol_position: (f64, f64),
+        symbol_rotation: f64,
     ) -> Option<((f64, f64), f64)> {
-        // Delegate to recursive helper that understands nested sub-symbols.
-        // For now we ignore rotation inside sub-symbols as most library parts keep rotation at 0°.
-        self.find_pin_with_transform(symbol_data, pin_name, symbol_position, (0.0, 0.0))
-    }
-
-    /// Recursively search for the pin while accumulating local offsets from any nested sub-symbols.
-    /// For now we ignore rotation inside sub-symbols as most library parts keep rotation at 0°.
-    fn find_pin_with_transform(
-        &self,
-        sexpr: &Sexpr,
-        pin_name: &str,
-        symbol_position: (f64, f64),
-        local_offset: (f64, f64),
-    ) -> Option<((f64, f64), f64)> {
-        if let Sexpr::List(items) = sexpr {
-            // First, attempt to match a pin at this level (using current local_offset)
-            for item in items {
-                if let Sexpr::List(item_data) = item {
-                    if let Some(tag) = item_data.first().and_then(|s| s.as_atom()) {
-                        if tag == "pin" {
-                            if let Some(mut result) = self.check_pin(item_data, pin_name) {
-                                // KiCad symbol coordinates have +Y upward, but schematic coordinates have +Y downward.
-                                // Therefore, subtract the local Y (pin_y + offsets) from the symbol Y.
-                                result.0 .0 += symbol_position.0 + local_offset.0;
-                                result.0 .1 = symbol_position.1 - (local_offset.1 + result.0 .1);
-                                return Some(result);
-                            }
-                        }
-                    }
-                }
-            }
-
-            // If not found, recurse into nested symbols / lists.
-            for item in items {
-                if let Sexpr::List(item_data) = item {
-                    if let Some(tag) = item_data.first().and_then(|s| s.as_atom()) {
-                        if tag == "symbol" {
-                            // Extract the local "at" offset of this sub-symbol if present.
-                            let mut sub_offset = (0.0, 0.0);
-                            for sub_item in item_data {
-                                if let Sexpr::List(at_data) = sub_item {
-                                    if let Some("at") = at_data.first().and_then(|s| s.as_atom()) {
-                                        if let (Some(x_str), Some(y_str)) = (
-                                            at_data.get(1).and_then(|s| s.as_atom()),
-                                            at_data.get(2).and_then(|s| s.as_atom()),
-                                        ) {
-                                            if let (Ok(x), Ok(y)) =
-                                                (x_str.parse::<f64>(), y_str.parse::<f64>())
-                                            {
-                                                sub_offset = (x, y);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-
-                            // Combine offsets (rotation ignored)
-                            let combined_offset =
-                                (local_offset.0 + sub_offset.0, local_offset.1 + sub_offset.1);
-
-                            if let Some(res) = self.find_pin_with_transform(
-                                item,
-                                pin_name,
-                                symbol_position,
-                                combined_offset,
-                            ) {
-                                return Some(res);
-                            }
-                        } else if let Some(res) = self.find_pin_with_transform(
-                            item,
-                            pin_name,
-                            symbol_position,
-                            local_offset,
-                        ) {
-                            return Some(res);
-                        }
-                    }
-                }
-            }
-        }
-        None
-    }
-
-    fn check_pin(&self, pin_data: &[Sexpr], pin_name: &str) -> Option<((f64, f64), f64)> {
-        let mut is_matching_pin = false;
-        let mut pin_x = 0.0;
-        let mut pin_y = 0.0;
-        let mut _pin_length = 0.0;
-        let mut pin_angle = 0.0;
-
-        for item in pin_data {
-            if let Sexpr::List(sub_items) = item {
-                if let Some(tag) = sub_items.first().and_then(|s| s.as_atom()) {
-                    match tag {
-                        "name" | "number" => {
-                            if let Some(value) = sub_items.get(1).and_then(|s| s.as_atom()) {
-                                if value == pin_name {
-                                    is_matching_pin = true;
-                                }
-                            }
-                        }
-                        "at" => {
-                            if let (Some(x_str), Some(y_str)) = (
-                                sub_items.get(1).and_then(|s| s.as_atom()),
-                                sub_items.get(2).and_then(|s| s.as_atom()),
-                            ) {
-                                if let (Ok(x), Ok(y)) = (x_str.parse::<f64>(), y_str.parse::<f64>())
-                                {
-                                    pin_x = x;
-                                    pin_y = y;
-                                }
-                                // Angle is optional
-                                if let Some(angle_str) = sub_items.get(3).and_then(|s| s.as_atom())
-                                {
-                                    if let Ok(angle) = angle_str.parse::<f64>() {
-                                        pin_angle = angle;
-                                    }
-                                }
-                            }
-                        }
-                        "length" => {
-                            if let Some(length_str) = sub_items.get(1).and_then(|s| s.as_atom()) {
-                                if let Ok(length) = length_str.parse::<f64>() {
-                                    _pin_length = length;
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-        }
-
-        if is_matching_pin {
-            // Return the pin's local coordinates. The absolute position
-            // will be calculated by find_pin_with_transform.
-            Some(((pin_x, pin_y), pin_angle))
-        } else {
-            None
-        }
+        // The pin position already includes the offsets of any nested sub-symbols.
+        let (pin_x, pin_y, pin_angle) = find_pin(symbol_data, pin_name)?.position;
+        // Rotate the pin about the symbol origin in symbol space first.
+        let (x, y) = rotate_point((pin_x, pin_y), symbol_rotation);
+        // KiCad symbol coordinates have +Y upward, but schematic coordinates have +Y downward.
+        // Therefore, subtract the local Y from the symbol Y.
+        Some((
+            (symbol_position.0 + x, symbol_position.1 - y),
+            (pin_angle + symbol_rotation).rem_euclid(360.0),
+        ))
     }
 
     /// Calculate symbol bounds including space for net labels
@@ -1593,79 +1407,33 @@ impl SchematicConverter {
     }
 
     fn rectangle_to_sexpr(&self, rectangle: &Rectangle) -> Sexpr {
-        let mut rect_items = vec![
-            Sexpr::atom("rectangle"),
-            Sexpr::list(vec![
-                Sexpr::atom("start"),
-                Sexpr::atom(rectangle.start.0.to_string()),
-                Sexpr::atom(rectangle.start.1.to_string()),
-            ]),
-            Sexpr::list(vec![
-                Sexpr::atom("end"),
-                Sexpr::atom(rectangle.end.0.to_string()),
-                Sexpr::atom(rectangle.end.1.to_string()),
-            ]),
-        ];
-
         // Add stroke with color if specified
-        let stroke_items = if let Some((r, g, b, a)) = rectangle.color {
-            vec![
-                Sexpr::atom("stroke"),
-                Sexpr::list(vec![Sexpr::atom("width"), Sexpr::atom("0.254")]), // Slightly thicker for debug
-                Sexpr::list(vec![Sexpr::atom("type"), Sexpr::atom("default")]),
-                Sexpr::list(vec![
-                    Sexpr::atom("color"),
-                    Sexpr::atom(r.to_string()),
-                    Sexpr::atom(g.to_string()),
-                    Sexpr::atom(b.to_string()),
-                    Sexpr::atom(a.to_string()),
-                ]),
-            ]
+        let stroke = if let Some((r, g, b, a)) = rectangle.color {
+            let [r, g, b, a] = [r, g, b, a].map(i32::from);
+            // Slightly thicker for debug
+            sexpr!((stroke (width 0.254) (type default) (color {r} {g} {b} {a})))
         } else {
-            vec![
-                Sexpr::atom("stroke"),
-                Sexpr::list(vec![Sexpr::atom("width"), Sexpr::atom("0")]),
-                Sexpr::list(vec![Sexpr::atom("type"), Sexpr::atom("default")]),
-            ]
+            sexpr!((stroke (width 0) (type default)))
         };
-        rect_items.push(Sexpr::list(stroke_items));
-
-        rect_items.push(Sexpr::list(vec![
-            Sexpr::atom("fill"),
-            Sexpr::list(vec![Sexpr::atom("type"), Sexpr::atom("none")]),
-        ]));
-        rect_items.push(Sexpr::list(vec![
-            Sexpr::atom("uuid"),
-            Sexpr::atom(rectangle.uuid.clone()),
-        ]));
-
-        Sexpr::list(rect_items)
+
+        let (start_x, start_y) = rectangle.start;
+        let (end_x, end_y) = rectangle.end;
+        sexpr!((rectangle
+            (start {start_x} {start_y})
+            (end {end_x} {end_y})
+            {stroke}
+            (fill (type none))
+            (uuid {Sexpr::symbol(&rectangle.uuid)})))
     }
 
     fn text_to_sexpr(&self, text: &Text) -> Sexpr {
-        Sexpr::list(vec![
-            Sexpr::atom("text"),
-            Sexpr::string(text.content.clone()),
-            Sexpr::list(vec![Sexpr::atom("exclude_from_sim"), Sexpr::atom("no")]),
-            Sexpr::list(vec![
-                Sexpr::atom("at"),
-                Sexpr::atom(text.position.0.to_string()),
-                Sexpr::atom(text.position.1.to_string()),
-                Sexpr::atom(text.angle.to_string()),
-            ]),
-            Sexpr::list(vec![
-                Sexpr::atom("effects"),
-                Sexpr::list(vec![
-                    Sexpr::atom("font"),
-                    Sexpr::list(vec![
-                        Sexpr::atom("size"),
-                        Sexpr::atom("1.27"),
-                        Sexpr::atom("1.27"),
-                    ]),
-                ]),
-            ]),
-            Sexpr::list(vec![Sexpr::atom("uuid"), Sexpr::atom(text.uuid.clone())]),
-        ])
+        let (x, y) = text.position;
+        sexpr!((text
+            {text.content.as_str()}
+            (exclude_from_sim no)
+            (at {x} {y} {text.angle})
+            (effects (font (size 1.27 1.27)))
+            (uuid {Sexpr::symbol(&text.uuid)})))
     }
 
     fn build_module_hierarchy(&mut self, sch: &Schematic) {
@@ -1812,3 +1580,64 @@ impl SchematicConverter {
 pub fn write_schematic_file(schematic_content: &str, path: &Path) -> Result<(), std::io::Error> {
     fs::write(path, schematic_content)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_library_symbol_searches_dirs_in_order() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        fs::write(
+            first.path().join("Custom.kicad_sym"),
+            "(kicad_symbol_lib (symbol \"Other\"))",
+        )
+        .unwrap();
+        fs::write(
+            second.path().join("Custom.kicad_sym"),
+            "(kicad_symbol_lib (symbol \"R\" (property \"Reference\" \"R\")))",
+        )
+        .unwrap();
+
+        let mut converter = SchematicConverter::with_debug(false, LayoutConfig::default());
+        converter.symbol_dirs = vec![first.path().to_path_buf(), second.path().to_path_buf()];
+
+        let (symbol_info, lib_id) = converter.load_symbol_from_library("Custom:R").unwrap();
+        assert_eq!(lib_id, "Custom:R");
+        assert_eq!(symbol_info.reference, "R");
+
+        let error = converter
+            .load_symbol_from_library("Custom:Missing")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains(&format!(
+                "{}, {}",
+                first.path().display(),
+                second.path().display()
+            )),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_library_parsed_once() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("Device.kicad_sym"),
+            "(kicad_symbol_lib (symbol \"R\") (symbol \"C\"))",
+        )
+        .unwrap();
+
+        let mut converter = SchematicConverter::with_debug(false, LayoutConfig::default());
+        converter.symbol_dirs = vec![dir.path().to_path_buf()];
+        converter.load_symbol_from_library("Device:R").unwrap();
+
+        // Later lookups are served from the cache, not the file
+        fs::write(dir.path().join("Device.kicad_sym"), "(not a library").unwrap();
+        converter.load_symbol_from_library("Device:R").unwrap();
+        converter.load_symbol_from_library("Device:C").unwrap();
+        assert_eq!(converter.library_cache.len(), 1);
+    }
+}
@@ -11,11 +11,12 @@
 //!   stable [`netlist::InstanceRef`].
 //! * `nets` – all electrical nets keyed by their deduplicated name.
 
+pub mod bom;
 pub mod hierarchical_layout;
 pub mod kicad_netlist;
 pub mod kicad_schematic;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 
@@ -175,6 +176,25 @@ pub enum NetKind {
     Power,
 }
 
+impl NetKind {
+    /// Trace width in millimeters for nets of this kind that don't set one.
+    pub fn default_trace_width(&self) -> f64 {
+        match self {
+            NetKind::Normal => 0.25,
+            NetKind::Ground | NetKind::Power => 0.5,
+        }
+    }
+}
+
+/// Two nets carrying a differential signal, see [`Schematic::differential_pairs`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DifferentialPair {
+    /// Net carrying the non-inverted signal
+    pub positive: String,
+    /// Net carrying the inverted signal
+    pub negative: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Net {
     pub kind: NetKind,
@@ -270,6 +290,39 @@ impl Instance {
         self.reference_designator = Some(designator.into());
         self
     }
+
+    /// Placement rotation in degrees, taken from the optional `rotation`
+    /// attribute and snapped to the nearest of 0, 90, 180 or 270.
+    pub fn rotation(&self) -> f64 {
+        let degrees = match self.attributes.get("rotation") {
+            Some(AttributeValue::Number(n)) => *n,
+            Some(AttributeValue::String(s)) => s.trim().parse().unwrap_or(0.0),
+            _ => 0.0,
+        };
+
+        if !degrees.is_finite() {
+            return 0.0;
+        }
+        ((degrees / 90.0).round() * 90.0).rem_euclid(360.0)
+    }
+
+    /// Whether the part has a polarity, from an explicit `polarized`
+    /// attribute or else a polarized KiCad footprint (`CP_*`, `D_*`, `LED_*`).
+    pub fn is_polarized(&self) -> bool {
+        match self.attributes.get("polarized") {
+            Some(AttributeValue::Boolean(b)) => return *b,
+            Some(AttributeValue::String(s)) => return s.trim().eq_ignore_ascii_case("true"),
+            _ => {}
+        }
+
+        let Some(AttributeValue::String(footprint)) = self.attributes.get("footprint") else {
+            return false;
+        };
+        let name = footprint.rsplit(':').next().unwrap_or(footprint);
+        ["CP_", "D_", "LED_"]
+            .iter()
+            .any(|prefix| name.starts_with(prefix))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -286,6 +339,12 @@ pub struct Schematic {
 
     /// Symbol library - maps symbol paths to their s-expression content
     pub symbols: HashMap<String, String>,
+
+    /// Net ties, as `(from, to)` pairs: `from` is treated as part of `to` when
+    /// deciding connectivity (e.g. AGND tied to GND through a bead), while
+    /// both nets stay distinct for export.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub net_aliases: Vec<(String, String)>,
 }
 
 impl Schematic {
@@ -310,6 +369,25 @@ impl Schematic {
         self.instances.get_mut(reference)
     }
 
+    /// The component that `port` belongs to.
+    ///
+    /// Walks up the port's instance path until it reaches a
+    /// [`InstanceKind::Component`], so ports nested in interfaces still
+    /// resolve to the component that owns them.
+    pub fn component_of_port(&self, port: &InstanceRef) -> Option<(&InstanceRef, &Instance)> {
+        let mut path = port.instance_path.as_slice();
+        while let Some((_, parent)) = path.split_last() {
+            let candidate = InstanceRef::new(port.module.clone(), parent.to_vec());
+            if let Some((reference, instance)) = self.instances.get_key_value(&candidate) {
+                if instance.kind == InstanceKind::Component {
+                    return Some((reference, instance));
+                }
+            }
+            path = parent;
+        }
+        None
+    }
+
     /// Insert (or replace) a net.
     pub fn add_net(&mut self, net: Net) -> &mut Self {
         self.nets.insert(net.name.clone(), net);
@@ -321,6 +399,77 @@ impl Schematic {
         self.nets.get_mut(name)
     }
 
+    /// Tie net `from` to net `to` (see [`Schematic::net_aliases`]).
+    pub fn add_net_alias(&mut self, from: impl Into<String>, to: impl Into<String>) -> &mut Self {
+        self.net_aliases.push((from.into(), to.into()));
+        self
+    }
+
+    /// Name of the net that `name` is connected to once aliases are followed.
+    ///
+    /// Returns `name` itself for nets without an alias. If the chain loops
+    /// back on itself, the alphabetically first net in the loop is used so
+    /// every member resolves to the same name.
+    pub fn merged_net<'a>(&'a self, name: &'a str) -> &'a str {
+        let mut chain = vec![name];
+        let mut current = name;
+        while let Some((_, to)) = self.net_aliases.iter().find(|(from, _)| from == current) {
+            current = to;
+            if let Some(start) = chain.iter().position(|&seen| seen == current) {
+                return chain[start..].iter().min().copied().unwrap_or(current);
+            }
+            chain.push(current);
+        }
+        current
+    }
+
+    /// Nets grouped by the name they merge into via [`Schematic::merged_net`],
+    /// in name order. Nets without aliases form groups of one.
+    pub fn merged_nets(&self) -> BTreeMap<&str, Vec<&Net>> {
+        let mut groups: BTreeMap<&str, Vec<&Net>> = BTreeMap::new();
+        for net in self.nets.values() {
+            groups
+                .entry(self.merged_net(&net.name))
+                .or_default()
+                .push(net);
+        }
+        for nets in groups.values_mut() {
+            nets.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        groups
+    }
+
+    /// Differential pairs among this schematic's nets, sorted by positive net.
+    ///
+    /// `overrides` maps a positive net to its negative net and always wins;
+    /// nets it mentions are not paired by convention. Remaining nets are
+    /// paired by the `<name>_P` / `<name>_N` naming convention. Pairs are
+    /// only reported when both nets exist.
+    pub fn differential_pairs(&self, overrides: &HashMap<String, String>) -> Vec<DifferentialPair> {
+        let claimed: HashSet<&str> = overrides
+            .iter()
+            .flat_map(|(positive, negative)| [positive.as_str(), negative.as_str()])
+            .collect();
+
+        let by_convention = self.nets.keys().filter_map(|positive| {
+            let negative = format!("{}_N", positive.strip_suffix("_P")?);
+            let unclaimed = !claimed.contains(positive.as_str()) && !claimed.contains(&*negative);
+            (unclaimed && self.nets.contains_key(&negative)).then(|| (positive.clone(), negative))
+        });
+
+        let mut pairs: Vec<DifferentialPair> = overrides
+            .iter()
+            .filter(|(positive, negative)| {
+                self.nets.contains_key(*positive) && self.nets.contains_key(*negative)
+            })
+            .map(|(positive, negative)| (positive.clone(), negative.clone()))
+            .chain(by_convention)
+            .map(|(positive, negative)| DifferentialPair { positive, negative })
+            .collect();
+        pairs.sort_by(|a, b| a.positive.cmp(&b.positive));
+        pairs
+    }
+
     /// Set the root module reference.
     pub fn set_root_ref(&mut self, root: InstanceRef) -> &mut Self {
         self.root_ref = Some(root);
@@ -333,6 +482,57 @@ impl Schematic {
             .map(|r| self.instances.get(r).unwrap())
     }
 
+    /// The direct children of `parent`, ordered naturally by child name.
+    pub fn children_of(
+        &self,
+        parent: &InstanceRef,
+    ) -> impl Iterator<Item = (&InstanceRef, &Instance)> {
+        let mut children: Vec<(&Symbol, &InstanceRef, &Instance)> = self
+            .instances
+            .get(parent)
+            .into_iter()
+            .flat_map(|inst| inst.children.iter())
+            .filter_map(|(name, child)| {
+                self.instances
+                    .get_key_value(child)
+                    .map(|(child_ref, child)| (name, child_ref, child))
+            })
+            .collect();
+        children.sort_by(|a, b| natural_cmp(a.0, b.0));
+        children
+            .into_iter()
+            .map(|(_, child_ref, child)| (child_ref, child))
+    }
+
+    /// The instance one level above `child` in the hierarchy, if it exists
+    /// in the schematic.
+    pub fn parent_of(&self, child: &InstanceRef) -> Option<&InstanceRef> {
+        let (_, parent_path) = child.instance_path.split_last()?;
+        let parent = InstanceRef::new(child.module.clone(), parent_path.to_vec());
+        self.instances
+            .get_key_value(&parent)
+            .map(|(parent_ref, _)| parent_ref)
+    }
+
+    /// Every instance below `root`, depth-first with parents before their
+    /// children and siblings in [`Schematic::children_of`] order. `root`
+    /// itself is not included.
+    pub fn descendants(
+        &self,
+        root: &InstanceRef,
+    ) -> impl Iterator<Item = (&InstanceRef, &Instance)> {
+        let mut found = Vec::new();
+        let mut stack: Vec<_> = self.children_of(root).collect();
+        stack.reverse();
+        while let Some((inst_ref, inst)) = stack.pop() {
+            found.push((inst_ref, inst));
+            let start = stack.len();
+            stack.extend(self.children_of(inst_ref));
+            stack[start..].reverse();
+        }
+        found.into_iter()
+    }
+
     /// Assign reference designators to all components in the schematic.
     ///
     /// This follows the same logic as KiCad netlist export:
@@ -342,6 +542,18 @@ impl Schematic {
     ///
     /// Returns a map from InstanceRef to the assigned reference designator.
     pub fn assign_reference_designators(&mut self) -> HashMap<InstanceRef, String> {
+        self.assign_reference_designators_with_prefixes(&HashMap::new())
+    }
+
+    /// Like [`Schematic::assign_reference_designators`], with `prefixes`
+    /// mapping component `type` to designator prefix (e.g. `crystal` → `Y`)
+    /// on top of [`default_refdes_prefixes`].
+    pub fn assign_reference_designators_with_prefixes(
+        &mut self,
+        prefixes: &HashMap<String, String>,
+    ) -> HashMap<InstanceRef, String> {
+        let prefixes = merged_refdes_prefixes(prefixes);
+
         // Collect all components
         let mut components: Vec<(&InstanceRef, &mut Instance)> = self
             .instances
@@ -349,11 +561,12 @@ impl Schematic {
             .filter(|(_, inst)| inst.kind == InstanceKind::Component)
             .collect();
 
-        // Sort by hierarchical name (dot-separated instance path) for deterministic ordering
+        // Sort by hierarchical name (dot-separated instance path) for deterministic
+        // ordering, numbering `r2` before `r10`
         components.sort_by(|a, b| {
             let hier_a = a.0.instance_path.join(".");
             let hier_b = b.0.instance_path.join(".");
-            hier_a.cmp(&hier_b)
+            natural_cmp(&hier_a, &hier_b)
         });
 
         // Track counters for each prefix
@@ -362,7 +575,7 @@ impl Schematic {
 
         // Assign reference designators
         for (inst_ref, instance) in components {
-            let prefix = get_component_prefix(instance);
+            let prefix = get_component_prefix(instance, &prefixes);
             let counter = ref_counts.entry(prefix.clone()).or_default();
             *counter += 1;
             let refdes = format!("{}{}", prefix, *counter);
@@ -376,17 +589,228 @@ impl Schematic {
 
         ref_map
     }
+
+    /// Assign reference designators while keeping any designator a component
+    /// already has, either set directly on the instance or declared through a
+    /// `refdes` or `reference` attribute.
+    ///
+    /// Components without an explicit designator are numbered as in
+    /// [`Schematic::assign_reference_designators`], skipping numbers that are
+    /// already taken by explicit designators. When two components request the
+    /// same designator, the first one (by hierarchical path) keeps it and the
+    /// other is auto-numbered; each such collision is reported as a
+    /// [`RefdesConflict`].
+    ///
+    /// Returns the merged map from InstanceRef to reference designator along
+    /// with the list of conflicts detected.
+    pub fn assign_reference_designators_preserving(
+        &mut self,
+    ) -> (HashMap<InstanceRef, String>, Vec<RefdesConflict>) {
+        self.assign_reference_designators_preserving_with_prefixes(&HashMap::new())
+    }
+
+    /// Like [`Schematic::assign_reference_designators_preserving`], using
+    /// `prefixes` as in [`Schematic::assign_reference_designators_with_prefixes`].
+    pub fn assign_reference_designators_preserving_with_prefixes(
+        &mut self,
+        prefixes: &HashMap<String, String>,
+    ) -> (HashMap<InstanceRef, String>, Vec<RefdesConflict>) {
+        let prefixes = merged_refdes_prefixes(prefixes);
+
+        let mut components: Vec<(&InstanceRef, &mut Instance)> = self
+            .instances
+            .iter_mut()
+            .filter(|(_, inst)| inst.kind == InstanceKind::Component)
+            .collect();
+
+        components.sort_by(|a, b| {
+            let hier_a = a.0.instance_path.join(".");
+            let hier_b = b.0.instance_path.join(".");
+            natural_cmp(&hier_a, &hier_b)
+        });
+
+        let mut ref_map: HashMap<InstanceRef, String> = HashMap::new();
+        let mut owners: HashMap<String, InstanceRef> = HashMap::new();
+        let mut conflicts = Vec::new();
+        let mut auto: Vec<(&InstanceRef, &mut Instance)> = Vec::new();
+
+        // First pass: claim explicit designators in hierarchical order.
+        for (inst_ref, instance) in components {
+            match get_explicit_refdes(instance) {
+                Some(refdes) => {
+                    if let Some(owner) = owners.get(&refdes) {
+                        conflicts.push(RefdesConflict {
+                            designator: refdes,
+                            kept: owner.clone(),
+                            reassigned: inst_ref.clone(),
+                        });
+                        auto.push((inst_ref, instance));
+                    } else {
+                        owners.insert(refdes.clone(), inst_ref.clone());
+                        instance.reference_designator = Some(refdes.clone());
+                        ref_map.insert(inst_ref.clone(), refdes);
+                    }
+                }
+                None => auto.push((inst_ref, instance)),
+            }
+        }
+
+        // Second pass: auto-number the rest, skipping designators already taken.
+        let mut ref_counts: HashMap<String, u32> = HashMap::new();
+        for (inst_ref, instance) in auto {
+            let prefix = get_component_prefix(instance, &prefixes);
+            let counter = ref_counts.entry(prefix.clone()).or_default();
+            let refdes = loop {
+                *counter += 1;
+                let candidate = format!("{}{}", prefix, *counter);
+                if !owners.contains_key(&candidate) {
+                    break candidate;
+                }
+            };
+
+            owners.insert(refdes.clone(), inst_ref.clone());
+            instance.reference_designator = Some(refdes.clone());
+            ref_map.insert(inst_ref.clone(), refdes);
+        }
+
+        (ref_map, conflicts)
+    }
 }
 
-/// Helper function to determine the prefix for a component's reference designator.
-/// This follows the same logic as `comp_prefix` in kicad_netlist.rs.
-fn get_component_prefix(inst: &Instance) -> String {
+/// Two components requested the same explicit reference designator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefdesConflict {
+    /// The designator both components asked for.
+    pub designator: String,
+    /// The component that kept the designator.
+    pub kept: InstanceRef,
+    /// The component that was auto-numbered instead.
+    pub reassigned: InstanceRef,
+}
+
+/// Return the reference designator a component explicitly declares, if any:
+/// an already set `reference_designator`, or a `refdes`/`reference` attribute.
+fn get_explicit_refdes(inst: &Instance) -> Option<String> {
+    inst.reference_designator
+        .as_deref()
+        .or_else(|| {
+            ["refdes", "reference"]
+                .iter()
+                .find_map(|key| inst.attributes.get(*key).and_then(AttributeValue::string))
+        })
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_owned)
+}
+
+/// Rotate a point counter-clockwise by a multiple of 90° in a +Y-up frame,
+/// matching how KiCad applies the angle of a symbol's or footprint's
+/// `(at x y angle)`. This is the rotation convention used for all outputs.
+pub fn rotate_point((x, y): (f64, f64), rotation: f64) -> (f64, f64) {
+    match rotation.rem_euclid(360.0) as i32 {
+        90 => (-y, x),
+        180 => (-x, -y),
+        270 => (y, -x),
+        _ => (x, y),
+    }
+}
+
+/// [`rotate_point`] for a +Y-down (screen or schematic sheet) offset, so the
+/// rotation still appears counter-clockwise on screen.
+pub fn rotate_screen_offset((x, y): (f64, f64), rotation: f64) -> (f64, f64) {
+    let (x, y) = rotate_point((x, -y), rotation);
+    (x, -y)
+}
+
+/// Compare strings so that embedded numbers sort by value (`R2` before `R10`).
+///
+/// Runs of ASCII digits are compared numerically, everything else
+/// character by character. Strings that only differ in leading zeros fall
+/// back to a plain comparison so the order stays total. Use this wherever
+/// reference designators or instance paths are shown to people.
+pub fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    fn split_digits(s: &str) -> (&str, &str) {
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        s.split_at(end)
+    }
+
+    let (mut x, mut y) = (a, b);
+    loop {
+        match (x.chars().next(), y.chars().next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(cx), Some(cy)) if cx.is_ascii_digit() && cy.is_ascii_digit() => {
+                let (num_x, rest_x) = split_digits(x);
+                let (num_y, rest_y) = split_digits(y);
+                let num_x = num_x.trim_start_matches('0');
+                let num_y = num_y.trim_start_matches('0');
+                let ord = num_x.len().cmp(&num_y.len()).then_with(|| num_x.cmp(num_y));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                x = rest_x;
+                y = rest_y;
+            }
+            (Some(cx), Some(cy)) => {
+                if cx != cy {
+                    return cx.cmp(&cy);
+                }
+                x = &x[cx.len_utf8()..];
+                y = &y[cy.len_utf8()..];
+            }
+        }
+    }
+}
+
+/// Designator prefixes for component types whose first letter would be
+/// misleading, e.g. `crystal` → `Y` rather than `C`.
+///
+/// Types are matched case-insensitively; any other type falls back to its
+/// uppercased first letter.
+pub fn default_refdes_prefixes() -> HashMap<String, String> {
+    [
+        ("connector", "J"),
+        ("crystal", "Y"),
+        ("ferrite", "FB"),
+        ("header", "J"),
+        ("inductor", "L"),
+        ("led", "D"),
+        ("mosfet", "Q"),
+        ("oscillator", "Y"),
+        ("testpoint", "TP"),
+        ("transistor", "Q"),
+    ]
+    .into_iter()
+    .map(|(ty, prefix)| (ty.to_owned(), prefix.to_owned()))
+    .collect()
+}
+
+/// [`default_refdes_prefixes`] with `overrides` applied, keyed by lowercase type
+fn merged_refdes_prefixes(overrides: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut prefixes = default_refdes_prefixes();
+    prefixes.extend(
+        overrides
+            .iter()
+            .map(|(ty, prefix)| (ty.to_lowercase(), prefix.clone())),
+    );
+    prefixes
+}
+
+/// Determine the prefix for a component's reference designator.
+fn get_component_prefix(inst: &Instance, prefixes: &HashMap<String, String>) -> String {
     // Prefer explicit `prefix` attribute if present
     if let Some(AttributeValue::String(s)) = inst.attributes.get("prefix") {
         return s.clone();
     }
-    // Derive from component `type` attribute (e.g. `res` → `R`)
+    // Derive from component `type` attribute, using the prefix table first
+    // and the first letter otherwise (e.g. `res` → `R`)
     if let Some(AttributeValue::String(t)) = inst.attributes.get("type") {
+        if let Some(prefix) = prefixes.get(&t.to_lowercase()) {
+            return prefix.clone();
+        }
         if let Some(first) = t.chars().next() {
             return first.to_ascii_uppercase().to_string();
         }
@@ -438,6 +862,36 @@ impl Net {
         self.properties.insert(key.into(), value.into());
         self
     }
+
+    /// Explicit trace width in millimeters, from the `trace_width` property.
+    ///
+    /// Accepts a number or a string such as `"0.5"` or `"0.5mm"`; anything
+    /// else, including non-positive widths, is treated as unset.
+    pub fn trace_width(&self) -> Option<f64> {
+        let width = match self.properties.get("trace_width")? {
+            AttributeValue::Number(n) => *n,
+            AttributeValue::String(s) | AttributeValue::Physical(s) => {
+                s.trim().trim_end_matches("mm").trim_end().parse().ok()?
+            }
+            _ => return None,
+        };
+        (width.is_finite() && width > 0.0).then_some(width)
+    }
+
+    /// Set the `trace_width` property (millimeters), returning a mutable
+    /// reference for chaining.
+    pub fn set_trace_width(&mut self, width: f64) -> &mut Self {
+        self.properties
+            .insert("trace_width".into(), AttributeValue::Number(width));
+        self
+    }
+
+    /// Trace width to route and draw this net with: the explicit
+    /// [`trace_width`](Self::trace_width) or else the default for its kind.
+    pub fn effective_trace_width(&self) -> f64 {
+        self.trace_width()
+            .unwrap_or_else(|| self.kind.default_trace_width())
+    }
 }
 
 /// Fluent builder for constructing [`Schematic`] structures.
@@ -517,6 +971,47 @@ mod tests {
         assert_eq!(h1.finish(), h2.finish());
     }
 
+    #[test]
+    fn test_natural_cmp() {
+        use std::cmp::Ordering;
+
+        assert_eq!(natural_cmp("R2", "R10"), Ordering::Less);
+        assert_eq!(natural_cmp("R10", "R2"), Ordering::Greater);
+        assert_eq!(natural_cmp("C1", "R1"), Ordering::Less);
+        assert_eq!(natural_cmp("a.r10", "a.r2"), Ordering::Greater);
+        assert_eq!(natural_cmp("U1.p2", "U1.p10"), Ordering::Less);
+        assert_eq!(natural_cmp("R", "R1"), Ordering::Less);
+        assert_eq!(natural_cmp("R1", "R1"), Ordering::Equal);
+        // Leading zeros tie numerically but still give a total order
+        assert_eq!(natural_cmp("R01", "R1"), "R01".cmp("R1"));
+
+        let mut refs = vec!["R10", "C2", "R2", "R1", "C10", "U1"];
+        refs.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(refs, vec!["C2", "C10", "R1", "R2", "R10", "U1"]);
+    }
+
+    #[test]
+    fn test_assign_reference_designators_natural_order() {
+        let mut schematic = Schematic::new();
+        let mod_ref = ModuleRef::from_path(Path::new("/test.pmod"), "TestModule");
+
+        let refs: Vec<InstanceRef> = [10, 2, 1]
+            .iter()
+            .map(|i| {
+                let inst_ref = InstanceRef::new(mod_ref.clone(), vec![format!("r{i}")]);
+                let inst =
+                    Instance::component(mod_ref.clone()).with_attribute("type", "res".to_string());
+                schematic.add_instance(inst_ref.clone(), inst);
+                inst_ref
+            })
+            .collect();
+
+        let ref_map = schematic.assign_reference_designators();
+        assert_eq!(ref_map[&refs[0]], "R3");
+        assert_eq!(ref_map[&refs[1]], "R2");
+        assert_eq!(ref_map[&refs[2]], "R1");
+    }
+
     #[test]
     fn test_assign_reference_designators() {
         let mut schematic = Schematic::new();
@@ -611,4 +1106,287 @@ mod tests {
             Some("U2".to_string())
         );
     }
+
+    #[test]
+    fn test_assign_reference_designators_preserving() {
+        let mut schematic = Schematic::new();
+        let mod_ref = ModuleRef::from_path(Path::new("/test.pmod"), "TestModule");
+
+        // Explicit R1 claimed by `a`; `b` asks for R1 too and must be renumbered.
+        let a_ref = InstanceRef::new(mod_ref.clone(), vec!["a".into()]);
+        let a = Instance::component(mod_ref.clone())
+            .with_attribute("type", "res".to_string())
+            .with_attribute("refdes", "R1".to_string());
+        schematic.add_instance(a_ref.clone(), a);
+
+        let b_ref = InstanceRef::new(mod_ref.clone(), vec!["b".into()]);
+        let b = Instance::component(mod_ref.clone())
+            .with_attribute("type", "res".to_string())
+            .with_attribute("reference", "R1".to_string());
+        schematic.add_instance(b_ref.clone(), b);
+
+        // Explicit R3 leaves R2 free for auto-numbering.
+        let c_ref = InstanceRef::new(mod_ref.clone(), vec!["c".into()]);
+        let c = Instance::component(mod_ref.clone())
+            .with_attribute("type", "res".to_string())
+            .with_attribute("refdes", "R3".to_string());
+        schematic.add_instance(c_ref.clone(), c);
+
+        let d_ref = InstanceRef::new(mod_ref.clone(), vec!["d".into()]);
+        let d = Instance::component(mod_ref.clone()).with_attribute("type", "res".to_string());
+        schematic.add_instance(d_ref.clone(), d);
+
+        let (ref_map, conflicts) = schematic.assign_reference_designators_preserving();
+
+        assert_eq!(ref_map.get(&a_ref), Some(&"R1".to_string()));
+        assert_eq!(ref_map.get(&b_ref), Some(&"R2".to_string()));
+        assert_eq!(ref_map.get(&c_ref), Some(&"R3".to_string()));
+        assert_eq!(ref_map.get(&d_ref), Some(&"R4".to_string()));
+        assert_eq!(
+            conflicts,
+            vec![RefdesConflict {
+                designator: "R1".to_string(),
+                kept: a_ref.clone(),
+                reassigned: b_ref.clone(),
+            }]
+        );
+        assert_eq!(
+            schematic.instances[&b_ref].reference_designator,
+            Some("R2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_assign_reference_designators_preserving_keeps_set_designator() {
+        let mut schematic = Schematic::new();
+        let mod_ref = ModuleRef::from_path(Path::new("/test.pmod"), "TestModule");
+
+        // `mcu` sorts last but is pinned to U1, so `buffer` gets U2.
+        let buffer_ref = InstanceRef::new(mod_ref.clone(), vec!["buffer".into()]);
+        schematic.add_instance(
+            buffer_ref.clone(),
+            Instance::component(mod_ref.clone()).with_attribute("prefix", "U".to_string()),
+        );
+        let mcu_ref = InstanceRef::new(mod_ref.clone(), vec!["mcu".into()]);
+        schematic.add_instance(
+            mcu_ref.clone(),
+            Instance::component(mod_ref.clone())
+                .with_attribute("prefix", "U".to_string())
+                .with_reference_designator("U1"),
+        );
+
+        let (ref_map, conflicts) = schematic.assign_reference_designators_preserving();
+
+        assert!(conflicts.is_empty());
+        assert_eq!(ref_map[&mcu_ref], "U1");
+        assert_eq!(ref_map[&buffer_ref], "U2");
+    }
+
+    #[test]
+    fn test_assign_reference_designators_with_prefixes() {
+        let mut schematic = Schematic::new();
+        let mod_ref = ModuleRef::from_path(Path::new("/test.pmod"), "TestModule");
+
+        let mut add = |name: &str, ty: &str| {
+            let inst_ref = InstanceRef::new(mod_ref.clone(), vec![name.into()]);
+            schematic.add_instance(
+                inst_ref.clone(),
+                Instance::component(mod_ref.clone()).with_attribute("type", ty.to_string()),
+            );
+            inst_ref
+        };
+        let crystal = add("crystal", "crystal");
+        let led = add("led", "LED");
+        let sensor = add("sensor", "thermistor");
+        let widget = add("widget", "widget");
+
+        let overrides = HashMap::from([("Thermistor".to_string(), "RT".to_string())]);
+        let ref_map = schematic.assign_reference_designators_with_prefixes(&overrides);
+
+        assert_eq!(ref_map[&crystal], "Y1");
+        assert_eq!(ref_map[&led], "D1");
+        assert_eq!(ref_map[&sensor], "RT1");
+        assert_eq!(ref_map[&widget], "W1");
+    }
+
+    #[test]
+    fn test_hierarchy_iteration() {
+        let mod_ref = ModuleRef::from_path(Path::new("/test.zen"), "Test");
+        let at = |path: &[&str]| {
+            InstanceRef::new(
+                mod_ref.clone(),
+                path.iter().map(|part| part.to_string()).collect(),
+            )
+        };
+        let mut schematic = Schematic::new();
+
+        let mut power = Instance::module(mod_ref.clone());
+        for name in ["r10", "r2", "r1"] {
+            power.add_child(name, at(&["power", name]));
+            schematic.add_instance(at(&["power", name]), Instance::component(mod_ref.clone()));
+        }
+        schematic.add_instance(at(&["power"]), power);
+        schematic.add_instance(at(&["mcu"]), Instance::component(mod_ref.clone()));
+        schematic.add_instance(
+            at(&[]),
+            Instance::module(mod_ref.clone())
+                .with_child("power", at(&["power"]))
+                .with_child("mcu", at(&["mcu"])),
+        );
+
+        let names = |iter: Vec<(&InstanceRef, &Instance)>| -> Vec<String> {
+            iter.into_iter()
+                .map(|(inst_ref, _)| inst_ref.instance_path.join("."))
+                .collect()
+        };
+
+        assert_eq!(
+            names(schematic.children_of(&at(&["power"])).collect()),
+            ["power.r1", "power.r2", "power.r10"]
+        );
+        assert_eq!(
+            names(schematic.descendants(&at(&[])).collect()),
+            ["mcu", "power", "power.r1", "power.r2", "power.r10"]
+        );
+        assert_eq!(
+            schematic.parent_of(&at(&["power", "r2"])),
+            Some(&at(&["power"]))
+        );
+        assert_eq!(schematic.parent_of(&at(&["power"])), Some(&at(&[])));
+        assert_eq!(schematic.parent_of(&at(&[])), None);
+        assert_eq!(schematic.children_of(&at(&["missing"])).count(), 0);
+    }
+
+    #[test]
+    fn test_net_aliases() {
+        let mut schematic = Schematic::new();
+        for name in ["AGND", "DGND", "GND", "VCC", "LOOP_A", "LOOP_B"] {
+            schematic.add_net(Net::new(NetKind::Normal, name));
+        }
+        schematic
+            .add_net_alias("AGND", "DGND")
+            .add_net_alias("DGND", "GND")
+            .add_net_alias("LOOP_B", "LOOP_A")
+            .add_net_alias("LOOP_A", "LOOP_B");
+
+        assert_eq!(schematic.merged_net("AGND"), "GND");
+        assert_eq!(schematic.merged_net("DGND"), "GND");
+        assert_eq!(schematic.merged_net("VCC"), "VCC");
+        assert_eq!(schematic.merged_net("LOOP_A"), "LOOP_A");
+        assert_eq!(schematic.merged_net("LOOP_B"), "LOOP_A");
+
+        let groups: Vec<(&str, Vec<&str>)> = schematic
+            .merged_nets()
+            .into_iter()
+            .map(|(name, nets)| (name, nets.iter().map(|n| n.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("GND", vec!["AGND", "DGND", "GND"]),
+                ("LOOP_A", vec!["LOOP_A", "LOOP_B"]),
+                ("VCC", vec!["VCC"]),
+            ]
+        );
+
+        // Aliases survive serialization and are omitted when empty
+        let json = schematic.to_json().unwrap();
+        let parsed: Schematic = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.net_aliases, schematic.net_aliases);
+        assert!(!Schematic::new().to_json().unwrap().contains("net_aliases"));
+    }
+
+    #[test]
+    fn test_differential_pairs() {
+        let mut schematic = Schematic::new();
+        for name in [
+            "USB_P", "USB_N", "CLK+", "CLK-", "DATA_P", "RX_N", "LVDS_P", "LVDS_N",
+        ] {
+            schematic.add_net(Net::new(NetKind::Normal, name));
+        }
+
+        let pair = |positive: &str, negative: &str| DifferentialPair {
+            positive: positive.to_string(),
+            negative: negative.to_string(),
+        };
+        assert_eq!(
+            schematic.differential_pairs(&HashMap::new()),
+            [pair("LVDS_P", "LVDS_N"), pair("USB_P", "USB_N")]
+        );
+
+        // Overrides add pairs the convention misses and replace ones it finds
+        let overrides = HashMap::from([
+            ("CLK+".to_string(), "CLK-".to_string()),
+            ("LVDS_N".to_string(), "LVDS_P".to_string()),
+            ("MISSING_P".to_string(), "MISSING_N".to_string()),
+        ]);
+        assert_eq!(
+            schematic.differential_pairs(&overrides),
+            [
+                pair("CLK+", "CLK-"),
+                pair("LVDS_N", "LVDS_P"),
+                pair("USB_P", "USB_N")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace_width() {
+        let signal = Net::new(NetKind::Normal, "SDA");
+        assert_eq!(signal.trace_width(), None);
+        assert_eq!(signal.effective_trace_width(), 0.25);
+
+        let mut power = Net::new(NetKind::Power, "VBUS");
+        assert_eq!(power.effective_trace_width(), 0.5);
+        power.set_trace_width(1.2);
+        assert_eq!(power.trace_width(), Some(1.2));
+        assert_eq!(power.effective_trace_width(), 1.2);
+
+        let from_string =
+            Net::new(NetKind::Ground, "GND").with_property("trace_width", "0.8mm".to_string());
+        assert_eq!(from_string.effective_trace_width(), 0.8);
+        let invalid =
+            Net::new(NetKind::Ground, "GND").with_property("trace_width", "wide".to_string());
+        assert_eq!(invalid.effective_trace_width(), 0.5);
+    }
+
+    #[test]
+    fn test_component_of_port() {
+        let mod_ref = ModuleRef::from_path(Path::new("/test.zen"), "Test");
+        let at = |path: &[&str]| {
+            InstanceRef::new(
+                mod_ref.clone(),
+                path.iter().map(|s| s.to_string()).collect(),
+            )
+        };
+
+        let mut schematic = Schematic::new();
+        schematic.add_instance(at(&["power"]), Instance::module(mod_ref.clone()));
+        schematic.add_instance(at(&["power", "ldo"]), Instance::component(mod_ref.clone()));
+        schematic.add_instance(
+            at(&["power", "ldo", "VIN"]),
+            Instance::port(mod_ref.clone()),
+        );
+        schematic.add_instance(
+            at(&["power", "ldo", "USB"]),
+            Instance::interface(mod_ref.clone()),
+        );
+        schematic.add_instance(
+            at(&["power", "ldo", "USB", "DP"]),
+            Instance::port(mod_ref.clone()),
+        );
+
+        let ldo = at(&["power", "ldo"]);
+        let owner = |path: &[&str]| {
+            schematic
+                .component_of_port(&at(path))
+                .map(|(reference, _)| reference.clone())
+        };
+        assert_eq!(owner(&["power", "ldo", "VIN"]), Some(ldo.clone()));
+        // Ports inside interfaces resolve to the enclosing component
+        assert_eq!(owner(&["power", "ldo", "USB", "DP"]), Some(ldo));
+        // A port directly on a module has no component
+        assert_eq!(owner(&["power", "EN"]), None);
+        assert_eq!(owner(&[]), None);
+    }
 }
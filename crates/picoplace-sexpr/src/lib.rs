@@ -36,6 +36,13 @@ impl Sexpr {
         Sexpr::List(items)
     }
 
+    /// Create a list headed by the symbol `tag`, e.g. `(at 1 2 0)`
+    pub fn tagged(tag: impl Into<String>, children: impl IntoIterator<Item = Sexpr>) -> Self {
+        let mut items = vec![Sexpr::Symbol(tag.into())];
+        items.extend(children);
+        Sexpr::List(items)
+    }
+
     /// Check if this is an atom (symbol or string)
     pub fn is_atom(&self) -> bool {
         self.as_atom().is_some()
@@ -71,6 +78,103 @@ impl Sexpr {
     }
 }
 
+/// Numbers become symbols, formatted the way KiCad writes them (`0`, `2.54`)
+impl From<f64> for Sexpr {
+    fn from(value: f64) -> Self {
+        Sexpr::Symbol(value.to_string())
+    }
+}
+
+impl From<i64> for Sexpr {
+    fn from(value: i64) -> Self {
+        Sexpr::Symbol(value.to_string())
+    }
+}
+
+impl From<i32> for Sexpr {
+    fn from(value: i32) -> Self {
+        Sexpr::Symbol(value.to_string())
+    }
+}
+
+/// Text becomes a quoted string; use [`Sexpr::symbol`] for bare words
+impl From<&str> for Sexpr {
+    fn from(value: &str) -> Self {
+        Sexpr::String(value.to_string())
+    }
+}
+
+impl From<String> for Sexpr {
+    fn from(value: String) -> Self {
+        Sexpr::String(value)
+    }
+}
+
+/// Build a [`Sexpr`] from s-expression syntax.
+///
+/// Identifiers become symbols, string literals become strings, and number
+/// literals become symbols. Any other value can be spliced in with braces and
+/// is converted with [`From`], so `{x}` for an `f64` is a symbol and `{name}`
+/// for a `String` is a quoted string.
+///
+/// ```
+/// use picoplace_sexpr::{sexpr, Sexpr};
+///
+/// let x = 2.54;
+/// let at = sexpr!((at {x} -1.27 0));
+/// assert_eq!(at.to_string(), "(at 2.54 -1.27 0)");
+///
+/// let name = sexpr!((name "VCC" (effects (font (size 1.27 1.27)))));
+/// assert_eq!(
+///     name,
+///     Sexpr::tagged(
+///         "name",
+///         [
+///             Sexpr::string("VCC"),
+///             Sexpr::tagged(
+///                 "effects",
+///                 [Sexpr::tagged("font", [Sexpr::tagged("size", [1.27.into(), 1.27.into()])])],
+///             ),
+///         ],
+///     )
+/// );
+/// ```
+#[macro_export]
+macro_rules! sexpr {
+    (($($inner:tt)*)) => {
+        $crate::Sexpr::List($crate::sexpr!(@items [] $($inner)*))
+    };
+    ({$value:expr}) => {
+        $crate::Sexpr::from($value)
+    };
+    ($sym:ident) => {
+        $crate::Sexpr::symbol(stringify!($sym))
+    };
+    ($lit:literal) => {
+        $crate::Sexpr::from($lit)
+    };
+
+    // Munch list items one at a time, accumulating the converted values
+    (@items [$($out:expr,)*]) => {
+        vec![$($out,)*]
+    };
+    (@items [$($out:expr,)*] ($($inner:tt)*) $($rest:tt)*) => {
+        $crate::sexpr!(@items [$($out,)* $crate::sexpr!(($($inner)*)),] $($rest)*)
+    };
+    (@items [$($out:expr,)*] {$value:expr} $($rest:tt)*) => {
+        $crate::sexpr!(@items [$($out,)* $crate::Sexpr::from($value),] $($rest)*)
+    };
+    (@items [$($out:expr,)*] - $lit:literal $($rest:tt)*) => {
+        $crate::sexpr!(@items [$($out,)* $crate::Sexpr::from(-$lit),] $($rest)*)
+    };
+    (@items [$($out:expr,)*] $sym:ident $($rest:tt)*) => {
+        $crate::sexpr!(@items [$($out,)* $crate::sexpr!($sym),] $($rest)*)
+    };
+    (@items [$($out:expr,)*] $lit:literal $($rest:tt)*) => {
+        $crate::sexpr!(@items [$($out,)* $crate::Sexpr::from($lit),] $($rest)*)
+    };
+}
+
 /// Parser for S-expressions
 pub struct Parser<'a> {
     input: &'a str,
@@ -333,8 +437,38 @@ impl fmt::Display for ParseError {
 impl std::error::Error for ParseError {}
 
 /// Format an S-expression with proper indentation
+///
+/// The output always parses back to `sexpr`, except that symbols which can't
+/// be written bare, such as ones containing spaces, are quoted and so come
+/// back as strings.
 pub fn format_sexpr(sexpr: &Sexpr, indent_level: usize) -> String {
-    format_sexpr_inner(sexpr, indent_level, true)
+    let formatted = format_sexpr_inner(sexpr, indent_level, true);
+    debug_assert_eq!(
+        parse(&formatted).as_ref(),
+        Ok(&as_written(sexpr)),
+        "formatted S-expression does not parse back: {formatted}"
+    );
+    formatted
+}
+
+/// Whether `symbol` has to be quoted to survive a roundtrip through the
+/// parser: it is empty, contains whitespace or parentheses, or starts like a
+/// string or a comment.
+fn needs_quotes(symbol: &str) -> bool {
+    symbol.is_empty()
+        || symbol.starts_with(['"', ';'])
+        || symbol
+            .chars()
+            .any(|ch| ch.is_whitespace() || ch == '(' || ch == ')')
+}
+
+/// `sexpr` as it reads back after formatting, with quoted symbols as strings
+fn as_written(sexpr: &Sexpr) -> Sexpr {
+    match sexpr {
+        Sexpr::Symbol(s) if needs_quotes(s) => Sexpr::String(s.clone()),
+        Sexpr::List(items) => Sexpr::List(items.iter().map(as_written).collect()),
+        other => other.clone(),
+    }
 }
 
 /// Internal formatting function with control over whether to add initial indent
@@ -346,10 +480,11 @@ fn format_sexpr_inner(sexpr: &Sexpr, indent_level: usize, add_indent: bool) -> S
     };
 
     match sexpr {
-        Sexpr::Symbol(s) => {
-            // Symbols are never quoted
-            format!("{indent}{s}")
+        Sexpr::Symbol(s) if needs_quotes(s) => {
+            // Quote symbols that would otherwise corrupt the output
+            format!("{}\"{}\"", indent, escape_string(s))
         }
+        Sexpr::Symbol(s) => format!("{indent}{s}"),
         Sexpr::String(s) => {
             // Strings are always quoted
             format!("{}\"{}\"", indent, escape_string(s))
@@ -599,6 +734,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_builders() {
+        let uuid = "0000-1111".to_string();
+        let built = Sexpr::tagged(
+            "junction",
+            [
+                Sexpr::tagged("at", [50.8.into(), 25.0.into()]),
+                Sexpr::tagged("diameter", [0.into()]),
+                Sexpr::tagged("uuid", [uuid.clone().into()]),
+            ],
+        );
+        assert_eq!(
+            built,
+            parse(r#"(junction (at 50.8 25) (diameter 0) (uuid "0000-1111"))"#).unwrap()
+        );
+
+        let x = 50.8;
+        assert_eq!(
+            sexpr!((junction (at {x} 25.0) (diameter 0) (uuid {uuid}))),
+            built
+        );
+        assert_eq!(
+            sexpr!((stroke (width -1) (type default) (color 0 0 0 0))),
+            parse("(stroke (width -1) (type default) (color 0 0 0 0))").unwrap()
+        );
+        assert_eq!(sexpr!((pts)), Sexpr::List(vec![Sexpr::symbol("pts")]));
+    }
+
+    #[test]
+    fn test_quotes_unsafe_symbols() {
+        let sexpr = Sexpr::tagged(
+            "property",
+            [
+                Sexpr::symbol("Sim Name"),
+                Sexpr::symbol("f(x)"),
+                Sexpr::symbol(""),
+                Sexpr::symbol(";note"),
+                Sexpr::symbol("\"quoted\""),
+                Sexpr::symbol("0"),
+                Sexpr::string("0"),
+            ],
+        );
+
+        let formatted = format_sexpr(&sexpr, 0);
+        assert_eq!(
+            parse(&formatted).unwrap(),
+            Sexpr::tagged(
+                "property",
+                [
+                    Sexpr::string("Sim Name"),
+                    Sexpr::string("f(x)"),
+                    Sexpr::string(""),
+                    Sexpr::string(";note"),
+                    Sexpr::string("\"quoted\""),
+                    Sexpr::symbol("0"),
+                    Sexpr::string("0"),
+                ],
+            )
+        );
+    }
+
     #[test]
     fn test_utf8_handling() {
         // Test with multi-byte UTF-8 characters
@@ -22,8 +22,13 @@ use std::collections::HashSet;
 use std::fmt::Debug;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::RwLock;
+use std::time::Duration;
 
 use derivative::Derivative;
 use derive_more::Display;
@@ -38,11 +43,15 @@ use lsp_server::ResponseError;
 use lsp_types::notification::DidChangeTextDocument;
 use lsp_types::notification::DidCloseTextDocument;
 use lsp_types::notification::DidOpenTextDocument;
+use lsp_types::notification::Exit;
 use lsp_types::notification::LogMessage;
+use lsp_types::notification::Notification as _;
 use lsp_types::notification::PublishDiagnostics;
 use lsp_types::request::Completion;
 use lsp_types::request::GotoDefinition;
 use lsp_types::request::HoverRequest;
+use lsp_types::request::Request as _;
+use lsp_types::request::Shutdown;
 use lsp_types::CompletionItem;
 use lsp_types::CompletionItemKind;
 use lsp_types::CompletionOptions;
@@ -77,6 +86,7 @@ use lsp_types::TextDocumentSyncCapability;
 use lsp_types::TextDocumentSyncKind;
 use lsp_types::TextEdit;
 use lsp_types::Url;
+use lsp_types::VersionedTextDocumentIdentifier;
 use lsp_types::WorkDoneProgressOptions;
 use lsp_types::WorkspaceFolder;
 use serde::de::DeserializeOwned;
@@ -258,10 +268,13 @@ pub struct StringLiteralResult {
     /// A function that takes the AstModule at path specified by `url`, and
     /// allows resolving a location to jump to within the specific URL if desired.
     ///
-    /// If `None`, then just jump to the URL. Do not attempt to load the file.
+    /// If `None`, then just jump to the URL (or `range`). Do not attempt to load the file.
     #[derivative(Debug = "ignore")]
     #[allow(clippy::type_complexity)]
     pub location_finder: Option<Box<dyn FnOnce(&AstModule) -> anyhow::Result<Option<Span>> + Send>>,
+    /// A location within `url` that is already known, used when there is no
+    /// `location_finder`. This allows jumping into files that are not Starlark.
+    pub range: Option<Range>,
 }
 
 fn _assert_string_literal_result_is_send() {
@@ -270,7 +283,7 @@ fn _assert_string_literal_result_is_send() {
 }
 
 /// The result of evaluating a starlark program for use in the LSP.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct LspEvalResult {
     /// The list of diagnostic issues that were encountered while evaluating a starlark program.
     pub diagnostics: Vec<Diagnostic>,
@@ -299,6 +312,19 @@ pub trait LspContext {
     /// Parse a file with the given contents. The filename is used in the diagnostics.
     fn parse_file_with_contents(&self, uri: &LspUrl, content: String) -> LspEvalResult;
 
+    /// Like [`Self::parse_file_with_contents`], but may give up early once `cancel` is set
+    /// because a newer edit of the document superseded this parse. The result of a cancelled
+    /// parse is discarded.
+    fn parse_file_with_contents_cancellable(
+        &self,
+        uri: &LspUrl,
+        content: String,
+        cancel: Arc<AtomicBool>,
+    ) -> LspEvalResult {
+        let _ = cancel;
+        self.parse_file_with_contents(uri, content)
+    }
+
     /// Resolve a path given in a `load()` statement.
     ///
     /// `path` is the string representation in the `load()` statement. Its meaning is
@@ -337,6 +363,19 @@ pub trait LspContext {
         workspace_root: Option<&Path>,
     ) -> anyhow::Result<Option<StringLiteralResult>>;
 
+    /// Like [`Self::resolve_string_literal`], for the literal at `span` in `current_file`. This
+    /// lets a context resolve a literal differently depending on where it appears.
+    fn resolve_string_literal_at(
+        &self,
+        literal: &str,
+        span: ResolvedSpan,
+        current_file: &LspUrl,
+        workspace_root: Option<&Path>,
+    ) -> anyhow::Result<Option<StringLiteralResult>> {
+        let _ = span;
+        self.resolve_string_literal(literal, current_file, workspace_root)
+    }
+
     /// Get the contents of a starlark program at a given path, if it exists.
     fn get_load_contents(&self, uri: &LspUrl) -> anyhow::Result<Option<String>>;
 
@@ -368,6 +407,18 @@ pub trait LspContext {
         None
     }
 
+    /// Return the parameters of a callable `symbol` that is not a plain
+    /// `def` (e.g. a loaded module), as visible from `current_file`, for
+    /// signature help. Implementations can return `None` to fall back to
+    /// looking up `def` statements.
+    fn get_signature(
+        &self,
+        _current_file: &LspUrl,
+        _symbol: &str,
+    ) -> Option<Vec<SignatureParameter>> {
+        None
+    }
+
     /// Get valid completion options if possible, based on the kind of string
     /// completion expected (e.g. any string literal, versus the path argument in
     /// a load statement).
@@ -453,6 +504,18 @@ pub trait LspContext {
     ) -> anyhow::Result<Option<Hover>> {
         Ok(None)
     }
+
+    /// Provide custom hover information for a variable defined in the current file.
+    ///
+    /// This is consulted when the variable has no documentation of its own, e.g. to
+    /// describe the value it evaluates to. Return `None` to show nothing.
+    fn get_custom_hover_for_identifier(
+        &self,
+        _name: &str,
+        _current_file: &LspUrl,
+    ) -> anyhow::Result<Option<Hover>> {
+        Ok(None)
+    }
 }
 
 /// Errors when [`LspContext::resolve_load()`] cannot resolve a given path.
@@ -477,6 +540,18 @@ pub(crate) struct Backend<T: LspContext> {
     /// The `AstModule` from the last time that a file was opened / changed and parsed successfully.
     /// Entries are evicted when the file is closed.
     pub(crate) last_valid_parse: RwLock<HashMap<LspUrl, Arc<LspModule>>>,
+    /// Shared with the thread receiving messages so that a newer edit can cancel the parse it
+    /// supersedes.
+    in_flight: Arc<Mutex<InFlightParses>>,
+}
+
+/// Parses that may be superseded by edits which have been received but not handled yet.
+#[derive(Default)]
+struct InFlightParses {
+    /// Cancellation flag of the running parse of each document.
+    tokens: HashMap<Url, Arc<AtomicBool>>,
+    /// Number of edits received for each document that have not been validated yet.
+    pending_edits: HashMap<Url, usize>,
 }
 
 /// The logic implementations of stuff
@@ -524,7 +599,32 @@ impl<T: LspContext> Backend<T> {
 
     fn validate(&self, uri: Url, version: Option<i64>, text: String) -> anyhow::Result<()> {
         let lsp_url = uri.clone().try_into()?;
-        let eval_result = self.context.parse_file_with_contents(&lsp_url, text);
+        let cancel = Arc::new(AtomicBool::new(false));
+        {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            // A newer edit is already queued; it will be validated next
+            if in_flight.pending_edits.contains_key(&uri) {
+                return Ok(());
+            }
+            in_flight.tokens.insert(uri.clone(), cancel.dupe());
+        }
+        let eval_result =
+            self.context
+                .parse_file_with_contents_cancellable(&lsp_url, text, cancel.dupe());
+        {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            if in_flight
+                .tokens
+                .get(&uri)
+                .is_some_and(|token| Arc::ptr_eq(token, &cancel))
+            {
+                in_flight.tokens.remove(&uri);
+            }
+        }
+        // The edit that superseded this parse is validated next
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
         if let Some(ast) = eval_result.ast {
             let module = Arc::new(LspModule::new(ast));
             let mut last_valid_parse = self.last_valid_parse.write().unwrap();
@@ -551,6 +651,16 @@ impl<T: LspContext> Backend<T> {
     fn did_change(&self, params: DidChangeTextDocumentParams) -> anyhow::Result<()> {
         // We asked for Sync full, so can just grab all the text from params
         let change = params.content_changes.into_iter().next().unwrap();
+        {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            let uri = &params.text_document.uri;
+            if let Some(pending) = in_flight.pending_edits.get_mut(uri) {
+                *pending -= 1;
+                if *pending == 0 {
+                    in_flight.pending_edits.remove(uri);
+                }
+            }
+        }
         self.validate(
             params.text_document.uri,
             Some(params.text_document.version as i64),
@@ -730,7 +840,7 @@ impl<T: LspContext> Backend<T> {
             IdentifierDefinition::StringLiteral { literal, .. } => {
                 let Ok(resolved_literal) =
                     self.context
-                        .resolve_string_literal(&literal, uri, workspace_root)
+                        .resolve_string_literal_at(&literal, source, uri, workspace_root)
                 else {
                     return Ok(None);
                 };
@@ -738,6 +848,7 @@ impl<T: LspContext> Backend<T> {
                     Some(StringLiteralResult {
                         url,
                         location_finder: Some(location_finder),
+                        ..
                     }) => {
                         // If there's an error loading the file to parse it, at least
                         // try to get to the file.
@@ -762,7 +873,8 @@ impl<T: LspContext> Backend<T> {
                     Some(StringLiteralResult {
                         url,
                         location_finder: None,
-                    }) => Self::location_link(source, &url, Range::default())?,
+                        range,
+                    }) => Self::location_link(source, &url, range.unwrap_or_default())?,
                     _ => None,
                 }
             }
@@ -1202,7 +1314,7 @@ impl<T: LspContext> Backend<T> {
                 // TODO: This seems very inefficient. Once the document starts
                 // holding the `Scope` including AST nodes, this indirection
                 // should be removed.
-                find_symbols_at_location(
+                let documented = find_symbols_at_location(
                     document.ast.codemap(),
                     document.ast.statement(),
                     ResolvedPos {
@@ -1228,7 +1340,19 @@ impl<T: LspContext> Backend<T> {
                                 range: Some(source.into()),
                             })
                         })
-                })
+                });
+
+                match documented {
+                    Some(hover) => Some(hover),
+                    // Nothing documented, ask the context about the value instead
+                    None => self
+                        .context
+                        .get_custom_hover_for_identifier(&name, document_uri)?
+                        .map(|hover| Hover {
+                            range: hover.range.or(Some(source.into())),
+                            ..hover
+                        }),
+                }
             }
             IdentifierDefinition::LoadedLocation {
                 path, name, source, ..
@@ -1257,10 +1381,12 @@ impl<T: LspContext> Backend<T> {
                 })
             }
             IdentifierDefinition::StringLiteral { source, literal } => {
-                let Ok(resolved_literal) =
-                    self.context
-                        .resolve_string_literal(&literal, document_uri, workspace_root)
-                else {
+                let Ok(resolved_literal) = self.context.resolve_string_literal_at(
+                    &literal,
+                    source,
+                    document_uri,
+                    workspace_root,
+                ) else {
                     // We might just be hovering a string that's not a file/target/etc,
                     // so just return nothing.
                     return Ok(None);
@@ -1269,6 +1395,7 @@ impl<T: LspContext> Backend<T> {
                     Some(StringLiteralResult {
                         url,
                         location_finder: Some(location_finder),
+                        ..
                     }) => {
                         // If there's an error loading the file to parse it, at least
                         // try to get to the file.
@@ -1520,7 +1647,30 @@ impl<T: LspContext> Backend<T> {
 
         // Pre-parse relevant files.
         self.preload_workspace(&initialize_params);
-        for msg in &self.connection.receiver {
+
+        // Receive messages on their own thread so that an edit arriving while its document is
+        // still being parsed cancels that parse instead of queueing up behind it.
+        let (sender, receiver) = mpsc::channel();
+        let incoming = self.connection.receiver.clone();
+        let in_flight = self.in_flight.dupe();
+        std::thread::spawn(move || {
+            for msg in incoming {
+                if let Message::Notification(x) = &msg {
+                    if let Some(document) = changed_document(x) {
+                        let mut in_flight = in_flight.lock().unwrap();
+                        if let Some(token) = in_flight.tokens.get(&document.uri) {
+                            token.store(true, Ordering::Relaxed);
+                        }
+                        *in_flight.pending_edits.entry(document.uri).or_default() += 1;
+                    }
+                }
+                if sender.send(msg).is_err() {
+                    break;
+                }
+            }
+        });
+
+        for msg in &receiver {
             match msg {
                 Message::Request(req) => {
                     // TODO(nmj): Also implement DocumentSymbols so that some logic can
@@ -1533,8 +1683,17 @@ impl<T: LspContext> Backend<T> {
                         self.completion(req.id, params, &initialize_params);
                     } else if let Some(params) = as_request::<HoverRequest>(&req) {
                         self.hover(req.id, params, &initialize_params);
-                    } else if self.connection.handle_shutdown(&req)? {
-                        return Ok(());
+                    } else if req.method == Shutdown::METHOD {
+                        // `Connection::handle_shutdown` would wait for the `exit` notification
+                        // on the connection, but the receiving thread consumes those messages.
+                        self.send_response(Response::new_ok(req.id, ()));
+                        return match receiver.recv_timeout(Duration::from_secs(30)) {
+                            Ok(Message::Notification(x)) if x.method == Exit::METHOD => Ok(()),
+                            Ok(msg) => Err(anyhow::anyhow!(
+                                "Unexpected message during shutdown: {msg:?}"
+                            )),
+                            Err(e) => Err(anyhow::anyhow!("Unexpected error during shutdown: {e}")),
+                        };
                     } else if let Some(resp) =
                         self.context.handle_custom_request(&req, &initialize_params)
                     {
@@ -1603,12 +1762,23 @@ pub fn server_with_connection<T: LspContext>(
         connection,
         context,
         last_valid_parse: RwLock::default(),
+        in_flight: Arc::default(),
     }
     .main_loop(initialization_params)?;
 
     Ok(())
 }
 
+/// The document a `didChange` notification edits. Unlike [`as_notification`] this neither
+/// copies the new contents nor panics on malformed parameters.
+fn changed_document(x: &Notification) -> Option<VersionedTextDocumentIdentifier> {
+    if x.method == DidChangeTextDocument::METHOD {
+        serde_json::from_value(x.params.get("textDocument")?.clone()).ok()
+    } else {
+        None
+    }
+}
+
 fn as_notification<T>(x: &Notification) -> Option<T::Params>
 where
     T: lsp_types::notification::Notification,
@@ -1686,13 +1856,17 @@ mod tests {
     use anyhow::Context;
     use lsp_server::Request;
     use lsp_server::RequestId;
+    use lsp_types::notification::DidOpenTextDocument;
+    use lsp_types::notification::PublishDiagnostics;
     use lsp_types::request::GotoDefinition;
+    use lsp_types::DidOpenTextDocumentParams;
     use lsp_types::GotoDefinitionParams;
     use lsp_types::GotoDefinitionResponse;
     use lsp_types::LocationLink;
     use lsp_types::Position;
     use lsp_types::Range;
     use lsp_types::TextDocumentIdentifier;
+    use lsp_types::TextDocumentItem;
     use lsp_types::TextDocumentPositionParams;
     use lsp_types::Url;
     use starlark::codemap::ResolvedSpan;
@@ -1700,12 +1874,14 @@ mod tests {
     use textwrap::dedent;
 
     use crate::definition::helpers::FixtureWithRanges;
+    use crate::server::new_notification;
     use crate::server::LspServerSettings;
     use crate::server::LspUrl;
     use crate::server::StarlarkFileContentsParams;
     use crate::server::StarlarkFileContentsRequest;
     use crate::server::StarlarkFileContentsResponse;
     use crate::test::TestServer;
+    use crate::test::BLOCK_UNTIL_CANCELLED;
 
     fn goto_definition_request(
         server: &mut TestServer,
@@ -2822,6 +2998,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn newer_edit_cancels_in_flight_parse() -> anyhow::Result<()> {
+        if is_wasm() {
+            return Ok(());
+        }
+
+        let mut server = TestServer::new()?;
+        let uri = temp_file_uri("slow.star");
+
+        // Opening starts a parse that only ends once it is cancelled
+        server.send_notification(new_notification::<DidOpenTextDocument>(
+            DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: uri.clone(),
+                    language_id: String::new(),
+                    version: 0,
+                    text: format!("{BLOCK_UNTIL_CANCELLED}\n"),
+                },
+            },
+        ))?;
+        server.change_file(uri.clone(), "x = 1\n".to_owned())?;
+
+        // Only the newer edit is reported, well before the blocked parse would give up
+        let diagnostics = server.get_notification::<PublishDiagnostics>()?;
+        assert_eq!(diagnostics.uri, uri);
+        assert_eq!(diagnostics.version, Some(1));
+        assert!(diagnostics.diagnostics.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn custom_request_echo() -> anyhow::Result<()> {
         if starlark::wasm::is_wasm() {
@@ -2844,6 +3050,15 @@ mod tests {
     }
 }
 
+/// A parameter of a callable, as shown in signature help.
+#[derive(Debug, Clone)]
+pub struct SignatureParameter {
+    pub name: String,
+    /// Text shown for the parameter, e.g. `voltage: float = 3.3`
+    pub label: String,
+    pub documentation: Option<String>, // markdown
+}
+
 /// Rich metadata about a symbol used to enhance completion items.
 #[derive(Debug, Clone)]
 pub struct CompletionMeta {
@@ -21,9 +21,12 @@ use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::RwLock;
 use std::time::Duration;
+use std::time::Instant;
 
 use dupe::Dupe;
 use lsp_server::Connection;
@@ -115,6 +118,10 @@ pub(crate) enum TestServerError {
     IsADirectory(LspUrl),
 }
 
+/// Parsing a document containing this comment blocks until the parse is cancelled (or a few
+/// seconds pass), standing in for a slow evaluation.
+pub(crate) const BLOCK_UNTIL_CANCELLED: &str = "# block until cancelled";
+
 struct TestServerContext {
     file_contents: Arc<RwLock<HashMap<PathBuf, String>>>,
     dirs: Arc<RwLock<HashSet<PathBuf>>>,
@@ -155,6 +162,21 @@ impl LspContext for TestServerContext {
         }
     }
 
+    fn parse_file_with_contents_cancellable(
+        &self,
+        uri: &LspUrl,
+        content: String,
+        cancel: Arc<AtomicBool>,
+    ) -> LspEvalResult {
+        if content.contains(BLOCK_UNTIL_CANCELLED) {
+            let deadline = Instant::now() + Duration::from_secs(10);
+            while !cancel.load(Ordering::Relaxed) && Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+        self.parse_file_with_contents(uri, content)
+    }
+
     fn resolve_load(
         &self,
         path: &str,
@@ -248,10 +270,12 @@ impl LspContext for TestServerContext {
                     Some(e) if e == "star" || e == "zen" => Some(StringLiteralResult {
                         url,
                         location_finder: Some(Box::new(move |_ast| Ok(span))),
+                        range: None,
                     }),
                     _ => Some(StringLiteralResult {
                         url,
                         location_finder: None,
+                        range: None,
                     }),
                 },
                 _ => None,
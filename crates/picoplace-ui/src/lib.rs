@@ -14,16 +14,20 @@
 //! spinner.success("Done!");
 //! ```
 
+mod mode;
 mod progress;
 mod spinner;
 mod style;
+mod tasks;
 mod terminal;
 
+pub use mode::{output_mode, set_output_mode, OutputMode, OUTPUT_MODE_ENV};
 pub use progress::{ProgressBar, ProgressBarBuilder};
 pub use spinner::{Spinner, SpinnerBuilder};
 pub use style::{icons, Style, StyledText};
+pub use tasks::{TaskList, TaskListBuilder, TaskState};
 pub use terminal::{
-    clear_line, get_terminal_size, pad_text, truncate_text, Alignment, TerminalSize,
+    clear_line, clear_screen, get_terminal_size, pad_text, truncate_text, Alignment, TerminalSize,
 };
 
 // Re-export commonly used items from dependencies
@@ -35,6 +39,7 @@ pub mod prelude {
         progress::{ProgressBar, ProgressBarBuilder},
         spinner::{Spinner, SpinnerBuilder},
         style::{Style, StyledText},
+        tasks::{TaskList, TaskListBuilder, TaskState},
         Colorize,
     };
 }
@@ -0,0 +1,157 @@
+use serde_json::json;
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How UI components render their output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Animated spinners and progress bars with colors
+    Interactive,
+    /// One plain line per event, without animation or ANSI escape sequences
+    Plain,
+    /// No spinner or progress output at all
+    Silent,
+    /// One JSON object per event on stderr, for tools consuming the output
+    Json,
+}
+
+/// Environment variable that selects the output mode
+/// (`interactive`, `plain`, `silent` or `json`)
+pub const OUTPUT_MODE_ENV: &str = "PICOPLACE_OUTPUT";
+
+/// Not set yet; detected from the environment on first use
+const UNSET: u8 = 0;
+
+static MODE: AtomicU8 = AtomicU8::new(UNSET);
+
+impl OutputMode {
+    fn to_u8(self) -> u8 {
+        match self {
+            OutputMode::Interactive => 1,
+            OutputMode::Plain => 2,
+            OutputMode::Silent => 3,
+            OutputMode::Json => 4,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(OutputMode::Interactive),
+            2 => Some(OutputMode::Plain),
+            3 => Some(OutputMode::Silent),
+            4 => Some(OutputMode::Json),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for OutputMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "interactive" => Ok(OutputMode::Interactive),
+            "plain" => Ok(OutputMode::Plain),
+            "silent" => Ok(OutputMode::Silent),
+            "json" => Ok(OutputMode::Json),
+            _ => Err(format!("unknown output mode '{s}'")),
+        }
+    }
+}
+
+/// Set the output mode for all UI components, overriding detection.
+///
+/// Colors are only emitted in [`OutputMode::Interactive`].
+pub fn set_output_mode(mode: OutputMode) {
+    MODE.store(mode.to_u8(), Ordering::Relaxed);
+    colored::control::set_override(mode == OutputMode::Interactive);
+}
+
+/// The current output mode.
+///
+/// Unless set with [`set_output_mode`], this is taken from the
+/// [`OUTPUT_MODE_ENV`] variable if set, otherwise [`OutputMode::Plain`] when
+/// `NO_COLOR` is set or stdout is not a terminal, and
+/// [`OutputMode::Interactive`] otherwise.
+pub fn output_mode() -> OutputMode {
+    if let Some(mode) = OutputMode::from_u8(MODE.load(Ordering::Relaxed)) {
+        return mode;
+    }
+
+    let mode = std::env::var(OUTPUT_MODE_ENV)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| {
+            detect_output_mode(
+                std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+                std::io::stdout().is_terminal(),
+            )
+        });
+    // Another thread may have set the mode in the meantime; keep theirs
+    match MODE.compare_exchange(UNSET, mode.to_u8(), Ordering::Relaxed, Ordering::Relaxed) {
+        Ok(_) => {
+            if mode != OutputMode::Interactive {
+                colored::control::set_override(false);
+            }
+            mode
+        }
+        Err(current) => OutputMode::from_u8(current).unwrap_or(mode),
+    }
+}
+
+/// Print a `{"event":"step",...}` object to stderr in [`OutputMode::Json`]
+pub(crate) fn emit_step(label: &str, status: &str) {
+    eprintln!(
+        "{}",
+        json!({ "event": "step", "label": label, "status": status })
+    );
+}
+
+/// Print a `{"event":"progress",...}` object to stderr in [`OutputMode::Json`]
+pub(crate) fn emit_progress(label: &str, position: u64, total: u64) {
+    eprintln!(
+        "{}",
+        json!({ "event": "progress", "label": label, "position": position, "total": total })
+    );
+}
+
+fn detect_output_mode(no_color: bool, is_terminal: bool) -> OutputMode {
+    if no_color || !is_terminal {
+        OutputMode::Plain
+    } else {
+        OutputMode::Interactive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_output_mode() {
+        assert_eq!(detect_output_mode(false, true), OutputMode::Interactive);
+        assert_eq!(detect_output_mode(true, true), OutputMode::Plain);
+        assert_eq!(detect_output_mode(false, false), OutputMode::Plain);
+    }
+
+    #[test]
+    fn test_mode_roundtrip() {
+        for mode in [
+            OutputMode::Interactive,
+            OutputMode::Plain,
+            OutputMode::Silent,
+            OutputMode::Json,
+        ] {
+            assert_eq!(OutputMode::from_u8(mode.to_u8()), Some(mode));
+        }
+        assert_eq!(OutputMode::from_u8(UNSET), None);
+    }
+
+    #[test]
+    fn test_parse_output_mode() {
+        assert_eq!("json".parse(), Ok(OutputMode::Json));
+        assert_eq!("Plain".parse(), Ok(OutputMode::Plain));
+        assert!("fancy".parse::<OutputMode>().is_err());
+    }
+}
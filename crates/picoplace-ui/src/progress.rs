@@ -1,8 +1,15 @@
 use colored::Colorize;
-use indicatif::{MultiProgress, ProgressBar as IndicatifBar, ProgressDrawTarget, ProgressStyle};
+use indicatif::style::ProgressTracker;
+use indicatif::{
+    MultiProgress, ProgressBar as IndicatifBar, ProgressDrawTarget, ProgressState, ProgressStyle,
+};
 use once_cell::sync::Lazy;
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
+use crate::mode::{emit_progress, emit_step, output_mode, OutputMode};
 use crate::style::Style;
 
 static MULTI: Lazy<MultiProgress> = Lazy::new(MultiProgress::new);
@@ -10,10 +17,23 @@ static MULTI: Lazy<MultiProgress> = Lazy::new(MultiProgress::new);
 /// Default tick characters for progress bars (includes completion checkmark)
 const DEFAULT_TICK_CHARS: &str = "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏✓";
 
+/// Template key rendering elapsed time, ETA and rate (see [`ProgressBarBuilder::with_eta`])
+const ETA_KEY: &str = "eta_info";
+
+/// Number of recent position updates used to estimate the rate
+const RATE_WINDOW: usize = 16;
+
+/// Percentage step between progress lines in [`OutputMode::Plain`] and
+/// progress events in [`OutputMode::Json`]
+const PLAIN_PERCENT_STEP: u64 = 10;
+
 /// A progress bar for showing determinate progress
 pub struct ProgressBar {
     bar: IndicatifBar,
     total: u64,
+    mode: OutputMode,
+    /// Last percentage printed in plain mode
+    reported_percent: AtomicU64,
 }
 
 impl ProgressBar {
@@ -25,11 +45,13 @@ impl ProgressBar {
     /// Set the current position
     pub fn set_position(&self, pos: u64) {
         self.bar.set_position(pos);
+        self.report_progress();
     }
 
     /// Increment the position by the given amount
     pub fn inc(&self, delta: u64) {
         self.bar.inc(delta);
+        self.report_progress();
     }
 
     /// Set the message displayed with the progress bar
@@ -55,25 +77,64 @@ impl ProgressBar {
     /// Finish the progress bar with a success message
     pub fn success(self, message: impl Into<String>) {
         let msg = message.into();
-        self.bar
-            .finish_with_message(format!("{} {}", "✓".green(), msg));
+        self.finish_step(format!("{} {}", "✓".green(), msg), &msg, "ok");
     }
 
     /// Finish the progress bar with an error message
     pub fn error(self, message: impl Into<String>) {
         let msg = message.into();
-        self.bar
-            .finish_with_message(format!("{} {}", "✗".red(), msg));
+        self.finish_step(format!("{} {}", "✗".red(), msg), &msg, "error");
     }
 
     /// Finish and clear the progress bar
     pub fn finish(self) {
+        if self.mode == OutputMode::Json {
+            emit_step(&self.bar.message(), "done");
+        }
         self.bar.finish_and_clear();
     }
 
     /// Finish with a custom message
     pub fn finish_with_message(self, message: impl Into<String>) {
-        self.bar.finish_with_message(message.into());
+        let message = message.into();
+        self.finish_step(message.clone(), &message, "done");
+    }
+
+    /// Finish with `line` on a terminal or in plain mode, or report `label`
+    /// with `status` in JSON mode
+    fn finish_step(self, line: String, label: &str, status: &str) {
+        match self.mode {
+            OutputMode::Interactive => self.bar.finish_with_message(line),
+            OutputMode::Plain => {
+                self.bar.finish_and_clear();
+                eprintln!("{line}");
+            }
+            OutputMode::Json => {
+                self.bar.finish_and_clear();
+                emit_step(label, status);
+            }
+            OutputMode::Silent => self.bar.finish_and_clear(),
+        }
+    }
+
+    /// Print a percentage line (or JSON progress event) each time another
+    /// [`PLAIN_PERCENT_STEP`] is reached
+    fn report_progress(&self) {
+        if !matches!(self.mode, OutputMode::Plain | OutputMode::Json) || self.total == 0 {
+            return;
+        }
+        let percent = plain_percent(self.position(), self.total);
+        if percent > self.reported_percent.fetch_max(percent, Ordering::Relaxed) {
+            let message = self.bar.message();
+            if self.mode == OutputMode::Json {
+                emit_progress(&message, self.position(), self.total);
+            } else {
+                eprintln!(
+                    "{}",
+                    plain_progress_line(&message, percent, self.position(), self.total)
+                );
+            }
+        }
     }
 
     /// Temporarily hide the progress bar (useful when showing other output)
@@ -81,6 +142,9 @@ impl ProgressBar {
     where
         F: FnOnce() -> R,
     {
+        if self.mode != OutputMode::Interactive {
+            return f();
+        }
         self.bar.set_draw_target(ProgressDrawTarget::hidden());
         let result = f();
         self.bar.set_draw_target(ProgressDrawTarget::stderr());
@@ -98,6 +162,7 @@ pub struct ProgressBarBuilder {
     progress_chars: String,
     tick_chars: String,
     tick_interval: Option<Duration>,
+    eta_display: EtaDisplay,
     hidden: bool,
 }
 
@@ -111,6 +176,7 @@ impl ProgressBarBuilder {
             progress_chars: "=> ".to_string(),
             tick_chars: DEFAULT_TICK_CHARS.to_string(),
             tick_interval: Some(Duration::from_millis(100)),
+            eta_display: EtaDisplay::default(),
             hidden: false,
         }
     }
@@ -129,6 +195,7 @@ impl ProgressBarBuilder {
 
     /// Set a custom template (advanced usage)
     /// Default: "|{bar:40.green/gray}| {spinner:.green} [{pos}/{len}] {msg}"
+    /// (followed by " {eta_info}" when elapsed time, ETA or rate are shown)
     pub fn template(mut self, template: impl Into<String>) -> Self {
         self.template = Some(template.into());
         self
@@ -152,35 +219,101 @@ impl ProgressBarBuilder {
         self
     }
 
+    /// Show elapsed time, estimated time remaining and items/sec,
+    /// e.g. "00:12 elapsed (ETA 00:42, 12/s)".
+    /// Custom templates can place this with the `{eta_info}` key.
+    /// With a total of 0 (indeterminate) the ETA is omitted.
+    pub fn with_eta(mut self, eta: bool) -> Self {
+        self.eta_display = EtaDisplay {
+            elapsed: eta,
+            eta,
+            rate: eta,
+        };
+        self
+    }
+
+    /// Show the estimated time remaining, e.g. "(ETA 00:42)"
+    pub fn show_eta(mut self, show: bool) -> Self {
+        self.eta_display.eta = show;
+        self
+    }
+
+    /// Show the throughput in items/sec, e.g. "(12/s)"
+    pub fn show_rate(mut self, show: bool) -> Self {
+        self.eta_display.rate = show;
+        self
+    }
+
     /// Hide the progress bar (useful for non-interactive environments)
     pub fn hidden(mut self, hidden: bool) -> Self {
         self.hidden = hidden;
         self
     }
 
-    /// Start the progress bar
+    /// Start the progress bar.
+    /// Outside [`OutputMode::Interactive`] nothing is drawn; in
+    /// [`OutputMode::Plain`] the initial and final messages are printed as
+    /// lines, and in [`OutputMode::Json`] progress is reported as events.
     pub fn start(self) -> ProgressBar {
+        let mode = if self.hidden {
+            OutputMode::Silent
+        } else {
+            output_mode()
+        };
+        if mode != OutputMode::Interactive {
+            let bar = IndicatifBar::hidden();
+            bar.set_length(self.total);
+            if let Some(message) = &self.message {
+                if mode == OutputMode::Plain {
+                    eprintln!("{message}");
+                }
+                bar.set_message(message.clone());
+            }
+            if mode == OutputMode::Json {
+                emit_progress(self.message.as_deref().unwrap_or_default(), 0, self.total);
+            }
+            return ProgressBar {
+                bar,
+                total: self.total,
+                mode,
+                reported_percent: AtomicU64::new(0),
+            };
+        }
+
         let bar = MULTI.add(IndicatifBar::new(self.total));
 
+        let eta_display = self.eta_display;
         let template = self.template.unwrap_or_else(|| {
-            match self.style {
+            let template = match self.style {
                 Style::Green => "|{bar:40.green/gray}| {spinner:.green} [{pos}/{len}] {msg}",
                 Style::Yellow => "|{bar:40.yellow/gray}| {spinner:.yellow} [{pos}/{len}] {msg}",
                 Style::Red => "|{bar:40.red/gray}| {spinner:.red} [{pos}/{len}] {msg}",
                 Style::Blue => "|{bar:40.blue/gray}| {spinner:.blue} [{pos}/{len}] {msg}",
                 Style::Cyan => "|{bar:40.cyan/gray}| {spinner:.cyan} [{pos}/{len}] {msg}",
                 Style::Default => "|{bar:40.white/gray}| {spinner} [{pos}/{len}] {msg}",
+            };
+            if eta_display.any() {
+                format!("{template} {{{ETA_KEY}}}")
+            } else {
+                template.to_string()
             }
-            .to_string()
         });
 
-        bar.set_style(
-            ProgressStyle::default_bar()
-                .template(&template)
-                .unwrap()
-                .progress_chars(&self.progress_chars)
-                .tick_chars(&self.tick_chars),
-        );
+        let mut style = ProgressStyle::default_bar()
+            .template(&template)
+            .unwrap()
+            .progress_chars(&self.progress_chars)
+            .tick_chars(&self.tick_chars);
+        if eta_display.any() {
+            style = style.with_key(
+                ETA_KEY,
+                EtaTracker {
+                    display: eta_display,
+                    samples: VecDeque::new(),
+                },
+            );
+        }
+        bar.set_style(style);
 
         if let Some(message) = self.message {
             bar.set_message(message);
@@ -190,15 +323,153 @@ impl ProgressBarBuilder {
             bar.enable_steady_tick(interval);
         }
 
-        if self.hidden {
-            bar.set_draw_target(ProgressDrawTarget::hidden());
-        }
-
         ProgressBar {
             bar,
             total: self.total,
+            mode,
+            reported_percent: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Percentage complete, rounded down to a multiple of [`PLAIN_PERCENT_STEP`]
+fn plain_percent(pos: u64, total: u64) -> u64 {
+    (pos.min(total) * 100 / total) / PLAIN_PERCENT_STEP * PLAIN_PERCENT_STEP
+}
+
+/// Progress line for plain output, e.g. "Exporting 30% (3/10)"
+fn plain_progress_line(message: &str, percent: u64, pos: u64, total: u64) -> String {
+    if message.is_empty() {
+        format!("{percent}% ({pos}/{total})")
+    } else {
+        format!("{message} {percent}% ({pos}/{total})")
+    }
+}
+
+/// Which timing details to render under [`ETA_KEY`]
+#[derive(Debug, Clone, Copy, Default)]
+struct EtaDisplay {
+    elapsed: bool,
+    eta: bool,
+    rate: bool,
+}
+
+impl EtaDisplay {
+    fn any(&self) -> bool {
+        self.elapsed || self.eta || self.rate
+    }
+}
+
+/// Tracks recent position updates to estimate throughput and remaining time
+#[derive(Clone)]
+struct EtaTracker {
+    display: EtaDisplay,
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl EtaTracker {
+    /// Items per second over the sample window, falling back to the average
+    /// since the bar started until enough updates have been seen
+    fn rate(&self, state: &ProgressState) -> Option<f64> {
+        let (start, first) = self.samples.front()?;
+        let (end, last) = self.samples.back()?;
+        let secs = end.duration_since(*start).as_secs_f64();
+        if secs > 0.0 {
+            return Some(last.saturating_sub(*first) as f64 / secs);
+        }
+        let elapsed = state.elapsed().as_secs_f64();
+        (elapsed > 0.0 && state.pos() > 0).then(|| state.pos() as f64 / elapsed)
+    }
+}
+
+impl ProgressTracker for EtaTracker {
+    fn clone_box(&self) -> Box<dyn ProgressTracker> {
+        Box::new(self.clone())
+    }
+
+    fn tick(&mut self, state: &ProgressState, now: Instant) {
+        // Only record position changes so idle spinner ticks don't skew the rate
+        if self.samples.back().map(|(_, pos)| *pos) == Some(state.pos()) {
+            return;
+        }
+        if self.samples.len() == RATE_WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, state.pos()));
+    }
+
+    fn reset(&mut self, state: &ProgressState, now: Instant) {
+        self.samples.clear();
+        self.samples.push_back((now, state.pos()));
+    }
+
+    fn write(&self, state: &ProgressState, w: &mut dyn fmt::Write) {
+        let remaining = state
+            .len()
+            .filter(|len| *len > 0)
+            .map(|len| len.saturating_sub(state.pos()));
+        let _ = w.write_str(&format_eta(
+            self.display,
+            state.elapsed(),
+            self.rate(state),
+            remaining,
+        ));
+    }
+}
+
+/// Format the enabled timing details, e.g. "00:05 elapsed (ETA 00:05, 2.0/s)".
+/// The rate is omitted until known, and the ETA when the rate is zero or the
+/// total is unknown.
+fn format_eta(
+    display: EtaDisplay,
+    elapsed: Duration,
+    rate: Option<f64>,
+    remaining: Option<u64>,
+) -> String {
+    let mut details = Vec::new();
+    if display.eta {
+        if let (Some(rate), Some(remaining)) = (rate.filter(|r| *r > 0.0), remaining) {
+            let eta = Duration::from_secs_f64(remaining as f64 / rate);
+            details.push(format!("ETA {}", format_duration(eta)));
+        }
+    }
+    if display.rate {
+        if let Some(rate) = rate {
+            details.push(format_rate(rate));
         }
     }
+
+    let mut out = String::new();
+    if display.elapsed {
+        out.push_str(&format!("{} elapsed", format_duration(elapsed)));
+    }
+    if !details.is_empty() {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(&format!("({})", details.join(", ")));
+    }
+    out
+}
+
+/// Format a rate as items/sec, keeping one decimal for slow rates
+fn format_rate(rate: f64) -> String {
+    if rate >= 10.0 {
+        format!("{rate:.0}/s")
+    } else {
+        format!("{rate:.1}/s")
+    }
+}
+
+/// Format a duration as MM:SS, or H:MM:SS once it exceeds an hour
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
 }
 
 #[cfg(test)]
@@ -234,4 +505,85 @@ mod tests {
         assert_eq!(pb.percentage(), 50);
         pb.success("Complete!");
     }
-}
+
+    #[test]
+    fn test_plain_progress_lines() {
+        assert_eq!(plain_percent(0, 10), 0);
+        assert_eq!(plain_percent(3, 10), 30);
+        assert_eq!(plain_percent(29, 100), 20);
+        assert_eq!(plain_percent(12, 10), 100);
+        assert_eq!(
+            plain_progress_line("Exporting", 30, 3, 10),
+            "Exporting 30% (3/10)"
+        );
+        assert_eq!(plain_progress_line("", 100, 10, 10), "100% (10/10)");
+    }
+
+    #[test]
+    fn test_eta_formatting() {
+        let all = EtaDisplay {
+            elapsed: true,
+            eta: true,
+            rate: true,
+        };
+        let elapsed = Duration::from_secs(5);
+        assert_eq!(
+            format_eta(all, elapsed, Some(2.0), Some(10)),
+            "00:05 elapsed (ETA 00:05, 2.0/s)"
+        );
+        assert_eq!(
+            format_eta(all, Duration::from_secs(3725), Some(0.5), Some(1800)),
+            "1:02:05 elapsed (ETA 1:00:00, 0.5/s)"
+        );
+        // Indeterminate: no total, so no ETA
+        assert_eq!(
+            format_eta(all, elapsed, Some(2.0), None),
+            "00:05 elapsed (2.0/s)"
+        );
+        assert_eq!(format_eta(all, elapsed, None, Some(10)), "00:05 elapsed");
+    }
+
+    #[test]
+    fn test_eta_and_rate_toggles() {
+        let eta_and_rate = EtaDisplay {
+            elapsed: false,
+            eta: true,
+            rate: true,
+        };
+        let elapsed = Duration::from_secs(3);
+        assert_eq!(
+            format_eta(eta_and_rate, elapsed, Some(12.0), Some(504)),
+            "(ETA 00:42, 12/s)"
+        );
+        // Zero rate: the ETA would be infinite, so only the rate is shown
+        assert_eq!(
+            format_eta(eta_and_rate, elapsed, Some(0.0), Some(504)),
+            "(0.0/s)"
+        );
+
+        let rate_only = EtaDisplay {
+            rate: true,
+            ..EtaDisplay::default()
+        };
+        assert_eq!(
+            format_eta(rate_only, elapsed, Some(12.0), Some(504)),
+            "(12/s)"
+        );
+
+        let pb = ProgressBar::builder(10)
+            .show_eta(true)
+            .show_rate(true)
+            .hidden(true)
+            .start();
+        pb.inc(1);
+        pb.finish();
+    }
+
+    #[test]
+    fn test_progress_bar_with_eta() {
+        let pb = ProgressBar::builder(10).with_eta(true).hidden(true).start();
+        pb.inc(5);
+        assert_eq!(pb.percentage(), 50);
+        pb.finish();
+    }
+}
\ No newline at end of file
@@ -1,8 +1,10 @@
 use colored::Colorize;
 use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use once_cell::sync::Lazy;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use crate::mode::{emit_step, output_mode, OutputMode};
 use crate::style::Style;
 
 static MULTI: Lazy<MultiProgress> = Lazy::new(MultiProgress::new);
@@ -13,6 +15,95 @@ const DEFAULT_TICK_CHARS: &str = "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏";
 /// A spinner for showing indeterminate progress
 pub struct Spinner {
     progress_bar: ProgressBar,
+    mode: OutputMode,
+    tick_chars: String,
+    style: Style,
+    /// Child spinners started from this one
+    children: Arc<Children>,
+    /// Bookkeeping of the parent, if this is a child spinner
+    parent: Option<Arc<Children>>,
+}
+
+/// Child spinners of one spinner
+struct Children {
+    /// Bar of the spinner that owns the children
+    owner: ProgressBar,
+    /// Nesting depth of the owner (0 for top-level spinners)
+    depth: usize,
+    /// Animated style of the owner, restored once no child is running
+    animated: Option<(ProgressStyle, Duration)>,
+    tally: Mutex<ChildTally>,
+}
+
+#[derive(Default)]
+struct ChildTally {
+    running: usize,
+    succeeded: usize,
+    failed: usize,
+    /// Most recently started child, below which the next one is inserted
+    last: Option<Arc<Children>>,
+}
+
+impl Children {
+    /// Bar after which a new child is inserted: the deepest, most recently
+    /// started descendant, or the owner itself
+    fn anchor(&self) -> ProgressBar {
+        let last = self.tally.lock().unwrap().last.clone();
+        match last {
+            Some(child) => child.anchor(),
+            None => self.owner.clone(),
+        }
+    }
+
+    /// Record a started child, pausing the owner's animation so only the
+    /// active leaf spins
+    fn started(&self, child: Arc<Children>) {
+        let mut tally = self.tally.lock().unwrap();
+        tally.running += 1;
+        tally.last = Some(child);
+        if tally.running == 1 && self.animated.is_some() {
+            self.owner.disable_steady_tick();
+            self.owner
+                .set_style(ProgressStyle::with_template("{prefix}  {msg}").unwrap());
+        }
+    }
+
+    /// Record a finished child, resuming the owner's animation once the
+    /// last running child is done
+    fn finished(&self, ok: bool) {
+        let mut tally = self.tally.lock().unwrap();
+        tally.running = tally.running.saturating_sub(1);
+        if ok {
+            tally.succeeded += 1;
+        } else {
+            tally.failed += 1;
+        }
+        if let Some((style, interval)) = &self.animated {
+            if tally.running == 0 && !self.owner.is_finished() {
+                self.owner.set_style(style.clone());
+                self.owner.enable_steady_tick(*interval);
+            }
+        }
+    }
+
+    /// Summary of finished children, e.g. "3 steps" or "1 of 3 steps failed"
+    fn summary(&self) -> Option<String> {
+        let tally = self.tally.lock().unwrap();
+        let total = tally.succeeded + tally.failed;
+        match (total, tally.failed) {
+            (0, _) => None,
+            (total, 0) => Some(format!("{total} {}", steps(total))),
+            (total, failed) => Some(format!("{failed} of {total} {} failed", steps(total))),
+        }
+    }
+}
+
+fn steps(count: usize) -> &'static str {
+    if count == 1 {
+        "step"
+    } else {
+        "steps"
+    }
 }
 
 impl Spinner {
@@ -21,40 +112,101 @@ impl Spinner {
         SpinnerBuilder::new(message)
     }
 
-    /// Update the spinner message
+    /// Start a child spinner rendered indented beneath this one.
+    ///
+    /// While any child is running this spinner stops animating, so only the
+    /// active leaf spins. Once finished, this spinner's final message
+    /// summarizes how many child steps succeeded or failed.
+    pub fn child(&self, message: impl Into<String>) -> Spinner {
+        let builder = SpinnerBuilder {
+            tick_chars: self.tick_chars.clone(),
+            style: self.style,
+            hidden: self.mode == OutputMode::Silent,
+            ..SpinnerBuilder::new(message)
+        };
+        builder.start_child(self.mode, self.children.clone())
+    }
+
+    /// Update the spinner message.
+    /// In [`OutputMode::Plain`] each new message is printed on its own line,
+    /// and in [`OutputMode::Json`] it is reported as a running step.
     pub fn set_message(&self, message: impl Into<String>) {
-        self.progress_bar.set_message(message.into());
+        let message = message.into();
+        if message != self.progress_bar.message() {
+            match self.mode {
+                OutputMode::Plain => eprintln!("{}{message}", self.indent()),
+                OutputMode::Json => emit_step(&message, "running"),
+                OutputMode::Interactive | OutputMode::Silent => {}
+            }
+        }
+        self.progress_bar.set_message(message);
     }
 
     /// Finish the spinner with a success message
     pub fn success(self, message: impl Into<String>) {
         let msg = message.into();
-        self.progress_bar
-            .finish_with_message(format!("{} {}", "✓".green(), msg));
+        self.finish_step(format!("{} {}", "✓".green(), msg), &msg, "ok");
     }
 
     /// Finish the spinner with an error message
     pub fn error(self, message: impl Into<String>) {
         let msg = message.into();
-        self.progress_bar
-            .finish_with_message(format!("{} {}", "✗".red(), msg));
+        self.finish_step(format!("{} {}", "✗".red(), msg), &msg, "error");
     }
 
     /// Finish the spinner with a warning message
     pub fn warning(self, message: impl Into<String>) {
         let msg = message.into();
-        self.progress_bar
-            .finish_with_message(format!("{} {}", "!".yellow(), msg));
+        self.finish_step(format!("{} {}", "!".yellow(), msg), &msg, "warning");
     }
 
     /// Finish and clear the spinner
     pub fn finish(self) {
+        if self.mode == OutputMode::Json {
+            emit_step(&self.progress_bar.message(), "done");
+        }
         self.progress_bar.finish_and_clear();
+        if let Some(parent) = &self.parent {
+            parent.finished(true);
+        }
     }
 
     /// Finish with a custom message (no icon)
     pub fn finish_with_message(self, message: impl Into<String>) {
-        self.progress_bar.finish_with_message(message.into());
+        let message = message.into();
+        self.finish_step(message.clone(), &message, "done");
+    }
+
+    /// Finish with `line` on a terminal or in plain mode, or report `label`
+    /// with `status` in JSON mode
+    fn finish_step(self, line: String, label: &str, status: &str) {
+        let line = match self.children.summary() {
+            Some(summary) => format!("{line} ({summary})"),
+            None => line,
+        };
+        match self.mode {
+            OutputMode::Interactive => {
+                self.progress_bar
+                    .set_style(ProgressStyle::with_template("{prefix}{msg}").unwrap());
+                self.progress_bar.finish_with_message(line);
+            }
+            OutputMode::Plain => {
+                self.progress_bar.finish_and_clear();
+                eprintln!("{}{line}", self.indent());
+            }
+            OutputMode::Json => {
+                self.progress_bar.finish_and_clear();
+                emit_step(label, status);
+            }
+            OutputMode::Silent => self.progress_bar.finish_and_clear(),
+        }
+        if let Some(parent) = &self.parent {
+            parent.finished(status != "error");
+        }
+    }
+
+    fn indent(&self) -> String {
+        indent(self.children.depth)
     }
 
     /// Temporarily hide the spinner (useful when prompting for input)
@@ -62,6 +214,9 @@ impl Spinner {
     where
         F: FnOnce() -> R,
     {
+        if self.mode != OutputMode::Interactive {
+            return f();
+        }
         self.progress_bar
             .set_draw_target(ProgressDrawTarget::hidden());
         let result = f();
@@ -116,37 +271,88 @@ impl SpinnerBuilder {
         self
     }
 
-    /// Start the spinner
+    /// Start the spinner.
+    /// Outside [`OutputMode::Interactive`] the spinner is not animated; in
+    /// [`OutputMode::Plain`] its initial and final messages are printed as
+    /// lines, and in [`OutputMode::Json`] they are reported as step events.
     pub fn start(self) -> Spinner {
-        let progress_bar = MULTI.add(ProgressBar::new_spinner());
-
-        let template = match self.style {
-            Style::Green => "{spinner:.green} {msg}",
-            Style::Yellow => "{spinner:.yellow} {msg}",
-            Style::Red => "{spinner:.red} {msg}",
-            Style::Blue => "{spinner:.blue} {msg}",
-            Style::Cyan => "{spinner:.cyan} {msg}",
-            Style::Default => "{spinner} {msg}",
+        let mode = if self.hidden {
+            OutputMode::Silent
+        } else {
+            output_mode()
         };
+        self.start_in(mode, None)
+    }
 
-        progress_bar.set_style(
-            ProgressStyle::default_spinner()
+    fn start_child(self, mode: OutputMode, parent: Arc<Children>) -> Spinner {
+        self.start_in(mode, Some(parent))
+    }
+
+    fn start_in(self, mode: OutputMode, parent: Option<Arc<Children>>) -> Spinner {
+        let depth = parent.as_ref().map_or(0, |p| p.depth + 1);
+
+        let (progress_bar, animated) = if mode == OutputMode::Interactive {
+            let bar = ProgressBar::new_spinner();
+            let progress_bar = match &parent {
+                Some(parent) => MULTI.insert_after(&parent.anchor(), bar),
+                None => MULTI.add(bar),
+            };
+
+            let template = match self.style {
+                Style::Green => "{prefix}{spinner:.green} {msg}",
+                Style::Yellow => "{prefix}{spinner:.yellow} {msg}",
+                Style::Red => "{prefix}{spinner:.red} {msg}",
+                Style::Blue => "{prefix}{spinner:.blue} {msg}",
+                Style::Cyan => "{prefix}{spinner:.cyan} {msg}",
+                Style::Default => "{prefix}{spinner} {msg}",
+            };
+            let style = ProgressStyle::default_spinner()
                 .template(template)
                 .unwrap()
-                .tick_chars(&self.tick_chars),
-        );
+                .tick_chars(&self.tick_chars);
 
-        progress_bar.set_message(self.message);
-        progress_bar.enable_steady_tick(self.tick_interval);
+            progress_bar.set_style(style.clone());
+            progress_bar.set_prefix(indent(depth));
+            progress_bar.set_message(self.message);
+            progress_bar.enable_steady_tick(self.tick_interval);
+            (progress_bar, Some((style, self.tick_interval)))
+        } else {
+            let progress_bar = ProgressBar::hidden();
+            match mode {
+                OutputMode::Plain => eprintln!("{}{}", indent(depth), self.message),
+                OutputMode::Json => emit_step(&self.message, "started"),
+                OutputMode::Interactive | OutputMode::Silent => {}
+            }
+            progress_bar.set_message(self.message);
+            (progress_bar, None)
+        };
 
-        if self.hidden {
-            progress_bar.set_draw_target(ProgressDrawTarget::hidden());
+        let children = Arc::new(Children {
+            owner: progress_bar.clone(),
+            depth,
+            animated,
+            tally: Mutex::default(),
+        });
+        if let Some(parent) = &parent {
+            parent.started(children.clone());
         }
 
-        Spinner { progress_bar }
+        Spinner {
+            progress_bar,
+            mode,
+            tick_chars: self.tick_chars,
+            style: self.style,
+            children,
+            parent,
+        }
     }
 }
 
+/// Indentation for a spinner nested `depth` levels deep
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +372,22 @@ mod tests {
             .start();
         spinner.success("Done!");
     }
+
+    #[test]
+    fn test_child_spinners() {
+        let parent = Spinner::builder("Exporting").hidden(true).start();
+        let gerbers = parent.child("Exporting gerbers");
+        let drill = gerbers.child("Writing drill files");
+        drill.success("Drill files written");
+        gerbers.success("Gerbers exported");
+        parent.child("Exporting BOM").error("BOM export failed");
+
+        assert_eq!(parent.children.depth, 0);
+        assert_eq!(
+            parent.children.summary().as_deref(),
+            Some("1 of 2 steps failed")
+        );
+        assert_eq!(parent.children.tally.lock().unwrap().running, 0);
+        parent.finish();
+    }
 }
@@ -17,6 +17,15 @@ pub enum Style {
     Cyan,
 }
 
+impl Style {
+    /// Force plain output (no animation or ANSI escapes) regardless of the
+    /// terminal, e.g. to get stable output in tests.
+    /// Shorthand for `set_output_mode(OutputMode::Plain)`.
+    pub fn force_plain() {
+        crate::set_output_mode(crate::OutputMode::Plain);
+    }
+}
+
 /// Extension trait for applying consistent styles to text
 pub trait StyledText {
     /// Apply success styling (green with checkmark)
@@ -0,0 +1,258 @@
+use colored::Colorize;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use once_cell::sync::Lazy;
+use std::time::Duration;
+
+use crate::mode::{emit_step, output_mode, OutputMode};
+use crate::style::icons;
+
+static MULTI: Lazy<MultiProgress> = Lazy::new(MultiProgress::new);
+
+/// Default spinner tick characters (same as used in CLI)
+const DEFAULT_TICK_CHARS: &str = "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏";
+
+/// State of a single step in a [`TaskList`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskState {
+    /// Registered but not started yet
+    Pending,
+    /// Currently running
+    Running,
+    /// Finished successfully
+    Done,
+    /// Finished with an error
+    Failed,
+}
+
+struct TaskStep {
+    name: String,
+    state: TaskState,
+    bar: Option<ProgressBar>,
+}
+
+/// A checklist of named steps for multi-step operations.
+///
+/// In [`OutputMode::Interactive`] every step gets its own line that shows a
+/// spinner while running and a check or cross once finished. Otherwise each
+/// transition is printed as a plain line so logs stay readable, or as a step
+/// event in [`OutputMode::Json`].
+pub struct TaskList {
+    steps: Vec<TaskStep>,
+    plain: bool,
+    json: bool,
+    hidden: bool,
+}
+
+impl TaskList {
+    /// Create a new task list
+    pub fn builder() -> TaskListBuilder {
+        TaskListBuilder::new()
+    }
+
+    /// Mark a step as running
+    pub fn start(&mut self, step: &str) {
+        self.transition(step, TaskState::Running);
+    }
+
+    /// Mark a step as finished successfully
+    pub fn complete(&mut self, step: &str) {
+        self.transition(step, TaskState::Done);
+    }
+
+    /// Mark a step as failed
+    pub fn fail(&mut self, step: &str) {
+        self.transition(step, TaskState::Failed);
+    }
+
+    /// Current state of a step, if it was registered
+    pub fn state(&self, step: &str) -> Option<TaskState> {
+        self.steps.iter().find(|s| s.name == step).map(|s| s.state)
+    }
+
+    /// Whether every step has finished (successfully or not)
+    pub fn is_finished(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|s| matches!(s.state, TaskState::Done | TaskState::Failed))
+    }
+
+    /// Stop rendering, leaving finished steps on screen
+    pub fn finish(self) {
+        for step in &self.steps {
+            if let Some(bar) = &step.bar {
+                if !bar.is_finished() {
+                    bar.finish();
+                }
+            }
+        }
+    }
+
+    fn transition(&mut self, step: &str, state: TaskState) {
+        let Some(task) = self.steps.iter_mut().find(|s| s.name == step) else {
+            return;
+        };
+        task.state = state;
+
+        if let Some(bar) = &task.bar {
+            match state {
+                TaskState::Pending => {}
+                TaskState::Running => {
+                    bar.set_style(running_style());
+                    bar.enable_steady_tick(Duration::from_millis(100));
+                }
+                TaskState::Done | TaskState::Failed => {
+                    bar.set_style(ProgressStyle::with_template("{msg}").unwrap());
+                    bar.finish_with_message(status_line(state, &task.name));
+                }
+            }
+        } else if !self.hidden {
+            if self.json {
+                emit_step(&task.name, json_status(state));
+            } else if self.plain {
+                eprintln!("{}", plain_line(state, &task.name));
+            }
+        }
+    }
+}
+
+/// Builder for creating task lists
+pub struct TaskListBuilder {
+    steps: Vec<String>,
+    plain: Option<bool>,
+    hidden: bool,
+}
+
+impl TaskListBuilder {
+    fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            plain: None,
+            hidden: false,
+        }
+    }
+
+    /// Register a named step
+    pub fn step(mut self, name: impl Into<String>) -> Self {
+        self.steps.push(name.into());
+        self
+    }
+
+    /// Force plain line output instead of the animated checklist.
+    /// By default this follows the global [`OutputMode`].
+    pub fn plain(mut self, plain: bool) -> Self {
+        self.plain = Some(plain);
+        self
+    }
+
+    /// Hide all output (useful for non-interactive environments)
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Start rendering the task list
+    pub fn start(self) -> TaskList {
+        let mode = output_mode();
+        let json = mode == OutputMode::Json;
+        let plain = self.plain.unwrap_or(mode != OutputMode::Interactive);
+        let hidden = self.hidden || mode == OutputMode::Silent;
+        let animated = !plain && !hidden && !json;
+
+        let steps = self
+            .steps
+            .into_iter()
+            .map(|name| {
+                let bar = animated.then(|| {
+                    let bar = MULTI.add(ProgressBar::new_spinner());
+                    bar.set_style(ProgressStyle::with_template("{msg}").unwrap());
+                    bar.set_message(status_line(TaskState::Pending, &name));
+                    bar
+                });
+                TaskStep {
+                    name,
+                    state: TaskState::Pending,
+                    bar,
+                }
+            })
+            .collect();
+
+        TaskList {
+            steps,
+            plain,
+            json,
+            hidden,
+        }
+    }
+}
+
+fn running_style() -> ProgressStyle {
+    ProgressStyle::with_template("{spinner:.green} {msg}")
+        .unwrap()
+        .tick_chars(DEFAULT_TICK_CHARS)
+}
+
+/// Colored checklist line for the animated renderer
+fn status_line(state: TaskState, name: &str) -> String {
+    match state {
+        TaskState::Pending => format!("{} {}", icons::bullet(), name.dimmed()),
+        TaskState::Running => name.to_string(),
+        TaskState::Done => format!("{} {}", icons::success(), name),
+        TaskState::Failed => format!("{} {}", icons::error(), name),
+    }
+}
+
+/// Uncolored line for the non-TTY fallback
+fn plain_line(state: TaskState, name: &str) -> String {
+    let marker = match state {
+        TaskState::Pending => "[ ]",
+        TaskState::Running => "[..]",
+        TaskState::Done => "[ok]",
+        TaskState::Failed => "[failed]",
+    };
+    format!("{marker} {name}")
+}
+
+/// Status reported for a step in JSON mode
+fn json_status(state: TaskState) -> &'static str {
+    match state {
+        TaskState::Pending => "pending",
+        TaskState::Running => "running",
+        TaskState::Done => "ok",
+        TaskState::Failed => "error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_list_states() {
+        let mut tasks = TaskList::builder()
+            .step("Evaluate")
+            .step("Layout")
+            .hidden(true)
+            .start();
+
+        assert_eq!(tasks.state("Evaluate"), Some(TaskState::Pending));
+        assert_eq!(tasks.state("Missing"), None);
+
+        tasks.start("Evaluate");
+        assert_eq!(tasks.state("Evaluate"), Some(TaskState::Running));
+        tasks.complete("Evaluate");
+        tasks.start("Layout");
+        tasks.fail("Layout");
+
+        assert_eq!(tasks.state("Evaluate"), Some(TaskState::Done));
+        assert_eq!(tasks.state("Layout"), Some(TaskState::Failed));
+        assert!(tasks.is_finished());
+        tasks.finish();
+    }
+
+    #[test]
+    fn test_plain_lines() {
+        assert_eq!(plain_line(TaskState::Running, "Export"), "[..] Export");
+        assert_eq!(plain_line(TaskState::Done, "Export"), "[ok] Export");
+        assert_eq!(plain_line(TaskState::Failed, "Export"), "[failed] Export");
+    }
+}
@@ -1,3 +1,4 @@
+use std::io::Write;
 use terminal_size::terminal_size as get_size;
 use unicode_width::UnicodeWidthChar;
 
@@ -38,6 +39,12 @@ pub fn clear_line() {
     print!("\r\x1b[K");
 }
 
+/// Clear the whole screen and move the cursor to the top-left corner
+pub fn clear_screen() {
+    print!("\x1b[2J\x1b[H");
+    let _ = std::io::stdout().flush();
+}
+
 /// Calculate the display width of a string, accounting for Unicode characters
 pub fn text_width(text: &str) -> usize {
     text.chars()
@@ -1,9 +1,12 @@
 use log::debug;
 use picoplace_core::convert::ToSchematic;
-use picoplace_core::{EvalContext, FileProvider, InputMap, InputValue};
+use picoplace_core::{
+    EvalContext, EvalOutput, FileProvider, InputMap, InputValue, JsonDiagnostic, WithDiagnostics,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use wasm_bindgen::prelude::*;
 
@@ -126,7 +129,7 @@ impl WasmRemoteFetcher {
 
         // Store in the file provider
         if let Ok(mut provider) = self.file_provider.lock() {
-            provider.add_file(path.to_path_buf(), content);
+            provider.cache_file(path.to_path_buf(), content);
         }
 
         Ok(path.to_path_buf())
@@ -246,7 +249,7 @@ impl picoplace_core::FileProvider for WasmFileProvider {
             } else {
                 // Cache the loaded file for future use
                 if let Ok(mut provider) = self.inner.lock() {
-                    provider.add_file(path, content.clone());
+                    provider.cache_file(path, content.clone());
                 }
 
                 Ok(content)
@@ -327,25 +330,6 @@ fn json_to_input_value(json: &serde_json::Value) -> Option<InputValue> {
     }
 }
 
-/// Convert a Diagnostic to DiagnosticInfo
-fn diagnostic_to_json(diag: &picoplace_core::Diagnostic) -> DiagnosticInfo {
-    let level = match diag.severity {
-        starlark::errors::EvalSeverity::Error => "error",
-        starlark::errors::EvalSeverity::Warning => "warning",
-        starlark::errors::EvalSeverity::Advice => "info",
-        starlark::errors::EvalSeverity::Disabled => "info",
-    }
-    .to_string();
-
-    DiagnosticInfo {
-        level,
-        message: diag.body.clone(),
-        file: Some(diag.path.clone()),
-        line: diag.span.as_ref().map(|s| s.begin.line as u32),
-        child: diag.child.as_ref().map(|c| Box::new(diagnostic_to_json(c))),
-    }
-}
-
 /// A module that can be introspected or evaluated
 #[wasm_bindgen]
 pub struct Module {
@@ -354,6 +338,7 @@ pub struct Module {
     module_name: String,
     file_provider: Arc<WasmFileProvider>,
     load_resolver: Arc<picoplace_core::CoreLoadResolver>,
+    cancel_token: Arc<AtomicBool>,
 }
 
 #[wasm_bindgen]
@@ -394,6 +379,7 @@ impl Module {
             module_name,
             file_provider,
             load_resolver,
+            cancel_token: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -432,36 +418,14 @@ impl Module {
             module_name: module_name.to_string(),
             file_provider,
             load_resolver,
+            cancel_token: Arc::new(AtomicBool::new(false)),
         })
     }
 
     /// Evaluate the module with the given inputs
     #[wasm_bindgen]
     pub fn evaluate(&self, inputs_json: &str) -> Result<JsValue, JsValue> {
-        // Parse inputs
-        let inputs: HashMap<String, serde_json::Value> = serde_json::from_str(inputs_json)
-            .map_err(|e| JsValue::from_str(&format!("Failed to parse inputs JSON: {e}")))?;
-
-        // Create evaluation context using the stored providers
-        let ctx = EvalContext::new()
-            .set_file_provider(self.file_provider.clone())
-            .set_load_resolver(self.load_resolver.clone());
-
-        // Convert inputs to InputMap
-        let mut input_map = InputMap::new();
-        for (key, value) in inputs {
-            let input_value = json_to_input_value(&value)
-                .ok_or_else(|| JsValue::from_str(&format!("Invalid input type for '{key}'")))?;
-            input_map.insert(key, input_value);
-        }
-
-        // Evaluate the module
-        let main_path = PathBuf::from(&self.main_file);
-        let result = ctx
-            .set_source_path(main_path)
-            .set_module_name(self.module_name.clone())
-            .set_inputs(input_map)
-            .eval();
+        let result = self.eval_with_inputs(inputs_json)?;
 
         // Extract schematic from the result
         let schematic = result
@@ -487,8 +451,8 @@ impl Module {
             }),
             diagnostics: result
                 .diagnostics
-                .into_iter()
-                .map(|d| diagnostic_to_json(&d))
+                .iter()
+                .map(JsonDiagnostic::from)
                 .collect(),
         };
 
@@ -496,6 +460,36 @@ impl Module {
             .map_err(|e| JsValue::from_str(&format!("Failed to serialize result: {e}")))
     }
 
+    /// Evaluate the module, place its components, and return the layout as SVG text
+    #[wasm_bindgen(js_name = renderSvg)]
+    pub fn render_svg(&self, inputs_json: &str) -> Result<String, JsValue> {
+        let result = self.eval_with_inputs(inputs_json)?;
+
+        let Some(output) = result.output else {
+            let messages: Vec<String> = result.diagnostics.iter().map(|d| d.body.clone()).collect();
+            return Err(JsValue::from_str(&format!(
+                "Evaluation failed: {}",
+                messages.join("; ")
+            )));
+        };
+
+        let mut schematic = output
+            .sch_module
+            .to_schematic()
+            .map_err(|e| JsValue::from_str(&format!("Failed to convert to schematic: {e}")))?;
+        schematic.assign_reference_designators_preserving();
+
+        let layout = picoplace_engine::placer::run(&schematic);
+        Ok(picoplace_engine::svg_generator::render(&layout, &schematic))
+    }
+
+    /// Cancel an in-flight evaluation of this module
+    #[wasm_bindgen]
+    pub fn cancel(&self) {
+        debug!("Cancelling evaluation of module {}", self.id);
+        self.cancel_token.store(true, Ordering::Relaxed);
+    }
+
     #[wasm_bindgen(getter)]
     pub fn id(&self) -> String {
         self.id.clone()
@@ -510,48 +504,116 @@ impl Module {
 
     /// Read a file from the module's file system
     #[wasm_bindgen(js_name = readFile)]
-    pub fn read_file(&self, _path: &str) -> Result<String, JsValue> {
-        // TODO: Implement file reading through worker
-        Err(JsValue::from_str("File reading not yet implemented"))
+    pub fn read_file(&self, path: &str) -> Result<String, JsValue> {
+        self.file_provider
+            .inner
+            .lock()
+            .map_err(|e| JsValue::from_str(&format!("Failed to lock file provider: {e}")))?
+            .read_file(Path::new(path))
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
     /// Write a file to the module's file system
     #[wasm_bindgen(js_name = writeFile)]
-    pub fn write_file(&self, _path: &str, _content: &str) -> Result<(), JsValue> {
-        // TODO: Implement file writing through worker
-        Err(JsValue::from_str("File writing not yet implemented"))
+    pub fn write_file(&self, path: &str, content: &str) -> Result<(), JsValue> {
+        self.file_provider
+            .inner
+            .lock()
+            .map_err(|e| JsValue::from_str(&format!("Failed to lock file provider: {e}")))?
+            .add_file(path, content.to_string());
+        Ok(())
     }
 
     /// Delete a file from the module's file system
     #[wasm_bindgen(js_name = deleteFile)]
-    pub fn delete_file(&self, _path: &str) -> Result<(), JsValue> {
-        // TODO: Implement file deletion through worker
-        Err(JsValue::from_str("File deletion not yet implemented"))
+    pub fn delete_file(&self, path: &str) -> Result<(), JsValue> {
+        self.file_provider
+            .inner
+            .lock()
+            .map_err(|e| JsValue::from_str(&format!("Failed to lock file provider: {e}")))?
+            .remove_file(path);
+        Ok(())
+    }
+
+    /// Return the files written or deleted since the last call as a JSON array
+    /// of `{"kind": "written" | "removed", "path": ...}` objects, oldest first
+    #[wasm_bindgen(js_name = takeChanges)]
+    pub fn take_changes(&self) -> Result<String, JsValue> {
+        let changes = self
+            .file_provider
+            .inner
+            .lock()
+            .map_err(|e| JsValue::from_str(&format!("Failed to lock file provider: {e}")))?
+            .take_changes();
+
+        serde_json::to_string(&changes)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize changes: {e}")))
     }
 
-    /// List all files in the module's file system
+    /// List all files in the module's file system as a JSON array of paths
     #[wasm_bindgen(js_name = listFiles)]
     pub fn list_files(&self) -> Result<String, JsValue> {
-        // TODO: Implement file listing through worker
-        Ok("[]".to_string())
+        let provider = self
+            .file_provider
+            .inner
+            .lock()
+            .map_err(|e| JsValue::from_str(&format!("Failed to lock file provider: {e}")))?;
+
+        let mut paths: Vec<String> = provider
+            .files()
+            .keys()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        paths.sort();
+
+        serde_json::to_string(&paths)
+            .map_err(|e| JsValue::from_str(&format!("Failed to serialize file list: {e}")))
     }
 }
 
-// Data structures for serialization
+impl Module {
+    /// Parse `inputs_json` and evaluate the module's main file with the stored providers.
+    fn eval_with_inputs(&self, inputs_json: &str) -> Result<WithDiagnostics<EvalOutput>, JsValue> {
+        // Parse inputs
+        let inputs: HashMap<String, serde_json::Value> = serde_json::from_str(inputs_json)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse inputs JSON: {e}")))?;
 
-#[derive(Serialize, Deserialize)]
-pub struct DiagnosticInfo {
-    pub level: String,
-    pub message: String,
-    pub file: Option<String>,
-    pub line: Option<u32>,
-    pub child: Option<Box<DiagnosticInfo>>,
+        // A new evaluation starts out uncancelled
+        self.cancel_token.store(false, Ordering::Relaxed);
+
+        // Create evaluation context using the stored providers
+        let ctx = EvalContext::new()
+            .set_file_provider(self.file_provider.clone())
+            .set_load_resolver(self.load_resolver.clone())
+            .set_cancellation_token(self.cancel_token.clone());
+
+        // Convert inputs to InputMap
+        let mut input_map = InputMap::new();
+        for (key, value) in inputs {
+            let input_value = json_to_input_value(&value)
+                .ok_or_else(|| JsValue::from_str(&format!("Invalid input type for '{key}'")))?;
+            input_map.insert(key, input_value);
+        }
+
+        // Evaluate the module
+        let main_path = PathBuf::from(&self.main_file);
+        let result = ctx
+            .set_source_path(main_path)
+            .set_module_name(self.module_name.clone())
+            .set_inputs(input_map)
+            .eval();
+
+        Ok(result)
+    }
 }
 
+// Data structures for serialization
+
 #[derive(Serialize, Deserialize)]
 pub struct EvaluationResult {
     pub success: bool,
     pub parameters: Option<Vec<picoplace_core::lang::type_info::ParameterInfo>>,
     pub schematic: Option<String>,
-    pub diagnostics: Vec<DiagnosticInfo>,
+    /// In the format of [`picoplace_core::diagnostics_to_json`]
+    pub diagnostics: Vec<JsonDiagnostic>,
 }